@@ -0,0 +1,181 @@
+//! Bandwidth command for the Zenobuf CLI
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
+use console::style;
+use futures::StreamExt;
+use tokio::pin;
+use tokio::signal;
+
+use super::monitor::subscribe;
+use crate::commands::completions;
+use crate::error::Result;
+
+/// Arguments for the bw command
+#[derive(Args)]
+pub struct BwArgs {
+    /// Topic to measure
+    #[clap(add = ArgValueCompleter::new(completions::topics))]
+    topic: String,
+
+    /// Length of the sliding window in seconds
+    #[clap(short, long, default_value_t = 10)]
+    window: u64,
+}
+
+/// Payload sizes received within the sliding window
+struct Window {
+    length: Duration,
+    samples: VecDeque<(Instant, usize)>,
+    bytes: usize,
+}
+
+impl Window {
+    fn new(length: Duration) -> Self {
+        Self {
+            length,
+            samples: VecDeque::new(),
+            bytes: 0,
+        }
+    }
+
+    /// Records a payload received at `now`
+    fn push(&mut self, now: Instant, size: usize) {
+        self.samples.push_back((now, size));
+        self.bytes += size;
+        self.expire(now);
+    }
+
+    /// Drops the payloads that fell out of the window
+    fn expire(&mut self, now: Instant) {
+        while let Some(&(received, size)) = self.samples.front() {
+            if now.duration_since(received) <= self.length {
+                break;
+            }
+            self.samples.pop_front();
+            self.bytes -= size;
+        }
+    }
+
+    /// Returns the mean rate over the window, in bytes per second
+    fn rate(&self, elapsed: Duration) -> f64 {
+        let span = elapsed.min(self.length).as_secs_f64();
+        if span > 0.0 {
+            self.bytes as f64 / span
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the average payload size in the window, in bytes
+    fn average_size(&self) -> f64 {
+        if self.samples.is_empty() {
+            0.0
+        } else {
+            self.bytes as f64 / self.samples.len() as f64
+        }
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.50 KB`
+fn bytes_to_string(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{value:.0} {}", UNITS[unit])
+    } else {
+        format!("{value:.2} {}", UNITS[unit])
+    }
+}
+
+/// Formats a rate in bytes per second, e.g. `1.50 KB/s`
+fn rate_to_string(bytes_per_sec: f64) -> String {
+    format!("{}/s", bytes_to_string(bytes_per_sec))
+}
+
+/// Executes the bw command
+pub async fn execute(args: BwArgs) -> Result<()> {
+    println!(
+        "{label} {topic}",
+        label = style("Measuring bandwidth of topic:").bold(),
+        topic = args.topic
+    );
+    println!("Press Ctrl+C to exit");
+
+    // Connect to Zenoh and subscribe to the raw payloads
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+    let subscriber = subscribe(&session, &args.topic).await?;
+    let mut stream = subscriber.stream();
+
+    // Create a signal handler for Ctrl+C
+    let interrupt = signal::ctrl_c();
+    pin!(interrupt);
+
+    let start = Instant::now();
+    let mut window = Window::new(Duration::from_secs(args.window.max(1)));
+    let mut peak_rate = 0.0_f64;
+    let mut total_messages = 0_u64;
+    let mut total_bytes = 0_u64;
+    let mut report = tokio::time::interval(Duration::from_secs(1));
+    report.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = &mut interrupt => break,
+            _ = report.tick() => {
+                let now = Instant::now();
+                window.expire(now);
+                let rate = window.rate(now - start);
+                peak_rate = peak_rate.max(rate);
+                if window.samples.is_empty() {
+                    println!("no new messages");
+                } else {
+                    println!(
+                        "average: {rate} | peak: {peak} | mean size: {size} | window: {count}",
+                        rate = rate_to_string(rate),
+                        peak = rate_to_string(peak_rate),
+                        size = bytes_to_string(window.average_size()),
+                        count = window.samples.len(),
+                    );
+                }
+            }
+            sample = stream.next() => {
+                if let Some(sample) = sample {
+                    let size = sample.payload().len();
+                    window.push(Instant::now(), size);
+                    total_messages += 1;
+                    total_bytes += size as u64;
+                }
+            }
+        }
+    }
+
+    // Print a summary of the whole run
+    let elapsed = start.elapsed().as_secs_f64();
+    println!("\n{}", style("Summary:").bold());
+    println!("  Messages: {total_messages}");
+    println!("  Total: {}", bytes_to_string(total_bytes as f64));
+    if elapsed > 0.0 {
+        println!(
+            "  Mean rate: {}",
+            rate_to_string(total_bytes as f64 / elapsed)
+        );
+    }
+    println!("  Peak rate: {}", rate_to_string(peak_rate));
+    if total_messages > 0 {
+        println!(
+            "  Mean size: {}",
+            bytes_to_string(total_bytes as f64 / total_messages as f64)
+        );
+    }
+
+    Ok(())
+}
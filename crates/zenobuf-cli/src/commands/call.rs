@@ -1,16 +1,23 @@
 //! Call command for the Zenobuf CLI
 
+use std::path::{Path, PathBuf};
+
 use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
 use console::style;
 use serde_json::{json, Value};
+use zenobuf_core::reflect::{self, MessageDescriptor};
+use zenobuf_core::ErrorReply;
 use zenoh::{self, key_expr::KeyExpr};
 
+use crate::commands::completions;
 use crate::error::Result;
 
 /// Arguments for the call command
 #[derive(Args)]
 pub struct CallArgs {
     /// Service to call
+    #[clap(add = ArgValueCompleter::new(completions::services))]
     service: String,
 
     /// Request data (JSON)
@@ -20,6 +27,80 @@ pub struct CallArgs {
     /// Timeout in seconds
     #[clap(short, long, default_value = "5")]
     timeout: u64,
+
+    /// Encode the request and decode the response as Protocol Buffers with
+    /// this FileDescriptorSet
+    #[clap(long)]
+    descriptor: Option<PathBuf>,
+
+    /// Fully qualified request type, instead of the one advertised by the service
+    #[clap(short, long, requires = "descriptor")]
+    message: Option<String>,
+
+    /// Fully qualified response type, instead of the one advertised by the service
+    #[clap(short, long, requires = "descriptor")]
+    response_message: Option<String>,
+}
+
+/// Request and response descriptors of a service, looked up in a descriptor set
+struct Descriptors {
+    request: MessageDescriptor,
+    /// `None` if the response type is unknown or not in the set, in which case
+    /// the response is shown undecoded
+    response: Option<MessageDescriptor>,
+}
+
+impl Descriptors {
+    /// Loads the descriptor set file and finds the types of the call
+    async fn load(session: &zenoh::Session, args: &CallArgs, path: &Path) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let pool = reflect::load_descriptors(&bytes)?;
+
+        let advertised = match (&args.message, &args.response_message) {
+            (Some(_), Some(_)) => Value::Null,
+            _ => advertised_types(session, &args.service).await?,
+        };
+        let request_type = args
+            .message
+            .as_deref()
+            .or(advertised["request_type"].as_str())
+            .ok_or_else(|| {
+                format!(
+                    "The request type of service '{}' is unknown, pass it with --message",
+                    args.service
+                )
+            })?;
+        let request = reflect::find_message(&pool, request_type)?;
+        let response = args
+            .response_message
+            .as_deref()
+            .or(advertised["response_type"].as_str())
+            .and_then(|response_type| reflect::find_message(&pool, response_type).ok());
+
+        Ok(Self { request, response })
+    }
+}
+
+/// Returns the request and response types advertised by a service, or `null`
+/// if it does not answer
+async fn advertised_types(session: &zenoh::Session, service: &str) -> Result<Value> {
+    let key_expr = KeyExpr::try_from(format!(
+        "zenobuf/service/{}/__types__",
+        service.trim_start_matches('/')
+    ))?;
+    let replies = session
+        .get(key_expr)
+        .timeout(std::time::Duration::from_secs(2))
+        .await?;
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.result() {
+            if let Ok(types) = serde_json::from_slice(&sample.payload().to_bytes()) {
+                return Ok(types);
+            }
+        }
+    }
+    Ok(Value::Null)
 }
 
 /// Executes the call command
@@ -49,8 +130,22 @@ pub async fn execute(args: CallArgs) -> Result<()> {
     let service_path = format!("zenobuf/service/{}", args.service);
     let key_expr = KeyExpr::try_from(service_path)?;
 
+    let descriptors = match &args.descriptor {
+        Some(path) => Some(Descriptors::load(&session, &args, path).await?),
+        None => None,
+    };
+
     // Serialize the request data
-    let request_bytes = serde_json::to_vec(&request_data)?;
+    let (request_bytes, encoding) = match &descriptors {
+        Some(descriptors) => (
+            reflect::encode_from_json(&request_data, &descriptors.request)?,
+            zenoh::bytes::Encoding::APPLICATION_PROTOBUF,
+        ),
+        None => (
+            serde_json::to_vec(&request_data)?,
+            zenoh::bytes::Encoding::default(),
+        ),
+    };
 
     // Call the service
     println!("  Waiting for response...");
@@ -58,6 +153,7 @@ pub async fn execute(args: CallArgs) -> Result<()> {
     let replies = session
         .get(key_expr)
         .payload(request_bytes)
+        .encoding(encoding)
         .timeout(timeout)
         .await?;
 
@@ -69,8 +165,12 @@ pub async fn execute(args: CallArgs) -> Result<()> {
                     // Get the payload as bytes
                     let payload = sample.payload().to_bytes();
 
-                    // Try to parse as JSON
-                    match serde_json::from_slice::<Value>(&payload) {
+                    // Decode with the response descriptor, or try to parse as JSON
+                    let decoded = descriptors
+                        .as_ref()
+                        .and_then(|descriptors| descriptors.response.as_ref())
+                        .and_then(|descriptor| reflect::decode_to_json(&payload, descriptor).ok());
+                    match decoded.map_or_else(|| serde_json::from_slice::<Value>(&payload), Ok) {
                         Ok(json) => {
                             println!("\n{}", style("Response:").bold());
                             println!("{}", serde_json::to_string_pretty(&json)?);
@@ -83,10 +183,16 @@ pub async fn execute(args: CallArgs) -> Result<()> {
                         }
                     }
                 }
-                Err(e) => {
-                    eprintln!("\n{}", style("Error:").bold().red());
-                    eprintln!("  {e}");
-                }
+                Err(e) => match ErrorReply::from_bytes(&e.payload().to_bytes()) {
+                    Some(reply) => {
+                        eprintln!("\n{}", style("Service error:").bold().red());
+                        eprintln!("  {}", reply.error);
+                    }
+                    None => {
+                        eprintln!("\n{}", style("Error:").bold().red());
+                        eprintln!("  {e}");
+                    }
+                },
             }
         }
         Err(e) => {
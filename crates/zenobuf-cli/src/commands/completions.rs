@@ -0,0 +1,97 @@
+//! Completions command for the Zenobuf CLI
+//!
+//! The generated script asks the CLI itself for completions, through the
+//! `COMPLETE` environment variable, so that topic, service and node names are
+//! completed from the ones advertised by the running system.
+
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::io;
+use std::time::Duration;
+
+use clap::{Args, ValueEnum};
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::{Bash, EnvCompleter, Fish, Zsh};
+
+use crate::commands::list::names_under;
+use crate::error::Result;
+
+/// Environment variable through which the shell requests completions
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Time allowed for discovering names while completing
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Shells for which a completion script can be generated
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Arguments for the completions command
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    #[clap(value_enum)]
+    shell: Shell,
+}
+
+/// Executes the completions command, printing the script to stdout
+pub fn execute(args: CompletionsArgs) -> Result<()> {
+    let completer: &dyn EnvCompleter = match args.shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+    };
+    let bin = env!("CARGO_BIN_NAME");
+    completer
+        .write_registration(COMPLETE_VAR, bin, bin, bin, &mut io::stdout())
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Completes the names of the topics advertised by running nodes
+pub fn topics(current: &OsStr) -> Vec<CompletionCandidate> {
+    complete_names("zenobuf/topic/", current)
+}
+
+/// Completes the names of the services advertised by running nodes
+pub fn services(current: &OsStr) -> Vec<CompletionCandidate> {
+    complete_names("zenobuf/service/", current)
+}
+
+/// Completes the names of the running nodes
+pub fn nodes(current: &OsStr) -> Vec<CompletionCandidate> {
+    complete_names("zenobuf/node/", current)
+}
+
+/// Returns the names under `prefix` starting with what has been typed so far
+///
+/// Completion runs before the CLI starts its runtime, so this uses its own.
+/// Nothing is suggested if Zenoh cannot be reached in time.
+fn complete_names(prefix: &str, current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let Ok(runtime) = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    else {
+        return Vec::new();
+    };
+    let names = runtime.block_on(async {
+        tokio::time::timeout(DISCOVERY_TIMEOUT, async {
+            let session = zenoh::open(zenoh::config::Config::default()).await?;
+            names_under(&session, prefix).await
+        })
+        .await
+    });
+    names
+        .ok()
+        .and_then(|names| names.ok())
+        .unwrap_or_else(BTreeSet::new)
+        .into_iter()
+        .filter(|name| name.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
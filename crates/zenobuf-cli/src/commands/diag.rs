@@ -0,0 +1,66 @@
+//! Diagnostics command for the Zenobuf CLI
+
+use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
+use console::style;
+use zenobuf_core::{Node, NodeDiagnostics};
+use zenoh::{self, key_expr::KeyExpr};
+
+use crate::commands::completions;
+use crate::error::Result;
+
+/// Arguments for the diag command
+#[derive(Args)]
+pub struct DiagArgs {
+    /// Node to inspect
+    #[clap(add = ArgValueCompleter::new(completions::nodes))]
+    node: String,
+
+    /// Timeout in seconds
+    #[clap(short, long, default_value = "5")]
+    timeout: u64,
+}
+
+/// Executes the diag command
+pub async fn execute(args: DiagArgs) -> Result<()> {
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+
+    let key_expr = KeyExpr::try_from(format!(
+        "{}{}/{}",
+        Node::NODE_PREFIX,
+        args.node,
+        Node::DIAGNOSTICS_KEY
+    ))?;
+    let replies = session
+        .get(key_expr)
+        .timeout(std::time::Duration::from_secs(args.timeout))
+        .await?;
+
+    let Ok(reply) = replies.recv_async().await else {
+        return Err(format!("Node '{}' not found", args.node).into());
+    };
+    let sample = reply
+        .result()
+        .map_err(|e| format!("Node '{}' failed to answer: {e}", args.node))?;
+    let diagnostics: NodeDiagnostics = serde_json::from_slice(&sample.payload().to_bytes())?;
+
+    println!(
+        "{label} {node}",
+        label = style("Node:").bold(),
+        node = args.node
+    );
+    for (label, value) in [
+        ("Publishers:", diagnostics.publishers as u64),
+        ("Subscribers:", diagnostics.subscribers as u64),
+        ("Messages published:", diagnostics.messages_published),
+        ("Bytes published:", diagnostics.bytes_published),
+        ("Messages received:", diagnostics.messages_received),
+        ("Messages dropped:", diagnostics.messages_dropped),
+        ("Callback panics:", diagnostics.callback_panics),
+        ("Deadlines missed:", diagnostics.deadlines_missed),
+    ] {
+        println!("  {label} {value}", label = style(label).bold());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! Doctor command for the Zenobuf CLI
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use clap::Args;
+use console::style;
+use zenobuf_core::time::Time;
+use zenobuf_core::{Heartbeat, Node};
+use zenoh::{self, key_expr::KeyExpr};
+
+use super::list::names_under;
+use crate::error::Result;
+
+/// Arguments for the doctor command
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Seconds to listen for heartbeats
+    #[clap(short, long, default_value_t = 3)]
+    wait: u64,
+
+    /// Age in seconds beyond which a node's last heartbeat is stale
+    #[clap(short, long, default_value_t = 3)]
+    threshold: u64,
+}
+
+/// Executes the doctor command
+pub async fn execute(args: DoctorArgs) -> Result<()> {
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+
+    let key_expr = KeyExpr::try_from(format!("{}*/{}", Node::NODE_PREFIX, Node::HEARTBEAT_KEY))?;
+    let subscriber = session.declare_subscriber(key_expr).await?;
+
+    // Nodes that answer discovery but never send a heartbeat are reported too
+    let mut last_heartbeats: BTreeMap<String, Option<Heartbeat>> =
+        names_under(&session, Node::NODE_PREFIX)
+            .await?
+            .into_iter()
+            .map(|name| (name, None))
+            .collect();
+
+    let listen = tokio::time::sleep(Duration::from_secs(args.wait));
+    tokio::pin!(listen);
+    loop {
+        tokio::select! {
+            _ = &mut listen => break,
+            sample = subscriber.recv_async() => {
+                let Ok(sample) = sample else { break };
+                let Some(name) = sample
+                    .key_expr()
+                    .as_str()
+                    .strip_prefix(Node::NODE_PREFIX)
+                    .and_then(|rest| rest.strip_suffix(Node::HEARTBEAT_KEY))
+                    .and_then(|rest| rest.strip_suffix('/'))
+                else {
+                    continue;
+                };
+                let Ok(heartbeat) =
+                    serde_json::from_slice::<Heartbeat>(&sample.payload().to_bytes())
+                else {
+                    tracing::warn!("Ignoring malformed heartbeat of node '{}'", name);
+                    continue;
+                };
+                let last = last_heartbeats.entry(name.to_string()).or_default();
+                if last.is_none_or(|last| heartbeat.sequence > last.sequence) {
+                    *last = Some(heartbeat);
+                }
+            }
+        }
+    }
+
+    println!("{}", style("Nodes:").bold());
+    if last_heartbeats.is_empty() {
+        println!("  No nodes found");
+        return Ok(());
+    }
+
+    let threshold = Duration::from_secs(args.threshold);
+    let now = Time::now();
+    for (name, heartbeat) in last_heartbeats {
+        match heartbeat {
+            Some(heartbeat) => {
+                let age = now.elapsed_since(heartbeat.stamp);
+                let status = if age > threshold {
+                    style("stale").red()
+                } else {
+                    style("ok").green()
+                };
+                println!(
+                    "  {name} {status} (last heartbeat {age:.1?} ago, #{sequence})",
+                    sequence = heartbeat.sequence
+                );
+            }
+            None => println!("  {name} {} (no heartbeat)", style("stale").red()),
+        }
+    }
+
+    Ok(())
+}
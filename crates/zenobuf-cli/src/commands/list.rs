@@ -3,7 +3,11 @@
 use clap::Subcommand;
 use console::style;
 use std::collections::BTreeSet;
-use zenoh::{self, key_expr::KeyExpr};
+use zenoh::{
+    self,
+    key_expr::KeyExpr,
+    query::{Parameters, Selector},
+};
 
 use crate::error::Result;
 
@@ -27,12 +31,35 @@ pub async fn execute(cmd: ListCommands) -> Result<()> {
     }
 }
 
+/// Selector parameter that asks services for their metadata instead of calling them
+const DISCOVERY_PARAMETER: &str = "discovery";
+
 /// Queries Zenoh for all keys under the given prefix and prints the extracted names
 async fn list_by_prefix(label: &str, prefix: &str) -> Result<()> {
     println!("{}", style(format!("{label}:")).bold());
 
     let session = zenoh::open(zenoh::config::Config::default()).await?;
-    let selector = KeyExpr::try_from(format!("{prefix}**"))?;
+    let names = names_under(&session, prefix).await?;
+
+    if names.is_empty() {
+        println!("  No {label} found", label = label.to_lowercase());
+    } else {
+        for name in names {
+            println!("  {name}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the names advertised under the given prefix, e.g. the live topics
+/// under `zenobuf/topic/`
+pub(crate) async fn names_under(
+    session: &zenoh::Session,
+    prefix: &str,
+) -> Result<BTreeSet<String>> {
+    let key_expr = KeyExpr::try_from(format!("{prefix}**"))?;
+    let selector = Selector::from((key_expr, Parameters::from(DISCOVERY_PARAMETER)));
 
     let mut names = BTreeSet::new();
     let replies = session.get(selector).await?;
@@ -45,13 +72,5 @@ async fn list_by_prefix(label: &str, prefix: &str) -> Result<()> {
         }
     }
 
-    if names.is_empty() {
-        println!("  No {label} found", label = label.to_lowercase());
-    } else {
-        for name in names {
-            println!("  {name}");
-        }
-    }
-
-    Ok(())
+    Ok(names)
 }
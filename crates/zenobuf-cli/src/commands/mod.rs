@@ -1,6 +1,12 @@
 //! Commands for the Zenobuf CLI
 
+pub mod bw;
 pub mod call;
+pub mod completions;
+pub mod diag;
+pub mod doctor;
 pub mod list;
 pub mod monitor;
 pub mod param;
+pub mod peers;
+pub mod service_type;
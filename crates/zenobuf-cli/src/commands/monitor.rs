@@ -1,19 +1,29 @@
 //! Monitor command for the Zenobuf CLI
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
 use console::style;
 use futures::StreamExt;
 use serde_json::Value;
 use tokio::pin;
 use tokio::signal;
-use zenoh::{self, key_expr::KeyExpr};
+use zenobuf_core::reflect::{self, DescriptorPool, MessageDescriptor};
+use zenobuf_core::transport::attached_type_name;
+use zenoh::{
+    self, handlers::FifoChannelHandler, key_expr::KeyExpr, pubsub::Subscriber, sample::Sample,
+};
 
+use crate::commands::completions;
 use crate::error::Result;
 
 /// Arguments for the monitor command
 #[derive(Args)]
 pub struct MonitorArgs {
     /// Topic to monitor
+    #[clap(add = ArgValueCompleter::new(completions::topics))]
     topic: String,
 
     /// Show timestamps
@@ -27,6 +37,94 @@ pub struct MonitorArgs {
     /// Exit after this many seconds
     #[clap(short = 'T', long)]
     timeout: Option<u64>,
+
+    /// Decode Protocol Buffer messages with this FileDescriptorSet
+    #[clap(short, long)]
+    descriptor: Option<PathBuf>,
+
+    /// Fully qualified message type to decode, instead of the type attached to
+    /// each message or advertised by the topic's publishers
+    #[clap(short = 'm', long, requires = "descriptor")]
+    message_type: Option<String>,
+
+    /// Show the type name attached to each message
+    #[clap(short, long)]
+    show_type: bool,
+}
+
+/// Subscribes to the raw samples published on a topic
+pub(crate) async fn subscribe(
+    session: &zenoh::Session,
+    topic: &str,
+) -> Result<Subscriber<FifoChannelHandler<Sample>>> {
+    let key_expr = KeyExpr::try_from(format!("zenobuf/topic/{topic}"))?;
+    Ok(session.declare_subscriber(key_expr).await?)
+}
+
+/// Returns the message type advertised by the publishers of a topic
+async fn advertised_type(session: &zenoh::Session, topic: &str) -> Result<Option<String>> {
+    let key_expr = KeyExpr::try_from(format!("zenobuf/topic/{topic}"))?;
+    let replies = session
+        .get(key_expr)
+        .timeout(std::time::Duration::from_secs(2))
+        .await?;
+    while let Ok(reply) = replies.recv_async().await {
+        let Ok(sample) = reply.result() else {
+            continue;
+        };
+        if let Ok(info) = serde_json::from_slice::<Value>(&sample.payload().to_bytes()) {
+            if info["role"] == "publisher" {
+                return Ok(info["type"].as_str().map(str::to_string));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Descriptors of the monitored messages, looked up in a descriptor set
+struct Descriptors {
+    pool: DescriptorPool,
+    /// Type named by `--message-type`, which overrides the type of each sample
+    forced: Option<MessageDescriptor>,
+    /// Type advertised by the topic's publishers, for samples without a type name
+    advertised: Option<String>,
+    /// Descriptors already looked up by type name, `None` if not in the set
+    found: HashMap<String, Option<MessageDescriptor>>,
+}
+
+impl Descriptors {
+    /// Loads the descriptor set file
+    async fn load(session: &zenoh::Session, args: &MonitorArgs, path: &Path) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let pool = reflect::load_descriptors(&bytes)?;
+        let forced = match &args.message_type {
+            Some(message_type) => Some(reflect::find_message(&pool, message_type)?),
+            None => None,
+        };
+        let advertised = match forced {
+            Some(_) => None,
+            None => advertised_type(session, &args.topic).await?,
+        };
+        Ok(Self {
+            pool,
+            forced,
+            advertised,
+            found: HashMap::new(),
+        })
+    }
+
+    /// Returns the descriptor of a sample whose publisher attached `type_name`
+    fn find(&mut self, type_name: Option<&str>) -> Option<&MessageDescriptor> {
+        if self.forced.is_some() {
+            return self.forced.as_ref();
+        }
+        let type_name = type_name.or(self.advertised.as_deref())?;
+        self.found
+            .entry(type_name.to_string())
+            .or_insert_with(|| reflect::find_message(&self.pool, type_name).ok())
+            .as_ref()
+    }
 }
 
 /// Executes the monitor command
@@ -41,12 +139,13 @@ pub async fn execute(args: MonitorArgs) -> Result<()> {
     // Connect to Zenoh
     let session = zenoh::open(zenoh::config::Config::default()).await?;
 
-    // Create the full topic path
-    let topic_path = format!("zenobuf/topic/{}", args.topic);
-    let key_expr = KeyExpr::try_from(topic_path)?;
+    let mut descriptors = match &args.descriptor {
+        Some(path) => Some(Descriptors::load(&session, &args, path).await?),
+        None => None,
+    };
 
     // Subscribe to the topic
-    let subscriber = session.declare_subscriber(key_expr).await?;
+    let subscriber = subscribe(&session, &args.topic).await?;
 
     // Create a stream from the subscriber
     let mut stream = subscriber.stream();
@@ -78,8 +177,21 @@ pub async fn execute(args: MonitorArgs) -> Result<()> {
             sample = stream.next() => {
                 if let Some(sample) = sample {
                     let payload = sample.payload().to_bytes();
-
-                    let display = if args.json {
+                    let type_name = sample
+                        .attachment()
+                        .and_then(|attachment| attached_type_name(&attachment.to_bytes()));
+
+                    let decoded = descriptors
+                        .as_mut()
+                        .and_then(|descriptors| descriptors.find(type_name.as_deref()))
+                        .and_then(|descriptor| reflect::decode_to_json(&payload, descriptor).ok());
+                    let display = if let Some(json) = decoded {
+                        if args.json {
+                            serde_json::to_string_pretty(&json)?
+                        } else {
+                            json.to_string()
+                        }
+                    } else if args.json {
                         serde_json::from_slice::<Value>(&payload)
                             .ok()
                             .and_then(|json| serde_json::to_string_pretty(&json).ok())
@@ -88,6 +200,13 @@ pub async fn execute(args: MonitorArgs) -> Result<()> {
                         String::from_utf8_lossy(&payload).into_owned()
                     };
 
+                    let display = match type_name {
+                        Some(type_name) if args.show_type => {
+                            format!("{} {display}", style(format!("[{type_name}]")).dim())
+                        }
+                        _ => display,
+                    };
+
                     if args.timestamps {
                         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
                         println!("{timestamp} {display}");
@@ -1,12 +1,18 @@
 //! Parameter command for the Zenobuf CLI
 //!
-//! NOTE: Parameter get/set/list commands query `zenobuf/param/{name}` via Zenoh.
-//! This requires the target Node to have parameter queryables registered.
-//! Node-side parameter queryable support is a TODO in zenobuf-core.
+//! Parameter get/set/delete/dump/load/list commands use `zenobuf/param/<node>/<name>`
+//! via Zenoh, which every node serves: queries return values, while puts and
+//! deletes set and delete parameters. Nodes also answer
+//! `zenobuf/param/<node>/__describe__`, which `list` uses to show the declared
+//! parameters of each node with their types.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use clap::{Args, Subcommand};
 use console::style;
 use serde_json::Value;
+use zenobuf_core::{Node, ParameterDescriptor};
 use zenoh::{self, key_expr::KeyExpr};
 
 use crate::error::Result;
@@ -18,6 +24,12 @@ pub enum ParamCommands {
     Get(GetArgs),
     /// Set a parameter
     Set(SetArgs),
+    /// Delete a parameter
+    Delete(DeleteArgs),
+    /// Write all parameters to a JSON file
+    Dump(DumpArgs),
+    /// Set the parameters stored in a JSON file
+    Load(LoadArgs),
     /// List all parameters
     List,
 }
@@ -39,11 +51,36 @@ pub struct SetArgs {
     value: String,
 }
 
+/// Arguments for the delete command
+#[derive(Args)]
+pub struct DeleteArgs {
+    /// Parameter name
+    name: String,
+}
+
+/// Arguments for the dump command
+#[derive(Args)]
+pub struct DumpArgs {
+    /// Output file, printed to stdout if omitted
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// Arguments for the load command
+#[derive(Args)]
+pub struct LoadArgs {
+    /// JSON file mapping parameter names to values
+    file: PathBuf,
+}
+
 /// Executes the parameter command
 pub async fn execute(cmd: ParamCommands) -> Result<()> {
     match cmd {
         ParamCommands::Get(args) => get_param(args).await,
         ParamCommands::Set(args) => set_param(args).await,
+        ParamCommands::Delete(args) => delete_param(args).await,
+        ParamCommands::Dump(args) => dump_params(args).await,
+        ParamCommands::Load(args) => load_params(args).await,
         ParamCommands::List => list_params().await,
     }
 }
@@ -131,6 +168,95 @@ async fn set_param(args: SetArgs) -> Result<()> {
     Ok(())
 }
 
+/// Deletes a parameter
+async fn delete_param(args: DeleteArgs) -> Result<()> {
+    println!(
+        "{label} {name}",
+        label = style("Deleting parameter:").bold(),
+        name = args.name
+    );
+
+    // Connect to Zenoh
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+
+    // Create the full parameter path
+    let param_path = format!("zenobuf/param/{}", args.name);
+    let key_expr = KeyExpr::try_from(param_path)?;
+
+    // Delete the parameter
+    session.delete(key_expr).await?;
+
+    println!("  Parameter deleted successfully");
+    Ok(())
+}
+
+/// Writes all parameters to a JSON file
+async fn dump_params(args: DumpArgs) -> Result<()> {
+    // Connect to Zenoh
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+
+    // Query for all parameters
+    let replies = session.get(KeyExpr::try_from("zenobuf/param/**")?).await?;
+
+    let mut params = BTreeMap::new();
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.result() {
+            let key = sample.key_expr().as_str();
+            if key.ends_with(Node::DESCRIBE_KEY) {
+                continue;
+            }
+            if let Some(param_name) = key.strip_prefix("zenobuf/param/") {
+                // Keep the value type by storing parsed JSON, falling back to a string
+                let payload = sample.payload().to_bytes();
+                let value = serde_json::from_slice::<Value>(&payload).unwrap_or_else(|_| {
+                    Value::String(String::from_utf8_lossy(&payload).into_owned())
+                });
+                params.insert(param_name.to_string(), value);
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&params)?;
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, json)
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            println!(
+                "{label} {count} parameters to {path}",
+                label = style("Dumped").bold(),
+                count = params.len(),
+                path = path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+/// Sets the parameters stored in a JSON file
+async fn load_params(args: LoadArgs) -> Result<()> {
+    let json = std::fs::read_to_string(&args.file)
+        .map_err(|e| format!("Failed to read {}: {e}", args.file.display()))?;
+    let params: BTreeMap<String, Value> = serde_json::from_str(&json)?;
+
+    // Connect to Zenoh
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+
+    for (name, value) in &params {
+        let key_expr = KeyExpr::try_from(format!("zenobuf/param/{name}"))?;
+        session.put(key_expr, serde_json::to_vec(value)?).await?;
+        println!("  {name}: {value}");
+    }
+
+    println!(
+        "{label} {count} parameters from {path}",
+        label = style("Loaded").bold(),
+        count = params.len(),
+        path = args.file.display()
+    );
+    Ok(())
+}
+
 /// Lists all parameters
 async fn list_params() -> Result<()> {
     println!("{}", style("Parameters:").bold());
@@ -138,19 +264,42 @@ async fn list_params() -> Result<()> {
     // Connect to Zenoh
     let session = zenoh::open(zenoh::config::Config::default()).await?;
 
+    let mut found = false;
+
+    // Query the parameters declared by each node, with their types and constraints
+    let describe = format!("{}*/{}", Node::PARAM_PREFIX, Node::DESCRIBE_KEY);
+    let replies = session.get(KeyExpr::try_from(describe)?).await?;
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.result() {
+            let key = sample.key_expr().as_str();
+            let node = key
+                .strip_prefix(Node::PARAM_PREFIX)
+                .and_then(|rest| rest.strip_suffix(Node::DESCRIBE_KEY))
+                .unwrap_or(key);
+            let payload = sample.payload().to_bytes();
+            if let Ok(descriptors) = serde_json::from_slice::<Vec<ParameterDescriptor>>(&payload) {
+                for descriptor in descriptors {
+                    found = true;
+                    print_descriptor(node, &descriptor);
+                }
+            }
+        }
+    }
+
     // Query for all parameters
     let param_prefix = "zenobuf/param/**";
     let selector = KeyExpr::try_from(param_prefix)?;
 
     let replies = session.get(selector).await?;
 
-    let mut found = false;
-
     // Process the responses
     while let Ok(reply) = replies.recv_async().await {
         if let Ok(sample) = reply.result() {
-            found = true;
             let key = sample.key_expr().as_str();
+            if key.ends_with(Node::DESCRIBE_KEY) {
+                continue;
+            }
+            found = true;
             // Extract parameter name from the key expression
             if let Some(param_name) = key.strip_prefix("zenobuf/param/") {
                 // Get the payload as bytes
@@ -181,3 +330,25 @@ async fn list_params() -> Result<()> {
 
     Ok(())
 }
+
+/// Prints a declared parameter with its type, default and constraints
+fn print_descriptor(node: &str, descriptor: &ParameterDescriptor) {
+    let mut details = format!("default {}", descriptor.default);
+    if descriptor.min.is_some() || descriptor.max.is_some() {
+        let bound = |value: Option<f64>| value.map_or("..".to_string(), |v| v.to_string());
+        details.push_str(&format!(
+            ", range [{}, {}]",
+            bound(descriptor.min),
+            bound(descriptor.max)
+        ));
+    }
+
+    println!(
+        "  {node}{name}: {ty} ({details})",
+        name = descriptor.name,
+        ty = style(&descriptor.type_name).cyan(),
+    );
+    if let Some(description) = &descriptor.description {
+        println!("    {}", style(description).dim());
+    }
+}
@@ -0,0 +1,39 @@
+//! Peers command for the Zenobuf CLI
+
+use std::time::Duration;
+
+use clap::Args;
+use console::style;
+use zenobuf_core::ZenohTransport;
+
+use crate::error::Result;
+
+/// Arguments for the peers command
+#[derive(Args)]
+pub struct PeersArgs {
+    /// Time in seconds to wait for peers to be discovered
+    #[clap(short, long, default_value = "1")]
+    wait: u64,
+}
+
+/// Executes the peers command
+pub async fn execute(args: PeersArgs) -> Result<()> {
+    let transport = ZenohTransport::new().await?;
+    tokio::time::sleep(Duration::from_secs(args.wait)).await;
+
+    let peers = transport.peers().await;
+    for (index, peer) in peers.iter().enumerate() {
+        let label = if index == 0 { "Self:" } else { "Peer:" };
+        println!("{} {}", style(label).bold(), peer.zid);
+        for endpoint in &peer.endpoints {
+            println!("  {endpoint}");
+        }
+    }
+    println!(
+        "{} {}",
+        style("Connected peers:").bold(),
+        peers.len().saturating_sub(1)
+    );
+
+    Ok(())
+}
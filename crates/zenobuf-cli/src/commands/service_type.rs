@@ -0,0 +1,59 @@
+//! Service type command for the Zenobuf CLI
+
+use clap::Args;
+use clap_complete::engine::ArgValueCompleter;
+use console::style;
+use serde_json::Value;
+use zenoh::{self, key_expr::KeyExpr};
+
+use crate::commands::completions;
+use crate::error::Result;
+
+/// Arguments for the service-type command
+#[derive(Args)]
+pub struct ServiceTypeArgs {
+    /// Service to inspect
+    #[clap(add = ArgValueCompleter::new(completions::services))]
+    service: String,
+
+    /// Timeout in seconds
+    #[clap(short, long, default_value = "5")]
+    timeout: u64,
+}
+
+/// Executes the service-type command
+pub async fn execute(args: ServiceTypeArgs) -> Result<()> {
+    let session = zenoh::open(zenoh::config::Config::default()).await?;
+
+    let key_expr = KeyExpr::try_from(format!(
+        "zenobuf/service/{}/__types__",
+        args.service.trim_start_matches('/')
+    ))?;
+    let replies = session
+        .get(key_expr)
+        .timeout(std::time::Duration::from_secs(args.timeout))
+        .await?;
+
+    let Ok(reply) = replies.recv_async().await else {
+        return Err(format!("Service '{}' not found", args.service).into());
+    };
+    let sample = reply
+        .result()
+        .map_err(|e| format!("Service '{}' failed to answer: {e}", args.service))?;
+    let types: Value = serde_json::from_slice(&sample.payload().to_bytes())?;
+
+    println!(
+        "{label} {service}",
+        label = style("Service:").bold(),
+        service = args.service
+    );
+    for (label, field) in [("Request:", "request_type"), ("Response:", "response_type")] {
+        println!(
+            "  {label} {type_name}",
+            label = style(label).bold(),
+            type_name = types[field].as_str().unwrap_or("unknown")
+        );
+    }
+
+    Ok(())
+}
@@ -9,6 +9,8 @@ pub enum Error {
     Zenoh(zenoh::Error),
     /// JSON error
     Json(serde_json::Error),
+    /// Zenobuf core error
+    Core(zenobuf_core::Error),
     /// Other error
     Other(String),
 }
@@ -18,6 +20,7 @@ impl fmt::Display for Error {
         match self {
             Error::Zenoh(e) => write!(f, "Zenoh error: {e}"),
             Error::Json(e) => write!(f, "JSON error: {e}"),
+            Error::Core(e) => write!(f, "{e}"),
             Error::Other(e) => write!(f, "{e}"),
         }
     }
@@ -37,6 +40,12 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+impl From<zenobuf_core::Error> for Error {
+    fn from(e: zenobuf_core::Error) -> Self {
+        Error::Core(e)
+    }
+}
+
 impl From<String> for Error {
     fn from(e: String) -> Self {
         Error::Other(e)
@@ -61,3 +70,17 @@ where
 
 /// Result type for the Zenobuf CLI
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_error_keeps_message() {
+        let core = zenobuf_core::Error::parameter("rate", "not found");
+        let message = core.to_string();
+        let error = Error::from(core);
+        assert!(matches!(error, Error::Core(_)));
+        assert_eq!(error.to_string(), message);
+    }
+}
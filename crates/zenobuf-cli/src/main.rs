@@ -33,6 +33,12 @@
 //!
 //! # Monitor with custom timeout
 //! zenobuf-cli monitor sensor_data --timeout 30
+//!
+//! # Decode Protocol Buffer messages with a descriptor set from protoc
+//! zenobuf-cli monitor sensor_data --descriptor messages.bin
+//!
+//! # Measure bandwidth over a 5 second window
+//! zenobuf-cli bw sensor_data --window 5
 //! ```
 //!
 //! ### Call Services
@@ -43,6 +49,22 @@
 //!
 //! # Call with custom timeout
 //! zenobuf-cli call status_service --timeout 10
+//!
+//! # Show the request and response types a service expects
+//! zenobuf-cli service-type add_service
+//! ```
+//!
+//! ### Inspect Nodes
+//!
+//! ```bash
+//! # Show message counters aggregated over a node's publishers and subscribers
+//! zenobuf-cli diag my_node
+//!
+//! # Report nodes whose heartbeat went silent
+//! zenobuf-cli doctor --threshold 3
+//!
+//! # Show the Zenoh sessions this machine can reach
+//! zenobuf-cli peers
 //! ```
 //!
 //! ### Manage Parameters
@@ -54,10 +76,34 @@
 //! # Set a parameter value
 //! zenobuf-cli param set max_speed 15.0
 //!
+//! # Delete a parameter
+//! zenobuf-cli param delete max_speed
+//!
+//! # Save all parameters to a file and restore them later
+//! zenobuf-cli param dump -o params.json
+//! zenobuf-cli param load params.json
+//!
 //! # List all parameters
 //! zenobuf-cli param list
 //! ```
 //!
+//! ### Logging
+//!
+//! ```bash
+//! # Emit the log lines as JSON objects, for log aggregation
+//! zenobuf-cli --log-format json --log-level debug list topics
+//! ```
+//!
+//! ### Shell Completion
+//!
+//! ```bash
+//! # Enable completion in the current bash session, also for zsh and fish
+//! source <(zenobuf-cli completions bash)
+//!
+//! # Topic, service and node names are completed from the running system
+//! zenobuf-cli monitor <TAB>
+//! ```
+//!
 //! ## Examples
 //!
 //! ### Development Workflow
@@ -94,7 +140,9 @@
 //! zenobuf-cli param get /app/config/debug_mode
 //! ```
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::env::CompleteEnv;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
 
 mod commands;
 mod error;
@@ -105,11 +153,28 @@ use error::Result;
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
+    /// Format of the log lines
+    #[clap(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    /// Most verbose level logged, e.g. `debug`, instead of the filter in `RUST_LOG`
+    #[clap(long, global = true)]
+    log_level: Option<LevelFilter>,
+
     /// Subcommand to run
     #[clap(subcommand)]
     command: Commands,
 }
 
+/// Formats of the log lines
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, for log aggregation
+    Json,
+}
+
 /// Subcommands for the Zenobuf CLI
 #[derive(Subcommand)]
 enum Commands {
@@ -120,28 +185,74 @@ enum Commands {
     /// Monitor a topic
     Monitor(commands::monitor::MonitorArgs),
 
+    /// Measure the bandwidth of a topic
+    Bw(commands::bw::BwArgs),
+
     /// Call a service
     Call(commands::call::CallArgs),
 
-    /// Get or set a parameter
+    /// Show the request and response types of a service
+    ServiceType(commands::service_type::ServiceTypeArgs),
+
+    /// Show the publisher and subscriber statistics of a node
+    Diag(commands::diag::DiagArgs),
+
+    /// Report nodes whose last heartbeat is older than a threshold as stale
+    Doctor(commands::doctor::DoctorArgs),
+
+    /// Show the Zenoh peers and routers reachable from here
+    Peers(commands::peers::PeersArgs),
+
+    /// Get, set, delete, dump or load parameters
     #[clap(subcommand)]
     Param(commands::param::ParamCommands),
+
+    /// Print a shell completion script
+    Completions(commands::completions::CompletionsArgs),
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+fn main() -> Result<()> {
+    // Answer completion requests from the shell, which set `COMPLETE`
+    CompleteEnv::with_factory(Cli::command)
+        .var(commands::completions::COMPLETE_VAR)
+        .complete();
 
+    run()
+}
+
+/// Installs the tracing subscriber writing the log lines
+fn init_logging(format: LogFormat, level: Option<LevelFilter>) {
+    let filter = match level {
+        Some(level) => EnvFilter::default().add_directive(level.into()),
+        None => EnvFilter::from_default_env(),
+    };
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+}
+
+#[tokio::main]
+async fn run() -> Result<()> {
     // Parse command-line arguments
     let cli = Cli::parse();
 
+    // Initialize tracing before any command runs
+    init_logging(cli.log_format, cli.log_level);
+
     // Execute the command
     match cli.command {
         Commands::List(cmd) => commands::list::execute(cmd).await?,
         Commands::Monitor(args) => commands::monitor::execute(args).await?,
+        Commands::Bw(args) => commands::bw::execute(args).await?,
         Commands::Call(args) => commands::call::execute(args).await?,
+        Commands::ServiceType(args) => commands::service_type::execute(args).await?,
+        Commands::Diag(args) => commands::diag::execute(args).await?,
+        Commands::Doctor(args) => commands::doctor::execute(args).await?,
+        Commands::Peers(args) => commands::peers::execute(args).await?,
         Commands::Param(cmd) => commands::param::execute(cmd).await?,
+        Commands::Completions(args) => commands::completions::execute(args)?,
     }
 
     Ok(())
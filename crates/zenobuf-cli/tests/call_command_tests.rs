@@ -0,0 +1,82 @@
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+use zenobuf_examples::proto::service::{AddTwoIntsRequest, AddTwoIntsResponse};
+use zenobuf_examples::proto::FILE_DESCRIPTOR_SET;
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Move {
+    distance: f64,
+}
+
+impl JsonMessage for Move {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_call_shows_service_error() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("cli_error_node", transport)
+        .await
+        .unwrap();
+    let _service = node
+        .service::<Json<Move>, Json<Move>>("cli/move")
+        .build(|_| Err(Error::service("cli/move", "battery too low to move")))
+        .await
+        .unwrap();
+
+    let output = tokio::task::spawn_blocking(|| {
+        Command::new(env!("CARGO_BIN_EXE_zenobuf-cli"))
+            .args(["call", "cli/move", "--data", r#"{"distance": 1.0}"#])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Service error:"), "{stderr}");
+    assert!(stderr.contains("battery too low to move"), "{stderr}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_call_with_descriptor_encodes_protobuf() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("cli_add_two_ints_node", transport)
+        .await
+        .unwrap();
+    let _service = node
+        .service::<AddTwoIntsRequest, AddTwoIntsResponse>("cli/add_two_ints")
+        .build(|request| {
+            Ok(AddTwoIntsResponse {
+                sum: request.a + request.b,
+            })
+        })
+        .await
+        .unwrap();
+
+    let descriptor = std::env::temp_dir().join(format!("zenobuf_call_{}.bin", std::process::id()));
+    std::fs::write(&descriptor, FILE_DESCRIPTOR_SET).unwrap();
+
+    let path = descriptor.clone();
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_zenobuf-cli"))
+            .args(["call", "cli/add_two_ints", "--data", r#"{"a": 5, "b": 3}"#])
+            .arg("--descriptor")
+            .arg(&path)
+            .args(["--message", "zenobuf.examples.service.AddTwoIntsRequest"])
+            .args([
+                "--response-message",
+                "zenobuf.examples.service.AddTwoIntsResponse",
+            ])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+    std::fs::remove_file(&descriptor).unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains(r#""sum": 8"#), "{stdout}");
+}
@@ -0,0 +1,79 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Odometry {
+    x: f64,
+}
+
+impl JsonMessage for Odometry {}
+
+#[test]
+fn test_completions_script_is_valid_bash() {
+    let output = Command::new(env!("CARGO_BIN_EXE_zenobuf-cli"))
+        .args(["completions", "bash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let script = String::from_utf8(output.stdout).unwrap();
+    assert!(script.contains("COMPLETE=\"bash\""), "{script}");
+
+    // `bash -n` only parses the script
+    let mut bash = Command::new("bash")
+        .arg("-n")
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    bash.stdin
+        .take()
+        .unwrap()
+        .write_all(script.as_bytes())
+        .unwrap();
+    let checked = bash.wait_with_output().unwrap();
+    assert!(
+        checked.status.success(),
+        "{}",
+        String::from_utf8_lossy(&checked.stderr)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_monitor_completes_live_topics() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("cli_completion_node", transport)
+        .await
+        .unwrap();
+    let _publisher = node
+        .publisher::<Json<Odometry>>("completion/odometry")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // What the bash script runs when completing `zenobuf-cli monitor completion/<TAB>`
+    let output = tokio::task::spawn_blocking(|| {
+        Command::new(env!("CARGO_BIN_EXE_zenobuf-cli"))
+            .env("COMPLETE", "bash")
+            .env("_CLAP_COMPLETE_INDEX", "2")
+            .env("_CLAP_IFS", "\n")
+            .args(["--", "zenobuf-cli", "monitor", "completion/"])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+
+    let suggestions = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        suggestions
+            .lines()
+            .any(|line| line == "completion/odometry"),
+        "{suggestions}"
+    );
+}
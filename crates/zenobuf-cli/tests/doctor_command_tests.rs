@@ -0,0 +1,33 @@
+use std::process::Command;
+
+use zenobuf_core::Node;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_doctor_reports_live_and_silent_nodes() {
+    let _live = Node::builder("doctor_live_node")
+        .heartbeat()
+        .build()
+        .await
+        .unwrap();
+    let _silent = Node::new("doctor_silent_node").await.unwrap();
+
+    let output = tokio::task::spawn_blocking(|| {
+        Command::new(env!("CARGO_BIN_EXE_zenobuf-cli"))
+            .args(["doctor", "--wait", "2"])
+            .output()
+            .unwrap()
+    })
+    .await
+    .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = |name: &str| {
+        stdout
+            .lines()
+            .find(|line| line.contains(name))
+            .unwrap_or_else(|| panic!("{name} missing: {stdout}"))
+            .to_string()
+    };
+    assert!(line("doctor_live_node").contains("ok"), "{stdout}");
+    assert!(line("doctor_silent_node").contains("stale"), "{stdout}");
+}
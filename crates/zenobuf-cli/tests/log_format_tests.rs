@@ -0,0 +1,29 @@
+use std::process::Command;
+
+#[test]
+fn test_json_log_format_emits_json_lines() {
+    let output = Command::new(env!("CARGO_BIN_EXE_zenobuf-cli"))
+        .args([
+            "--log-format",
+            "json",
+            "--log-level",
+            "debug",
+            "list",
+            "topics",
+        ])
+        .env_remove("RUST_LOG")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let log_lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(!log_lines.is_empty(), "{stdout}");
+    assert!(log_lines
+        .iter()
+        .all(|line| line["level"].is_string() && line["fields"].is_object()));
+}
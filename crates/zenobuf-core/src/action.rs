@@ -0,0 +1,647 @@
+//! Long-running goals with feedback and cancellation, similar to actions in ROS
+//!
+//! An [`ActionServer`] accepts goals sent by an [`ActionClient`] and runs each on
+//! a blocking task, publishing the feedback its handler reports along the way and
+//! then the result. The client can cancel a goal while it runs; the handler sees
+//! this through [`GoalContext::is_cancelled`] and should return early.
+//!
+//! An action named `name` is made of the `name/_action/goal`,
+//! `name/_action/cancel` and `name/_action/result` services and the
+//! `name/_action/feedback` topic. The topic carries both the feedback and the
+//! final outcome of each goal, so that a goal's feedback arrives before its
+//! result. The client also asks the result service for the outcome of each goal
+//! it sent, which the server answers once the goal has finished, so that a goal
+//! whose outcome was lost on the topic still finishes.
+
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::message::{decode_message, encode_message, Message};
+use crate::node::{
+    ClientHandle, DropGuard, Node, PublisherHandle, ServiceHandle, SubscriberHandle,
+};
+use crate::service::{RequestStream, Responder};
+
+/// Number of updates the client buffers before dropping them
+const UPDATE_CAPACITY: usize = 1024;
+
+/// Number of finished goals whose outcome a server keeps for result requests
+const FINISHED_CAPACITY: usize = 256;
+
+/// Time a client waits for the feedback topic to match a server before sending
+/// a goal
+const MATCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval between checks of the feedback topic's matching
+const MATCH_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Kind of an [`ActionMessage`] on the feedback topic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+enum Update {
+    Feedback = 0,
+    Succeeded = 1,
+    Canceled = 2,
+    Aborted = 3,
+}
+
+impl Update {
+    fn from_i32(kind: i32) -> Option<Self> {
+        match kind {
+            0 => Some(Update::Feedback),
+            1 => Some(Update::Succeeded),
+            2 => Some(Update::Canceled),
+            3 => Some(Update::Aborted),
+            _ => None,
+        }
+    }
+}
+
+/// Envelope carrying an encoded goal, feedback or result along with its goal ID
+#[derive(Clone, PartialEq, prost::Message)]
+struct ActionMessage {
+    #[prost(bytes = "vec", tag = "1")]
+    goal_id: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    payload: Vec<u8>,
+    #[prost(int32, tag = "3")]
+    kind: i32,
+    #[prost(string, tag = "4")]
+    error: String,
+}
+
+impl ActionMessage {
+    fn new(id: Uuid, kind: Update, payload: Vec<u8>) -> Self {
+        Self {
+            goal_id: id.as_bytes().to_vec(),
+            payload,
+            kind: kind as i32,
+            error: String::new(),
+        }
+    }
+
+    fn id(&self) -> Result<Uuid> {
+        Uuid::from_slice(&self.goal_id).map_err(|e| Error::other(format!("Invalid goal ID: {e}")))
+    }
+}
+
+impl Message for ActionMessage {
+    fn type_name() -> &'static str {
+        "zenobuf.action.ActionMessage"
+    }
+}
+
+fn goal_service(name: &str) -> String {
+    format!("{name}/_action/goal")
+}
+
+fn cancel_service(name: &str) -> String {
+    format!("{name}/_action/cancel")
+}
+
+fn result_service(name: &str) -> String {
+    format!("{name}/_action/result")
+}
+
+fn feedback_topic(name: &str) -> String {
+    format!("{name}/_action/feedback")
+}
+
+/// Goals known to a server, keyed by goal ID
+#[derive(Default)]
+struct Goals {
+    /// Cancellation tokens of the running goals
+    running: HashMap<Uuid, CancellationToken>,
+    /// Result requests waiting for a running goal to finish
+    waiting: HashMap<Uuid, Vec<Responder<ActionMessage>>>,
+    /// Outcomes of the latest finished goals, oldest first
+    finished: VecDeque<ActionMessage>,
+}
+
+impl Goals {
+    /// Returns whether a goal is running or has finished recently
+    fn knows(&self, id: Uuid) -> bool {
+        self.running.contains_key(&id) || self.outcome(id).is_some()
+    }
+
+    /// Returns the outcome of a finished goal, if it is still kept
+    fn outcome(&self, id: Uuid) -> Option<&ActionMessage> {
+        self.finished
+            .iter()
+            .find(|outcome| outcome.goal_id == id.as_bytes())
+    }
+}
+
+type SharedGoals = Arc<Mutex<Goals>>;
+
+/// Context handed to an action handler for the goal it runs
+pub struct GoalContext<F: Message> {
+    id: Uuid,
+    token: CancellationToken,
+    updates: Arc<PublisherHandle<ActionMessage>>,
+    _phantom: PhantomData<fn(F)>,
+}
+
+impl<F: Message> GoalContext<F> {
+    /// Returns the ID of the goal
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns whether the client has cancelled the goal
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Returns a token cancelled along with the goal, e.g. to interrupt a wait
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Publishes feedback on the progress of the goal
+    pub fn publish_feedback(&self, feedback: &F) -> Result<()> {
+        let message = ActionMessage::new(self.id, Update::Feedback, encode_message(feedback)?);
+        self.updates.publish(&message)
+    }
+}
+
+/// Server running the goals sent to an action
+///
+/// Dropping the server stops it accepting goals and cancels the running ones,
+/// whose outcome is still published once their handler returns.
+pub struct ActionServer<G: Message, F: Message, R: Message> {
+    name: String,
+    goals: SharedGoals,
+    _goal_service: ServiceHandle,
+    _cancel_service: ServiceHandle,
+    _result_service: ServiceHandle,
+    results: JoinHandle<()>,
+    _phantom: PhantomData<fn(G) -> (F, R)>,
+}
+
+impl<G: Message, F: Message, R: Message> ActionServer<G, F, R> {
+    /// Creates a server running `handler` for each goal sent to the action `name`
+    ///
+    /// Each goal runs on its own blocking task, so the handler may block. When
+    /// the goal is cancelled the client sees it as cancelled whatever the handler
+    /// returns; otherwise an error from the handler aborts the goal.
+    pub async fn new<H>(node: &Node, name: &str, handler: H) -> Result<Self>
+    where
+        H: Fn(G, &GoalContext<F>) -> Result<R> + Send + Sync + 'static,
+    {
+        let updates = Arc::new(
+            node.publisher::<ActionMessage>(&feedback_topic(name))
+                .build()
+                .await?,
+        );
+        let goals = SharedGoals::default();
+        let handler = Arc::new(handler);
+        let runtime = tokio::runtime::Handle::current();
+
+        let running = goals.clone();
+        let goal_service = node
+            .service::<ActionMessage, ActionMessage>(&goal_service(name))
+            .build(move |request| {
+                let id = request.id()?;
+                let goal = decode_message::<G>(&request.payload)?;
+                let token = {
+                    let mut goals = running.lock().unwrap_or_else(|e| e.into_inner());
+                    if goals.knows(id) {
+                        // A retried request for a goal that is already running
+                        // or has finished
+                        return Ok(request);
+                    }
+                    let token = CancellationToken::new();
+                    goals.running.insert(id, token.clone());
+                    token
+                };
+
+                let context = GoalContext {
+                    id,
+                    token,
+                    updates: updates.clone(),
+                    _phantom: PhantomData,
+                };
+                let handler = handler.clone();
+                let running = running.clone();
+                runtime.spawn_blocking(move || {
+                    let outcome = handler(goal, &context);
+                    Self::finish(&context, &running, outcome);
+                });
+                Ok(ActionMessage::new(id, Update::Feedback, Vec::new()))
+            })
+            .await?;
+
+        let running = goals.clone();
+        let cancel_service = node
+            .service::<ActionMessage, ActionMessage>(&cancel_service(name))
+            .build(move |request| {
+                let id = request.id()?;
+                let goals = running.lock().unwrap_or_else(|e| e.into_inner());
+                match goals.running.get(&id) {
+                    Some(token) => {
+                        token.cancel();
+                        Ok(request)
+                    }
+                    None => Ok(ActionMessage {
+                        error: format!("goal {id} is not running"),
+                        ..request
+                    }),
+                }
+            })
+            .await?;
+
+        let (result_service, requests) = node
+            .service::<ActionMessage, ActionMessage>(&result_service(name))
+            .build_manual()
+            .await?;
+        let results = tokio::spawn(Self::answer_results(goals.clone(), requests));
+
+        Ok(Self {
+            name: name.to_string(),
+            goals,
+            _goal_service: goal_service,
+            _cancel_service: cancel_service,
+            _result_service: result_service,
+            results,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the name of the action
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of goals currently running
+    pub fn running_goals(&self) -> usize {
+        self.goals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .running
+            .len()
+    }
+
+    /// Answers each result request with the outcome of its goal, once the goal
+    /// has finished
+    async fn answer_results(
+        goals: SharedGoals,
+        mut requests: RequestStream<ActionMessage, ActionMessage>,
+    ) {
+        while let Some((request, responder)) = requests.next().await {
+            let id = match request.id() {
+                Ok(id) => id,
+                Err(e) => {
+                    responder.send(Err(e));
+                    continue;
+                }
+            };
+            let mut goals = goals.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(outcome) = goals.outcome(id) {
+                let outcome = outcome.clone();
+                drop(goals);
+                responder.send(Ok(outcome));
+            } else if goals.running.contains_key(&id) {
+                goals.waiting.entry(id).or_default().push(responder);
+            } else {
+                drop(goals);
+                responder.send(Err(Error::other(format!("goal {id} is unknown"))));
+            }
+        }
+    }
+
+    /// Publishes the outcome of a goal whose handler has returned, and answers
+    /// the result requests waiting for it
+    fn finish(context: &GoalContext<F>, goals: &SharedGoals, outcome: Result<R>) {
+        let message = match outcome {
+            _ if context.is_cancelled() => {
+                ActionMessage::new(context.id, Update::Canceled, Vec::new())
+            }
+            Ok(result) => match encode_message(&result) {
+                Ok(payload) => ActionMessage::new(context.id, Update::Succeeded, payload),
+                Err(e) => Self::aborted(context.id, &e),
+            },
+            Err(e) => Self::aborted(context.id, &e),
+        };
+        if let Err(e) = context.updates.publish(&message) {
+            tracing::warn!(
+                "Failed to publish the outcome of goal {}: {}",
+                context.id,
+                e
+            );
+        }
+
+        let waiting = {
+            let mut goals = goals.lock().unwrap_or_else(|e| e.into_inner());
+            goals.running.remove(&context.id);
+            if goals.finished.len() == FINISHED_CAPACITY {
+                goals.finished.pop_front();
+            }
+            goals.finished.push_back(message.clone());
+            goals.waiting.remove(&context.id).unwrap_or_default()
+        };
+        for responder in waiting {
+            responder.send(Ok(message.clone()));
+        }
+    }
+
+    fn aborted(id: Uuid, error: &Error) -> ActionMessage {
+        ActionMessage {
+            error: error.to_string(),
+            ..ActionMessage::new(id, Update::Aborted, Vec::new())
+        }
+    }
+}
+
+impl<G: Message, F: Message, R: Message> Drop for ActionServer<G, F, R> {
+    fn drop(&mut self) {
+        self.results.abort();
+        for token in self
+            .goals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .running
+            .values()
+        {
+            token.cancel();
+        }
+    }
+}
+
+/// Channels through which the client hands a goal's updates to its handle
+struct GoalSender<F, R> {
+    feedback: mpsc::UnboundedSender<F>,
+    result: oneshot::Sender<Result<R>>,
+    /// Stops the task asking the result service for the outcome once it is known
+    _outcome_request: Option<DropGuard>,
+}
+
+/// Goals sent by a client that have not finished, keyed by goal ID
+type PendingGoals<F, R> = Arc<Mutex<HashMap<Uuid, GoalSender<F, R>>>>;
+
+/// Client sending goals to an action
+pub struct ActionClient<G: Message, F: Message, R: Message> {
+    name: String,
+    goals: PendingGoals<F, R>,
+    goal_client: ClientHandle<ActionMessage, ActionMessage>,
+    cancel_client: ClientHandle<ActionMessage, ActionMessage>,
+    result_client: Arc<ClientHandle<ActionMessage, ActionMessage>>,
+    router: JoinHandle<()>,
+    updates: SubscriberHandle,
+    _phantom: PhantomData<fn(G)>,
+}
+
+impl<G: Message, F: Message, R: Message> ActionClient<G, F, R> {
+    /// Creates a client for the action `name`
+    ///
+    /// Updates are routed to the goals as soon as they arrive, without spinning
+    /// the node. A node can only have one client per action.
+    pub async fn new(node: &Node, name: &str) -> Result<Self> {
+        let (updates, mut receiver) = node
+            .subscriber::<ActionMessage>(&feedback_topic(name))
+            .build_channel(UPDATE_CAPACITY)
+            .await?;
+        let goal_client = node
+            .client::<ActionMessage, ActionMessage>(&goal_service(name))
+            .build()?;
+        let cancel_client = node
+            .client::<ActionMessage, ActionMessage>(&cancel_service(name))
+            .build()?;
+        let result_client = node
+            .client::<ActionMessage, ActionMessage>(&result_service(name))
+            .build()?;
+
+        let goals = PendingGoals::default();
+        let pending = goals.clone();
+        let action = name.to_string();
+        let router = tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                Self::route(&pending, &action, message);
+            }
+        });
+
+        Ok(Self {
+            name: name.to_string(),
+            goals,
+            goal_client,
+            cancel_client,
+            result_client: Arc::new(result_client),
+            router,
+            updates,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the name of the action
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Waits until a server for the action is available
+    ///
+    /// Returns [`Error::ServiceCallTimeout`] if no server appears within `timeout`.
+    pub async fn wait_for_server(&self, timeout: Duration) -> Result<()> {
+        self.goal_client.wait_for_service(timeout).await
+    }
+
+    /// Sends a goal, returning once the server has accepted it
+    ///
+    /// The goal is only sent once the client receives the feedback topic of a
+    /// server, so that its first feedback is not missed, and fails with
+    /// [`Error::Timeout`] if none is matched within 5 seconds. The returned
+    /// handle yields the goal's feedback and then its result.
+    pub async fn send_goal(&self, goal: &G) -> Result<GoalHandle<F, R>> {
+        self.wait_for_feedback().await?;
+
+        let id = Uuid::new_v4();
+        let (feedback, feedback_receiver) = mpsc::unbounded_channel();
+        let (result, result_receiver) = oneshot::channel();
+        // Register the goal first, so that no update is missed
+        self.lock_goals().insert(
+            id,
+            GoalSender {
+                feedback,
+                result,
+                _outcome_request: None,
+            },
+        );
+
+        let request = ActionMessage::new(id, Update::Feedback, encode_message(goal)?);
+        if let Err(e) = self.goal_client.call_async(&request).await {
+            self.lock_goals().remove(&id);
+            return Err(e);
+        }
+
+        let outcome_request = tokio::spawn(Self::request_outcome(
+            self.result_client.clone(),
+            self.goals.clone(),
+            self.name.clone(),
+            id,
+        ))
+        .abort_handle();
+        match self.lock_goals().get_mut(&id) {
+            Some(goal) => {
+                goal._outcome_request = Some(DropGuard::new(move || outcome_request.abort()))
+            }
+            // The outcome already arrived on the feedback topic
+            None => outcome_request.abort(),
+        }
+
+        Ok(GoalHandle {
+            id,
+            feedback: feedback_receiver,
+            result: result_receiver,
+            name: self.name.clone(),
+        })
+    }
+
+    /// Asks the server to cancel a running goal
+    ///
+    /// The goal finishes with [`Error::ServiceCallCancelled`] once its handler
+    /// has returned. Fails if the server does not know of the goal, e.g. because
+    /// it has already finished.
+    pub async fn cancel(&self, id: Uuid) -> Result<()> {
+        let request = ActionMessage::new(id, Update::Canceled, Vec::new());
+        let reply = self.cancel_client.call_async(&request).await?;
+        if reply.error.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::service_call_failed(&self.name, reply.error))
+        }
+    }
+
+    fn lock_goals(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, GoalSender<F, R>>> {
+        self.goals.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits until the feedback topic is matched with a server's publisher
+    async fn wait_for_feedback(&self) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + MATCH_TIMEOUT;
+        while self.updates.publisher_count() == 0 {
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::timeout(
+                    format!("waiting for the feedback of action '{}'", self.name),
+                    MATCH_TIMEOUT.as_millis() as u64,
+                ));
+            }
+            tokio::time::sleep(MATCH_POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+
+    /// Asks the result service for the outcome of a goal until it is known
+    ///
+    /// The server answers once the goal has finished, so calls that run out of
+    /// time while the goal is still running are made again. Any other failure
+    /// fails the goal, rather than leaving it waiting for an outcome that may
+    /// never come.
+    async fn request_outcome(
+        client: Arc<ClientHandle<ActionMessage, ActionMessage>>,
+        goals: PendingGoals<F, R>,
+        name: String,
+        id: Uuid,
+    ) {
+        let request = ActionMessage::new(id, Update::Feedback, Vec::new());
+        loop {
+            match client.call_async(&request).await {
+                Ok(outcome) => return Self::route(&goals, &name, outcome),
+                Err(e) if e.is_timeout() => continue,
+                Err(e) => {
+                    let goal = goals.lock().unwrap_or_else(|e| e.into_inner()).remove(&id);
+                    if let Some(goal) = goal {
+                        let _ = goal.result.send(Err(e));
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Hands an update to the goal it belongs to, if this client sent it
+    fn route(goals: &PendingGoals<F, R>, name: &str, message: ActionMessage) {
+        let Ok(id) = message.id() else {
+            tracing::warn!(
+                "Dropping an update with an invalid goal ID for action {}",
+                name
+            );
+            return;
+        };
+        let mut goals = goals.lock().unwrap_or_else(|e| e.into_inner());
+        let kind = Update::from_i32(message.kind);
+        if kind == Some(Update::Feedback) {
+            if let Some(goal) = goals.get(&id) {
+                match decode_message::<F>(&message.payload) {
+                    Ok(feedback) => {
+                        let _ = goal.feedback.send(feedback);
+                    }
+                    Err(e) => tracing::warn!("Failed to decode feedback for goal {}: {}", id, e),
+                }
+            }
+            return;
+        }
+
+        let Some(goal) = goals.remove(&id) else {
+            return;
+        };
+        let outcome = match kind {
+            Some(Update::Succeeded) => decode_message::<R>(&message.payload),
+            Some(Update::Canceled) => Err(Error::service_call_cancelled(name)),
+            Some(Update::Aborted) => Err(Error::service_call_failed(name, message.error)),
+            _ => Err(Error::other(format!(
+                "Unknown outcome {} for goal {}",
+                message.kind, id
+            ))),
+        };
+        let _ = goal.result.send(outcome);
+    }
+}
+
+impl<G: Message, F: Message, R: Message> Drop for ActionClient<G, F, R> {
+    fn drop(&mut self) {
+        self.router.abort();
+    }
+}
+
+/// Handle to a goal sent by an [`ActionClient`]
+pub struct GoalHandle<F, R> {
+    id: Uuid,
+    name: String,
+    feedback: mpsc::UnboundedReceiver<F>,
+    result: oneshot::Receiver<Result<R>>,
+}
+
+impl<F, R> GoalHandle<F, R> {
+    /// Returns the ID of the goal, to cancel it with [`ActionClient::cancel`]
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Waits for the next feedback of the goal
+    ///
+    /// Returns `None` once the goal has finished and all its feedback has been
+    /// received.
+    pub async fn next_feedback(&mut self) -> Option<F> {
+        self.feedback.recv().await
+    }
+
+    /// Waits for the result of the goal, discarding any unread feedback
+    ///
+    /// Returns [`Error::ServiceCallCancelled`] if the goal was cancelled and
+    /// [`Error::ServiceCallFailed`] if its handler failed.
+    pub async fn result(self) -> Result<R> {
+        self.result.await.unwrap_or_else(|_| {
+            Err(Error::service_call_failed(
+                self.name,
+                "the action client was dropped",
+            ))
+        })
+    }
+}
@@ -0,0 +1,259 @@
+//! ROS 2 compatible CDR serialization
+//!
+//! ROS 2 nodes exchange messages in the OMG CDR layout: an encapsulation header
+//! followed by the fields in declaration order, each aligned to its own size
+//! relative to the end of the header. Messages that describe their layout through
+//! [`Message::write_cdr`] and [`Message::read_cdr`], usually by deriving
+//! `ZenobufMessage` with `#[zenobuf(cdr)]`, can be published and received in this
+//! layout by selecting [`Encoding::Cdr`] on a publisher or subscriber builder.
+//!
+//! The layout follows the Rust field types, so a message matches a ROS message
+//! when its fields have the corresponding types, e.g. `f64` for `float64`.
+//!
+//! [`Encoding::Cdr`]: crate::message::Encoding::Cdr
+
+use crate::error::{Error, Result};
+use crate::message::Message;
+
+/// Encapsulation header of little-endian CDR payloads
+const CDR_LE: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+/// Encapsulation identifier of big-endian CDR payloads
+const CDR_BE: [u8; 2] = [0x00, 0x00];
+
+/// Writes values in little-endian CDR layout
+#[derive(Debug)]
+pub struct CdrWriter {
+    buf: Vec<u8>,
+}
+
+impl CdrWriter {
+    /// Creates a writer, starting with the encapsulation header
+    pub fn new() -> Self {
+        Self {
+            buf: CDR_LE.to_vec(),
+        }
+    }
+
+    /// Pads the payload so that the next value starts at a multiple of `alignment`
+    pub fn align(&mut self, alignment: usize) {
+        let offset = self.buf.len() - CDR_LE.len();
+        let padding = (alignment - offset % alignment) % alignment;
+        self.buf.resize(self.buf.len() + padding, 0);
+    }
+
+    /// Appends bytes without alignment
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Returns the encoded payload, including the encapsulation header
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+impl Default for CdrWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads values from a CDR payload of either byte order
+#[derive(Debug)]
+pub struct CdrReader<'a> {
+    buf: &'a [u8],
+    position: usize,
+    little_endian: bool,
+}
+
+impl<'a> CdrReader<'a> {
+    /// Creates a reader over a payload, checking its encapsulation header
+    pub fn new(bytes: &'a [u8]) -> Result<Self> {
+        let header = bytes
+            .get(..CDR_LE.len())
+            .ok_or_else(|| Error::cdr("payload is shorter than the encapsulation header"))?;
+        let little_endian = match &header[..2] {
+            id if id == &CDR_LE[..2] => true,
+            id if id == CDR_BE => false,
+            id => {
+                return Err(Error::cdr(format!(
+                    "unsupported encapsulation {:#04x}{:02x}",
+                    id[0], id[1]
+                )))
+            }
+        };
+        Ok(Self {
+            buf: &bytes[CDR_LE.len()..],
+            position: 0,
+            little_endian,
+        })
+    }
+
+    /// Returns true if the payload is little-endian
+    pub fn is_little_endian(&self) -> bool {
+        self.little_endian
+    }
+
+    /// Skips the padding before a value aligned to `alignment`
+    pub fn align(&mut self, alignment: usize) {
+        self.position += (alignment - self.position % alignment) % alignment;
+    }
+
+    /// Reads `len` bytes without alignment
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.buf.get(self.position..end))
+            .ok_or_else(|| {
+                Error::cdr(format!(
+                    "payload ends before reading {len} bytes at offset {}",
+                    self.position
+                ))
+            })?;
+        self.position += len;
+        Ok(bytes)
+    }
+}
+
+/// A value that can be written in and read from CDR layout
+///
+/// Implemented for primitives, strings, sequences and nested messages, which
+/// covers the field types of `#[zenobuf(cdr)]` messages.
+pub trait CdrField: Sized {
+    /// Writes the value, aligned as CDR requires
+    fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()>;
+
+    /// Reads a value written by [`CdrField::write_cdr`]
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self>;
+}
+
+macro_rules! impl_cdr_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl CdrField for $ty {
+                fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()> {
+                    writer.align(std::mem::size_of::<$ty>());
+                    writer.write_bytes(&self.to_le_bytes());
+                    Ok(())
+                }
+
+                fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self> {
+                    const SIZE: usize = std::mem::size_of::<$ty>();
+                    reader.align(SIZE);
+                    let mut bytes = [0u8; SIZE];
+                    bytes.copy_from_slice(reader.read_bytes(SIZE)?);
+                    Ok(if reader.is_little_endian() {
+                        <$ty>::from_le_bytes(bytes)
+                    } else {
+                        <$ty>::from_be_bytes(bytes)
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_cdr_primitive!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+impl CdrField for bool {
+    fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()> {
+        writer.write_bytes(&[u8::from(*self)]);
+        Ok(())
+    }
+
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self> {
+        Ok(reader.read_bytes(1)?[0] != 0)
+    }
+}
+
+impl CdrField for String {
+    /// Writes the length including the terminating NUL, then the bytes and the NUL
+    fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()> {
+        let len = u32::try_from(self.len() + 1)
+            .map_err(|_| Error::cdr(format!("string of {} bytes is too long", self.len())))?;
+        len.write_cdr(writer)?;
+        writer.write_bytes(self.as_bytes());
+        writer.write_bytes(&[0]);
+        Ok(())
+    }
+
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self> {
+        let len = u32::read_cdr(reader)? as usize;
+        let bytes = reader.read_bytes(len)?;
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        String::from_utf8(bytes.to_vec()).map_err(|e| Error::cdr(e.to_string()))
+    }
+}
+
+impl<T: CdrField> CdrField for Vec<T> {
+    /// Writes the element count, then each element
+    fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()> {
+        let len = u32::try_from(self.len())
+            .map_err(|_| Error::cdr(format!("sequence of {} elements is too long", self.len())))?;
+        len.write_cdr(writer)?;
+        self.iter()
+            .try_for_each(|element| element.write_cdr(writer))
+    }
+
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self> {
+        let len = u32::read_cdr(reader)? as usize;
+        // Every element takes at least a byte, which bounds allocations on corrupt input
+        let mut elements = Vec::with_capacity(len.min(reader.buf.len()));
+        for _ in 0..len {
+            elements.push(T::read_cdr(reader)?);
+        }
+        Ok(elements)
+    }
+}
+
+/// Nested messages
+impl<M: Message> CdrField for M {
+    fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()> {
+        Message::write_cdr(self, writer)
+    }
+
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self> {
+        <M as Message>::read_cdr(reader)
+    }
+}
+
+/// Optional nested messages, as prost generates them
+///
+/// CDR has no notion of an absent field, so `None` is written as the default message.
+impl<M: Message> CdrField for Option<M> {
+    fn write_cdr(&self, writer: &mut CdrWriter) -> Result<()> {
+        match self {
+            Some(message) => Message::write_cdr(message, writer),
+            None => Message::write_cdr(&M::default(), writer),
+        }
+    }
+
+    fn read_cdr(reader: &mut CdrReader<'_>) -> Result<Self> {
+        <M as Message>::read_cdr(reader).map(Some)
+    }
+}
+
+/// Encodes a message in CDR layout, including the encapsulation header
+pub fn encode<M: Message>(message: &M) -> Result<Vec<u8>> {
+    let mut writer = CdrWriter::new();
+    Message::write_cdr(message, &mut writer)?;
+    Ok(writer.into_bytes())
+}
+
+/// Decodes a message from a CDR payload
+pub fn decode<M: Message>(bytes: &[u8]) -> Result<M> {
+    <M as Message>::read_cdr(&mut CdrReader::new(bytes)?)
+}
+
+/// Returns the error reported for messages without a CDR layout
+pub(crate) fn unsupported<M: Message>() -> Error {
+    Error::not_supported(
+        "CDR encoding",
+        format!(
+            "{} has no CDR layout, derive it with #[zenobuf(cdr)]",
+            M::type_name()
+        ),
+    )
+}
@@ -1,11 +1,89 @@
 //! Client implementation for Zenobuf
 
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use futures::future::BoxFuture;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::message::Message;
 use crate::transport;
 
+/// Statistics for a client
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientStats {
+    /// Number of calls made, whether they succeeded or not
+    pub calls: u64,
+    /// Number of calls that returned an error
+    pub failures: u64,
+    /// Number of retries made by the transport, across all calls
+    pub retries: u64,
+    /// Median latency of the recent successful calls, or zero if there were none
+    pub p50: Duration,
+    /// 99th percentile latency of the recent successful calls, or zero if there were none
+    pub p99: Duration,
+}
+
+/// Counters updated on every call
+#[derive(Debug, Default)]
+struct ClientCounters {
+    calls: AtomicU64,
+    failures: AtomicU64,
+    /// Latencies of the most recent successful calls, oldest first
+    latencies: Mutex<VecDeque<Duration>>,
+}
+
+impl ClientCounters {
+    /// Number of successful call latencies kept for the percentiles
+    const LATENCY_WINDOW: usize = 1024;
+
+    /// Records the outcome of a call that took `latency`
+    fn record<T>(&self, result: &Result<T>, latency: Duration) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if result.is_err() {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let mut latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        if latencies.len() == Self::LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+
+    /// Returns a snapshot of the counters
+    fn snapshot(&self, retries: u64) -> ClientStats {
+        let mut latencies: Vec<Duration> = self
+            .latencies
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .copied()
+            .collect();
+        latencies.sort_unstable();
+        let percentile = |p: usize| {
+            latencies
+                .len()
+                .checked_sub(1)
+                .map_or(Duration::ZERO, |last| latencies[last * p / 100])
+        };
+
+        ClientStats {
+            calls: self.calls.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            retries,
+            p50: percentile(50),
+            p99: percentile(99),
+        }
+    }
+}
+
 /// Client for Zenobuf
 ///
 /// A Client is used to send requests to a service and receive responses.
@@ -14,12 +92,18 @@ pub struct Client<Req: Message, Res: Message> {
     name: String,
     /// Inner client implementation
     inner: Box<dyn transport::Client<Req, Res>>,
+    /// Call statistics
+    counters: ClientCounters,
 }
 
 impl<Req: Message, Res: Message> Client<Req, Res> {
     /// Creates a new Client
     pub(crate) fn new(name: String, inner: Box<dyn transport::Client<Req, Res>>) -> Self {
-        Self { name, inner }
+        Self {
+            name,
+            inner,
+            counters: ClientCounters::default(),
+        }
     }
 
     /// Returns the service name
@@ -29,11 +113,114 @@ impl<Req: Message, Res: Message> Client<Req, Res> {
 
     /// Calls the service with the given request
     pub fn call(&self, request: &Req) -> Result<Res> {
-        self.inner.call(request)
+        let started = Instant::now();
+        let result = self.inner.call(request);
+        self.counters.record(&result, started.elapsed());
+        result
     }
 
     /// Calls the service with the given request asynchronously
     pub fn call_async<'a>(&'a self, request: &'a Req) -> BoxFuture<'a, Result<Res>> {
-        self.inner.call_async(request)
+        Box::pin(async move {
+            let (result, _) = self.timed(self.inner.call_async(request)).await;
+            result
+        })
+    }
+
+    /// Calls the service asynchronously as part of an existing trace
+    ///
+    /// The service handler receives `trace_id` in its
+    /// [`RequestContext`](crate::RequestContext), e.g. to propagate the trace of
+    /// a request the caller is itself handling.
+    pub async fn call_with_trace_id(&self, request: &Req, trace_id: Uuid) -> Result<Res> {
+        let (result, _) = self.timed(self.inner.call_traced(request, trace_id)).await;
+        result
+    }
+
+    /// Calls the service asynchronously until a response arrives or `token` is
+    /// cancelled
+    ///
+    /// Cancelling drops the in-flight request, including any pending retries,
+    /// and returns [`Error::ServiceCallCancelled`](crate::Error::ServiceCallCancelled)
+    /// right away. A response arriving afterwards is discarded.
+    pub async fn call_cancellable(&self, request: &Req, token: CancellationToken) -> Result<Res> {
+        let call = async {
+            tokio::select! {
+                result = self.inner.call_async(request) => result,
+                () = token.cancelled() => Err(Error::service_call_cancelled(&self.name)),
+            }
+        };
+        let (result, _) = self.timed(call).await;
+        result
+    }
+
+    /// Calls the service asynchronously, returning the response along with the
+    /// time it took to arrive
+    ///
+    /// The duration covers sending the request, any retries, and decoding the
+    /// response.
+    pub async fn call_timed(&self, request: &Req) -> Result<(Res, Duration)> {
+        let (result, latency) = self.timed(self.inner.call_async(request)).await;
+        result.map(|response| (response, latency))
+    }
+
+    /// Returns the call statistics of this client
+    ///
+    /// The latency percentiles are computed over the most recent successful calls.
+    pub fn stats(&self) -> ClientStats {
+        self.counters.snapshot(self.inner.retries())
+    }
+
+    /// Awaits a call, recording its outcome and latency in the statistics
+    async fn timed(&self, call: impl Future<Output = Result<Res>>) -> (Result<Res>, Duration) {
+        let started = Instant::now();
+        let result = call.await;
+        let latency = started.elapsed();
+        self.counters.record(&result, latency);
+        (result, latency)
+    }
+
+    /// Calls the service with the given request, giving up once `timeout` has elapsed
+    ///
+    /// Returns [`Error::ServiceCallTimeout`](crate::Error::ServiceCallTimeout) if no
+    /// response arrives in time, while errors reported by the service or while
+    /// decoding its response remain
+    /// [`Error::ServiceCallFailed`](crate::Error::ServiceCallFailed). Retries are
+    /// bounded by the same deadline.
+    pub async fn call_with_timeout(&self, request: &Req, timeout: Duration) -> Result<Res> {
+        let call = async {
+            tokio::time::timeout(timeout, self.inner.call_with_timeout(request, timeout))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(Error::service_call_timeout(
+                        &self.name,
+                        timeout.as_millis() as u64,
+                    ))
+                })
+        };
+        let (result, _) = self.timed(call).await;
+        result
+    }
+
+    /// Sends the request to every server of the service and returns all the
+    /// responses that arrive within `timeout`
+    ///
+    /// Unlike the other calls, this one does not stop at the first response,
+    /// which suits discovery-style services answered by several nodes. The call
+    /// is not retried, and servers that answer with an error or too late are
+    /// left out, so the result is empty if no server responded.
+    pub async fn call_all(&self, request: &Req, timeout: Duration) -> Result<Vec<Res>> {
+        let started = Instant::now();
+        let result = self.inner.call_all(request, timeout).await;
+        self.counters.record(&result, started.elapsed());
+        result
+    }
+
+    /// Waits until a server for the service is available
+    ///
+    /// Returns [`Error::ServiceCallTimeout`](crate::Error::ServiceCallTimeout) if no
+    /// server appears within `timeout`.
+    pub async fn wait_for_service(&self, timeout: Duration) -> Result<()> {
+        self.inner.wait_for_service(timeout).await
     }
 }
@@ -0,0 +1,97 @@
+//! Pluggable codecs for encoding messages in a format of your choosing
+//!
+//! Messages are encoded by their own [`Message`] implementation, Protocol
+//! Buffers unless overridden, which [`ProstCodec`] stands for. A [`Codec`] set
+//! with `with_codec` on a publisher or subscriber builder replaces it for that
+//! endpoint, e.g. to send a message with bincode or MessagePack. Payloads carry
+//! the name of their codec, so a subscriber expecting another codec drops them
+//! with [`Error::CodecMismatch`] instead of misdecoding them.
+
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+use crate::message::{decode_message, encode_message, Message};
+
+/// A format for encoding and decoding messages of type `M`
+///
+/// ```rust,ignore
+/// struct JsonCodec;
+///
+/// impl<M: Serialize + DeserializeOwned> Codec<M> for JsonCodec {
+///     fn name(&self) -> &str {
+///         "json"
+///     }
+///
+///     fn encode(&self, message: &M) -> Result<Vec<u8>> {
+///         serde_json::to_vec(message).map_err(|e| Error::other(e.to_string()))
+///     }
+///
+///     fn decode(&self, bytes: &[u8]) -> Result<M> {
+///         serde_json::from_slice(bytes).map_err(|e| Error::other(e.to_string()))
+///     }
+/// }
+///
+/// let publisher = node.publisher::<Pose>("pose").with_codec(JsonCodec).build().await?;
+/// ```
+pub trait Codec<M>: Send + Sync + 'static {
+    /// Name tagging the payloads encoded with this codec, e.g. `"bincode"`
+    ///
+    /// Publishers and subscribers match codecs by name, so it must identify
+    /// the format. Names longer than 255 bytes are cut on the wire.
+    fn name(&self) -> &str;
+
+    /// Encodes a message
+    fn encode(&self, message: &M) -> Result<Vec<u8>>;
+
+    /// Decodes a message encoded by [`Codec::encode`]
+    fn decode(&self, bytes: &[u8]) -> Result<M>;
+}
+
+/// Codec shared by an endpoint and the tasks handling its payloads
+pub(crate) type SharedCodec<M> = Arc<dyn Codec<M>>;
+
+/// Codec encoding messages with their own [`Message`] implementation
+///
+/// This is the codec of publishers and subscribers built without `with_codec`.
+/// Its payloads are sent without a codec name, so peers that do not know about
+/// codecs remain compatible.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProstCodec;
+
+impl ProstCodec {
+    /// Name of the codec, assumed for payloads that carry no codec name
+    pub const NAME: &'static str = "prost";
+}
+
+impl<M: Message> Codec<M> for ProstCodec {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn encode(&self, message: &M) -> Result<Vec<u8>> {
+        encode_message(message)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<M> {
+        decode_message(bytes)
+    }
+}
+
+/// Returns the codec name to send with payloads encoded by a codec named `name`
+///
+/// Payloads of [`ProstCodec`] are left untagged.
+pub(crate) fn codec_tag(name: &str) -> Option<String> {
+    (name != ProstCodec::NAME).then(|| name.to_string())
+}
+
+/// Checks the codec name received with a payload against the expected codec
+///
+/// Untagged payloads are taken to be encoded with [`ProstCodec`].
+pub(crate) fn check_codec(expected: &str, received: Option<&str>) -> Result<()> {
+    let received = received.unwrap_or(ProstCodec::NAME);
+    if received == expected {
+        Ok(())
+    } else {
+        Err(Error::codec_mismatch(expected, received))
+    }
+}
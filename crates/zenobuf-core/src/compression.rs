@@ -0,0 +1,92 @@
+//! Payload compression for published messages
+//!
+//! Publishers can compress the encoded bytes of their messages, which is
+//! worthwhile for large payloads such as maps or point clouds on constrained
+//! links. The codec is recorded in the message header, so subscribers
+//! decompress transparently and peers that do not compress are unaffected.
+
+use std::io::Read;
+
+use crate::error::{Error, Result};
+
+/// Compression codec applied to published payloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Payloads are sent as encoded
+    #[default]
+    None,
+    /// LZ4, fast with a moderate compression ratio
+    Lz4,
+    /// Zstandard, slower with a higher compression ratio
+    Zstd,
+}
+
+impl Compression {
+    /// Default size in bytes below which payloads are sent uncompressed
+    pub const DEFAULT_THRESHOLD: usize = 1024;
+
+    /// Zstandard compression level
+    const ZSTD_LEVEL: i32 = 3;
+
+    /// Returns the tag identifying the codec in message headers
+    pub(crate) fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    /// Returns the codec identified by a header tag, if known
+    pub(crate) fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Lz4),
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Compresses a payload
+    pub(crate) fn compress(self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes.to_vec()),
+            Compression::Lz4 => Ok(lz4_flex::compress_prepend_size(bytes)),
+            Compression::Zstd => zstd::bulk::compress(bytes, Self::ZSTD_LEVEL)
+                .map_err(|e| Error::other(format!("Zstd compression failed: {e}"))),
+        }
+    }
+
+    /// Decompresses a payload compressed with this codec
+    ///
+    /// Returns `Ok(None)` if the payload decompresses to more than `max_size`
+    /// bytes, which is found out before allocating more than that.
+    pub(crate) fn decompress(self, bytes: &[u8], max_size: usize) -> Result<Option<Vec<u8>>> {
+        let decompressed = match self {
+            Compression::None => bytes.to_vec(),
+            Compression::Lz4 => {
+                // The size is prepended, so it can be checked up front
+                let (size, _) = lz4_flex::block::uncompressed_size(bytes)
+                    .map_err(|e| Error::other(format!("LZ4 decompression failed: {e}")))?;
+                if size > max_size {
+                    return Ok(None);
+                }
+                lz4_flex::decompress_size_prepended(bytes)
+                    .map_err(|e| Error::other(format!("LZ4 decompression failed: {e}")))?
+            }
+            Compression::Zstd => {
+                // Reading one byte past the limit tells an oversized payload apart
+                let mut decompressed = Vec::new();
+                zstd::stream::Decoder::new(bytes)
+                    .and_then(|decoder| {
+                        decoder
+                            .take(max_size as u64 + 1)
+                            .read_to_end(&mut decompressed)
+                    })
+                    .map_err(|e| Error::other(format!("Zstd decompression failed: {e}")))?;
+                decompressed
+            }
+        };
+        Ok((decompressed.len() <= max_size).then_some(decompressed))
+    }
+}
@@ -5,6 +5,12 @@ use thiserror::Error;
 /// Result type for Zenobuf operations
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Reason of the [`Error::Parameter`] reporting that a parameter has no value
+pub(crate) const PARAMETER_NOT_FOUND: &str = "Parameter not found";
+
+/// Reason of the [`Error::ServiceCallFailed`] reporting that no service answered
+pub(crate) const NO_SERVICE: &str = "No service answered";
+
 /// Error type for Zenobuf operations
 #[derive(Error, Debug)]
 pub enum Error {
@@ -32,6 +38,29 @@ pub enum Error {
         type_name: &'static str,
     },
 
+    /// Error during JSON message serialization or deserialization
+    #[error("JSON serialization failed for type {type_name}")]
+    JsonSerialization {
+        #[source]
+        source: serde_json::Error,
+        type_name: &'static str,
+    },
+
+    /// Error during CDR serialization or deserialization
+    #[error("CDR serialization failed: {reason}")]
+    Cdr { reason: String },
+
+    /// Error when a payload is tagged with a different encoding than expected
+    #[error("Encoding mismatch: expected {expected}, received {actual}")]
+    EncodingMismatch {
+        expected: crate::message::Encoding,
+        actual: crate::message::Encoding,
+    },
+
+    /// Error when a payload was encoded with a different codec than expected
+    #[error("Codec mismatch: expected '{expected}', received '{actual}'")]
+    CodecMismatch { expected: String, actual: String },
+
     /// Error during serialization or deserialization (legacy)
     #[error("Serialization error: {0}")]
     Serialization(String),
@@ -60,6 +89,14 @@ pub enum Error {
     #[error("Service call to '{service}' timed out after {timeout_ms}ms")]
     ServiceCallTimeout { service: String, timeout_ms: u64 },
 
+    /// Error when a service call is cancelled before a response arrives
+    #[error("Service call to '{service}' was cancelled")]
+    ServiceCallCancelled { service: String },
+
+    /// Error when waiting for a condition times out
+    #[error("Timed out {operation} after {timeout_ms}ms")]
+    Timeout { operation: String, timeout_ms: u64 },
+
     /// Error when a service call fails
     #[error("Service call to '{service}' failed: {reason}")]
     ServiceCallFailed { service: String, reason: String },
@@ -96,6 +133,18 @@ pub enum Error {
     #[error("Operation '{operation}' not implemented: {reason}")]
     NotImplemented { operation: String, reason: String },
 
+    /// Error when a message fails validation
+    #[error("Invalid {type_name} message: {reason}")]
+    InvalidMessage { type_name: String, reason: String },
+
+    /// Error when a transform between two frames cannot be looked up
+    #[error("No transform from '{parent}' to '{child}': {reason}")]
+    Transform {
+        parent: String,
+        child: String,
+        reason: String,
+    },
+
     /// Configuration error
     #[error("Configuration error: {reason}")]
     Configuration { reason: String },
@@ -173,6 +222,34 @@ impl Error {
         Error::MessageDeserialization { source, type_name }
     }
 
+    /// Create a JSON serialization error
+    pub fn json_serialization(source: serde_json::Error, type_name: &'static str) -> Self {
+        Error::JsonSerialization { source, type_name }
+    }
+
+    /// Create an encoding mismatch error
+    pub fn encoding_mismatch(
+        expected: crate::message::Encoding,
+        actual: crate::message::Encoding,
+    ) -> Self {
+        Error::EncodingMismatch { expected, actual }
+    }
+
+    /// Create a codec mismatch error, see [`crate::codec`]
+    pub fn codec_mismatch(expected: impl Into<String>, actual: impl Into<String>) -> Self {
+        Error::CodecMismatch {
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+
+    /// Create a CDR serialization error
+    pub fn cdr(reason: impl Into<String>) -> Self {
+        Error::Cdr {
+            reason: reason.into(),
+        }
+    }
+
     /// Create a node already exists error
     pub fn node_already_exists(name: impl Into<String>) -> Self {
         Error::NodeAlreadyExists { name: name.into() }
@@ -202,6 +279,21 @@ impl Error {
         }
     }
 
+    /// Create a service call cancelled error
+    pub fn service_call_cancelled(service: impl Into<String>) -> Self {
+        Error::ServiceCallCancelled {
+            service: service.into(),
+        }
+    }
+
+    /// Create a timeout error, e.g. for `operation` "waiting for a publisher on 'scan'"
+    pub fn timeout(operation: impl Into<String>, timeout_ms: u64) -> Self {
+        Error::Timeout {
+            operation: operation.into(),
+            timeout_ms,
+        }
+    }
+
     /// Create a service call failed error
     pub fn service_call_failed(service: impl Into<String>, reason: impl Into<String>) -> Self {
         Error::ServiceCallFailed {
@@ -258,6 +350,35 @@ impl Error {
         }
     }
 
+    /// Create a not supported error
+    pub fn not_supported(operation: impl Into<String>, reason: impl Into<String>) -> Self {
+        Error::NotSupported {
+            operation: operation.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Create a message validation error
+    pub fn invalid_message(type_name: impl Into<String>, reason: impl Into<String>) -> Self {
+        Error::InvalidMessage {
+            type_name: type_name.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Create a transform lookup error
+    pub fn transform(
+        parent: impl Into<String>,
+        child: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Error::Transform {
+            parent: parent.into(),
+            child: child.into(),
+            reason: reason.into(),
+        }
+    }
+
     /// Create a configuration error
     pub fn configuration(reason: impl Into<String>) -> Self {
         Error::Configuration {
@@ -279,3 +400,41 @@ impl Error {
         }
     }
 }
+
+// Helper functions for classifying errors
+impl Error {
+    /// Returns true if an operation or service call ran out of time
+    pub fn is_timeout(&self) -> bool {
+        matches!(
+            self,
+            Error::Timeout { .. } | Error::ServiceCallTimeout { .. }
+        )
+    }
+
+    /// Returns true if the Zenoh transport or the network failed
+    pub fn is_transport(&self) -> bool {
+        matches!(self, Error::Transport { .. } | Error::Network { .. })
+    }
+
+    /// Returns true if a parameter has no value or no service answered a call
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::Parameter { reason, .. } => reason == PARAMETER_NOT_FOUND,
+            Error::ServiceCallFailed { reason, .. } => reason.starts_with(NO_SERVICE),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the same operation may succeed when tried again
+    ///
+    /// Timeouts, transport failures and service calls no service answered are
+    /// retryable. Errors reported by the service's handler are not, since
+    /// calling again would run the handler again, nor are errors in the
+    /// messages, names or configuration, or cancelled calls.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::ServiceCallFailed { reason, .. } => reason.starts_with(NO_SERVICE),
+            _ => self.is_timeout() || self.is_transport(),
+        }
+    }
+}
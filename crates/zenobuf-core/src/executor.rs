@@ -1,22 +1,33 @@
 //! Callback executor for processing subscriber callbacks
 //!
 //! This module provides a simple callback queue that allows subscribers to enqueue
-//! callbacks for later processing by the node's spin methods.
+//! callbacks for later processing by the node's spin methods, and a worker pool
+//! that runs them in parallel while keeping the callbacks of each subscriber in
+//! order.
 
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 /// A callback that can be executed by the executor
 pub type Callback = Box<dyn FnOnce() + Send>;
 
+/// Returns a key unique within the process, for callbacks that must run in order
+pub fn ordering_key() -> u64 {
+    static NEXT_KEY: AtomicU64 = AtomicU64::new(0);
+    NEXT_KEY.fetch_add(1, Ordering::Relaxed)
+}
+
 /// A simple executor that queues callbacks for later processing
 ///
 /// The executor provides a thread-safe way to enqueue callbacks from subscriber
 /// threads and process them in the node's spin loop.
 #[derive(Clone)]
 pub struct CallbackExecutor {
-    callbacks: Arc<Mutex<VecDeque<Callback>>>,
+    /// Pending callbacks with their ordering keys
+    callbacks: Arc<Mutex<VecDeque<(u64, Callback)>>>,
     shutdown: Arc<AtomicBool>,
     notify: Arc<tokio::sync::Notify>,
 }
@@ -41,8 +52,14 @@ impl CallbackExecutor {
     ///
     /// This method is thread-safe and can be called from subscriber callbacks.
     pub fn enqueue(&self, callback: Callback) {
+        self.enqueue_ordered(ordering_key(), callback);
+    }
+
+    /// Enqueues a callback that runs after the callbacks enqueued before it with
+    /// the same key, even when callbacks are processed by a [`WorkerPool`]
+    pub fn enqueue_ordered(&self, key: u64, callback: Callback) {
         if !self.is_shutdown() {
-            self.callbacks.lock().unwrap().push_back(callback);
+            self.callbacks.lock().unwrap().push_back((key, callback));
             self.notify.notify_one();
         }
     }
@@ -51,19 +68,42 @@ impl CallbackExecutor {
     ///
     /// Returns the number of callbacks that were processed.
     pub fn process_pending(&self) -> usize {
-        let callbacks: Vec<Callback> = {
-            let mut queue = self.callbacks.lock().unwrap();
-            queue.drain(..).collect()
-        };
-
+        let callbacks = self.take_pending();
         let count = callbacks.len();
-        for callback in callbacks {
+        for (_, callback) in callbacks {
+            callback();
+        }
+
+        count
+    }
+
+    /// Processes pending callbacks one at a time until the queue is empty or
+    /// `budget` has elapsed
+    ///
+    /// A running callback is not interrupted, so the last one may end after the
+    /// budget. Returns the number of callbacks that were processed.
+    pub fn process_for(&self, budget: Duration) -> usize {
+        let start = Instant::now();
+        let mut count = 0;
+        while start.elapsed() < budget {
+            // Release the lock before running, as callbacks may enqueue more
+            let next = self.callbacks.lock().unwrap().pop_front();
+            let Some((_, callback)) = next else {
+                break;
+            };
             callback();
+            count += 1;
         }
 
         count
     }
 
+    /// Removes and returns all pending callbacks with their ordering keys
+    pub fn take_pending(&self) -> Vec<(u64, Callback)> {
+        let mut queue = self.callbacks.lock().unwrap();
+        queue.drain(..).collect()
+    }
+
     /// Returns the number of pending callbacks
     pub fn pending_count(&self) -> usize {
         self.callbacks.lock().unwrap().len()
@@ -89,6 +129,114 @@ impl CallbackExecutor {
     }
 }
 
+/// Callbacks waiting in a [`WorkerPool`], grouped by ordering key
+#[derive(Default)]
+struct PoolQueue {
+    /// Callbacks not yet started, by key
+    pending: HashMap<u64, VecDeque<Callback>>,
+    /// Keys with a callback currently running
+    running: HashSet<u64>,
+    /// Keys with pending callbacks and none running, in the order they became ready
+    ready: VecDeque<u64>,
+    /// Whether the workers should exit once no callbacks are left
+    closing: bool,
+}
+
+/// A fixed set of threads running callbacks in parallel
+///
+/// Callbacks with the same ordering key run one at a time, in the order they
+/// were submitted, while callbacks with different keys may run concurrently on
+/// different threads.
+pub struct WorkerPool {
+    queue: Arc<(Mutex<PoolQueue>, Condvar)>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Starts a pool of `num_threads` worker threads, at least one
+    pub fn new(num_threads: usize) -> Self {
+        let queue = Arc::new((Mutex::new(PoolQueue::default()), Condvar::new()));
+        let workers = (0..num_threads.max(1))
+            .map(|i| {
+                let queue = queue.clone();
+                std::thread::Builder::new()
+                    .name(format!("zenobuf-worker-{i}"))
+                    .spawn(move || Self::work(&queue))
+                    .expect("failed to spawn executor worker thread")
+            })
+            .collect();
+        Self { queue, workers }
+    }
+
+    /// Submits a callback to run after those submitted before it with the same key
+    pub fn submit(&self, key: u64, callback: Callback) {
+        let (lock, ready) = &*self.queue;
+        let mut queue = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let callbacks = queue.pending.entry(key).or_default();
+        callbacks.push_back(callback);
+        // Otherwise the key is already ready, or requeued once its callback returns
+        if callbacks.len() == 1 && !queue.running.contains(&key) {
+            queue.ready.push_back(key);
+            ready.notify_one();
+        }
+    }
+
+    /// Runs the callbacks submitted so far, then stops the worker threads
+    pub fn join(mut self) {
+        self.close();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+
+    /// Lets the workers exit once the submitted callbacks have run
+    fn close(&self) {
+        let (lock, ready) = &*self.queue;
+        lock.lock().unwrap_or_else(|e| e.into_inner()).closing = true;
+        ready.notify_all();
+    }
+
+    /// Runs callbacks of ready keys until the pool is closing and none are left
+    ///
+    /// A worker finishing a callback requeues its key if more are pending, so
+    /// the remaining callbacks are run before the last worker exits.
+    fn work(queue: &(Mutex<PoolQueue>, Condvar)) {
+        let (lock, ready) = queue;
+        let mut guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let Some(key) = guard.ready.pop_front() else {
+                if guard.closing {
+                    return;
+                }
+                guard = ready.wait(guard).unwrap_or_else(|e| e.into_inner());
+                continue;
+            };
+            let Some(callback) = guard.pending.get_mut(&key).and_then(VecDeque::pop_front) else {
+                continue;
+            };
+            guard.running.insert(key);
+            drop(guard);
+
+            callback();
+
+            guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+            guard.running.remove(&key);
+            if guard.pending.get(&key).is_some_and(|c| !c.is_empty()) {
+                guard.ready.push_back(key);
+                ready.notify_one();
+            } else {
+                guard.pending.remove(&key);
+            }
+        }
+    }
+}
+
+impl Drop for WorkerPool {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,7 +146,11 @@
 //! cargo run
 //! ```
 
+pub mod action;
+pub mod cdr;
 pub mod client;
+pub mod codec;
+pub mod compression;
 pub mod error;
 pub mod executor;
 pub mod message;
@@ -154,19 +158,36 @@ pub mod node;
 pub mod parameter;
 pub mod publisher;
 pub mod qos;
+pub mod reflect;
+pub mod retry;
 pub mod service;
 pub mod subscriber;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod tf;
 pub mod time;
 pub mod transport;
+pub mod util;
 
 // Re-export key types
-pub use client::Client;
+pub use client::{Client, ClientStats};
+pub use codec::{Codec, ProstCodec};
+pub use compression::Compression;
 pub use error::{Error, Result};
-pub use message::Message;
-pub use node::{ClientHandle, DropGuard, Node, PublisherHandle, ServiceHandle, SubscriberHandle};
-pub use parameter::Parameter;
-pub use publisher::Publisher;
+pub use message::{Encoding, FieldInfo, Json, JsonMessage, Message, RawBytes};
+pub use node::{
+    BridgeHandle, BufferedSubscriberHandle, ClientHandle, DropGuard, Heartbeat, Node,
+    NodeDiagnostics, ProjectedPublisherHandle, PublisherHandle, ResourceCounts, ServiceHandle,
+    ServiceInfo, SubscriberHandle, TimerHandle, TopicInfo,
+};
+pub use parameter::{Parameter, ParameterClient, ParameterDescriptor};
+pub use publisher::{Publisher, PublisherStats};
 pub use qos::{QosPreset, QosProfile};
-pub use service::Service;
-pub use subscriber::Subscriber;
-pub use transport::{Transport, ZenohTransport};
+pub use retry::RetryPolicy;
+pub use service::{ErrorReply, RequestContext, RequestStream, Responder, Service};
+pub use subscriber::{PayloadView, Subscriber, SubscriberHandler, SubscriberStats};
+pub use tokio_util::sync::CancellationToken;
+pub use transport::{
+    GraphEvent, MockTransport, PeerInfo, Transport, TransportEvent, ZenohTransport,
+};
+pub use uuid::Uuid;
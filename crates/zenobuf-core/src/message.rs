@@ -1,9 +1,66 @@
 //! Message trait and utilities for working with Protocol Buffer messages
 
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use prost::bytes::{Buf, BufMut};
 use prost::Message as ProstMessage;
+use serde::{de::DeserializeOwned, Serialize};
 
+use crate::cdr::{self, CdrReader, CdrWriter};
 use crate::error::{Error, Result};
 
+/// Largest payload in bytes that subscribers and services decode unless set
+/// otherwise with `with_max_message_size`
+///
+/// Larger payloads are dropped before decoding, and compressed payloads are not
+/// decompressed beyond it, so that a faulty or malicious peer cannot make the
+/// node allocate without bound.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Wire encoding of a message payload
+///
+/// The transport tags every payload with the encoding it was produced with, so a
+/// peer expecting a different encoding fails with [`Error::EncodingMismatch`]
+/// instead of silently misdecoding the bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// Protocol Buffers (the default)
+    #[default]
+    Protobuf,
+    /// JSON via serde
+    Json,
+    /// ROS 2 compatible CDR, see [`crate::cdr`]
+    Cdr,
+}
+
+impl Encoding {
+    /// Returns the MIME type used to tag payloads with this encoding
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Encoding::Protobuf => "application/protobuf",
+            Encoding::Json => "application/json",
+            Encoding::Cdr => "application/cdr",
+        }
+    }
+
+    /// Returns the encoding for the given MIME type, if it is known
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "application/protobuf" => Some(Encoding::Protobuf),
+            "application/json" => Some(Encoding::Json),
+            "application/cdr" => Some(Encoding::Cdr),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.mime_type())
+    }
+}
+
 /// Trait for Zenobuf messages
 ///
 /// This trait is implemented for all Protocol Buffer messages that can be used
@@ -13,24 +70,375 @@ use crate::error::{Error, Result};
 /// Users can implement this trait for their own Protocol Buffer messages, or
 /// use the `ZenobufMessage` derive macro from the `zenobuf-macros` crate.
 pub trait Message: ProstMessage + Default + Clone + Send + Sync + 'static {
+    /// Wire encoding produced by this message's `prost::Message` implementation
+    const ENCODING: Encoding = Encoding::Protobuf;
+
     /// Returns the type name of the message
     ///
     /// This is used for type checking and debugging.
     fn type_name() -> &'static str;
 
+    /// Returns a stable hash identifying the message schema
+    ///
+    /// Publishers send this hash with every message and subscribers drop messages
+    /// whose hash does not match their own type. The default hashes the unqualified
+    /// type name, so the same message compiled into different crates stays
+    /// compatible. The `ZenobufMessage` derive hashes the field layout as well
+    /// (see [`type_hash_of_schema`]). Override it to version a schema explicitly.
+    fn type_hash() -> u64 {
+        type_hash_of(Self::type_name())
+    }
+
+    /// Encodes the message to a byte vector
+    ///
+    /// The default encodes with `prost::Message::encode`. Formats whose
+    /// serialization can fail override it to report the error, since
+    /// `prost::Message::encode_raw` cannot.
+    fn encode_to_bytes(&self) -> Result<Vec<u8>> {
+        <ProtobufSerializer as Serializer<Self>>::serialize(self)
+    }
+
+    /// Appends the encoded message to `buf`
+    ///
+    /// Publishers encode into a reused buffer with this method. Types overriding
+    /// [`Message::encode_to_bytes`] must override it to produce the same bytes.
+    fn encode_into(&self, buf: &mut Vec<u8>) -> Result<()> {
+        <ProtobufSerializer as Serializer<Self>>::serialize_into(self, buf)
+    }
+
     /// Decodes a message from a byte slice
     ///
     /// This is a convenience method that calls `prost::Message::decode`.
     fn decode_from_slice(bytes: &[u8]) -> Result<Self> {
         Self::decode(bytes).map_err(Error::from)
     }
+
+    /// Checks the invariants of the message
+    ///
+    /// Publishers reject messages that fail validation, and subscribers drop
+    /// received messages that fail it instead of delivering them. Return
+    /// [`Error::InvalidMessage`] to describe the violation. The default accepts
+    /// every message.
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Writes the message in CDR layout, for [`Encoding::Cdr`]
+    ///
+    /// Deriving `ZenobufMessage` with `#[zenobuf(cdr)]` implements this and
+    /// [`Message::read_cdr`] from the struct fields. The default reports that
+    /// the message has no CDR layout.
+    fn write_cdr(&self, _writer: &mut CdrWriter) -> Result<()> {
+        Err(cdr::unsupported::<Self>())
+    }
+
+    /// Reads a message written by [`Message::write_cdr`]
+    fn read_cdr(_reader: &mut CdrReader<'_>) -> Result<Self> {
+        Err(cdr::unsupported::<Self>())
+    }
+
+    /// Describes the fields of the message
+    ///
+    /// Used by introspection tools to render messages field by field. The
+    /// `ZenobufMessage` derive generates the descriptors from the struct
+    /// definition; the default describes no fields.
+    fn fields() -> &'static [FieldInfo] {
+        &[]
+    }
+}
+
+/// Descriptor of a message field, as returned by [`Message::fields`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// Field name
+    pub name: &'static str,
+    /// Protocol Buffer type, e.g. `int32`, `string`, `message`, `enum`,
+    /// `oneof` or `map<string, int32>`
+    pub proto_type: &'static str,
+    /// Whether the field is repeated
+    pub repeated: bool,
+    /// Whether the field is declared `optional`
+    pub optional: bool,
+    /// Names of the variants of a `oneof` field, empty for other fields
+    pub variants: &'static [&'static str],
+}
+
+/// Lists the variants of a `oneof` or enum type
+///
+/// The `ZenobufMessage` derive implements this for enums, which lets it list
+/// the variants of `oneof` fields in [`Message::fields`].
+pub trait Variants {
+    /// Variant names, in declaration order
+    const VARIANTS: &'static [&'static str];
+}
+
+/// Trait for serde types that are sent as JSON instead of Protocol Buffers
+///
+/// Implement this marker trait and wrap values in [`Json`] to use them with the
+/// regular publisher, subscriber, service, and client builders:
+///
+/// ```rust,ignore
+/// #[derive(Clone, Default, Serialize, Deserialize)]
+/// struct Status { ok: bool }
+/// impl JsonMessage for Status {}
+///
+/// let publisher = node.publisher::<Json<Status>>("status").build().await?;
+/// publisher.publish(&Json(Status { ok: true }))?;
+/// ```
+pub trait JsonMessage:
+    Serialize + DeserializeOwned + Default + Clone + Send + Sync + 'static
+{
+    /// Returns the type name of the message
+    fn type_name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Checks the invariants of the message, see [`Message::validate`]
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A serialization format for messages of type `M`
+pub trait Serializer<M> {
+    /// Encoding tag carried alongside payloads produced by this serializer
+    const ENCODING: Encoding;
+
+    /// Serializes a message to bytes
+    fn serialize(message: &M) -> Result<Vec<u8>>;
+
+    /// Serializes a message, appending its bytes to `buf`
+    ///
+    /// The default appends the result of [`Serializer::serialize`]; serializers
+    /// that can write in place override it to skip the intermediate vector.
+    fn serialize_into(message: &M, buf: &mut Vec<u8>) -> Result<()> {
+        buf.extend_from_slice(&Self::serialize(message)?);
+        Ok(())
+    }
+
+    /// Deserializes a message from bytes
+    fn deserialize(bytes: &[u8]) -> Result<M>;
+}
+
+/// Protocol Buffers serializer for [`Message`] types
+pub struct ProtobufSerializer;
+
+impl<M: Message> Serializer<M> for ProtobufSerializer {
+    const ENCODING: Encoding = Encoding::Protobuf;
+
+    fn serialize(message: &M) -> Result<Vec<u8>> {
+        let mut buf = Vec::with_capacity(message.encoded_len());
+        Self::serialize_into(message, &mut buf)?;
+        Ok(buf)
+    }
+
+    fn serialize_into(message: &M, buf: &mut Vec<u8>) -> Result<()> {
+        message
+            .encode(buf)
+            .map_err(|e| Error::message_serialization(e, M::type_name()))
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<M> {
+        M::decode(bytes).map_err(|e| Error::message_deserialization(e, M::type_name()))
+    }
+}
+
+/// JSON serializer for [`JsonMessage`] types
+pub struct JsonSerializer;
+
+impl<M: JsonMessage> Serializer<M> for JsonSerializer {
+    const ENCODING: Encoding = Encoding::Json;
+
+    fn serialize(message: &M) -> Result<Vec<u8>> {
+        serde_json::to_vec(message).map_err(|e| Error::json_serialization(e, M::type_name()))
+    }
+
+    fn serialize_into(message: &M, buf: &mut Vec<u8>) -> Result<()> {
+        serde_json::to_writer(buf, message)
+            .map_err(|e| Error::json_serialization(e, M::type_name()))
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<M> {
+        serde_json::from_slice(bytes).map_err(|e| Error::json_serialization(e, M::type_name()))
+    }
+}
+
+/// A [`JsonMessage`] adapted to the [`Message`] trait
+///
+/// The wrapped value is encoded as JSON and tagged with [`Encoding::Json`] on the wire.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Json<T>(pub T);
+
+impl<T> Json<T> {
+    /// Unwraps the inner value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// Encodes the value as JSON through `prost::Message`
+///
+/// Zenobuf encodes JSON messages with [`Message::encode_to_bytes`], which reports
+/// serialization errors. `prost::Message::encode_raw` cannot, so it panics on a
+/// value serde fails to serialize rather than produce an empty payload.
+impl<T: JsonMessage> ProstMessage for Json<T> {
+    fn encode_raw(&self, buf: &mut impl BufMut) {
+        match <JsonSerializer as Serializer<T>>::serialize(&self.0) {
+            Ok(bytes) => buf.put_slice(&bytes),
+            Err(e) => {
+                panic!("{e}; encode JSON messages with Message::encode_to_bytes to handle it")
+            }
+        }
+    }
+
+    fn merge(&mut self, mut buf: impl Buf) -> std::result::Result<(), prost::DecodeError> {
+        let bytes = buf.copy_to_bytes(buf.remaining());
+        #[allow(deprecated)]
+        let value = <JsonSerializer as Serializer<T>>::deserialize(&bytes)
+            .map_err(|e| prost::DecodeError::new(e.to_string()))?;
+        self.0 = value;
+        Ok(())
+    }
+
+    fn merge_field(
+        &mut self,
+        _tag: u32,
+        _wire_type: prost::encoding::WireType,
+        _buf: &mut impl Buf,
+        _ctx: prost::encoding::DecodeContext,
+    ) -> std::result::Result<(), prost::DecodeError> {
+        // JSON payloads are decoded as a whole in `merge`
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, &self.0).map_or(0, |()| counter.0)
+    }
+
+    fn clear(&mut self) {
+        self.0 = T::default();
+    }
+}
+
+impl<T: JsonMessage> Message for Json<T> {
+    const ENCODING: Encoding = Encoding::Json;
+
+    fn type_name() -> &'static str {
+        T::type_name()
+    }
+
+    fn encode_to_bytes(&self) -> Result<Vec<u8>> {
+        <JsonSerializer as Serializer<T>>::serialize(&self.0)
+    }
+
+    fn encode_into(&self, buf: &mut Vec<u8>) -> Result<()> {
+        <JsonSerializer as Serializer<T>>::serialize_into(&self.0, buf)
+    }
+
+    fn decode_from_slice(bytes: &[u8]) -> Result<Self> {
+        <JsonSerializer as Serializer<T>>::deserialize(bytes).map(Json)
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.0.validate()
+    }
+}
+
+/// Writer counting the bytes written to it without keeping them
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An opaque byte payload usable with the typed publisher and subscriber builders
+///
+/// On the wire this is a Protocol Buffer message with a single `bytes` field,
+/// equivalent to `message RawBytes { bytes data = 1; }`:
+///
+/// ```rust,ignore
+/// let publisher = node.publisher::<RawBytes>("blob").build().await?;
+/// publisher.publish(&RawBytes::new(vec![0xde, 0xad, 0xbe, 0xef]))?;
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, prost::Message)]
+pub struct RawBytes {
+    /// The payload
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+}
+
+impl RawBytes {
+    /// Wraps a payload
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into() }
+    }
+
+    /// Unwraps the payload
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl Deref for RawBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl From<Vec<u8>> for RawBytes {
+    fn from(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+}
+
+impl From<&[u8]> for RawBytes {
+    fn from(data: &[u8]) -> Self {
+        Self::new(data)
+    }
+}
+
+impl From<RawBytes> for Vec<u8> {
+    fn from(raw: RawBytes) -> Self {
+        raw.data
+    }
+}
+
+impl Message for RawBytes {
+    fn type_name() -> &'static str {
+        "zenobuf.RawBytes"
+    }
 }
 
 /// Helper function to encode a message to a byte vector
+///
+/// Returns [`Error::MessageSerialization`] or [`Error::JsonSerialization`] if the
+/// message cannot be encoded.
 pub fn encode_message<M: Message>(message: &M) -> Result<Vec<u8>> {
-    let mut buf = Vec::with_capacity(message.encoded_len());
-    message.encode(&mut buf).map_err(Error::from)?;
-    Ok(buf)
+    message.encode_to_bytes()
 }
 
 /// Helper function to decode a message from a byte slice
@@ -38,6 +446,126 @@ pub fn decode_message<M: Message>(bytes: &[u8]) -> Result<M> {
     M::decode_from_slice(bytes)
 }
 
+/// Helper function to encode a message in the given encoding
+///
+/// Messages can be encoded in their own [`Message::ENCODING`] or in
+/// [`Encoding::Cdr`] if they have a CDR layout.
+pub fn encode_message_as<M: Message>(message: &M, encoding: Encoding) -> Result<Vec<u8>> {
+    match encoding {
+        encoding if encoding == M::ENCODING => message.encode_to_bytes(),
+        Encoding::Cdr => cdr::encode(message),
+        encoding => Err(Error::encoding_mismatch(M::ENCODING, encoding)),
+    }
+}
+
+/// Panics in debug builds if a default `M` does not survive being encoded and
+/// decoded in its own encoding
+///
+/// Nodes run this when declaring an endpoint, so that a hand-written
+/// `prost::Message` whose encoding and decoding disagree, e.g. on the size of a
+/// field, fails as soon as it is used rather than when the first message arrives.
+pub(crate) fn debug_check_framing<M: Message>() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let check = || -> Result<()> {
+        let bytes = M::default().encode_to_bytes()?;
+        let reencoded = M::decode_from_slice(&bytes)?.encode_to_bytes()?;
+        if reencoded != bytes {
+            return Err(Error::other(format!(
+                "a default instance encodes to {bytes:?} but re-encodes to {reencoded:?} once decoded"
+            )));
+        }
+        Ok(())
+    };
+    if let Err(e) = check() {
+        panic!(
+            "{} does not round-trip its own {:?} encoding, check that its encode and decode agree: {}",
+            M::type_name(),
+            M::ENCODING,
+            e
+        );
+    }
+}
+
+/// Encodes a message in the given encoding, appending it to `buf`
+pub(crate) fn encode_message_into<M: Message>(
+    message: &M,
+    encoding: Encoding,
+    buf: &mut Vec<u8>,
+) -> Result<()> {
+    match encoding {
+        encoding if encoding == M::ENCODING => message.encode_into(buf),
+        Encoding::Cdr => {
+            buf.extend_from_slice(&cdr::encode(message)?);
+            Ok(())
+        }
+        encoding => Err(Error::encoding_mismatch(M::ENCODING, encoding)),
+    }
+}
+
+/// Helper function to decode a message from a payload in the given encoding
+pub fn decode_message_as<M: Message>(bytes: &[u8], encoding: Encoding) -> Result<M> {
+    match encoding {
+        encoding if encoding == M::ENCODING => M::decode_from_slice(bytes),
+        Encoding::Cdr => cdr::decode(bytes),
+        encoding => Err(Error::encoding_mismatch(M::ENCODING, encoding)),
+    }
+}
+
+/// Helper function to encode a message with an explicit serializer
+pub fn encode_message_with<S: Serializer<M>, M>(message: &M) -> Result<Vec<u8>> {
+    S::serialize(message)
+}
+
+/// Helper function to decode a message with an explicit serializer
+///
+/// `encoding` is the tag received alongside the payload. Untagged payloads
+/// (`None`) are accepted for compatibility with peers that do not tag them.
+pub fn decode_message_with<S: Serializer<M>, M>(
+    bytes: &[u8],
+    encoding: Option<Encoding>,
+) -> Result<M> {
+    check_encoding(S::ENCODING, encoding)?;
+    S::deserialize(bytes)
+}
+
+/// Checks a received encoding tag against the expected encoding
+pub fn check_encoding(expected: Encoding, received: Option<Encoding>) -> Result<()> {
+    match received {
+        Some(actual) if actual != expected => Err(Error::encoding_mismatch(expected, actual)),
+        _ => Ok(()),
+    }
+}
+
+/// Computes the default type hash for a type name
+///
+/// Module paths (`a::b::Point`) are stripped before hashing, so only the type name
+/// itself (including any generic arguments) contributes to the hash.
+pub fn type_hash_of(type_name: &str) -> u64 {
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    let short = base.rsplit("::").next().unwrap_or(base);
+    let generics = &type_name[base.len()..];
+    fnv1a(short.bytes().chain(generics.bytes()))
+}
+
+/// Computes the type hash of a message layout
+///
+/// The `ZenobufMessage` derive describes each struct as its unqualified name
+/// followed by the tag, name and type of every field, e.g.
+/// `Point{1:x:double,2:y:double}`, and hashes that description, so adding,
+/// renumbering or retyping a field changes the hash.
+pub fn type_hash_of_schema(schema: &str) -> u64 {
+    fnv1a(schema.bytes())
+}
+
+/// 64-bit FNV-1a
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// Helper function to get the type name of a message
 pub fn message_type_name<M: Message>() -> &'static str {
     M::type_name()
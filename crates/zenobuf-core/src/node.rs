@@ -1,20 +1,573 @@
 //! Node abstraction for Zenobuf
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use crate::client::Client;
-use crate::error::{Error, Result};
-use crate::executor::CallbackExecutor;
-use crate::message::Message;
-use crate::parameter::Parameter;
-use crate::publisher::Publisher;
-use crate::qos::{QosPreset, QosProfile};
-use crate::service::Service;
-use crate::subscriber::Subscriber;
-use crate::transport::ZenohTransport;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{Instrument, Level};
+use uuid::Uuid;
+
+use crate::client::{Client, ClientStats};
+use crate::codec::{Codec, SharedCodec};
+use crate::compression::Compression;
+use crate::error::{Error, Result, PARAMETER_NOT_FOUND};
+use crate::executor::{ordering_key, CallbackExecutor, WorkerPool};
+use crate::message::{
+    debug_check_framing, encode_message_as, Encoding, Message, DEFAULT_MAX_MESSAGE_SIZE,
+};
+use crate::parameter::{Parameter, ParameterClient, ParameterDescriptor};
+use crate::publisher::{Publisher, PublisherStats, RateLimit};
+use crate::qos::{Compatibility, History, QosPreset, QosProfile};
+use crate::retry::RetryPolicy;
+use crate::service::{ErrorReply, RequestContext, RequestDispatch, RequestStream, Service};
+use crate::subscriber::{
+    DeadlineCallback, DropCounter, PayloadView, Subscriber, SubscriberHandler, SubscriberStats,
+};
+use crate::time::Time;
+use crate::transport::{
+    DecodedSink, GraphEvents, PayloadSink, PeerInfo, SampleSink, SinkOptions, Subscriber as _,
+    Transport, TransportEvents, ZenohTransport,
+};
+use crate::util::{validate_name, validate_pattern};
+
+/// An entity registered on a node, along with its message type names
+struct Registration {
+    /// Keeps the entity alive while it is registered
+    entity: Box<dyn std::any::Any + Send + Sync>,
+    /// Discovery metadata for the entity, withdrawn when it is unregistered
+    _advertisements: Vec<Advertisement>,
+    /// Message type name, or the request type name for services and clients
+    type_name: &'static str,
+    /// Response type name for services and clients
+    response_type_name: Option<&'static str>,
+    /// Adds the entity's counters to the node diagnostics
+    probe: Option<StatsProbe>,
+    /// Checks the QoS profile of the publishers joining a subscriber's topic
+    _qos_watch: Option<zenoh::pubsub::Subscriber<()>>,
+}
+
+/// Adds the counters of a registered entity to [`NodeDiagnostics`]
+type StatsProbe = Box<dyn Fn(&mut NodeDiagnostics) + Send + Sync>;
+
+impl Registration {
+    /// Registers a publisher or subscriber of `M`
+    fn topic<M: Message>(entity: Box<dyn std::any::Any + Send + Sync>) -> Self {
+        Self {
+            entity,
+            _advertisements: Vec::new(),
+            type_name: M::type_name(),
+            response_type_name: None,
+            probe: None,
+            _qos_watch: None,
+        }
+    }
+
+    /// Registers a service or client of `Req` and `Res`
+    fn service<Req: Message, Res: Message>(entity: Box<dyn std::any::Any + Send + Sync>) -> Self {
+        Self {
+            entity,
+            _advertisements: Vec::new(),
+            type_name: Req::type_name(),
+            response_type_name: Some(Res::type_name()),
+            probe: None,
+            _qos_watch: None,
+        }
+    }
+
+    /// Attaches discovery metadata to the registration
+    fn advertised(mut self, advertisements: impl IntoIterator<Item = Advertisement>) -> Self {
+        self._advertisements.extend(advertisements);
+        self
+    }
+
+    /// Keeps checking the QoS profile of new publishers while registered
+    fn qos_watched(mut self, watch: Option<zenoh::pubsub::Subscriber<()>>) -> Self {
+        self._qos_watch = watch;
+        self
+    }
+
+    /// Includes the entity's counters in the node diagnostics
+    fn probed<F>(mut self, probe: F) -> Self
+    where
+        F: Fn(&mut NodeDiagnostics) + Send + Sync + 'static,
+    {
+        self.probe = Some(Box::new(probe));
+        self
+    }
+}
+
+/// Queryable answering discovery queries with metadata about a node, topic or service
+///
+/// The metadata is withdrawn when the advertisement is dropped.
+struct Advertisement {
+    _queryable: zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>,
+}
+
+impl Advertisement {
+    /// Declares a queryable on `key` that replies with `info` to the queries in `answers`
+    async fn declare(
+        transport: &ZenohTransport,
+        key: String,
+        info: serde_json::Value,
+        answers: Answers,
+    ) -> Result<Self> {
+        let info = info.to_string();
+        Self::declare_with(transport, key, move || info.clone(), answers).await
+    }
+
+    /// Declares a queryable on `key` that replies with the current output of `info`
+    async fn declare_with<F>(
+        transport: &ZenohTransport,
+        key: String,
+        info: F,
+        answers: Answers,
+    ) -> Result<Self>
+    where
+        F: Fn() -> String + Send + 'static,
+    {
+        let queryable = transport
+            .session()
+            .declare_queryable(key.clone())
+            .await
+            .map_err(Error::from)?;
+
+        // The task ends once the queryable is dropped and its channel closes
+        let queries = queryable.handler().clone();
+        tokio::spawn(async move {
+            while let Ok(query) = queries.recv_async().await {
+                let discovery = query
+                    .parameters()
+                    .contains_key(ZenohTransport::DISCOVERY_PARAMETER);
+                if !answers.includes(discovery) {
+                    continue;
+                }
+                let _ = query.reply(&key, info()).await;
+            }
+        });
+
+        Ok(Self {
+            _queryable: queryable,
+        })
+    }
+}
+
+/// Queries an [`Advertisement`] answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Answers {
+    /// Every query
+    All,
+    /// Only queries carrying the [`ZenohTransport::DISCOVERY_PARAMETER`]
+    /// selector parameter, so that the advertisement can share its key with a service
+    Discovery,
+    /// Only queries without it, so that the advertisement stays out of listings
+    Direct,
+}
+
+impl Answers {
+    /// Returns true if a query, which is a discovery query or not, is answered
+    fn includes(self, discovery: bool) -> bool {
+        match self {
+            Answers::All => true,
+            Answers::Discovery => discovery,
+            Answers::Direct => !discovery,
+        }
+    }
+}
+
+/// Current values of a node's parameters, keyed by name
+type Parameters = Arc<Mutex<HashMap<String, Parameter>>>;
+
+/// Declared parameters of a node, keyed by name
+type ParameterDescriptors = Arc<Mutex<HashMap<String, ParameterDescriptor>>>;
+
+/// Sets a parameter, checking the value against the descriptor of a declared one
+fn store_parameter<T>(
+    parameters: &Parameters,
+    descriptors: &ParameterDescriptors,
+    name: &str,
+    value: T,
+) -> Result<()>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    if let Some(descriptor) = descriptors.lock().unwrap().get(name) {
+        let json = serde_json::to_value(&value)
+            .map_err(|e| Error::parameter(name, format!("Failed to serialize: {e}")))?;
+        descriptor.check(&json)?;
+    }
+
+    let mut parameters = parameters.lock().unwrap();
+    parameters.insert(name.to_string(), Parameter::new(name, value)?);
+    Ok(())
+}
+
+/// Deletes a parameter, resetting a declared one to its default value
+fn remove_parameter(
+    parameters: &Parameters,
+    descriptors: &ParameterDescriptors,
+    name: &str,
+) -> Result<()> {
+    let mut parameters = parameters.lock().unwrap();
+    if parameters.remove(name).is_none() {
+        return Err(Error::parameter(name, PARAMETER_NOT_FOUND));
+    }
+
+    if let Some(descriptor) = descriptors.lock().unwrap().get(name) {
+        parameters.insert(
+            name.to_string(),
+            Parameter::new(name, descriptor.default.clone())?,
+        );
+    }
+    Ok(())
+}
+
+/// Serves the parameters of a node under `zenobuf/param/<node>/<name>`
+///
+/// A query is answered with the value of every parameter its key matches, or,
+/// if it carries a JSON value, sets the parameter it names and answers with the
+/// new value. Puts and deletes, as sent by `zenobuf-cli param set` and
+/// `delete`, set and delete parameters without a reply.
+struct ParameterServer {
+    _queryable: zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>,
+    _subscriber: zenoh::pubsub::Subscriber<()>,
+}
+
+impl ParameterServer {
+    /// Declares the queryable and subscriber serving the parameters of node `name`
+    async fn declare(
+        transport: &ZenohTransport,
+        name: &str,
+        parameters: Parameters,
+        descriptors: ParameterDescriptors,
+    ) -> Result<Self> {
+        let prefix = format!("{}{}/", Node::PARAM_PREFIX, name);
+        let key = format!("{prefix}**");
+        zenoh::key_expr::KeyExpr::try_from(key.as_str())
+            .map_err(|e| Error::node(name, format!("Failed to create parameter key: {}", e)))?;
+
+        let queryable = transport
+            .session()
+            .declare_queryable(key.clone())
+            .await
+            .map_err(Error::from)?;
+        // The task ends once the queryable is dropped and its channel closes
+        let queries = queryable.handler().clone();
+        let (query_prefix, query_parameters, query_descriptors) =
+            (prefix.clone(), parameters.clone(), descriptors.clone());
+        tokio::spawn(async move {
+            while let Ok(query) = queries.recv_async().await {
+                Self::answer(&query, &query_prefix, &query_parameters, &query_descriptors).await;
+            }
+        });
+
+        let subscriber = transport
+            .session()
+            .declare_subscriber(key)
+            .callback(move |sample| {
+                let Some(name) = sample.key_expr().as_str().strip_prefix(&prefix) else {
+                    return;
+                };
+                let result = match sample.kind() {
+                    zenoh::sample::SampleKind::Put => {
+                        serde_json::from_slice::<serde_json::Value>(&sample.payload().to_bytes())
+                            .map_err(|e| Error::parameter(name, format!("Invalid value: {e}")))
+                            .and_then(|value| {
+                                store_parameter(&parameters, &descriptors, name, value)
+                            })
+                    }
+                    zenoh::sample::SampleKind::Delete => {
+                        remove_parameter(&parameters, &descriptors, name)
+                    }
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Ignoring update of a parameter: {}", e);
+                }
+            })
+            .await
+            .map_err(Error::from)?;
+
+        Ok(Self {
+            _queryable: queryable,
+            _subscriber: subscriber,
+        })
+    }
+
+    /// Answers a query for parameter values, setting the named parameter first
+    /// if the query carries a value
+    async fn answer(
+        query: &zenoh::query::Query,
+        prefix: &str,
+        parameters: &Parameters,
+        descriptors: &ParameterDescriptors,
+    ) {
+        let key = query.key_expr();
+        let name = key.as_str().strip_prefix(prefix).unwrap_or_default();
+        // The descriptors are answered by their own queryable
+        if name == Node::DESCRIBE_KEY {
+            return;
+        }
+
+        if key.is_wild() {
+            if query.payload().is_some() {
+                Self::reply_error(query, Error::parameter(name, "Cannot set a wildcard")).await;
+                return;
+            }
+            let values: Vec<(String, serde_json::Value)> = parameters
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, parameter)| (format!("{prefix}{name}"), parameter.to_json()))
+                .collect();
+            for (parameter_key, value) in values {
+                let matches = zenoh::key_expr::KeyExpr::try_from(parameter_key.as_str())
+                    .is_ok_and(|parameter_key| key.intersects(&parameter_key));
+                if matches {
+                    let _ = query.reply(parameter_key, value.to_string()).await;
+                }
+            }
+            return;
+        }
+
+        if let Some(payload) = query.payload() {
+            let stored = serde_json::from_slice::<serde_json::Value>(&payload.to_bytes())
+                .map_err(|e| Error::parameter(name, format!("Invalid value: {e}")))
+                .and_then(|value| store_parameter(parameters, descriptors, name, value));
+            if let Err(e) = stored {
+                Self::reply_error(query, e).await;
+                return;
+            }
+        }
+        let value = parameters.lock().unwrap().get(name).map(Parameter::to_json);
+        match value {
+            Some(value) => {
+                let _ = query.reply(key.clone(), value.to_string()).await;
+            }
+            None => Self::reply_error(query, Error::parameter(name, PARAMETER_NOT_FOUND)).await,
+        }
+    }
+
+    /// Replies with an [`ErrorReply`], carrying only the reason of parameter errors
+    async fn reply_error(query: &zenoh::query::Query, error: Error) {
+        let reason = match error {
+            Error::Parameter { reason, .. } => reason,
+            error => error.to_string(),
+        };
+        let _ = query
+            .reply_err(ErrorReply::new(reason).to_bytes())
+            .encoding(zenoh::bytes::Encoding::APPLICATION_JSON)
+            .await;
+    }
+}
+
+/// How a publisher created by `Node::create_configured_publisher` sends messages
+struct PublisherOptions<M> {
+    /// Encoding of the payloads
+    encoding: Encoding,
+    /// Codec encoding the messages in place of their own encoding, if any
+    codec: Option<SharedCodec<M>>,
+    /// Codec applied to payloads of at least `compression_threshold` bytes
+    compression: Compression,
+    compression_threshold: usize,
+    /// Maximum publish rate, if any
+    rate_limit: Option<RateLimit>,
+    /// Initial capacity in bytes of the buffer messages are encoded into
+    buffer_capacity: usize,
+}
+
+impl<M: Message> PublisherOptions<M> {
+    /// Sends uncompressed payloads in the message's own encoding, at any rate
+    fn new() -> Self {
+        Self {
+            encoding: M::ENCODING,
+            codec: None,
+            compression: Compression::None,
+            compression_threshold: 0,
+            rate_limit: None,
+            buffer_capacity: 0,
+        }
+    }
+}
+
+/// Topics a node may publish or subscribe to, set with [`NodeBuilder::allow_topics`]
+/// and [`NodeBuilder::deny_topics`]
+#[derive(Debug, Default)]
+struct TopicPolicy {
+    /// Patterns of the allowed topics, or empty to allow any topic not denied
+    allow: Vec<zenoh::key_expr::OwnedKeyExpr>,
+    /// Patterns of the denied topics, which take precedence over allowed ones
+    deny: Vec<zenoh::key_expr::OwnedKeyExpr>,
+}
+
+impl TopicPolicy {
+    /// Parses the allowed and denied topic patterns
+    fn new(allow: &[String], deny: &[String]) -> Result<Self> {
+        let parse = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    validate_pattern(pattern)?;
+                    zenoh::key_expr::OwnedKeyExpr::autocanonize(
+                        pattern.trim_start_matches('/').to_string(),
+                    )
+                    .map_err(|e| {
+                        Error::configuration(format!("Invalid topic pattern '{pattern}': {e}"))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            allow: parse(allow)?,
+            deny: parse(deny)?,
+        })
+    }
+
+    /// Fails if the node `node` may not use the resolved topic or pattern `topic`
+    ///
+    /// A pattern is denied if it matches any topic of a denied pattern, and only
+    /// allowed if every topic it matches is allowed.
+    fn check(&self, node: &str, topic: &str) -> Result<()> {
+        if self.allow.is_empty() && self.deny.is_empty() {
+            return Ok(());
+        }
+        let key = zenoh::key_expr::keyexpr::new(Node::transport_key(topic))
+            .map_err(|e| Error::configuration(format!("Invalid topic '{topic}': {e}")))?;
+        if let Some(denied) = self.deny.iter().find(|denied| denied.intersects(key)) {
+            return Err(Error::configuration(format!(
+                "Topic '{topic}' is denied to node '{node}' by the pattern '{denied}'"
+            )));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|allowed| allowed.includes(key)) {
+            return Err(Error::configuration(format!(
+                "Topic '{topic}' is not among the topics allowed to node '{node}'"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// How a subscriber created by `Node::create_monitored_subscriber` behaves
+#[derive(Default)]
+struct SubscriberOptions {
+    /// Invoked whenever the QoS deadline passes without a message
+    on_deadline_missed: Option<DeadlineCallback>,
+    /// Whether the topic may contain wildcards
+    wildcards: bool,
+    /// Counts the messages a channel subscriber dropped on a full channel
+    ///
+    /// Channel subscribers run their callback as soon as a message arrives
+    /// rather than when the node spins.
+    channel_drops: Option<Arc<DropCounter>>,
+    /// Number of recent messages remembered to drop duplicates, if any
+    dedup: Option<usize>,
+    /// How samples are buffered before the callback runs
+    handler: SubscriberHandler,
+    /// Whether messages are acknowledged to their publisher once processed
+    ack: bool,
+    /// Largest payload decoded, or [`DEFAULT_MAX_MESSAGE_SIZE`] if not set
+    max_message_size: Option<usize>,
+}
+
+/// Entities registered on a node, keyed by resolved name
+type Registry = Arc<Mutex<HashMap<String, Registration>>>;
+
+/// Name and message type of a topic declared by a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicInfo {
+    /// Resolved topic name
+    pub name: String,
+    /// Message type name
+    pub type_name: &'static str,
+}
+
+/// Liveness signal a node publishes periodically under `zenobuf/node/<node>/heartbeat`
+///
+/// Enabled with [`NodeBuilder::heartbeat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    /// Number of heartbeats the node published before this one
+    pub sequence: u64,
+    /// Time the heartbeat was published
+    pub stamp: Time,
+}
+
+/// Name and message types of a service declared by a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInfo {
+    /// Resolved service name
+    pub name: String,
+    /// Request type name
+    pub request_type: &'static str,
+    /// Response type name
+    pub response_type: &'static str,
+}
+
+/// Totals of the statistics of a node's publishers and subscribers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeDiagnostics {
+    /// Number of publishers
+    pub publishers: usize,
+    /// Number of subscribers
+    pub subscribers: usize,
+    /// Messages published successfully
+    pub messages_published: u64,
+    /// Size of the payloads sent for the published messages, in bytes
+    pub bytes_published: u64,
+    /// Messages delivered to subscriber callbacks
+    pub messages_received: u64,
+    /// Messages received but dropped: mismatched types, invalid, duplicate or
+    /// oversized messages, and messages that did not fit in a subscriber channel
+    pub messages_dropped: u64,
+    /// Panics of subscriber callbacks
+    pub callback_panics: u64,
+    /// Subscriber QoS deadlines that passed without a message
+    pub deadlines_missed: u64,
+}
+
+impl NodeDiagnostics {
+    /// Adds the statistics of a publisher
+    fn add_publisher(&mut self, stats: &PublisherStats) {
+        self.messages_published += stats.messages_sent;
+        self.bytes_published += stats.bytes_sent;
+    }
+
+    /// Adds the statistics of a subscriber, with `channel_drops` messages
+    /// dropped on a full channel
+    fn add_subscriber(&mut self, stats: &SubscriberStats, channel_drops: u64) {
+        self.messages_received += stats.messages_received;
+        self.messages_dropped += stats.type_mismatches
+            + stats.invalid_messages
+            + stats.duplicates
+            + stats.oversized_messages
+            + channel_drops;
+        self.callback_panics += stats.callback_panics;
+        self.deadlines_missed += stats.deadlines_missed;
+    }
+}
+
+/// Numbers of entities registered on a node, returned by [`Node::resource_counts`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceCounts {
+    /// Number of publishers
+    pub publishers: usize,
+    /// Number of subscribers
+    pub subscribers: usize,
+    /// Number of services
+    pub services: usize,
+    /// Number of clients
+    pub clients: usize,
+}
+
+impl ResourceCounts {
+    /// Returns the total number of entities
+    pub fn total(&self) -> usize {
+        self.publishers + self.subscribers + self.services + self.clients
+    }
+}
 
 /// A guard that automatically cleans up resources when dropped
 pub struct DropGuard {
@@ -47,11 +600,7 @@ pub struct PublisherHandle<M: Message> {
 }
 
 impl<M: Message> PublisherHandle<M> {
-    fn new(
-        publisher: Arc<Publisher<M>>,
-        topic: String,
-        publishers_map: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    ) -> Self {
+    fn new(publisher: Arc<Publisher<M>>, topic: String, publishers_map: Registry) -> Self {
         let cleanup = DropGuard::new(move || {
             publishers_map
                 .lock()
@@ -76,24 +625,114 @@ impl<M: Message> PublisherHandle<M> {
         self.publisher.publish(message)
     }
 
-    /// Get the topic name
+    /// Publish a message unless it would block, returning whether it was sent
+    pub fn try_publish(&self, message: &M) -> Result<bool> {
+        self.publisher.try_publish(message)
+    }
+
+    /// Publish a message and wait until a subscriber acknowledges having processed it
+    pub async fn publish_reliable(&self, message: &M, timeout: Duration) -> Result<()> {
+        self.publisher.publish_reliable(message, timeout).await
+    }
+
+    /// Publish several messages in a single payload
+    pub fn publish_batch(&self, messages: &[M]) -> Result<()> {
+        self.publisher.publish_batch(messages)
+    }
+
+    /// Publish a message unless it equals the last one published this way,
+    /// returning whether it was sent
+    pub fn publish_if_changed(&self, message: &M) -> Result<bool>
+    where
+        M: PartialEq,
+    {
+        self.publisher.publish_if_changed(message)
+    }
+
+    /// Get the topic name, resolved against the node namespace and remapping rules
+    pub fn topic(&self) -> &str {
+        self.publisher.topic()
+    }
+
+    /// Get the Zenoh key the messages are published on, e.g. `zenobuf/topic/robot1/pose`
+    pub fn resolved_key(&self) -> String {
+        Node::topic_key(self.topic())
+    }
+
+    /// Get the publisher statistics
+    pub fn stats(&self) -> PublisherStats {
+        self.publisher.stats()
+    }
+
+    /// Get the capacity in bytes of the buffer messages are encoded into
+    pub fn buffer_capacity(&self) -> usize {
+        self.publisher.buffer_capacity()
+    }
+
+    /// Get the number of subscribers currently matched with this publisher
+    ///
+    /// Matching is tracked through liveliness tokens, so a newly created
+    /// subscriber may take a moment to be counted.
+    pub fn subscriber_count(&self) -> usize {
+        self.publisher.subscriber_count()
+    }
+
+    /// Unregisters the publisher from the node, freeing its topic
+    ///
+    /// This is equivalent to dropping the handle.
+    pub fn close(self) {
+        drop(self);
+    }
+}
+
+/// A handle to a publisher sending a projection of each message, created with
+/// [`PublisherBuilder::project`]
+///
+/// Messages of type `M` are published as the message of type `N` the
+/// projection produces, so the topic carries `N`.
+pub struct ProjectedPublisherHandle<M: Message, N: Message> {
+    publisher: PublisherHandle<N>,
+    projection: Box<dyn Fn(&M) -> N + Send + Sync>,
+}
+
+impl<M: Message, N: Message> ProjectedPublisherHandle<M, N> {
+    /// Get the handle of the publisher sending the projected messages
+    pub fn publisher(&self) -> &PublisherHandle<N> {
+        &self.publisher
+    }
+
+    /// Publish the projection of a message
+    pub fn publish(&self, message: &M) -> Result<()> {
+        self.publisher.publish(&(self.projection)(message))
+    }
+
+    /// Publish the projection of a message unless it would block, returning
+    /// whether it was sent
+    pub fn try_publish(&self, message: &M) -> Result<bool> {
+        self.publisher.try_publish(&(self.projection)(message))
+    }
+
+    /// Get the topic name, resolved against the node namespace and remapping rules
     pub fn topic(&self) -> &str {
         self.publisher.topic()
     }
+
+    /// Get the publisher statistics, counting the projected messages
+    pub fn stats(&self) -> PublisherStats {
+        self.publisher.stats()
+    }
 }
 
 /// A handle to a subscriber with automatic cleanup
 pub struct SubscriberHandle {
     subscriber: Arc<Subscriber>,
+    /// Messages dropped by a channel subscriber
+    dropped: Option<Arc<DropCounter>>,
     _cleanup: DropGuard,
 }
 
 impl SubscriberHandle {
-    fn new(
-        subscriber: Arc<Subscriber>,
-        topic: String,
-        subscribers_map: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    ) -> Self {
+    fn new(subscriber: Arc<Subscriber>, topic: String, subscribers_map: Registry) -> Self {
         let cleanup = DropGuard::new(move || {
             subscribers_map
                 .lock()
@@ -104,6 +743,7 @@ impl SubscriberHandle {
 
         Self {
             subscriber,
+            dropped: None,
             _cleanup: cleanup,
         }
     }
@@ -112,6 +752,95 @@ impl SubscriberHandle {
     pub fn subscriber(&self) -> &Arc<Subscriber> {
         &self.subscriber
     }
+
+    /// Get the topic name or pattern, resolved against the node namespace and
+    /// remapping rules
+    pub fn topic(&self) -> &str {
+        self.subscriber.topic()
+    }
+
+    /// Get the Zenoh key expression subscribed to, e.g. `zenobuf/topic/robot1/pose`
+    pub fn resolved_key(&self) -> String {
+        Node::topic_key(self.topic())
+    }
+
+    /// Get the subscriber statistics
+    pub fn stats(&self) -> SubscriberStats {
+        self.subscriber.stats()
+    }
+
+    /// Get the number of publishers currently matched with this subscriber
+    pub fn publisher_count(&self) -> usize {
+        self.subscriber.publisher_count()
+    }
+
+    /// Get the number of messages dropped because the channel of a subscriber
+    /// built with [`SubscriberBuilder::build_channel`] was full
+    ///
+    /// Always 0 for callback subscribers.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.as_ref().map_or(0, |dropped| dropped.count())
+    }
+
+    /// Closes the subscriber and unregisters it from the node
+    ///
+    /// No message is delivered after this returns, and the callbacks of
+    /// messages still waiting for a spin are skipped. The topic is freed, so a new subscriber can be
+    /// created on it, for example with a different callback.
+    pub fn close(self) -> Result<()> {
+        self.subscriber.close()
+    }
+}
+
+/// A handle to a subscriber keeping the latest messages in a buffer, created
+/// with [`SubscriberBuilder::build_buffered`]
+///
+/// Messages are added when the node spins, so the buffer can be drained after
+/// each spin to process the messages received since the last one in a batch.
+pub struct BufferedSubscriberHandle<M: Message> {
+    subscriber: SubscriberHandle,
+    buffer: Arc<Mutex<VecDeque<M>>>,
+}
+
+impl<M: Message> BufferedSubscriberHandle<M> {
+    /// Get the handle of the subscriber filling the buffer
+    pub fn subscriber(&self) -> &SubscriberHandle {
+        &self.subscriber
+    }
+
+    /// Get the topic name, resolved against the node namespace and remapping rules
+    pub fn topic(&self) -> &str {
+        self.subscriber.topic()
+    }
+
+    /// Take the buffered messages, oldest first, leaving the buffer empty
+    pub fn drain(&self) -> Vec<M> {
+        self.lock().drain(..).collect()
+    }
+
+    /// Get a copy of the most recent buffered message, without removing it
+    pub fn peek_latest(&self) -> Option<M> {
+        self.lock().back().cloned()
+    }
+
+    /// Get the number of buffered messages
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check whether the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Get the subscriber statistics
+    pub fn stats(&self) -> SubscriberStats {
+        self.subscriber.stats()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<M>> {
+        self.buffer.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 /// A handle to a service with automatic cleanup
@@ -121,11 +850,7 @@ pub struct ServiceHandle {
 }
 
 impl ServiceHandle {
-    fn new(
-        service: Arc<Service>,
-        service_name: String,
-        services_map: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    ) -> Self {
+    fn new(service: Arc<Service>, service_name: String, services_map: Registry) -> Self {
         let cleanup = DropGuard::new(move || {
             services_map
                 .lock()
@@ -144,6 +869,15 @@ impl ServiceHandle {
     pub fn service(&self) -> &Arc<Service> {
         &self.service
     }
+
+    /// Closes the service and unregisters it from the node
+    ///
+    /// The service stops answering requests even if it is still referenced,
+    /// e.g. through [`ServiceHandle::service`], and the service name is freed,
+    /// so the service can be created again.
+    pub fn close(self) -> Result<()> {
+        self.service.close()
+    }
 }
 
 /// A handle to a client with automatic cleanup
@@ -153,16 +887,13 @@ pub struct ClientHandle<Req: Message, Res: Message> {
 }
 
 impl<Req: Message, Res: Message> ClientHandle<Req, Res> {
-    fn new(
-        client: Arc<Client<Req, Res>>,
-        service_name: String,
-        clients_map: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    ) -> Self {
+    fn new(client: Arc<Client<Req, Res>>, key: String, clients_map: Registry) -> Self {
+        let service_name = client.name().to_string();
         let cleanup = DropGuard::new(move || {
             clients_map
                 .lock()
                 .unwrap_or_else(|e| e.into_inner())
-                .remove(&service_name);
+                .remove(&key);
             tracing::debug!("Client dropped for service: {}", service_name);
         });
 
@@ -186,75 +917,271 @@ impl<Req: Message, Res: Message> ClientHandle<Req, Res> {
     pub async fn call_async(&self, request: &Req) -> Result<Res> {
         self.client.call_async(request).await
     }
-}
 
-/// Node abstraction for Zenobuf
-///
-/// A Node is the main entry point for using Zenobuf. It provides methods for
-/// creating publishers, subscribers, services, and clients.
-pub struct Node {
-    /// Name of the node
-    name: String,
-    /// Transport layer
-    transport: ZenohTransport,
-    /// Callback executor for processing subscriber callbacks
-    executor: Arc<CallbackExecutor>,
-    /// Publishers
-    publishers: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    /// Subscribers
-    subscribers: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    /// Services
-    services: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    /// Clients
-    clients: Arc<Mutex<HashMap<String, Box<dyn std::any::Any + Send + Sync>>>>,
-    /// Parameters
-    parameters: Mutex<HashMap<String, Parameter>>,
-    /// Discovery queryable (keeps node discoverable while alive)
-    _discovery_queryable:
-        Option<zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>>,
-    /// Discovery task handle (detached on drop; terminates when queryable is dropped)
-    _discovery_task: Option<tokio::task::JoinHandle<()>>,
-}
+    /// Call the service asynchronously as part of an existing trace
+    pub async fn call_with_trace_id(&self, request: &Req, trace_id: Uuid) -> Result<Res> {
+        self.client.call_with_trace_id(request, trace_id).await
+    }
 
-impl Node {
-    /// Prefix for node discovery key expressions
-    pub const NODE_PREFIX: &str = "zenobuf/node/";
+    /// Call the service asynchronously until a response arrives or `token` is
+    /// cancelled
+    ///
+    /// Returns [`Error::ServiceCallCancelled`] once the token is cancelled.
+    pub async fn call_cancellable(&self, request: &Req, token: CancellationToken) -> Result<Res> {
+        self.client.call_cancellable(request, token).await
+    }
 
-    /// Creates a new Node with the given name
-    pub async fn new(name: &str) -> Result<Self> {
-        let transport = ZenohTransport::new().await?;
-        Self::with_transport(name, transport).await
+    /// Call the service asynchronously, giving up once `timeout` has elapsed
+    ///
+    /// Returns [`Error::ServiceCallTimeout`] on expiry, as opposed to
+    /// [`Error::ServiceCallFailed`] for errors reported by the service.
+    pub async fn call_with_timeout(&self, request: &Req, timeout: Duration) -> Result<Res> {
+        self.client.call_with_timeout(request, timeout).await
     }
 
-    /// Creates a new Node with the given name and transport
-    pub async fn with_transport(name: &str, transport: ZenohTransport) -> Result<Self> {
-        let (discovery_queryable, discovery_task) =
-            Self::create_discovery_queryable(&transport, name).await?;
+    /// Call the service asynchronously, also returning how long the call took
+    pub async fn call_timed(&self, request: &Req) -> Result<(Res, Duration)> {
+        self.client.call_timed(request).await
+    }
 
-        Ok(Self {
-            name: name.to_string(),
-            transport,
-            executor: Arc::new(CallbackExecutor::new()),
-            publishers: Arc::new(Mutex::new(HashMap::new())),
-            subscribers: Arc::new(Mutex::new(HashMap::new())),
-            services: Arc::new(Mutex::new(HashMap::new())),
-            clients: Arc::new(Mutex::new(HashMap::new())),
-            parameters: Mutex::new(HashMap::new()),
-            _discovery_queryable: Some(discovery_queryable),
-            _discovery_task: Some(discovery_task),
+    /// Call every server of the service, collecting the responses that arrive
+    /// within `timeout`
+    pub async fn call_all(&self, request: &Req, timeout: Duration) -> Result<Vec<Res>> {
+        self.client.call_all(request, timeout).await
+    }
+
+    /// Get the call statistics of the client
+    pub fn stats(&self) -> ClientStats {
+        self.client.stats()
+    }
+
+    /// Wait until a server for the service is available
+    ///
+    /// Returns [`Error::ServiceCallTimeout`] if no server appears within `timeout`.
+    pub async fn wait_for_service(&self, timeout: Duration) -> Result<()> {
+        self.client.wait_for_service(timeout).await
+    }
+}
+
+/// A handle to a periodic timer with automatic cleanup
+///
+/// The timer stops firing when the handle is dropped or [`TimerHandle::cancel`] is called.
+pub struct TimerHandle {
+    period: Duration,
+    task: tokio::task::AbortHandle,
+    /// Set as soon as the timer is cancelled, while its task may still be
+    /// aborting
+    cancelled: Arc<AtomicBool>,
+    _cleanup: DropGuard,
+}
+
+impl TimerHandle {
+    fn new(period: Duration, task: tokio::task::AbortHandle) -> Self {
+        let abort = task.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let dropped = cancelled.clone();
+        let cleanup = DropGuard::new(move || {
+            dropped.store(true, Ordering::SeqCst);
+            abort.abort();
+            tracing::debug!("Timer dropped (period: {:?})", period);
+        });
+
+        Self {
+            period,
+            task,
+            cancelled,
+            _cleanup: cleanup,
+        }
+    }
+
+    /// Returns the timer period
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Stops the timer before the handle is dropped
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.task.abort();
+    }
+
+    /// Returns true if the timer has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// A handle to a bridge between two topics with automatic cleanup
+///
+/// The bridge's subscriber and publisher are removed from the node when the
+/// handle is dropped.
+pub struct BridgeHandle {
+    to: String,
+    subscriber: SubscriberHandle,
+    /// Keeps the publisher on the target topic registered
+    _publisher: Box<dyn std::any::Any + Send + Sync>,
+}
+
+impl BridgeHandle {
+    /// Returns the resolved topic messages are received on
+    pub fn from(&self) -> &str {
+        self.subscriber.topic()
+    }
+
+    /// Returns the resolved topic messages are republished on
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// Returns the statistics of the bridge's subscriber
+    pub fn stats(&self) -> SubscriberStats {
+        self.subscriber.stats()
+    }
+}
+
+/// Queryables through which a node on Zenoh is discovered, and its parameters
+/// and diagnostics are served
+struct NodeAdvertisements {
+    /// Discovery metadata (keeps node discoverable while alive)
+    _discovery: Advertisement,
+    /// Answers queries for the declared parameters
+    _parameter_description: Advertisement,
+    /// Answers queries for the parameter values and applies remote updates
+    _parameter_server: ParameterServer,
+    /// Answers queries for the node diagnostics
+    _diagnostics: Advertisement,
+}
+
+impl NodeAdvertisements {
+    /// Declares the queryables of the node `name`
+    async fn declare(
+        transport: &ZenohTransport,
+        name: &str,
+        parameters: &Parameters,
+        parameter_descriptors: &ParameterDescriptors,
+        publishers: &Registry,
+        subscribers: &Registry,
+    ) -> Result<Self> {
+        let discovery = Node::create_discovery_queryable(transport, name).await?;
+        let parameter_description =
+            Node::describe_parameters(transport, name, parameter_descriptors.clone()).await?;
+        let parameter_server = ParameterServer::declare(
+            transport,
+            name,
+            parameters.clone(),
+            parameter_descriptors.clone(),
+        )
+        .await?;
+        let diagnostics =
+            Node::advertise_diagnostics(transport, name, publishers.clone(), subscribers.clone())
+                .await?;
+        Ok(Self {
+            _discovery: discovery,
+            _parameter_description: parameter_description,
+            _parameter_server: parameter_server,
+            _diagnostics: diagnostics,
         })
     }
+}
+
+/// Node abstraction for Zenobuf
+///
+/// A Node is the main entry point for using Zenobuf. It provides methods for
+/// creating publishers, subscribers, services, and clients.
+///
+/// A node runs on a [`ZenohTransport`] unless built with
+/// [`Node::with_transport`] on another [`Transport`], such as a
+/// [`MockTransport`](crate::transport::MockTransport) in tests. The methods of
+/// `impl<Tr: Transport> Node<Tr>` work on any transport, while the others, e.g.
+/// the builders, discovery and remote parameters, need Zenoh.
+pub struct Node<Tr: Transport = ZenohTransport> {
+    /// Name of the node
+    name: String,
+    /// Namespace prepended to relative names, e.g. `/robot1`
+    namespace: Option<String>,
+    /// Remapping rules from resolved names to resolved names
+    remappings: Mutex<HashMap<String, String>>,
+    /// Transport layer
+    transport: Tr,
+    /// Callback executor for processing subscriber callbacks
+    executor: Arc<CallbackExecutor>,
+    /// Publishers
+    publishers: Registry,
+    /// Subscribers
+    subscribers: Registry,
+    /// Services
+    services: Registry,
+    /// Clients, keyed by resolved name and a unique suffix
+    clients: Registry,
+    /// Parameters
+    parameters: Parameters,
+    /// Declared parameters, keyed by name
+    parameter_descriptors: ParameterDescriptors,
+    /// Topics the node may publish or subscribe to
+    topic_policy: TopicPolicy,
+    /// Serves the node's discovery metadata, parameters and diagnostics, on Zenoh
+    _advertisements: Option<NodeAdvertisements>,
+    /// Publishes the node's heartbeat, if enabled
+    _heartbeat: Option<TimerHandle>,
+}
+
+impl Node {
+    /// Prefix for node discovery key expressions
+    pub const NODE_PREFIX: &str = "zenobuf/node/";
+
+    /// Prefix for parameter key expressions
+    pub const PARAM_PREFIX: &str = "zenobuf/param/";
+
+    /// Key under `zenobuf/param/<node>/` answering with the parameter descriptors
+    pub const DESCRIBE_KEY: &str = "__describe__";
+
+    /// Key under `zenobuf/node/<node>/` answering with the [`NodeDiagnostics`]
+    pub const DIAGNOSTICS_KEY: &str = "__diagnostics__";
+
+    /// Key under `zenobuf/node/<node>/` the node publishes its [`Heartbeat`] on
+    pub const HEARTBEAT_KEY: &str = "heartbeat";
+
+    /// Heartbeat period used by [`NodeBuilder::heartbeat`]
+    pub const DEFAULT_HEARTBEAT_PERIOD: Duration = Duration::from_secs(1);
+
+    /// Time a new subscriber waits for publishers to report their QoS profile
+    const QOS_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Creates a new Node with the given name
+    pub async fn new(name: &str) -> Result<Self> {
+        let transport = ZenohTransport::new().await?;
+        Self::with_transport(name, transport).await
+    }
+
+    /// Creates a new Node whose relative names are resolved under `namespace`
+    ///
+    /// A relative name such as `"pose"` resolves to `"/robot1/pose"` for the
+    /// namespace `"robot1"` (or `"/robot1"`), while absolute names starting with
+    /// `/` are used as given.
+    pub async fn with_namespace(name: &str, namespace: &str) -> Result<Self> {
+        let mut node = Self::new(name).await?;
+        let namespace = namespace.trim_matches('/');
+        if !namespace.is_empty() {
+            node.namespace = Some(format!("/{namespace}"));
+        }
+        Ok(node)
+    }
+
+    /// Creates a builder for a node with explicit Zenoh settings
+    ///
+    /// The builder can select client or peer mode, the endpoints to connect to
+    /// and listen on, or start from a complete Zenoh configuration.
+    pub fn builder(name: &str) -> NodeBuilder {
+        NodeBuilder::new(name)
+    }
 
     /// Creates a discovery queryable that responds to node discovery queries
     async fn create_discovery_queryable(
         transport: &ZenohTransport,
         name: &str,
-    ) -> Result<(
-        zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>,
-        tokio::task::JoinHandle<()>,
-    )> {
+    ) -> Result<Advertisement> {
         let key = format!("{}{}", Self::NODE_PREFIX, name);
-        let key_expr = zenoh::key_expr::KeyExpr::try_from(key.clone())
+        zenoh::key_expr::KeyExpr::try_from(key.as_str())
             .map_err(|e| Error::node(name, format!("Failed to create discovery key: {}", e)))?;
 
         let node_info = serde_json::json!({
@@ -263,26 +1190,487 @@ impl Node {
             "pid": std::process::id(),
         });
 
-        let queryable = transport
-            .session()
-            .declare_queryable(key_expr)
-            .await
-            .map_err(Error::from)?;
+        let advertisement =
+            Advertisement::declare(transport, key.clone(), node_info, Answers::All).await?;
+
+        tracing::debug!("Node '{}' registered for discovery at {}", name, key);
+
+        Ok(advertisement)
+    }
+
+    /// Answers `zenobuf/param/<node>/__describe__` with the declared parameters
+    ///
+    /// The reply is a JSON list of [`ParameterDescriptor`]s, sorted by name.
+    async fn describe_parameters(
+        transport: &ZenohTransport,
+        name: &str,
+        descriptors: ParameterDescriptors,
+    ) -> Result<Advertisement> {
+        let key = format!("{}{}/{}", Self::PARAM_PREFIX, name, Self::DESCRIBE_KEY);
+        zenoh::key_expr::KeyExpr::try_from(key.as_str())
+            .map_err(|e| Error::node(name, format!("Failed to create parameter key: {}", e)))?;
+
+        Advertisement::declare_with(
+            transport,
+            key,
+            move || {
+                let descriptors = descriptors.lock().unwrap_or_else(|e| e.into_inner());
+                let mut descriptors: Vec<&ParameterDescriptor> = descriptors.values().collect();
+                descriptors.sort_by(|a, b| a.name.cmp(&b.name));
+                serde_json::to_string(&descriptors).unwrap_or_default()
+            },
+            Answers::All,
+        )
+        .await
+    }
+
+    /// Answers `zenobuf/node/<node>/__diagnostics__` with the current [`NodeDiagnostics`]
+    ///
+    /// The queryable only answers direct queries, keeping it out of node listings.
+    async fn advertise_diagnostics(
+        transport: &ZenohTransport,
+        name: &str,
+        publishers: Registry,
+        subscribers: Registry,
+    ) -> Result<Advertisement> {
+        let key = format!("{}{}/{}", Self::NODE_PREFIX, name, Self::DIAGNOSTICS_KEY);
+        zenoh::key_expr::KeyExpr::try_from(key.as_str())
+            .map_err(|e| Error::node(name, format!("Failed to create diagnostics key: {}", e)))?;
+
+        Advertisement::declare_with(
+            transport,
+            key,
+            move || {
+                let diagnostics = Self::collect_diagnostics(&publishers, &subscribers);
+                serde_json::to_string(&diagnostics).unwrap_or_default()
+            },
+            Answers::Direct,
+        )
+        .await
+    }
 
-        // Clone for the spawned task
-        let queryable_clone = queryable.clone();
-        let info_str = node_info.to_string();
-        let key_clone = key.clone();
+    /// Publishes a [`Heartbeat`] under `zenobuf/node/<node>/heartbeat` every `period`
+    ///
+    /// The first heartbeat is published right away.
+    fn start_heartbeat(&self, period: Duration) -> Result<TimerHandle> {
+        if period.is_zero() {
+            return Err(Error::configuration("Heartbeat period must be non-zero"));
+        }
+        let key = zenoh::key_expr::KeyExpr::try_from(format!(
+            "{}{}/{}",
+            Self::NODE_PREFIX,
+            self.name,
+            Self::HEARTBEAT_KEY
+        ))
+        .map_err(|e| Error::node(&self.name, format!("Failed to create heartbeat key: {}", e)))?;
 
+        let session = self.transport.session().clone();
         let task = tokio::spawn(async move {
-            while let Ok(query) = queryable_clone.recv_async().await {
-                let _ = query.reply(&key_clone, info_str.clone()).await;
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            for sequence in 0u64.. {
+                interval.tick().await;
+                let heartbeat = Heartbeat {
+                    sequence,
+                    stamp: Time::now(),
+                };
+                let payload = serde_json::to_vec(&heartbeat).unwrap_or_default();
+                if let Err(e) = session.put(key.clone(), payload).await {
+                    tracing::warn!("Failed to publish heartbeat on {}: {}", key, e);
+                }
             }
         });
 
-        tracing::debug!("Node '{}' registered for discovery at {}", name, key);
+        tracing::debug!(
+            "Heartbeat started on node '{}' (period: {:?})",
+            self.name,
+            period
+        );
+
+        Ok(TimerHandle::new(period, task.abort_handle()))
+    }
+
+    /// Returns the transport key for a resolved name
+    fn transport_key(resolved: &str) -> &str {
+        resolved.trim_start_matches('/')
+    }
 
-        Ok((queryable, task))
+    /// Returns the full Zenoh key of a resolved topic name
+    fn topic_key(resolved: &str) -> String {
+        format!(
+            "{}{}",
+            ZenohTransport::TOPIC_PREFIX,
+            Self::transport_key(resolved)
+        )
+    }
+
+    /// Creates a subscriber whose callback receives the messages of each sample together
+    ///
+    /// A batch published with [`Publisher::publish_batch`] is delivered as one
+    /// vector, while other messages arrive as single-element vectors.
+    pub async fn create_batched_subscriber<M: Message, F>(
+        &self,
+        topic: &str,
+        qos: QosProfile,
+        callback: F,
+    ) -> Result<Arc<Subscriber>>
+    where
+        F: Fn(Vec<M>) + Send + Sync + 'static,
+    {
+        let callback = move |_topic: String, messages: Vec<M>| callback(messages);
+        let sink = DecodedSink::new(M::ENCODING, callback);
+        self.create_monitored_subscriber::<M, _>(topic, qos, sink, SubscriberOptions::default())
+            .await
+    }
+
+    /// Creates a subscriber on a topic pattern such as `sensors/**`
+    ///
+    /// `*` matches a single chunk of the topic and `**` any number of chunks. The
+    /// callback receives the concrete topic of each message, without a leading `/`.
+    pub async fn create_wildcard_subscriber<M: Message, F>(
+        &self,
+        pattern: &str,
+        qos: QosProfile,
+        callback: F,
+    ) -> Result<Arc<Subscriber>>
+    where
+        F: Fn(String, M) + Send + Sync + 'static,
+    {
+        let callback = move |topic: String, messages: Vec<M>| {
+            for message in messages {
+                callback(topic.clone(), message);
+            }
+        };
+        let options = SubscriberOptions {
+            wildcards: true,
+            ..SubscriberOptions::default()
+        };
+        let sink = DecodedSink::new(M::ENCODING, callback);
+        self.create_monitored_subscriber::<M, _>(pattern, qos, sink, options)
+            .await
+    }
+
+    /// Republishes every message received on `from` onto `to`
+    ///
+    /// See [`Node::bridge_map`].
+    pub async fn bridge<M: Message>(&self, from: &str, to: &str) -> Result<BridgeHandle> {
+        self.bridge_map::<M, M, _>(from, to, |message| message)
+            .await
+    }
+
+    /// Republishes the messages received on `from` onto `to`, transformed by `f`
+    ///
+    /// The bridge is a subscriber on `from` feeding a publisher on `to`, both
+    /// registered with the node, so messages are republished when the node
+    /// spins. Failed publishes are logged. The bridge stops when the returned
+    /// handle is dropped.
+    pub async fn bridge_map<A: Message, B: Message, F>(
+        &self,
+        from: &str,
+        to: &str,
+        f: F,
+    ) -> Result<BridgeHandle>
+    where
+        F: Fn(A) -> B + Send + Sync + 'static,
+    {
+        let publisher = self.publisher::<B>(to).build().await?;
+        let target = publisher.publisher().clone();
+        let to = target.topic().to_string();
+        let subscriber = self
+            .subscriber::<A>(from)
+            .build(move |message| {
+                if let Err(e) = target.publish(&f(message)) {
+                    tracing::warn!("Bridge failed to republish on {}: {}", target.topic(), e);
+                }
+            })
+            .await?;
+
+        tracing::debug!(
+            "Bridge created on node '{}': {} -> {}",
+            self.name,
+            subscriber.topic(),
+            to
+        );
+
+        Ok(BridgeHandle {
+            to,
+            subscriber,
+            _publisher: Box::new(publisher),
+        })
+    }
+
+    /// Declares a parameter with a default value and optional constraints
+    ///
+    /// The parameter is set to `default` unless it already has a value. Declared
+    /// parameters are listed by [`Node::parameter_descriptors`] and by the node's
+    /// `zenobuf/param/<node>/__describe__` query.
+    pub fn declare_parameter<
+        T: serde::Serialize + serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+    >(
+        &self,
+        name: &str,
+        default: T,
+    ) -> ParameterBuilder<'_, T> {
+        ParameterBuilder::new(self, name, default)
+    }
+
+    /// Returns a client reading and setting the parameters of the node named `target`
+    ///
+    /// Every node serves its parameters under `zenobuf/param/<node>/<name>`,
+    /// which is also what `zenobuf-cli param` uses.
+    pub fn parameter_client(&self, target: &str) -> ParameterClient {
+        ParameterClient::new(self.transport.session().clone(), target)
+    }
+
+    /// Returns the Zenoh sessions visible to the node's transport
+    ///
+    /// The first entry is the node's own session. See [`ZenohTransport::peers`].
+    pub async fn peers(&self) -> Vec<PeerInfo> {
+        self.transport.peers().await
+    }
+
+    /// Returns a stream of the publishers, subscribers and services appearing and
+    /// disappearing across all nodes
+    ///
+    /// Endpoints that already exist are reported as added first. Names are
+    /// given without their leading `/`.
+    pub async fn graph_events(&self) -> Result<GraphEvents> {
+        self.transport.graph_events().await
+    }
+
+    /// Waits until a publisher exists on `topic`, on this or any other node
+    ///
+    /// Returns [`Error::Timeout`] if none appears within `timeout`.
+    pub async fn wait_for_publisher(&self, topic: &str, timeout: Duration) -> Result<()> {
+        let topic_name = self.resolve_name(topic);
+        validate_name(&topic_name)?;
+        self.transport
+            .wait_for_publisher(Self::transport_key(&topic_name), timeout)
+            .await
+    }
+
+    /// Waits for the next message on `topic` and returns it
+    ///
+    /// A subscriber is created for the duration of the wait, so the node must not
+    /// already subscribe to the topic, and it does not need to spin. Returns
+    /// [`Error::Timeout`] if no message arrives within `timeout`.
+    pub async fn wait_for_message<M: Message>(&self, topic: &str, timeout: Duration) -> Result<M> {
+        let (subscriber, mut receiver) = self.subscriber::<M>(topic).build_channel(1).await?;
+        let received = tokio::time::timeout(timeout, receiver.recv()).await;
+        let topic = subscriber.topic().to_string();
+        drop(subscriber);
+        match received {
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => Err(Error::subscriber(topic, "subscriber closed")),
+            Err(_) => Err(Error::timeout(
+                format!("waiting for a message on '{topic}'"),
+                timeout.as_millis() as u64,
+            )),
+        }
+    }
+
+    // Builder pattern methods for simplified API
+
+    /// Creates a publisher builder for the given topic
+    pub fn publisher<M: Message>(&self, topic: &str) -> PublisherBuilder<'_, M> {
+        PublisherBuilder::new(self, topic)
+    }
+
+    /// Creates a subscriber builder for the given topic
+    pub fn subscriber<M: Message>(&self, topic: &str) -> SubscriberBuilder<'_, M> {
+        SubscriberBuilder::new(self, topic)
+    }
+
+    /// Creates a subscriber builder for a topic pattern such as `sensors/**`
+    ///
+    /// The callback of the subscriber receives the concrete topic of each message.
+    pub fn subscriber_wildcard<M: Message>(
+        &self,
+        pattern: &str,
+    ) -> WildcardSubscriberBuilder<'_, M> {
+        WildcardSubscriberBuilder::new(self, pattern)
+    }
+
+    /// Creates a service builder for the given service name
+    pub fn service<Req: Message, Res: Message>(&self, name: &str) -> ServiceBuilder<'_, Req, Res> {
+        ServiceBuilder::new(self, name)
+    }
+
+    /// Creates a client builder for the given service name
+    pub fn client<Req: Message, Res: Message>(&self, name: &str) -> ClientBuilder<'_, Req, Res> {
+        ClientBuilder::new(self, name)
+    }
+
+    /// Calls a service once through a transient client
+    ///
+    /// The client is not registered with the node and is dropped once the
+    /// call completes.
+    pub async fn call_service<Req: Message, Res: Message>(
+        &self,
+        name: &str,
+        request: &Req,
+    ) -> Result<Res> {
+        let full_service_name = self.resolve_name(name);
+        validate_name(&full_service_name)?;
+        let inner_client = self.transport.create_client::<Req, Res>(
+            Self::transport_key(&full_service_name),
+            &QosProfile::default(),
+        )?;
+        let client = Client::new(full_service_name, Box::new(inner_client));
+        client.call_async(request).await
+    }
+}
+
+impl<Tr: Transport> Node<Tr> {
+    /// Creates a new Node with the given name and transport
+    ///
+    /// On a [`ZenohTransport`], operations of the node run in `tracing` spans at
+    /// [`Level::INFO`], or the level set with [`NodeBuilder::span_level`].
+    pub async fn with_transport(name: &str, mut transport: Tr) -> Result<Self> {
+        let parameters: Parameters = Arc::new(Mutex::new(HashMap::new()));
+        let parameter_descriptors: ParameterDescriptors = Arc::new(Mutex::new(HashMap::new()));
+        let publishers: Registry = Arc::new(Mutex::new(HashMap::new()));
+        let subscribers: Registry = Arc::new(Mutex::new(HashMap::new()));
+        let advertisements = match transport.as_zenoh_mut() {
+            Some(zenoh) => {
+                zenoh.spans_mut().set_node(name);
+                let advertisements = NodeAdvertisements::declare(
+                    zenoh,
+                    name,
+                    &parameters,
+                    &parameter_descriptors,
+                    &publishers,
+                    &subscribers,
+                )
+                .await?;
+                Some(advertisements)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            namespace: None,
+            remappings: Mutex::new(HashMap::new()),
+            transport,
+            executor: Arc::new(CallbackExecutor::new()),
+            publishers,
+            subscribers,
+            services: Arc::new(Mutex::new(HashMap::new())),
+            clients: Arc::new(Mutex::new(HashMap::new())),
+            parameters,
+            parameter_descriptors,
+            topic_policy: TopicPolicy::default(),
+            _advertisements: advertisements,
+            _heartbeat: None,
+        })
+    }
+
+    /// Returns the Zenoh transport of the node, for the features only it provides
+    fn zenoh(&self) -> Result<&ZenohTransport> {
+        self.transport
+            .as_zenoh()
+            .ok_or_else(|| Error::node(&self.name, "this feature requires the Zenoh transport"))
+    }
+
+    /// Adds up the counters of the registered publishers and subscribers
+    fn collect_diagnostics(publishers: &Registry, subscribers: &Registry) -> NodeDiagnostics {
+        let mut diagnostics = NodeDiagnostics::default();
+        for registration in publishers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+        {
+            diagnostics.publishers += 1;
+            if let Some(probe) = &registration.probe {
+                probe(&mut diagnostics);
+            }
+        }
+        for registration in subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+        {
+            diagnostics.subscribers += 1;
+            if let Some(probe) = &registration.probe {
+                probe(&mut diagnostics);
+            }
+        }
+        diagnostics
+    }
+
+    /// Advertises a publisher or subscriber under `zenobuf/topic/<topic>`
+    async fn advertise_topic<M: Message>(
+        &self,
+        topic_name: &str,
+        role: &str,
+        qos: &QosProfile,
+    ) -> Result<Advertisement> {
+        let info = serde_json::json!({
+            "topic": topic_name,
+            "type": M::type_name(),
+            "role": role,
+            "node": self.name,
+            "qos": qos,
+        });
+        Advertisement::declare(
+            self.zenoh()?,
+            Node::topic_key(topic_name),
+            info,
+            Answers::All,
+        )
+        .await
+    }
+
+    /// Advertises a service under `zenobuf/service/<name>`
+    ///
+    /// The advertisement only answers discovery queries, leaving calls to the service.
+    async fn advertise_service<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+    ) -> Result<Advertisement> {
+        let info = serde_json::json!({
+            "service": service_name,
+            "request_type": Req::type_name(),
+            "response_type": Res::type_name(),
+            "node": self.name,
+        });
+        Advertisement::declare(
+            self.zenoh()?,
+            format!(
+                "{}{}",
+                ZenohTransport::SERVICE_PREFIX,
+                Node::transport_key(service_name)
+            ),
+            info,
+            Answers::Discovery,
+        )
+        .await
+    }
+
+    /// Answers `zenobuf/service/<name>/__types__` with the request and response types
+    ///
+    /// This lets tools such as `zenobuf-cli service-type` show what a service expects.
+    async fn advertise_service_types<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+    ) -> Result<Advertisement> {
+        let info = serde_json::json!({
+            "request_type": Req::type_name(),
+            "response_type": Res::type_name(),
+        });
+        Advertisement::declare(
+            self.zenoh()?,
+            format!(
+                "{}{}{}",
+                ZenohTransport::SERVICE_PREFIX,
+                Node::transport_key(service_name),
+                ZenohTransport::SERVICE_TYPES_SUFFIX
+            ),
+            info,
+            Answers::Direct,
+        )
+        .await
     }
 
     /// Returns a reference to the callback executor
@@ -298,72 +1686,468 @@ impl Node {
         &self.name
     }
 
+    /// Returns the namespace of the node, if any
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Adds a remapping rule applied to topic and service names
+    ///
+    /// Both names are resolved against the node namespace, so with the namespace
+    /// `/robot1`, `remap("cmd", "/cmd_vel")` rewrites `/robot1/cmd` to `/cmd_vel`.
+    /// Rules only affect publishers, subscribers, services and clients created
+    /// afterwards.
+    pub fn remap(&self, from: &str, to: &str) {
+        let from = self.expand_name(from);
+        let to = self.expand_name(to);
+        tracing::debug!("Node '{}' remapping {} -> {}", self.name, from, to);
+        self.remappings.lock().unwrap().insert(from, to);
+    }
+
+    /// Resolves a topic or service name against the namespace and remapping rules
+    pub fn resolve_name(&self, name: &str) -> String {
+        let expanded = self.expand_name(name);
+        self.remappings
+            .lock()
+            .unwrap()
+            .get(&expanded)
+            .cloned()
+            .unwrap_or(expanded)
+    }
+
+    /// Returns the topics this node publishes, sorted by name
+    pub fn publishers(&self) -> Vec<TopicInfo> {
+        Self::topic_infos(&self.publishers)
+    }
+
+    /// Returns the topics this node subscribes to, sorted by name
+    pub fn subscribers(&self) -> Vec<TopicInfo> {
+        Self::topic_infos(&self.subscribers)
+    }
+
+    /// Returns the services this node provides, sorted by name
+    pub fn services(&self) -> Vec<ServiceInfo> {
+        let mut infos: Vec<ServiceInfo> = self
+            .services
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, registration)| ServiceInfo {
+                name: name.clone(),
+                request_type: registration.type_name,
+                response_type: registration.response_type_name.unwrap_or_default(),
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    /// Returns the totals of the statistics of this node's publishers and subscribers
+    ///
+    /// The same totals are served to remote tools such as `zenobuf-cli diag`
+    /// under `zenobuf/node/<node>/__diagnostics__`.
+    pub fn diagnostics(&self) -> NodeDiagnostics {
+        Self::collect_diagnostics(&self.publishers, &self.subscribers)
+    }
+
+    /// Returns the numbers of publishers, subscribers, services and clients
+    /// registered on the node
+    ///
+    /// Dropping a handle unregisters its entity, so counts that stay above zero
+    /// after the handles are gone point to a leaked handle, e.g. one moved into
+    /// a task that never ends.
+    pub fn resource_counts(&self) -> ResourceCounts {
+        let count = |registry: &Registry| registry.lock().unwrap_or_else(|e| e.into_inner()).len();
+        ResourceCounts {
+            publishers: count(&self.publishers),
+            subscribers: count(&self.subscribers),
+            services: count(&self.services),
+            clients: count(&self.clients),
+        }
+    }
+
+    /// Panics in debug builds if any publisher, subscriber, service or client
+    /// is still registered on the node
+    ///
+    /// Call it once a test has dropped its handles to catch those that leaked.
+    /// Release builds skip the check.
+    pub fn assert_no_orphans(&self) {
+        let counts = self.resource_counts();
+        debug_assert!(
+            counts.total() == 0,
+            "Node '{}' has orphaned resources: {:?}",
+            self.name,
+            counts
+        );
+    }
+
+    /// Collects the topic infos of a registry, sorted by name
+    fn topic_infos(registry: &Registry) -> Vec<TopicInfo> {
+        let mut infos: Vec<TopicInfo> = registry
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, registration)| TopicInfo {
+                name: name.clone(),
+                type_name: registration.type_name,
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    /// Prepends the namespace to relative names
+    fn expand_name(&self, name: &str) -> String {
+        match &self.namespace {
+            Some(namespace) if !name.starts_with('/') => format!("{namespace}/{name}"),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Fails if messages of `M` cannot be encoded in `encoding`
+    ///
+    /// Debug builds also check that `M` decodes its own encoding.
+    fn check_encoding_supported<M: Message>(encoding: Encoding) -> Result<()> {
+        debug_check_framing::<M>();
+        if encoding != M::ENCODING {
+            encode_message_as(&M::default(), encoding)?;
+        }
+        Ok(())
+    }
+
     /// Creates a publisher for the given topic
     pub async fn create_publisher<M: Message>(
         &self,
         topic: &str,
-        qos: QosProfile,
-    ) -> Result<Arc<Publisher<M>>> {
-        let topic_name = topic.to_string();
+        qos: QosProfile,
+    ) -> Result<Arc<Publisher<M>>> {
+        self.create_configured_publisher(topic, qos, PublisherOptions::new())
+            .await
+    }
+
+    /// Creates a publisher sending messages as configured by `options`
+    async fn create_configured_publisher<M: Message>(
+        &self,
+        topic: &str,
+        qos: QosProfile,
+        options: PublisherOptions<M>,
+    ) -> Result<Arc<Publisher<M>>> {
+        let PublisherOptions {
+            encoding,
+            codec,
+            compression,
+            compression_threshold,
+            rate_limit,
+            buffer_capacity,
+        } = options;
+        let topic_name = self.resolve_name(topic);
+        validate_name(&topic_name)?;
+        self.topic_policy.check(&self.name, &topic_name)?;
+        Self::check_encoding_supported::<M>(encoding)?;
+
+        // Fast-path rejection before expensive transport call
+        if self.publishers.lock().unwrap().contains_key(&topic_name) {
+            return Err(Error::topic_already_exists(&topic_name, &self.name));
+        }
+
+        let (publisher, advertisement) = match self.transport.as_zenoh() {
+            Some(zenoh) => {
+                // Advertised first, so that subscribers seeing the publisher's
+                // liveliness token can find its QoS profile
+                let advertisement = self
+                    .advertise_topic::<M>(&topic_name, "publisher", &qos)
+                    .await?;
+                let mut inner_publisher = zenoh
+                    .create_encoded_publisher::<M>(Node::transport_key(&topic_name), &qos, encoding)
+                    .await?
+                    .with_compression(compression, compression_threshold)
+                    .with_buffer_capacity(buffer_capacity);
+                if let Some(codec) = codec {
+                    inner_publisher = inner_publisher.with_codec(codec);
+                }
+                let publisher = Publisher::new(topic_name.clone(), Box::new(inner_publisher))
+                    .with_rate_limit(rate_limit);
+                (Arc::new(publisher), Some(advertisement))
+            }
+            // Only the builders, which need Zenoh, set the other options
+            None => {
+                let key = Node::transport_key(&topic_name);
+                let publisher = match codec {
+                    Some(codec) => {
+                        self.transport
+                            .create_publisher_with_codec(key, codec)
+                            .await?
+                    }
+                    None => self.transport.create_publisher(key).await?,
+                };
+                (publisher, None)
+            }
+        };
+
+        // Re-check under lock to handle concurrent creation
+        let mut publishers = self.publishers.lock().unwrap();
+        if publishers.contains_key(&topic_name) {
+            return Err(Error::topic_already_exists(&topic_name, &self.name));
+        }
+        let probed = publisher.clone();
+        publishers.insert(
+            topic_name,
+            Registration::topic::<M>(Box::new(publisher.clone()))
+                .advertised(advertisement)
+                .probed(move |diagnostics| diagnostics.add_publisher(&probed.stats())),
+        );
+
+        Ok(publisher)
+    }
+
+    /// Creates a subscriber for the given topic with a callback
+    pub async fn create_subscriber<M: Message, F>(
+        &self,
+        topic: &str,
+        qos: QosProfile,
+        callback: F,
+    ) -> Result<Arc<Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        if self.transport.as_zenoh().is_none() {
+            return self.create_queued_subscriber(topic, callback).await;
+        }
+        let callback = move |_topic: String, messages: Vec<M>| {
+            messages.into_iter().for_each(&callback);
+        };
+        let sink = DecodedSink::new(M::ENCODING, callback);
+        self.create_monitored_subscriber::<M, _>(topic, qos, sink, SubscriberOptions::default())
+            .await
+    }
+
+    /// Creates a subscriber through the [`Transport`] trait, whose callback is
+    /// queued on the executor for each message
+    async fn create_queued_subscriber<M: Message, F>(
+        &self,
+        topic: &str,
+        callback: F,
+    ) -> Result<Arc<Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        let topic_name = self.resolve_name(topic);
+        validate_name(&topic_name)?;
+        self.topic_policy.check(&self.name, &topic_name)?;
+        Self::check_encoding_supported::<M>(M::ENCODING)?;
 
-        // Fast-path rejection before expensive transport call
-        if self.publishers.lock().unwrap().contains_key(&topic_name) {
+        if self.subscribers.lock().unwrap().contains_key(&topic_name) {
             return Err(Error::topic_already_exists(&topic_name, &self.name));
         }
 
-        let inner_publisher = self
+        let callback = Arc::new(callback);
+        let executor = self.executor.clone();
+        // Keeps the callbacks of this subscriber in order on a multi-threaded spin
+        let key = ordering_key();
+        let subscriber = self
             .transport
-            .create_publisher::<M>(&topic_name, &qos)
+            .create_subscriber(Node::transport_key(&topic_name), move |message: M| {
+                let callback = callback.clone();
+                executor.enqueue_ordered(key, Box::new(move || callback(message)));
+            })
             .await?;
-        let publisher = Arc::new(Publisher::new(
-            topic_name.clone(),
-            Box::new(inner_publisher),
-        ));
 
-        // Re-check under lock to handle concurrent creation
-        let mut publishers = self.publishers.lock().unwrap();
-        if publishers.contains_key(&topic_name) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        if subscribers.contains_key(&topic_name) {
             return Err(Error::topic_already_exists(&topic_name, &self.name));
         }
-        publishers.insert(topic_name, Box::new(publisher.clone()));
+        let probed = subscriber.clone();
+        subscribers.insert(
+            topic_name,
+            Registration::topic::<M>(Box::new(subscriber.clone()))
+                .probed(move |diagnostics| diagnostics.add_subscriber(&probed.stats(), 0)),
+        );
 
-        Ok(publisher)
+        Ok(subscriber)
     }
 
-    /// Creates a subscriber for the given topic with a callback
-    pub async fn create_subscriber<M: Message, F>(
+    /// Creates a subscriber on a topic or pattern, with a callback for missed QoS deadlines
+    ///
+    /// The callback receives the topic and the messages of each sample. `*` and
+    /// `**` chunks are only accepted if `wildcards` is set.
+    async fn create_monitored_subscriber<M: Message, S: SampleSink>(
         &self,
         topic: &str,
-        _qos: QosProfile,
-        callback: F,
-    ) -> Result<Arc<Subscriber>>
-    where
-        F: Fn(M) + Send + Sync + 'static,
-    {
-        let topic_name = topic.to_string();
+        qos: QosProfile,
+        sink: S,
+        options: SubscriberOptions,
+    ) -> Result<Arc<Subscriber>> {
+        let topic_name = self.resolve_name(topic);
+        if options.wildcards {
+            validate_pattern(&topic_name)?;
+        } else {
+            validate_name(&topic_name)?;
+        }
+        self.topic_policy.check(&self.name, &topic_name)?;
+        Self::check_encoding_supported::<M>(sink.encoding())?;
 
         if self.subscribers.lock().unwrap().contains_key(&topic_name) {
             return Err(Error::topic_already_exists(&topic_name, &self.name));
         }
 
         let inner_subscriber = self
-            .transport
-            .create_subscriber::<M, F>(&topic_name, callback, Some(self.executor.clone()))
+            .zenoh()?
+            .create_sink_subscriber::<M, S>(
+                Node::transport_key(&topic_name),
+                &qos,
+                sink,
+                options
+                    .channel_drops
+                    .is_none()
+                    .then(|| self.executor.clone()),
+                SinkOptions {
+                    dedup: options.dedup,
+                    handler: options.handler,
+                    ack: options.ack,
+                    max_message_size: options.max_message_size,
+                },
+            )
+            .await?;
+        if let Some(on_deadline_missed) = options.on_deadline_missed {
+            inner_subscriber.set_deadline_callback(on_deadline_missed);
+        }
+        let advertisement = self
+            .advertise_topic::<M>(&topic_name, "subscriber", &qos)
             .await?;
         let subscriber = Arc::new(Subscriber::new(
             topic_name.clone(),
             Box::new(inner_subscriber),
         ));
+        let qos_watch = if options.wildcards {
+            None
+        } else {
+            Some(self.watch_publisher_qos(&topic_name, qos).await?)
+        };
 
         let mut subscribers = self.subscribers.lock().unwrap();
         if subscribers.contains_key(&topic_name) {
             return Err(Error::topic_already_exists(&topic_name, &self.name));
         }
-        subscribers.insert(topic_name, Box::new(subscriber.clone()));
+        let probed = subscriber.clone();
+        let channel_drops = options.channel_drops;
+        subscribers.insert(
+            topic_name,
+            Registration::topic::<M>(Box::new(subscriber.clone()))
+                .advertised([advertisement])
+                .qos_watched(qos_watch)
+                .probed(move |diagnostics| {
+                    let dropped = channel_drops.as_ref().map_or(0, |drops| drops.count());
+                    diagnostics.add_subscriber(&probed.stats(), dropped);
+                }),
+        );
 
         Ok(subscriber)
     }
 
+    /// Warns about the publishers of a topic whose QoS profile is incompatible
+    /// with the `qos` of a subscriber, for as long as the returned watch is kept
+    ///
+    /// The publishers' liveliness tokens are watched, and every time one appears
+    /// the advertisements on the topic are queried in the background, so neither
+    /// creating the subscriber nor the liveliness callback waits for them. Each
+    /// advertisement is checked once, and publishers that do not advertise a QoS
+    /// profile are skipped.
+    async fn watch_publisher_qos(
+        &self,
+        topic_name: &str,
+        qos: QosProfile,
+    ) -> Result<zenoh::pubsub::Subscriber<()>> {
+        let zenoh = self.zenoh()?;
+        let session = zenoh.session().clone();
+        let topic_key = Node::topic_key(topic_name);
+        let name = topic_name.to_string();
+        let checked = Arc::new(Mutex::new(HashSet::new()));
+        let runtime = tokio::runtime::Handle::current();
+        let span = tracing::Span::current();
+        zenoh
+            .watch_publishers(Node::transport_key(topic_name), move || {
+                let check = Self::check_publisher_qos(
+                    session.clone(),
+                    topic_key.clone(),
+                    name.clone(),
+                    qos.clone(),
+                    checked.clone(),
+                );
+                runtime.spawn(check.instrument(span.clone()));
+            })
+            .await
+    }
+
+    /// Queries the advertisements on a topic and warns about the publishers
+    /// whose QoS profile is incompatible with `qos`, skipping those in `checked`
+    async fn check_publisher_qos(
+        session: Arc<zenoh::Session>,
+        topic_key: String,
+        topic_name: String,
+        qos: QosProfile,
+        checked: Arc<Mutex<HashSet<String>>>,
+    ) {
+        let Ok(key_expr) = zenoh::key_expr::KeyExpr::try_from(topic_key) else {
+            return;
+        };
+        let selector = zenoh::query::Selector::from((
+            key_expr,
+            zenoh::query::Parameters::from(ZenohTransport::DISCOVERY_PARAMETER),
+        ));
+        // Publishers and subscribers all answer on the topic key, so replies
+        // must not be consolidated
+        let replies = match session
+            .get(selector)
+            .target(zenoh::query::QueryTarget::All)
+            .consolidation(zenoh::query::ConsolidationMode::None)
+            .timeout(Node::QOS_CHECK_TIMEOUT)
+            .await
+        {
+            Ok(replies) => replies,
+            Err(e) => {
+                tracing::debug!("Failed to discover the publishers of {}: {}", topic_name, e);
+                return;
+            }
+        };
+        while let Ok(reply) = replies.recv_async().await {
+            let Ok(sample) = reply.result() else {
+                continue;
+            };
+            let Ok(info) =
+                serde_json::from_slice::<serde_json::Value>(&sample.payload().to_bytes())
+            else {
+                continue;
+            };
+            if info["role"] != "publisher" {
+                continue;
+            }
+            // Replies carry the ID of the advertisement, which tells apart
+            // publishers of different sessions advertising the same info
+            let advertisement = format!("{:?} {info}", reply.replier_id());
+            if !checked
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(advertisement)
+            {
+                continue;
+            }
+            let Some(offered) = info
+                .get("qos")
+                .and_then(|qos| QosProfile::deserialize(qos).ok())
+            else {
+                continue;
+            };
+            if let Compatibility::Incompatible { reason } = offered.compatible_with(&qos) {
+                tracing::warn!(
+                        "Subscriber on {} has a QoS profile incompatible with the publisher of node {}: {}",
+                        topic_name,
+                        info["node"],
+                        reason
+                    );
+            }
+        }
+    }
+
     /// Creates a service for the given name with a handler
     pub async fn create_service<Req: Message, Res: Message, F>(
         &self,
@@ -373,7 +2157,39 @@ impl Node {
     where
         F: Fn(Req) -> Result<Res> + Send + Sync + 'static,
     {
-        let full_service_name = service_name.to_string();
+        self.create_contextual_service(service_name, move |request, _| handler(request))
+            .await
+    }
+
+    /// Creates a service whose handler also receives the context of each request
+    async fn create_contextual_service<Req: Message, Res: Message, F>(
+        &self,
+        service_name: &str,
+        handler: F,
+    ) -> Result<Arc<Service>>
+    where
+        F: Fn(Req, RequestContext) -> Result<Res> + Send + Sync + 'static,
+    {
+        self.create_dispatching_service(
+            service_name,
+            RequestDispatch::Handler(Box::new(handler)),
+            DEFAULT_MAX_MESSAGE_SIZE,
+        )
+        .await
+    }
+
+    /// Creates a service handing its requests over as set by `dispatch`, which
+    /// rejects requests larger than `max_message_size` bytes
+    async fn create_dispatching_service<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+        dispatch: RequestDispatch<Req, Res>,
+        max_message_size: usize,
+    ) -> Result<Arc<Service>> {
+        let full_service_name = self.resolve_name(service_name);
+        validate_name(&full_service_name)?;
+        debug_check_framing::<Req>();
+        debug_check_framing::<Res>();
 
         if self
             .services
@@ -387,14 +2203,37 @@ impl Node {
             ));
         }
 
-        let inner_service = self
-            .transport
-            .create_service::<Req, Res, F>(&full_service_name, handler)
-            .await?;
-        let service = Arc::new(Service::new(
-            full_service_name.clone(),
-            Box::new(inner_service),
-        ));
+        let key = Node::transport_key(&full_service_name);
+        let (service, advertisements) = match (self.transport.as_zenoh(), dispatch) {
+            (Some(zenoh), dispatch) => {
+                let inner_service = zenoh
+                    .create_dispatching_service(key, dispatch, max_message_size)
+                    .await?;
+                let advertisements = vec![
+                    self.advertise_service::<Req, Res>(&full_service_name)
+                        .await?,
+                    self.advertise_service_types::<Req, Res>(&full_service_name)
+                        .await?,
+                ];
+                let service = Service::new(full_service_name.clone(), Box::new(inner_service));
+                (Arc::new(service), advertisements)
+            }
+            (None, RequestDispatch::Handler(handler)) => {
+                let service = self
+                    .transport
+                    .create_service(key, move |request| {
+                        handler(request, RequestContext::default())
+                    })
+                    .await?;
+                (service, Vec::new())
+            }
+            (None, RequestDispatch::Stream(_)) => {
+                return Err(Error::node(
+                    &self.name,
+                    "request streams are only supported on the Zenoh transport",
+                ))
+            }
+        };
 
         let mut services = self.services.lock().unwrap();
         if services.contains_key(&full_service_name) {
@@ -403,44 +2242,114 @@ impl Node {
                 &self.name,
             ));
         }
-        services.insert(full_service_name, Box::new(service.clone()));
+        services.insert(
+            full_service_name,
+            Registration::service::<Req, Res>(Box::new(service.clone())).advertised(advertisements),
+        );
 
         Ok(service)
     }
 
     /// Creates a client for the given service name
+    ///
+    /// The QoS deadline, when set, bounds each call attempt. Any number of
+    /// clients can be created for the same service.
     pub fn create_client<Req: Message, Res: Message>(
         &self,
         service_name: &str,
+        qos: QosProfile,
     ) -> Result<Arc<Client<Req, Res>>> {
-        // Use the service name as provided by the user (global services by default)
-        let full_service_name = service_name.to_string();
+        self.register_client(service_name, qos, None)
+            .map(|(client, _)| client)
+    }
 
-        // Check if the client already exists
-        let mut clients = self.clients.lock().unwrap();
-        if clients.contains_key(&full_service_name) {
-            return Err(Error::service_already_exists(
-                &full_service_name,
-                &self.name,
-            ));
-        }
+    /// Creates a client and registers it under a key unique to the client
+    fn register_client<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+        qos: QosProfile,
+        retry: Option<RetryPolicy>,
+    ) -> Result<(Arc<Client<Req, Res>>, String)> {
+        static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(0);
+
+        let full_service_name = self.resolve_name(service_name);
+        validate_name(&full_service_name)?;
+        debug_check_framing::<Req>();
+        debug_check_framing::<Res>();
 
         // Create the client
-        let inner_client = self
-            .transport
-            .create_client::<Req, Res>(&full_service_name)?;
-        let client = Arc::new(Client::new(
-            full_service_name.clone(),
-            Box::new(inner_client),
-        ));
+        let key = Node::transport_key(&full_service_name);
+        let client = match self.transport.as_zenoh() {
+            Some(zenoh) => {
+                let inner_client = zenoh.create_retrying_client::<Req, Res>(key, &qos, retry)?;
+                Arc::new(Client::new(
+                    full_service_name.clone(),
+                    Box::new(inner_client),
+                ))
+            }
+            None => self.transport.create_client(key)?,
+        };
 
         // Store the client
-        clients.insert(full_service_name, Box::new(client.clone()));
+        let id = NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed);
+        let key = format!("{full_service_name}#{id}");
+        self.clients.lock().unwrap().insert(
+            key.clone(),
+            Registration::service::<Req, Res>(Box::new(client.clone())),
+        );
+
+        Ok((client, key))
+    }
 
-        Ok(client)
+    /// Creates a timer that invokes the callback every `period`
+    ///
+    /// The first tick fires one period after creation. The callback runs on a
+    /// Tokio task, so it does not require `spin()` to be called.
+    pub fn create_timer<F>(&self, period: Duration, mut callback: F) -> Result<TimerHandle>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        if period.is_zero() {
+            return Err(Error::configuration("Timer period must be non-zero"));
+        }
+
+        let task = tokio::spawn(async move {
+            let start = tokio::time::Instant::now() + period;
+            let mut interval = tokio::time::interval_at(start, period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                callback();
+            }
+        });
+
+        tracing::debug!(
+            "Timer created on node '{}' (period: {:?})",
+            self.name,
+            period
+        );
+
+        Ok(TimerHandle::new(period, task.abort_handle()))
+    }
+
+    /// Returns the descriptors of the declared parameters, sorted by name
+    pub fn parameter_descriptors(&self) -> Vec<ParameterDescriptor> {
+        let mut descriptors: Vec<ParameterDescriptor> = self
+            .parameter_descriptors
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect();
+        descriptors.sort_by(|a, b| a.name.cmp(&b.name));
+        descriptors
     }
 
     /// Sets a parameter
+    ///
+    /// Returns [`Error::Parameter`] if the parameter was declared with a range
+    /// that the value falls outside of, or declared strict with another type.
+    /// Other parameters accept values of any type.
     pub fn set_parameter<
         T: serde::Serialize + serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
     >(
@@ -448,9 +2357,17 @@ impl Node {
         name: &str,
         value: T,
     ) -> Result<()> {
-        let mut parameters = self.parameters.lock().unwrap();
-        parameters.insert(name.to_string(), Parameter::new(name, value)?);
-        Ok(())
+        store_parameter(&self.parameters, &self.parameter_descriptors, name, value)
+    }
+
+    /// Deletes a parameter
+    ///
+    /// A declared parameter falls back to its default value, and stays declared.
+    /// Other parameters are removed, so getting them afterwards fails with
+    /// "Parameter not found". Returns [`Error::Parameter`] if the parameter has
+    /// no value to delete.
+    pub fn delete_parameter(&self, name: &str) -> Result<()> {
+        remove_parameter(&self.parameters, &self.parameter_descriptors, name)
     }
 
     /// Gets a parameter
@@ -461,10 +2378,77 @@ impl Node {
         let parameters = self.parameters.lock().unwrap();
         parameters
             .get(name)
-            .ok_or_else(|| Error::parameter(name, "Parameter not found"))?
+            .ok_or_else(|| Error::parameter(name, PARAMETER_NOT_FOUND))?
             .get_value()
     }
 
+    /// Returns the current values of all parameters as JSON, keyed by name
+    ///
+    /// The values are read under a single lock, so the snapshot is consistent
+    /// even while other tasks set parameters.
+    pub fn parameters_snapshot(&self) -> HashMap<String, serde_json::Value> {
+        let parameters = self.parameters.lock().unwrap();
+        parameters
+            .iter()
+            .map(|(name, parameter)| (name.clone(), parameter.to_json()))
+            .collect()
+    }
+
+    /// Saves all parameters to a JSON file, as an object mapping names to values
+    pub fn save_parameters(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let path = path.as_ref();
+        let values = {
+            let parameters = self.parameters.lock().unwrap();
+            parameters
+                .iter()
+                .map(|(name, parameter)| Ok((name.clone(), parameter.get_value()?)))
+                .collect::<Result<std::collections::BTreeMap<String, serde_json::Value>>>()?
+        };
+
+        let json = serde_json::to_string_pretty(&values)
+            .map_err(|e| Error::node(&self.name, format!("Failed to serialize parameters: {e}")))?;
+        std::fs::write(path, json).map_err(|e| {
+            Error::node(
+                &self.name,
+                format!("Failed to write parameters to {}: {e}", path.display()),
+            )
+        })
+    }
+
+    /// Loads parameters from a JSON file written by [`Node::save_parameters`]
+    ///
+    /// Every parameter in the file is set, keeping the JSON type of its value.
+    /// Values are checked against the declared ranges before any of them is set.
+    pub fn load_parameters(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            Error::node(
+                &self.name,
+                format!("Failed to read parameters from {}: {e}", path.display()),
+            )
+        })?;
+        let values: std::collections::BTreeMap<String, serde_json::Value> =
+            serde_json::from_str(&json).map_err(|e| {
+                Error::node(
+                    &self.name,
+                    format!("Invalid parameter file {}: {e}", path.display()),
+                )
+            })?;
+
+        {
+            let descriptors = self.parameter_descriptors.lock().unwrap();
+            for (name, value) in &values {
+                if let Some(descriptor) = descriptors.get(name) {
+                    descriptor.check(value)?;
+                }
+            }
+        }
+        for (name, value) in values {
+            self.set_parameter(&name, value)?;
+        }
+        Ok(())
+    }
+
     /// Spins the node once, processing all pending callbacks
     ///
     /// Returns the number of callbacks that were processed.
@@ -472,6 +2456,19 @@ impl Node {
         Ok(self.executor.process_pending())
     }
 
+    /// Spins the node for at most `budget`, processing pending callbacks until
+    /// none are left or the budget has elapsed
+    ///
+    /// This suits an event loop that owns the thread, e.g. of a game engine or
+    /// GUI, and gives the node a slice of each frame. Callbacks queued while
+    /// spinning are processed too if the budget allows. A running callback is
+    /// not interrupted, so the last one may end after the budget.
+    ///
+    /// Returns the number of callbacks that were processed.
+    pub fn spin_once_timeout(&self, budget: Duration) -> Result<usize> {
+        Ok(self.executor.process_for(budget))
+    }
+
     /// Spins the node, processing callbacks until the node is shutdown
     ///
     /// This method will block until `shutdown()` is called on the node.
@@ -489,6 +2486,32 @@ impl Node {
         Ok(())
     }
 
+    /// Spins the node like [`Node::spin`], running callbacks on a pool of
+    /// `num_threads` worker threads
+    ///
+    /// The callbacks of one subscriber run one at a time, in the order its
+    /// messages arrived, while callbacks of different subscribers may run
+    /// concurrently. Callbacks must therefore be safe to run in parallel with
+    /// those of other subscribers. Once the node is shut down, the callbacks
+    /// already dispatched are run before this method returns.
+    pub async fn spin_multithreaded(&self, num_threads: usize) -> Result<()> {
+        let pool = WorkerPool::new(num_threads);
+        while !self.executor.is_shutdown() {
+            // Register interest before draining, as in spin()
+            let notified = self.executor.notified();
+            let callbacks = self.executor.take_pending();
+            if callbacks.is_empty() {
+                let _ = tokio::time::timeout(Duration::from_secs(1), notified).await;
+            }
+            for (key, callback) in callbacks {
+                pool.submit(key, callback);
+            }
+        }
+        tokio::task::spawn_blocking(move || pool.join())
+            .await
+            .map_err(|e| Error::node(&self.name, format!("Executor pool failed: {e}")))
+    }
+
     /// Shuts down the node
     ///
     /// This will cause `spin()` to return and prevent new callbacks from being queued.
@@ -496,31 +2519,90 @@ impl Node {
         self.executor.shutdown();
     }
 
+    /// Shuts down the node once the requests its services are handling are answered
+    ///
+    /// The node's services stop accepting requests right away and are
+    /// unregistered. Requests they are already handling, including those
+    /// waiting for the [`Responder`](crate::Responder) of a [`RequestStream`],
+    /// are given up to `timeout` to be answered before the node is shut down as
+    /// with [`Node::shutdown`]. Returns [`Error::Timeout`] if some are still
+    /// unanswered by then, after shutting down the node all the same.
+    pub async fn shutdown_graceful(&self, timeout: Duration) -> Result<()> {
+        let services: Vec<Registration> = self
+            .services
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .map(|(_, registration)| registration)
+            .collect();
+        for registration in &services {
+            if let Some(service) = registration.entity.downcast_ref::<Arc<Service>>() {
+                if let Err(e) = service.close() {
+                    tracing::warn!("Failed to close service {}: {}", service.name(), e);
+                }
+            }
+        }
+
+        // The registrations may hold the only reference to a service, whose
+        // task answering the in-flight requests ends when it is dropped
+        let drained = tokio::time::timeout(timeout, async {
+            while self
+                .transport
+                .as_zenoh()
+                .is_some_and(|zenoh| zenoh.in_flight_requests() > 0)
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .is_ok();
+        drop(services);
+        self.shutdown();
+
+        if drained {
+            tracing::debug!("Node '{}' shut down gracefully", self.name);
+            Ok(())
+        } else {
+            Err(Error::timeout(
+                format!(
+                    "waiting for the requests of node '{}' to be answered",
+                    self.name
+                ),
+                timeout.as_millis() as u64,
+            ))
+        }
+    }
+
     /// Returns true if the node has been shutdown
     pub fn is_shutdown(&self) -> bool {
         self.executor.is_shutdown()
     }
 
-    // Builder pattern methods for simplified API
-
-    /// Creates a publisher builder for the given topic
-    pub fn publisher<M: Message>(&self, topic: &str) -> PublisherBuilder<'_, M> {
-        PublisherBuilder::new(self, topic)
-    }
-
-    /// Creates a subscriber builder for the given topic
-    pub fn subscriber<M: Message>(&self, topic: &str) -> SubscriberBuilder<'_, M> {
-        SubscriberBuilder::new(self, topic)
+    /// Returns a stream of the connectivity changes of the node's transport
+    ///
+    /// Each call returns an independent stream that only reports changes from then on.
+    pub fn transport_events(&self) -> TransportEvents {
+        self.transport.transport_events()
     }
 
-    /// Creates a service builder for the given service name
-    pub fn service<Req: Message, Res: Message>(&self, name: &str) -> ServiceBuilder<'_, Req, Res> {
-        ServiceBuilder::new(self, name)
+    /// Returns true until the node is shut down
+    ///
+    /// Intended as a loop condition, e.g. `while node.ok() { ... }`.
+    pub fn ok(&self) -> bool {
+        !self.is_shutdown()
     }
 
-    /// Creates a client builder for the given service name
-    pub fn client<Req: Message, Res: Message>(&self, name: &str) -> ClientBuilder<'_, Req, Res> {
-        ClientBuilder::new(self, name)
+    /// Shuts down the node when the process receives Ctrl+C (SIGINT)
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn shutdown_on_ctrl_c(&self) {
+        let executor = self.executor.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Received Ctrl+C, shutting down");
+                executor.shutdown();
+            }
+        });
     }
 
     // Simplified convenience methods
@@ -530,17 +2612,231 @@ impl Node {
         self.create_publisher(topic, QosProfile::default()).await
     }
 
-    /// Creates a subscriber with default QoS and a callback
-    pub async fn subscribe<M: Message, F>(
-        &self,
-        topic: &str,
-        callback: F,
-    ) -> Result<Arc<Subscriber>>
-    where
-        F: Fn(M) + Send + Sync + 'static,
-    {
-        self.create_subscriber(topic, QosProfile::default(), callback)
-            .await
+    /// Creates a subscriber with default QoS and a callback
+    pub async fn subscribe<M: Message, F>(
+        &self,
+        topic: &str,
+        callback: F,
+    ) -> Result<Arc<Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        self.create_subscriber(topic, QosProfile::default(), callback)
+            .await
+    }
+}
+
+impl<Tr: Transport> Drop for Node<Tr> {
+    /// Warns in debug builds about the entities still registered when a node
+    /// that was not shut down is dropped
+    fn drop(&mut self) {
+        if cfg!(debug_assertions) && !self.is_shutdown() {
+            let counts = self.resource_counts();
+            if counts.total() > 0 {
+                tracing::warn!(
+                    "Node '{}' dropped with resources still registered: {:?}",
+                    self.name,
+                    counts
+                );
+            }
+        }
+    }
+}
+
+/// Builder for creating nodes with explicit Zenoh settings
+pub struct NodeBuilder {
+    name: String,
+    config: zenoh::config::Config,
+    mode: Option<zenoh::config::WhatAmI>,
+    connect: Vec<String>,
+    listen: Vec<String>,
+    session: Option<Arc<zenoh::Session>>,
+    span_level: Level,
+    /// How long to wait for a peer or router before failing to build
+    require_connectivity: Option<Duration>,
+    /// Patterns of the topics the node may use, if restricted
+    allow_topics: Vec<String>,
+    /// Patterns of the topics the node may not use
+    deny_topics: Vec<String>,
+    /// Period of the heartbeat, if enabled
+    heartbeat: Option<Duration>,
+}
+
+impl NodeBuilder {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            config: zenoh::config::Config::default(),
+            mode: None,
+            connect: Vec::new(),
+            listen: Vec::new(),
+            session: None,
+            span_level: Level::INFO,
+            require_connectivity: None,
+            allow_topics: Vec::new(),
+            deny_topics: Vec::new(),
+            heartbeat: None,
+        }
+    }
+
+    /// Adds an endpoint to connect to, e.g. `tcp/192.168.1.10:7447`
+    pub fn connect(mut self, endpoint: &str) -> Self {
+        self.connect.push(endpoint.to_string());
+        self
+    }
+
+    /// Adds an endpoint to listen on, e.g. `tcp/0.0.0.0:7447`
+    pub fn listen(mut self, endpoint: &str) -> Self {
+        self.listen.push(endpoint.to_string());
+        self
+    }
+
+    /// Sets the Zenoh mode, e.g. [`WhatAmI::Client`](zenoh::config::WhatAmI::Client)
+    /// to route all traffic through a router
+    pub fn mode(mut self, mode: zenoh::config::WhatAmI) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the Zenoh configuration to start from
+    ///
+    /// The mode and endpoints set on the builder override those of the configuration.
+    pub fn config(mut self, config: zenoh::config::Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Builds the node over an existing session, e.g. from [`SharedSession`]
+    ///
+    /// The session is used as is, so the configuration, mode and endpoints set
+    /// on the builder are ignored.
+    ///
+    /// [`SharedSession`]: crate::transport::SharedSession
+    pub fn session(mut self, session: Arc<zenoh::Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Sets the level of the `tracing` spans wrapping the node's operations
+    ///
+    /// Publishes, received messages, service requests and client calls run in
+    /// spans carrying the node name, the topic or service, and a sequence number.
+    /// Spans are only recorded when the subscriber's filter enables their level,
+    /// so e.g. [`Level::DEBUG`] leaves them out of logs filtered at `info`.
+    /// Defaults to [`Level::INFO`].
+    pub fn span_level(mut self, level: Level) -> Self {
+        self.span_level = level;
+        self
+    }
+
+    /// Fails to build the node unless a peer or router is discovered within `timeout`
+    ///
+    /// Opening a session succeeds even in complete isolation, so without this a
+    /// misconfigured deployment only shows up later, e.g. as service timeouts.
+    pub fn require_connectivity(mut self, timeout: Duration) -> Self {
+        self.require_connectivity = Some(timeout);
+        self
+    }
+
+    /// Restricts the node to the topics matching one of `patterns`
+    ///
+    /// Patterns are key expressions matched against resolved topic names, e.g.
+    /// `sensors/**` or `/robot1/*/pose`. Creating a publisher or subscriber on
+    /// any other topic fails with [`Error::Configuration`]. This is a safeguard
+    /// against mistakes in the node's own code, not access control: other
+    /// sessions are not restricted. Can be called several times to add patterns.
+    pub fn allow_topics<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.allow_topics
+            .extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Forbids the node from using the topics matching one of `patterns`
+    ///
+    /// Denied patterns take precedence over [`Self::allow_topics`], and a
+    /// wildcard subscriber is refused if its pattern matches any denied topic.
+    pub fn deny_topics<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.deny_topics
+            .extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Publishes a [`Heartbeat`] every [`Node::DEFAULT_HEARTBEAT_PERIOD`] while
+    /// the node is alive
+    ///
+    /// Tools such as `zenobuf-cli doctor` report nodes whose last heartbeat is
+    /// too old as stale, which catches nodes that hang without leaving the network.
+    pub fn heartbeat(self) -> Self {
+        self.heartbeat_period(Node::DEFAULT_HEARTBEAT_PERIOD)
+    }
+
+    /// Publishes a [`Heartbeat`] every `period` while the node is alive
+    ///
+    /// See [`Self::heartbeat`].
+    pub fn heartbeat_period(mut self, period: Duration) -> Self {
+        self.heartbeat = Some(period);
+        self
+    }
+
+    /// Builds the node, opening a Zenoh session with the resulting configuration
+    /// unless one was given
+    ///
+    /// With [`Self::require_connectivity`], returns [`Error::Network`] if no peer
+    /// or router is discovered in time. Returns [`Error::Configuration`] if a
+    /// topic pattern is invalid or the heartbeat period is zero.
+    pub async fn build(self) -> Result<Node> {
+        let topic_policy = TopicPolicy::new(&self.allow_topics, &self.deny_topics)?;
+        let heartbeat = self.heartbeat;
+        let mut node = self.build_node().await?;
+        node.topic_policy = topic_policy;
+        if let Some(period) = heartbeat {
+            node._heartbeat = Some(node.start_heartbeat(period)?);
+        }
+        Ok(node)
+    }
+
+    /// Builds the node without its topic policy
+    async fn build_node(self) -> Result<Node> {
+        if let Some(session) = self.session {
+            let mut transport = ZenohTransport::from_session(session);
+            transport.spans_mut().set_level(self.span_level);
+            if let Some(timeout) = self.require_connectivity {
+                transport.wait_for_peers(timeout).await?;
+            }
+            return Node::with_transport(&self.name, transport).await;
+        }
+
+        let mut config = self.config;
+        let mut settings = Vec::new();
+        if let Some(mode) = self.mode {
+            settings.push(("mode", serde_json::json!(mode.to_string())));
+        }
+        if !self.connect.is_empty() {
+            settings.push(("connect/endpoints", serde_json::json!(self.connect)));
+        }
+        if !self.listen.is_empty() {
+            settings.push(("listen/endpoints", serde_json::json!(self.listen)));
+        }
+        for (key, value) in settings {
+            config
+                .insert_json5(key, &value.to_string())
+                .map_err(|e| Error::configuration(format!("Invalid {key}: {e}")))?;
+        }
+
+        let mut transport = ZenohTransport::with_config(config).await?;
+        transport.spans_mut().set_level(self.span_level);
+        if let Some(timeout) = self.require_connectivity {
+            transport.wait_for_peers(timeout).await?;
+        }
+        Node::with_transport(&self.name, transport).await
     }
 }
 
@@ -549,7 +2845,13 @@ pub struct PublisherBuilder<'a, M: Message> {
     node: &'a Node,
     topic: String,
     qos: QosProfile,
-    _phantom: PhantomData<M>,
+    encoding: Encoding,
+    codec: Option<SharedCodec<M>>,
+    compression: Compression,
+    compression_threshold: usize,
+    /// Maximum rate in Hz and whether publishing waits for it
+    max_rate: Option<(f64, bool)>,
+    buffer_capacity: usize,
 }
 
 impl<'a, M: Message> PublisherBuilder<'a, M> {
@@ -558,7 +2860,12 @@ impl<'a, M: Message> PublisherBuilder<'a, M> {
             node,
             topic: topic.to_string(),
             qos: QosProfile::default(),
-            _phantom: PhantomData,
+            encoding: M::ENCODING,
+            codec: None,
+            compression: Compression::None,
+            compression_threshold: Compression::DEFAULT_THRESHOLD,
+            max_rate: None,
+            buffer_capacity: 0,
         }
     }
 
@@ -574,15 +2881,17 @@ impl<'a, M: Message> PublisherBuilder<'a, M> {
         self
     }
 
-    /// Sets reliability
+    /// Sets reliability, blocking when the network is congested
     pub fn reliable(mut self) -> Self {
         self.qos.reliability = crate::qos::Reliability::Reliable;
+        self.qos.congestion_control = crate::qos::CongestionControl::Block;
         self
     }
 
-    /// Sets best effort reliability
+    /// Sets best effort reliability, dropping messages when the network is congested
     pub fn best_effort(mut self) -> Self {
         self.qos.reliability = crate::qos::Reliability::BestEffort;
+        self.qos.congestion_control = crate::qos::CongestionControl::Drop;
         self
     }
 
@@ -592,10 +2901,145 @@ impl<'a, M: Message> PublisherBuilder<'a, M> {
         self
     }
 
+    /// Sets the priority of published messages
+    pub fn with_priority(mut self, priority: crate::qos::Priority) -> Self {
+        self.qos.priority = priority;
+        self
+    }
+
+    /// Sets the congestion control mode
+    pub fn with_congestion_control(
+        mut self,
+        congestion_control: crate::qos::CongestionControl,
+    ) -> Self {
+        self.qos.congestion_control = congestion_control;
+        self
+    }
+
+    /// Sends messages in the given encoding instead of the message's own
+    ///
+    /// Use [`Encoding::Cdr`] to interoperate with ROS 2 nodes, which requires
+    /// the message to have a CDR layout.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Encodes messages with `codec` instead of the message's own encoding
+    ///
+    /// The payloads carry the codec's name, and subscribers built without the
+    /// same codec drop them. Takes precedence over [`Self::with_encoding`].
+    pub fn with_codec<C: Codec<M>>(mut self, codec: C) -> Self {
+        self.codec = Some(Arc::new(codec));
+        self
+    }
+
+    /// Compresses published payloads with the given codec
+    ///
+    /// Payloads smaller than the compression threshold, by default
+    /// [`Compression::DEFAULT_THRESHOLD`] bytes, are sent uncompressed.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the size in bytes from which payloads are compressed
+    pub fn with_compression_threshold(mut self, threshold: usize) -> Self {
+        self.compression_threshold = threshold;
+        self
+    }
+
+    /// Publishes at most `hz` messages per second
+    ///
+    /// `publish` drops the messages that come sooner than `1 / hz` after the
+    /// previous one, and `try_publish` returns `Ok(false)` for them. Dropped
+    /// messages are counted in [`PublisherStats::rate_limited`].
+    pub fn max_rate(mut self, hz: f64) -> Self {
+        self.max_rate = Some((hz, false));
+        self
+    }
+
+    /// Publishes at most `hz` messages per second, with `publish` waiting for
+    /// the next free slot instead of dropping messages
+    ///
+    /// The wait blocks the calling thread. `try_publish` never waits, and still
+    /// returns `Ok(false)` for messages exceeding the rate.
+    pub fn max_rate_blocking(mut self, hz: f64) -> Self {
+        self.max_rate = Some((hz, true));
+        self
+    }
+
+    /// Pre-sizes the buffer messages are encoded into to `bytes`
+    ///
+    /// The publisher encodes every message into the same buffer, which grows to
+    /// the largest message encoded so far. Sizing it for the usual message up
+    /// front avoids growing it during the first publishes.
+    pub fn with_buffer_capacity(mut self, bytes: usize) -> Self {
+        self.buffer_capacity = bytes;
+        self
+    }
+
+    /// Publishes the projection `f` of each message instead of the message
+    ///
+    /// Only the smaller message of type `N` is sent, so subscribers needing part
+    /// of a large message do not receive the rest. The topic carries type `N`,
+    /// and subscribers must subscribe as `N`. The other settings carry over to
+    /// the projected publisher, and so does the encoding if it was changed from
+    /// that of `M`.
+    pub fn project<N: Message, F>(self, f: F) -> ProjectedPublisherBuilder<'a, M, N, F>
+    where
+        F: Fn(&M) -> N + Send + Sync + 'static,
+    {
+        let encoding = if self.encoding == M::ENCODING {
+            N::ENCODING
+        } else {
+            self.encoding
+        };
+        ProjectedPublisherBuilder {
+            builder: PublisherBuilder {
+                node: self.node,
+                topic: self.topic,
+                qos: self.qos,
+                encoding,
+                codec: None,
+                compression: self.compression,
+                compression_threshold: self.compression_threshold,
+                max_rate: self.max_rate,
+                buffer_capacity: self.buffer_capacity,
+            },
+            projection: f,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Builds the publisher
+    ///
+    /// Returns [`Error::Configuration`] if the maximum rate is not positive.
     pub async fn build(self) -> Result<PublisherHandle<M>> {
-        let topic = self.topic.clone();
-        let publisher = self.node.create_publisher(&self.topic, self.qos).await?;
+        let rate_limit = self
+            .max_rate
+            .map(|(hz, blocking)| RateLimit::new(hz, blocking))
+            .transpose()?;
+        let publisher = self
+            .node
+            .create_configured_publisher(
+                &self.topic,
+                self.qos,
+                PublisherOptions {
+                    encoding: if self.codec.is_some() {
+                        M::ENCODING
+                    } else {
+                        self.encoding
+                    },
+                    codec: self.codec,
+                    compression: self.compression,
+                    compression_threshold: self.compression_threshold,
+                    rate_limit,
+                    buffer_capacity: self.buffer_capacity,
+                },
+            )
+            .await?;
+        let topic = publisher.topic().to_string();
         Ok(PublisherHandle::new(
             publisher,
             topic,
@@ -604,21 +3048,62 @@ impl<'a, M: Message> PublisherBuilder<'a, M> {
     }
 }
 
+/// Builder for publishers sending a projection of each message, created with
+/// [`PublisherBuilder::project`]
+pub struct ProjectedPublisherBuilder<'a, M: Message, N: Message, F> {
+    builder: PublisherBuilder<'a, N>,
+    projection: F,
+    _phantom: PhantomData<fn(&M)>,
+}
+
+impl<'a, M: Message, N: Message, F> ProjectedPublisherBuilder<'a, M, N, F>
+where
+    F: Fn(&M) -> N + Send + Sync + 'static,
+{
+    /// Builds the publisher
+    ///
+    /// Returns [`Error::Configuration`] if the maximum rate is not positive.
+    pub async fn build(self) -> Result<ProjectedPublisherHandle<M, N>> {
+        let publisher = self.builder.build().await?;
+        Ok(ProjectedPublisherHandle {
+            publisher,
+            projection: Box::new(self.projection),
+        })
+    }
+}
+
 /// Builder for creating subscribers with fluent API
 pub struct SubscriberBuilder<'a, M: Message> {
     node: &'a Node,
     topic: String,
     qos: QosProfile,
-    _phantom: PhantomData<M>,
+    encoding: Encoding,
+    codec: Option<SharedCodec<M>>,
+    on_deadline_missed: Option<DeadlineCallback>,
+    filter: Option<MessageFilter<M>>,
+    dedup: Option<usize>,
+    handler: SubscriberHandler,
+    ack: bool,
+    max_message_size: Option<usize>,
 }
 
+/// Predicate deciding which received messages reach a subscriber callback
+type MessageFilter<M> = Box<dyn Fn(&M) -> bool + Send + Sync>;
+
 impl<'a, M: Message> SubscriberBuilder<'a, M> {
     fn new(node: &'a Node, topic: &str) -> Self {
         Self {
             node,
             topic: topic.to_string(),
             qos: QosProfile::default(),
-            _phantom: PhantomData,
+            encoding: M::ENCODING,
+            codec: None,
+            on_deadline_missed: None,
+            filter: None,
+            dedup: None,
+            handler: SubscriberHandler::Callback,
+            ack: false,
+            max_message_size: None,
         }
     }
 
@@ -652,16 +3137,266 @@ impl<'a, M: Message> SubscriberBuilder<'a, M> {
         self
     }
 
+    /// Expects messages in the given encoding instead of the message's own
+    ///
+    /// Use [`Encoding::Cdr`] to receive messages from ROS 2 nodes, which requires
+    /// the message to have a CDR layout.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Decodes messages with `codec` instead of the message's own encoding
+    ///
+    /// Messages encoded with another codec are dropped with a warning. Takes
+    /// precedence over [`Self::with_encoding`].
+    pub fn with_codec<C: Codec<M>>(mut self, codec: C) -> Self {
+        self.codec = Some(Arc::new(codec));
+        self
+    }
+
+    /// Sets a callback invoked whenever the QoS deadline passes without a message
+    ///
+    /// Has no effect unless the QoS profile sets a deadline. The callback fires
+    /// again for every further deadline period that passes in silence, and misses
+    /// are counted in [`SubscriberStats::deadlines_missed`] whether or not a
+    /// callback is set.
+    pub fn on_deadline_missed<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_deadline_missed = Some(Box::new(callback));
+        self
+    }
+
+    /// Only delivers the messages for which `predicate` returns `true`
+    ///
+    /// The predicate runs on each decoded message before the callback, so
+    /// subscriber statistics still count the messages it rejects. Calling
+    /// `filter` again adds a predicate that must also pass.
+    pub fn filter<P>(mut self, predicate: P) -> Self
+    where
+        P: Fn(&M) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(match self.filter.take() {
+            Some(previous) => Box::new(move |message| previous(message) && predicate(message)),
+            None => Box::new(predicate),
+        });
+        self
+    }
+
+    /// Drops messages already received from the same publisher
+    ///
+    /// Publishers stamp every message with their ID and a sequence number, and
+    /// the subscriber remembers the last [`Self::DEFAULT_DEDUP_WINDOW`] of them.
+    /// Dropped duplicates are counted in [`SubscriberStats::duplicates`].
+    /// Messages from publishers that do not stamp them are always delivered.
+    pub fn dedup(self) -> Self {
+        self.with_dedup_window(Self::DEFAULT_DEDUP_WINDOW)
+    }
+
+    /// Drops duplicates among the last `window` messages, see [`Self::dedup`]
+    pub fn with_dedup_window(mut self, window: usize) -> Self {
+        self.dedup = Some(window);
+        self
+    }
+
+    /// Number of messages remembered by [`Self::dedup`]
+    pub const DEFAULT_DEDUP_WINDOW: usize = 1024;
+
+    /// Sets how samples are buffered before the callback runs
+    ///
+    /// [`SubscriberHandler::Fifo`] and [`SubscriberHandler::Ring`] hold up to the
+    /// QoS depth of samples while a callback is pending, bounding the memory a
+    /// slow callback backs up; the depth must then be at least 1. With a
+    /// [`History::KeepAll`](crate::qos::History::KeepAll) QoS history, they queue
+    /// every sample in an unbounded FIFO instead and the depth is ignored.
+    pub fn with_handler(mut self, handler: SubscriberHandler) -> Self {
+        self.handler = handler;
+        self
+    }
+
+    /// Drops messages larger than `bytes` before decoding them, instead of those
+    /// larger than [`DEFAULT_MAX_MESSAGE_SIZE`]
+    ///
+    /// The limit applies to both the payload as received and its decompressed
+    /// size. Dropped messages are logged and counted in
+    /// [`SubscriberStats::oversized_messages`].
+    pub fn with_max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
     /// Builds the subscriber with a callback
     pub async fn build<F>(self, callback: F) -> Result<SubscriberHandle>
     where
         F: Fn(M) + Send + Sync + 'static,
     {
-        let topic = self.topic.clone();
+        let callback = move |messages: Vec<M>| messages.into_iter().for_each(&callback);
+        self.build_batched(callback).await
+    }
+
+    /// Builds the subscriber with a callback, acknowledging each message to its
+    /// publisher once the callback has run
+    ///
+    /// This resolves [`PublisherHandle::publish_reliable`] calls. Messages from
+    /// publishers that do not stamp them, and messages whose callback panics,
+    /// are not acknowledged.
+    pub async fn build_acking<F>(mut self, callback: F) -> Result<SubscriberHandle>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        self.ack = true;
+        self.build(callback).await
+    }
+
+    /// Builds the subscriber with a callback that receives whole batches
+    ///
+    /// A batch published with [`PublisherHandle::publish_batch`] is delivered as
+    /// one vector, while other messages arrive as single-element vectors.
+    pub async fn build_batched<F>(self, callback: F) -> Result<SubscriberHandle>
+    where
+        F: Fn(Vec<M>) + Send + Sync + 'static,
+    {
+        self.build_with(callback, None).await
+    }
+
+    /// Builds the subscriber with a callback receiving the encoded messages
+    ///
+    /// The callback gets a [`PayloadView`] borrowing the received payload rather
+    /// than a decoded message, so large messages can be inspected or decoded
+    /// lazily with [`PayloadView::decode`] without copying them first. Messages
+    /// of another type are still dropped, but the callback is responsible for
+    /// validating the messages it decodes. Filters and codecs cannot be combined
+    /// with it.
+    pub async fn build_zerocopy<F>(self, callback: F) -> Result<SubscriberHandle>
+    where
+        F: for<'p> Fn(PayloadView<'p>) + Send + Sync + 'static,
+    {
+        if self.filter.is_some() {
+            return Err(Error::configuration(
+                "Zero-copy subscribers do not support filters",
+            ));
+        }
+        if self.codec.is_some() {
+            return Err(Error::configuration(
+                "Zero-copy subscribers do not support codecs",
+            ));
+        }
+        let sink = PayloadSink::new(self.encoding, callback);
+        self.build_sink(sink, None).await
+    }
+
+    /// Builds the subscriber delivering messages into a bounded channel
+    ///
+    /// Messages are sent as soon as they arrive, without spinning the node.
+    /// Messages arriving while `capacity` messages wait in the channel are
+    /// dropped, counted by [`SubscriberHandle::dropped_count`] and reported
+    /// with a throttled warning.
+    pub async fn build_channel(
+        self,
+        capacity: usize,
+    ) -> Result<(SubscriberHandle, mpsc::Receiver<M>)> {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let dropped = Arc::new(DropCounter::default());
+        let counter = dropped.clone();
+        let topic = self.node.resolve_name(&self.topic);
+        let callback = move |messages: Vec<M>| {
+            for message in messages {
+                if let Err(mpsc::error::TrySendError::Full(_)) = sender.try_send(message) {
+                    counter.record(&topic);
+                }
+            }
+        };
+        let mut handle = self.build_with(callback, Some(dropped.clone())).await?;
+        handle.dropped = Some(dropped);
+        Ok((handle, receiver))
+    }
+
+    /// Builds the subscriber keeping the latest messages in a buffer
+    ///
+    /// The buffer holds up to the QoS depth of messages, dropping the oldest
+    /// when full, or every message with a
+    /// [`History::KeepAll`](crate::qos::History::KeepAll) QoS history. It is
+    /// filled when the node spins and emptied with
+    /// [`BufferedSubscriberHandle::drain`].
+    pub async fn build_buffered(self) -> Result<BufferedSubscriberHandle<M>> {
+        let capacity = match self.qos.history {
+            History::KeepLast if self.qos.depth == 0 => {
+                return Err(Error::configuration(
+                    "Buffered subscribers need a QoS depth of at least 1",
+                ));
+            }
+            History::KeepLast => Some(self.qos.depth),
+            History::KeepAll => None,
+        };
+        let buffer = Arc::new(Mutex::new(VecDeque::new()));
+        let sink = buffer.clone();
+        let subscriber = self
+            .build_batched(move |messages: Vec<M>| {
+                let mut buffer = sink.lock().unwrap_or_else(|e| e.into_inner());
+                buffer.extend(messages);
+                if let Some(capacity) = capacity {
+                    let excess = buffer.len().saturating_sub(capacity);
+                    buffer.drain(..excess);
+                }
+            })
+            .await?;
+        Ok(BufferedSubscriberHandle { subscriber, buffer })
+    }
+
+    /// Builds the subscriber, running `callback` when the node spins or, for a
+    /// channel subscriber counting its drops in `channel_drops`, as soon as
+    /// messages arrive
+    async fn build_with<F>(
+        mut self,
+        callback: F,
+        channel_drops: Option<Arc<DropCounter>>,
+    ) -> Result<SubscriberHandle>
+    where
+        F: Fn(Vec<M>) + Send + Sync + 'static,
+    {
+        let filter = self.filter.take();
+        let callback = move |_topic: String, mut messages: Vec<M>| {
+            if let Some(filter) = &filter {
+                messages.retain(|message| filter(message));
+                if messages.is_empty() {
+                    return;
+                }
+            }
+            callback(messages)
+        };
+        let sink = match self.codec.take() {
+            Some(codec) => DecodedSink::new(M::ENCODING, callback).with_codec(codec),
+            None => DecodedSink::new(self.encoding, callback),
+        };
+        self.build_sink(sink, channel_drops).await
+    }
+
+    /// Builds the subscriber handing the samples to `sink`
+    async fn build_sink<S: SampleSink>(
+        self,
+        sink: S,
+        channel_drops: Option<Arc<DropCounter>>,
+    ) -> Result<SubscriberHandle> {
         let subscriber = self
             .node
-            .create_subscriber(&self.topic, self.qos, callback)
+            .create_monitored_subscriber::<M, _>(
+                &self.topic,
+                self.qos,
+                sink,
+                SubscriberOptions {
+                    on_deadline_missed: self.on_deadline_missed,
+                    channel_drops,
+                    dedup: self.dedup,
+                    handler: self.handler,
+                    ack: self.ack,
+                    max_message_size: self.max_message_size,
+                    ..SubscriberOptions::default()
+                },
+            )
             .await?;
+        let topic = subscriber.topic().to_string();
         Ok(SubscriberHandle::new(
             subscriber,
             topic,
@@ -670,10 +3405,140 @@ impl<'a, M: Message> SubscriberBuilder<'a, M> {
     }
 }
 
+/// Builder for creating subscribers on a topic pattern with fluent API
+pub struct WildcardSubscriberBuilder<'a, M: Message> {
+    node: &'a Node,
+    pattern: String,
+    qos: QosProfile,
+    _phantom: PhantomData<M>,
+}
+
+impl<'a, M: Message> WildcardSubscriberBuilder<'a, M> {
+    fn new(node: &'a Node, pattern: &str) -> Self {
+        Self {
+            node,
+            pattern: pattern.to_string(),
+            qos: QosProfile::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the QoS profile
+    pub fn with_qos(mut self, qos: QosProfile) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Sets the QoS preset
+    pub fn with_qos_preset(mut self, preset: QosPreset) -> Self {
+        self.qos = preset.into();
+        self
+    }
+
+    /// Builds the subscriber with a callback receiving the topic and the message
+    pub async fn build<F>(self, callback: F) -> Result<SubscriberHandle>
+    where
+        F: Fn(String, M) + Send + Sync + 'static,
+    {
+        let subscriber = self
+            .node
+            .create_wildcard_subscriber(&self.pattern, self.qos, callback)
+            .await?;
+        let pattern = subscriber.topic().to_string();
+        Ok(SubscriberHandle::new(
+            subscriber,
+            pattern,
+            self.node.subscribers.clone(),
+        ))
+    }
+}
+
+/// Builder for declaring parameters with fluent API
+pub struct ParameterBuilder<'a, T> {
+    node: &'a Node,
+    name: String,
+    default: T,
+    min: Option<f64>,
+    max: Option<f64>,
+    description: Option<String>,
+    strict: bool,
+}
+
+impl<'a, T> ParameterBuilder<'a, T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    fn new(node: &'a Node, name: &str, default: T) -> Self {
+        Self {
+            node,
+            name: name.to_string(),
+            default,
+            min: None,
+            max: None,
+            description: None,
+            strict: false,
+        }
+    }
+
+    /// Restricts numeric values to `min..=max`
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Sets the human-readable description
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Rejects values of another type than the default value's
+    ///
+    /// Without it, setting the parameter may change its type, e.g. from a
+    /// number to a string. Integers are still accepted for a floating-point
+    /// parameter.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Declares the parameter
+    ///
+    /// Returns [`Error::Parameter`] if the default value or an existing value
+    /// violates the constraints.
+    pub fn build(self) -> Result<()> {
+        let mut descriptor = ParameterDescriptor::new(&self.name, &self.default)?;
+        descriptor.min = self.min;
+        descriptor.max = self.max;
+        descriptor.description = self.description;
+        descriptor.strict = self.strict;
+        descriptor.check(&descriptor.default)?;
+
+        let mut parameters = self.node.parameters.lock().unwrap();
+        match parameters.get(&self.name) {
+            Some(existing) => {
+                let value = existing.get_value::<serde_json::Value>()?;
+                descriptor.check(&value)?;
+            }
+            None => {
+                parameters.insert(self.name.clone(), Parameter::new(&self.name, self.default)?);
+            }
+        }
+        self.node
+            .parameter_descriptors
+            .lock()
+            .unwrap()
+            .insert(self.name, descriptor);
+        Ok(())
+    }
+}
+
 /// Builder for creating services with fluent API
 pub struct ServiceBuilder<'a, Req: Message, Res: Message> {
     node: &'a Node,
     name: String,
+    max_message_size: usize,
     _phantom: PhantomData<(Req, Res)>,
 }
 
@@ -682,29 +3547,82 @@ impl<'a, Req: Message, Res: Message> ServiceBuilder<'a, Req, Res> {
         Self {
             node,
             name: name.to_string(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
             _phantom: PhantomData,
         }
     }
 
+    /// Rejects requests larger than `bytes` before decoding them, instead of
+    /// those larger than [`DEFAULT_MAX_MESSAGE_SIZE`]
+    ///
+    /// The caller receives an error reply.
+    pub fn with_max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = bytes;
+        self
+    }
+
     /// Builds the service with a handler
     pub async fn build<F>(self, handler: F) -> Result<ServiceHandle>
     where
         F: Fn(Req) -> Result<Res> + Send + Sync + 'static,
     {
-        let name = self.name.clone();
-        let service = self.node.create_service(&self.name, handler).await?;
+        self.build_with_context(move |request, _| handler(request))
+            .await
+    }
+
+    /// Builds the service with a handler that also receives the context of each
+    /// request, such as its trace ID and the name of the calling node
+    pub async fn build_with_context<F>(self, handler: F) -> Result<ServiceHandle>
+    where
+        F: Fn(Req, RequestContext) -> Result<Res> + Send + Sync + 'static,
+    {
+        let service = self
+            .node
+            .create_dispatching_service(
+                &self.name,
+                RequestDispatch::Handler(Box::new(handler)),
+                self.max_message_size,
+            )
+            .await?;
+        let name = service.name().to_string();
         Ok(ServiceHandle::new(
             service,
             name,
             self.node.services.clone(),
         ))
     }
+
+    /// Builds the service handing its requests to a [`RequestStream`] instead
+    /// of a handler
+    ///
+    /// Each request comes with a [`Responder`] replying to it, so the caller
+    /// owns the request loop and can answer requests in any order, from any
+    /// task, with state that needs no locking. While 16 requests wait to be
+    /// taken from the stream, further requests stay queued in Zenoh.
+    ///
+    /// [`Responder`]: crate::service::Responder
+    pub async fn build_manual(self) -> Result<(ServiceHandle, RequestStream<Req, Res>)> {
+        let (requests, stream) = RequestStream::new();
+        let service = self
+            .node
+            .create_dispatching_service(
+                &self.name,
+                RequestDispatch::Stream(requests),
+                self.max_message_size,
+            )
+            .await?;
+        let name = service.name().to_string();
+        let handle = ServiceHandle::new(service, name, self.node.services.clone());
+        Ok((handle, stream))
+    }
 }
 
 /// Builder for creating clients with fluent API
 pub struct ClientBuilder<'a, Req: Message, Res: Message> {
     node: &'a Node,
     name: String,
+    qos: QosProfile,
+    retry: Option<RetryPolicy>,
     _phantom: PhantomData<(Req, Res)>,
 }
 
@@ -713,14 +3631,51 @@ impl<'a, Req: Message, Res: Message> ClientBuilder<'a, Req, Res> {
         Self {
             node,
             name: name.to_string(),
+            qos: QosProfile::default(),
+            retry: None,
             _phantom: PhantomData,
         }
     }
 
+    /// Sets the QoS profile, whose deadline bounds each call attempt
+    pub fn with_qos(mut self, qos: QosProfile) -> Self {
+        self.qos = qos;
+        self
+    }
+
+    /// Sets the QoS profile from a preset
+    pub fn with_qos_preset(mut self, preset: QosPreset) -> Self {
+        self.qos = preset.into();
+        self
+    }
+
+    /// Sets the policy for retrying failed call attempts
+    ///
+    /// The policy sets the delays between attempts, whether they are jittered,
+    /// and with `max_total` a bound on the time spent on a call, after which it
+    /// fails with [`Error::ServiceCallTimeout`]. Calls without a timeout still
+    /// make at most three attempts. Without a policy, the delays start at 200ms
+    /// and double up to 1.6s.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
     /// Builds the client
     pub fn build(self) -> Result<ClientHandle<Req, Res>> {
-        let name = self.name.clone();
-        let client = self.node.create_client(&self.name)?;
-        Ok(ClientHandle::new(client, name, self.node.clients.clone()))
+        let (client, key) = self
+            .node
+            .register_client(&self.name, self.qos, self.retry)?;
+        Ok(ClientHandle::new(client, key, self.node.clients.clone()))
+    }
+
+    /// Builds the client and waits until a server for the service is available
+    ///
+    /// Returns [`Error::ServiceCallTimeout`] if no server appears within `timeout`,
+    /// in which case the client is dropped.
+    pub async fn build_and_wait(self, timeout: Duration) -> Result<ClientHandle<Req, Res>> {
+        let client = self.build()?;
+        client.wait_for_service(timeout).await?;
+        Ok(client)
     }
 }
@@ -1,11 +1,106 @@
 //! Parameter system for Zenobuf
 
 use std::any::Any;
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::error::{Error, Result};
+use crate::node::Node;
+use crate::service::ErrorReply;
+
+/// Declared type, default value and constraints of a parameter
+///
+/// Descriptors are created with [`Node::declare_parameter`](crate::Node::declare_parameter)
+/// and serialize to the JSON objects returned by the node's
+/// `zenobuf/param/<node>/__describe__` query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterDescriptor {
+    /// Name of the parameter
+    pub name: String,
+    /// JSON type of the default value: `bool`, `integer`, `number`, `string`,
+    /// `array`, `object` or `null`
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Default value of the parameter
+    pub default: serde_json::Value,
+    /// Smallest allowed value, for numeric parameters
+    pub min: Option<f64>,
+    /// Largest allowed value, for numeric parameters
+    pub max: Option<f64>,
+    /// Human-readable description
+    pub description: Option<String>,
+    /// Whether values must keep the declared type
+    #[serde(default)]
+    pub strict: bool,
+}
+
+impl ParameterDescriptor {
+    /// Creates a descriptor for a parameter with the given default value
+    pub fn new<T: Serialize>(name: &str, default: &T) -> Result<Self> {
+        let default = serde_json::to_value(default)
+            .map_err(|e| Error::parameter(name, format!("Failed to serialize: {e}")))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            type_name: json_type_name(&default).to_string(),
+            default,
+            min: None,
+            max: None,
+            description: None,
+            strict: false,
+        })
+    }
+
+    /// Checks that a value satisfies the constraints of the parameter
+    ///
+    /// A strict parameter also rejects values of another type than the declared
+    /// one, except integers for a `number` parameter, which convert losslessly.
+    pub fn check(&self, value: &serde_json::Value) -> Result<()> {
+        let type_name = json_type_name(value);
+        let convertible =
+            type_name == self.type_name || (type_name == "integer" && self.type_name == "number");
+        if self.strict && !convertible {
+            return Err(Error::parameter(
+                &self.name,
+                format!(
+                    "Value of type {type_name} does not match the declared type {}",
+                    self.type_name
+                ),
+            ));
+        }
+        if let Some(number) = value.as_f64() {
+            let below = self.min.is_some_and(|min| number < min);
+            let above = self.max.is_some_and(|max| number > max);
+            if below || above {
+                return Err(Error::parameter(
+                    &self.name,
+                    format!(
+                        "Value {number} is outside [{}, {}]",
+                        self.min.map_or("-inf".to_string(), |min| min.to_string()),
+                        self.max.map_or("inf".to_string(), |max| max.to_string()),
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the JSON type name of a value
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(number) if number.is_f64() => "number",
+        serde_json::Value::Number(_) => "integer",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
 
 /// Parameter for Zenobuf
 ///
@@ -54,6 +149,13 @@ impl Parameter {
         Ok(deserialized)
     }
 
+    /// Returns the value of the parameter as JSON, without knowing its type
+    pub fn to_json(&self) -> serde_json::Value {
+        let guard = self.inner.lock().unwrap();
+        // The serialized form was produced by serde_json, so it always parses
+        serde_json::from_str(&guard.1).unwrap_or(serde_json::Value::Null)
+    }
+
     /// Sets the value of the parameter
     pub fn set_value<T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static>(
         &self,
@@ -68,3 +170,134 @@ impl Parameter {
         Ok(())
     }
 }
+
+/// Reads and sets the parameters of another node
+///
+/// Created with [`Node::parameter_client`]. Requests are Zenoh queries on
+/// `zenobuf/param/<node>/<name>`, answered by the target node, so errors such as
+/// a value outside the declared range are reported back to the caller.
+#[derive(Clone)]
+pub struct ParameterClient {
+    session: Arc<zenoh::Session>,
+    /// Name of the node whose parameters are accessed
+    target: String,
+    /// How long to wait for the target node to reply
+    timeout: Duration,
+}
+
+impl ParameterClient {
+    /// Time the client waits for a reply unless set with [`ParameterClient::with_timeout`]
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Creates a client for the parameters of node `target`
+    pub(crate) fn new(session: Arc<zenoh::Session>, target: &str) -> Self {
+        Self {
+            session,
+            target: target.to_string(),
+            timeout: Self::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Sets how long to wait for the target node to reply
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the name of the node whose parameters are accessed
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Gets the value of a parameter of the target node
+    ///
+    /// Returns [`Error::Parameter`] if the node has no such parameter, the value
+    /// does not deserialize to `T`, or the node does not reply in time.
+    pub async fn get<T: DeserializeOwned>(&self, name: &str) -> Result<T> {
+        let value = self.query(name, None).await?;
+        serde_json::from_value(value)
+            .map_err(|e| Error::parameter(name, format!("Failed to deserialize: {e}")))
+    }
+
+    /// Sets a parameter of the target node
+    ///
+    /// The node checks the value as [`Node::set_parameter`] does, and
+    /// [`Error::Parameter`] carries the reason if it rejects it.
+    pub async fn set<T: Serialize>(&self, name: &str, value: T) -> Result<()> {
+        let value = serde_json::to_vec(&value)
+            .map_err(|e| Error::parameter(name, format!("Failed to serialize: {e}")))?;
+        self.query(name, Some(value)).await.map(|_| ())
+    }
+
+    /// Returns the current values of all parameters of the target node as JSON,
+    /// keyed by name
+    ///
+    /// The map is empty if the node does not reply in time.
+    pub async fn list(&self) -> Result<HashMap<String, serde_json::Value>> {
+        let prefix = format!("{}{}/", Node::PARAM_PREFIX, self.target);
+        let replies = self
+            .session
+            .get(format!("{prefix}**"))
+            .timeout(self.timeout)
+            .await
+            .map_err(Error::from)?;
+
+        let mut values = HashMap::new();
+        while let Ok(reply) = replies.recv_async().await {
+            let Ok(sample) = reply.result() else {
+                continue;
+            };
+            let Some(name) = sample.key_expr().as_str().strip_prefix(&prefix) else {
+                continue;
+            };
+            if name == Node::DESCRIBE_KEY {
+                continue;
+            }
+            if let Ok(value) = serde_json::from_slice(&sample.payload().to_bytes()) {
+                values.insert(name.to_string(), value);
+            }
+        }
+        Ok(values)
+    }
+
+    /// Queries a parameter, setting it first to `value` if given, and returns
+    /// its value
+    async fn query(&self, name: &str, value: Option<Vec<u8>>) -> Result<serde_json::Value> {
+        let key = zenoh::key_expr::KeyExpr::try_from(format!(
+            "{}{}/{}",
+            Node::PARAM_PREFIX,
+            self.target,
+            name
+        ))
+        .map_err(|e| Error::parameter(name, format!("Invalid parameter name: {e}")))?;
+
+        let mut get = self.session.get(key).timeout(self.timeout);
+        if let Some(value) = value {
+            get = get
+                .payload(value)
+                .encoding(zenoh::bytes::Encoding::APPLICATION_JSON);
+        }
+        let replies = get.await.map_err(Error::from)?;
+
+        let reply = replies.recv_async().await.map_err(|_| {
+            Error::parameter(
+                name,
+                format!(
+                    "Node '{}' did not reply within {:?}",
+                    self.target, self.timeout
+                ),
+            )
+        })?;
+        match reply.result() {
+            Ok(sample) => serde_json::from_slice(&sample.payload().to_bytes())
+                .map_err(|e| Error::parameter(name, format!("Invalid value: {e}"))),
+            Err(e) => {
+                let payload = e.payload().to_bytes();
+                let reason = ErrorReply::from_bytes(&payload)
+                    .map(|reply| reply.error)
+                    .unwrap_or_else(|| String::from_utf8_lossy(&payload).into_owned());
+                Err(Error::parameter(name, reason))
+            }
+        }
+    }
+}
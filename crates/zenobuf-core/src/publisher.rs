@@ -1,9 +1,123 @@
 //! Publisher implementation for Zenobuf
 
-use crate::error::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
 use crate::message::Message;
+use crate::time::Time;
 use crate::transport;
 
+/// Statistics for a publisher
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublisherStats {
+    /// Number of messages published successfully
+    pub messages_sent: u64,
+    /// Total size of the payloads sent for the published messages, in bytes
+    ///
+    /// This is what the transport put on the wire, after compression and the
+    /// framing of batches, not counting headers.
+    pub bytes_sent: u64,
+    /// Time of the last successful publish
+    pub last_publish: Option<Time>,
+    /// Number of messages dropped for exceeding the maximum rate
+    pub rate_limited: u64,
+}
+
+/// Counters updated on every successful publish
+#[derive(Debug, Default)]
+struct PublisherCounters {
+    messages_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    /// Nanoseconds since the Unix epoch of the last publish, or 0 if none
+    last_publish_nanos: AtomicU64,
+    rate_limited: AtomicU64,
+}
+
+impl PublisherCounters {
+    /// Records a published message sent as a payload of `bytes` bytes
+    fn record_sent(&self, bytes: usize) {
+        self.record_batch(1, bytes);
+    }
+
+    /// Records `messages` published messages sent as payloads of `bytes` bytes
+    /// in total
+    fn record_batch(&self, messages: usize, bytes: usize) {
+        self.messages_sent
+            .fetch_add(messages as u64, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        let now = Time::now().to_duration().as_nanos() as u64;
+        self.last_publish_nanos.store(now, Ordering::Relaxed);
+    }
+
+    /// Records a message dropped by the rate limit
+    fn record_rate_limited(&self) {
+        self.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the counters
+    fn snapshot(&self) -> PublisherStats {
+        let last_publish_nanos = self.last_publish_nanos.load(Ordering::Relaxed);
+        PublisherStats {
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            last_publish: (last_publish_nanos != 0)
+                .then(|| Time::from_duration(Duration::from_nanos(last_publish_nanos))),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Maximum rate of a publisher, spacing messages at least `interval` apart
+#[derive(Debug)]
+pub(crate) struct RateLimit {
+    interval: Duration,
+    /// Whether `publish` waits for the next slot rather than dropping the message
+    blocking: bool,
+    /// Earliest time at which the next message may be sent
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimit {
+    /// Creates a limit of `hz` messages per second
+    pub(crate) fn new(hz: f64, blocking: bool) -> Result<Self> {
+        if !(hz.is_finite() && hz > 0.0) {
+            return Err(Error::configuration(format!(
+                "Maximum publish rate must be positive, got {hz}"
+            )));
+        }
+        Ok(Self {
+            interval: Duration::from_secs_f64(1.0 / hz),
+            blocking,
+            next_slot: Mutex::new(None),
+        })
+    }
+
+    /// Takes the current slot if it is free, returning whether it was
+    fn try_acquire(&self) -> bool {
+        let now = Instant::now();
+        let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        if next_slot.is_some_and(|slot| now < slot) {
+            return false;
+        }
+        *next_slot = Some(now + self.interval);
+        true
+    }
+
+    /// Reserves the next free slot and waits for it
+    fn acquire(&self) {
+        let now = Instant::now();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = next_slot.map_or(now, |slot| slot.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+        std::thread::sleep(slot - now);
+    }
+}
+
 /// Publisher for Zenobuf
 ///
 /// A Publisher is used to publish messages on a topic.
@@ -12,12 +126,48 @@ pub struct Publisher<M: Message> {
     topic: String,
     /// Inner publisher implementation
     inner: Box<dyn transport::Publisher<M>>,
+    /// Publish statistics
+    counters: PublisherCounters,
+    /// Maximum publish rate, if any
+    rate_limit: Option<RateLimit>,
+    /// Last message sent by [`Publisher::publish_if_changed`]
+    last_changed: Mutex<Option<M>>,
 }
 
 impl<M: Message> Publisher<M> {
     /// Creates a new Publisher
     pub(crate) fn new(topic: String, inner: Box<dyn transport::Publisher<M>>) -> Self {
-        Self { topic, inner }
+        Self {
+            topic,
+            inner,
+            counters: PublisherCounters::default(),
+            rate_limit: None,
+            last_changed: Mutex::new(None),
+        }
+    }
+
+    /// Limits the rate at which messages are published
+    pub(crate) fn with_rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Checks the rate limit for a message, returning whether it may be sent
+    ///
+    /// Waits for the next slot if the limit blocks and `wait` is set.
+    fn admit(&self, wait: bool) -> bool {
+        let Some(rate_limit) = &self.rate_limit else {
+            return true;
+        };
+        if wait && rate_limit.blocking {
+            rate_limit.acquire();
+            return true;
+        }
+        let admitted = rate_limit.try_acquire();
+        if !admitted {
+            self.counters.record_rate_limited();
+        }
+        admitted
     }
 
     /// Returns the topic name
@@ -26,7 +176,108 @@ impl<M: Message> Publisher<M> {
     }
 
     /// Publishes a message
+    ///
+    /// Returns [`Error::InvalidMessage`](crate::Error::InvalidMessage) without
+    /// sending if the message fails [`Message::validate`]. With a maximum rate,
+    /// messages exceeding it are dropped, or delayed until the next free slot
+    /// if the limit blocks.
     pub fn publish(&self, message: &M) -> Result<()> {
-        self.inner.publish(message)
+        message.validate()?;
+        if !self.admit(true) {
+            return Ok(());
+        }
+        let bytes = self.inner.publish(message)?;
+        self.counters.record_sent(bytes);
+        Ok(())
+    }
+
+    /// Publishes a message unless the network is congested
+    ///
+    /// Unlike [`Publisher::publish`] with blocking congestion control, this never
+    /// waits for the network: it returns `Ok(false)` and leaves the message
+    /// undelivered if the transport has no room to send it. This gives control
+    /// loops a bounded publishing path. Messages exceeding the maximum rate also
+    /// return `Ok(false)`, even if the limit blocks.
+    pub fn try_publish(&self, message: &M) -> Result<bool> {
+        message.validate()?;
+        if !self.admit(false) {
+            return Ok(false);
+        }
+        let sent = self.inner.try_publish(message)?;
+        if let Some(bytes) = sent {
+            self.counters.record_sent(bytes);
+        }
+        Ok(sent.is_some())
+    }
+
+    /// Publishes a message and waits until a subscriber has processed it
+    ///
+    /// Resolves once at least one subscriber built with
+    /// [`SubscriberBuilder::build_acking`](crate::node::SubscriberBuilder::build_acking)
+    /// acknowledges the message, after its callback has run, and returns
+    /// [`Error::Timeout`](crate::Error::Timeout) if none does within `timeout`.
+    /// The message is not dropped or delayed by a maximum rate.
+    pub async fn publish_reliable(&self, message: &M, timeout: Duration) -> Result<()> {
+        message.validate()?;
+        let bytes = self.inner.publish_acked(message, timeout).await?;
+        self.counters.record_sent(bytes);
+        Ok(())
+    }
+
+    /// Publishes several messages at once
+    ///
+    /// The messages are sent as a single framed payload, which subscribers split
+    /// back into individual messages.
+    ///
+    /// None of the messages are sent if any fails validation.
+    pub fn publish_batch(&self, messages: &[M]) -> Result<()> {
+        messages.iter().try_for_each(Message::validate)?;
+        let bytes = self.inner.publish_batch(messages)?;
+        if !messages.is_empty() {
+            self.counters.record_batch(messages.len(), bytes);
+        }
+        Ok(())
+    }
+
+    /// Returns the publish statistics
+    pub fn stats(&self) -> PublisherStats {
+        self.counters.snapshot()
+    }
+
+    /// Returns the number of subscribers currently matched with this publisher
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.subscriber_count()
+    }
+
+    /// Returns the capacity in bytes of the buffer messages are encoded into
+    ///
+    /// The buffer is reused across publishes and grows to the largest message
+    /// encoded so far. Transports without such a buffer report 0.
+    pub fn buffer_capacity(&self) -> usize {
+        self.inner.buffer_capacity()
+    }
+}
+
+impl<M: Message + PartialEq> Publisher<M> {
+    /// Publishes a message unless it equals the last one sent by this method
+    ///
+    /// Returns `Ok(false)` without sending if the message is unchanged, which
+    /// saves traffic on topics carrying slowly changing state. Messages sent with
+    /// the other publish methods are not compared against. A message dropped by
+    /// the maximum rate also returns `Ok(false)` and is not remembered, so it is
+    /// sent by the next call.
+    pub fn publish_if_changed(&self, message: &M) -> Result<bool> {
+        message.validate()?;
+        let mut last = self.last_changed.lock().unwrap_or_else(|e| e.into_inner());
+        if last.as_ref() == Some(message) {
+            return Ok(false);
+        }
+        if !self.admit(true) {
+            return Ok(false);
+        }
+        let bytes = self.inner.publish(message)?;
+        self.counters.record_sent(bytes);
+        *last = Some(message.clone());
+        Ok(true)
     }
 }
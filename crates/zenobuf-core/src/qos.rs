@@ -1,12 +1,18 @@
 //! Quality of Service (QoS) profiles for Zenobuf
 
+use std::fmt;
 use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
+
+use crate::util::{duration_to_string, string_to_duration};
+
 /// QoS preset for common use cases
 ///
 /// This enum provides convenient presets for common QoS configurations,
 /// making it easier to configure quality of service without verbose setup.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum QosPreset {
     /// Default QoS profile - reliable, volatile, keep last 10
     #[default]
@@ -17,9 +23,9 @@ pub enum QosPreset {
     Parameters,
     /// Optimized for services - reliable, volatile, keep last 10, 1s deadline
     Services,
-    /// High throughput - best effort, volatile, keep last 100
+    /// High throughput - best effort, volatile, keep last 100, low priority
     HighThroughput,
-    /// Low latency - best effort, volatile, keep last 1
+    /// Low latency - best effort, volatile, keep last 1, real-time priority
     LowLatency,
     /// Custom QoS profile
     Custom(QosProfile),
@@ -29,7 +35,11 @@ pub enum QosPreset {
 ///
 /// This struct defines the quality of service parameters for publishers and
 /// subscribers. It is similar to the QoS profiles in ROS.
-#[derive(Debug, Clone)]
+///
+/// Profiles can be loaded from configuration files, where missing fields take
+/// their default values and durations are written as strings like `500ms`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct QosProfile {
     /// Reliability of the communication
     pub reliability: Reliability,
@@ -40,9 +50,15 @@ pub struct QosProfile {
     /// Depth of the history queue
     pub depth: usize,
     /// Deadline for receiving messages
+    #[serde(with = "optional_duration")]
     pub deadline: Option<Duration>,
     /// Lifespan of messages
+    #[serde(with = "optional_duration")]
     pub lifespan: Option<Duration>,
+    /// Priority of published messages
+    pub priority: Priority,
+    /// Behavior of publishers when the network is congested
+    pub congestion_control: CongestionControl,
 }
 
 impl Default for QosProfile {
@@ -54,6 +70,8 @@ impl Default for QosProfile {
             depth: 10,
             deadline: None,
             lifespan: None,
+            priority: Priority::Data,
+            congestion_control: CongestionControl::Block,
         }
     }
 }
@@ -100,6 +118,18 @@ impl QosProfile {
         self
     }
 
+    /// Sets the priority of published messages
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the congestion control mode of publishers
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.congestion_control = congestion_control;
+        self
+    }
+
     /// Creates a QoS profile for sensors
     ///
     /// This profile is optimized for sensor data, which is typically
@@ -112,6 +142,8 @@ impl QosProfile {
             depth: 5,
             deadline: None,
             lifespan: None,
+            priority: Priority::Data,
+            congestion_control: CongestionControl::Drop,
         }
     }
 
@@ -127,6 +159,8 @@ impl QosProfile {
             depth: 1,
             deadline: None,
             lifespan: None,
+            priority: Priority::Data,
+            congestion_control: CongestionControl::Block,
         }
     }
 
@@ -142,12 +176,91 @@ impl QosProfile {
             depth: 10,
             deadline: Some(Duration::from_secs(1)),
             lifespan: None,
+            priority: Priority::Data,
+            congestion_control: CongestionControl::Block,
+        }
+    }
+
+    /// Checks whether a subscriber with the `subscriber` profile can receive the
+    /// messages of a publisher with this profile
+    ///
+    /// This follows the ROS 2 rules, under which such endpoints do not
+    /// communicate at all: a reliable subscriber is incompatible with a
+    /// best-effort publisher, and a transient-local subscriber with a volatile
+    /// publisher. Every other combination is compatible.
+    pub fn compatible_with(&self, subscriber: &QosProfile) -> Compatibility {
+        let mut reasons = Vec::new();
+        if self.reliability == Reliability::BestEffort
+            && subscriber.reliability == Reliability::Reliable
+        {
+            reasons.push("the subscriber is reliable but the publisher is best effort");
+        }
+        if self.durability == Durability::Volatile
+            && subscriber.durability == Durability::TransientLocal
+        {
+            reasons.push("the subscriber is transient local but the publisher is volatile");
+        }
+
+        if reasons.is_empty() {
+            Compatibility::Compatible
+        } else {
+            Compatibility::Incompatible {
+                reason: reasons.join(", and "),
+            }
         }
     }
 }
 
+/// Whether a publisher and a subscriber have compatible QoS profiles, as
+/// returned by [`QosProfile::compatible_with`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// The subscriber only requests what the publisher offers
+    Compatible,
+    /// The subscriber requests more than the publisher offers
+    Incompatible {
+        /// Policies that do not match, e.g. for logging
+        reason: String,
+    },
+}
+
+impl Compatibility {
+    /// Returns true if the profiles are compatible
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, Compatibility::Compatible)
+    }
+}
+
+impl fmt::Display for QosProfile {
+    /// Formats the profile as e.g. `reliable/volatile/keep_last(10)`
+    ///
+    /// Deadline and lifespan are appended when set, as are priority and
+    /// congestion control when they differ from their defaults.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.reliability, self.durability)?;
+        match self.history {
+            History::KeepLast => write!(f, "/keep_last({})", self.depth)?,
+            History::KeepAll => write!(f, "/keep_all")?,
+        }
+        if let Some(deadline) = self.deadline {
+            write!(f, "/deadline({})", duration_to_string(deadline))?;
+        }
+        if let Some(lifespan) = self.lifespan {
+            write!(f, "/lifespan({})", duration_to_string(lifespan))?;
+        }
+        if self.priority != Priority::default() {
+            write!(f, "/priority({})", self.priority)?;
+        }
+        if self.congestion_control != CongestionControl::default() {
+            write!(f, "/{}", self.congestion_control)?;
+        }
+        Ok(())
+    }
+}
+
 /// Reliability of the communication
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Reliability {
     /// Best effort delivery (may drop messages)
     BestEffort,
@@ -156,7 +269,8 @@ pub enum Reliability {
 }
 
 /// Durability of the communication
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Durability {
     /// Volatile durability (no persistence)
     Volatile,
@@ -165,14 +279,119 @@ pub enum Durability {
 }
 
 /// History policy
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum History {
     /// Keep the last N messages
     KeepLast,
     /// Keep all messages
+    ///
+    /// Subscribers with a [`SubscriberHandler::Fifo`](crate::SubscriberHandler::Fifo)
+    /// or [`SubscriberHandler::Ring`](crate::SubscriberHandler::Ring) handler then
+    /// queue samples in an unbounded FIFO and ignore the depth, so a callback that
+    /// cannot keep up lets the queue grow until memory runs out.
     KeepAll,
 }
 
+/// Priority of published messages, from most to least urgent
+///
+/// Higher priority traffic preempts lower priority traffic on congested links,
+/// so control commands can be given precedence over bulk telemetry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Real-time traffic, e.g. control commands
+    RealTime,
+    /// High priority interactive traffic
+    InteractiveHigh,
+    /// Low priority interactive traffic
+    InteractiveLow,
+    /// High priority data
+    DataHigh,
+    /// Regular data
+    #[default]
+    Data,
+    /// Low priority data
+    DataLow,
+    /// Background traffic, e.g. bulk transfers
+    Background,
+}
+
+impl fmt::Display for Reliability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Reliability::BestEffort => "best_effort",
+            Reliability::Reliable => "reliable",
+        })
+    }
+}
+
+impl fmt::Display for Durability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Durability::Volatile => "volatile",
+            Durability::TransientLocal => "transient_local",
+        })
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Priority::RealTime => "real_time",
+            Priority::InteractiveHigh => "interactive_high",
+            Priority::InteractiveLow => "interactive_low",
+            Priority::DataHigh => "data_high",
+            Priority::Data => "data",
+            Priority::DataLow => "data_low",
+            Priority::Background => "background",
+        })
+    }
+}
+
+impl From<Priority> for zenoh::qos::Priority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::RealTime => zenoh::qos::Priority::RealTime,
+            Priority::InteractiveHigh => zenoh::qos::Priority::InteractiveHigh,
+            Priority::InteractiveLow => zenoh::qos::Priority::InteractiveLow,
+            Priority::DataHigh => zenoh::qos::Priority::DataHigh,
+            Priority::Data => zenoh::qos::Priority::Data,
+            Priority::DataLow => zenoh::qos::Priority::DataLow,
+            Priority::Background => zenoh::qos::Priority::Background,
+        }
+    }
+}
+
+/// Behavior of publishers when the network is congested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CongestionControl {
+    /// Block the publisher until the message can be sent
+    #[default]
+    Block,
+    /// Drop the message
+    Drop,
+}
+
+impl fmt::Display for CongestionControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CongestionControl::Block => "block",
+            CongestionControl::Drop => "drop",
+        })
+    }
+}
+
+impl From<CongestionControl> for zenoh::qos::CongestionControl {
+    fn from(congestion_control: CongestionControl) -> Self {
+        match congestion_control {
+            CongestionControl::Block => zenoh::qos::CongestionControl::Block,
+            CongestionControl::Drop => zenoh::qos::CongestionControl::Drop,
+        }
+    }
+}
+
 impl From<QosPreset> for QosProfile {
     fn from(preset: QosPreset) -> Self {
         match preset {
@@ -187,6 +406,8 @@ impl From<QosPreset> for QosProfile {
                 depth: 100,
                 deadline: None,
                 lifespan: None,
+                priority: Priority::DataLow,
+                congestion_control: CongestionControl::Drop,
             },
             QosPreset::LowLatency => QosProfile {
                 reliability: Reliability::BestEffort,
@@ -195,8 +416,34 @@ impl From<QosPreset> for QosProfile {
                 depth: 1,
                 deadline: None,
                 lifespan: None,
+                priority: Priority::RealTime,
+                congestion_control: CongestionControl::Drop,
             },
             QosPreset::Custom(profile) => profile,
         }
     }
 }
+
+/// Serializes optional durations as human readable strings, e.g. `"500ms"`
+mod optional_duration {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{duration_to_string, string_to_duration};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        duration.map(duration_to_string).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| string_to_duration(&value).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
@@ -0,0 +1,61 @@
+//! Encoding and decoding Protocol Buffer messages without their Rust types
+//!
+//! Tools such as `zenobuf-cli monitor` only see encoded payloads. Given the
+//! `FileDescriptorSet` describing the messages, as written by
+//! `protoc --descriptor_set_out` or `prost_build::Config::file_descriptor_set_path`,
+//! [`decode_to_json`] turns a payload into a JSON value of its fields, and
+//! [`encode_from_json`] does the reverse. This is independent of the
+//! [`Message`](crate::Message) trait, which needs the compiled types.
+
+use prost::Message as _;
+use prost_reflect::{DynamicMessage, SerializeOptions};
+
+pub use prost_reflect::{DescriptorPool, MessageDescriptor};
+
+use crate::error::{Error, Result};
+
+/// Loads the message descriptors of an encoded `FileDescriptorSet`
+pub fn load_descriptors(bytes: &[u8]) -> Result<DescriptorPool> {
+    DescriptorPool::decode(bytes)
+        .map_err(|e| Error::configuration(format!("Invalid file descriptor set: {e}")))
+}
+
+/// Returns the descriptor of a message by its fully qualified name, such as
+/// `my_app.Point`
+pub fn find_message(pool: &DescriptorPool, name: &str) -> Result<MessageDescriptor> {
+    pool.get_message_by_name(name.trim_start_matches('.'))
+        .ok_or_else(|| {
+            Error::configuration(format!("Message '{name}' not found in descriptor set"))
+        })
+}
+
+/// Decodes an encoded message into a JSON object of its fields
+///
+/// Fields are named as in the `.proto` file and included even when they hold
+/// their default value, so that the output shows the full message.
+pub fn decode_to_json(bytes: &[u8], descriptor: &MessageDescriptor) -> Result<serde_json::Value> {
+    let message = DynamicMessage::decode(descriptor.clone(), bytes)?;
+    let options = SerializeOptions::new()
+        .use_proto_field_name(true)
+        .skip_default_fields(false);
+    message
+        .serialize_with_options(serde_json::value::Serializer, &options)
+        .map_err(|e| Error::Serialization(e.to_string()))
+}
+
+/// Encodes a JSON object of fields into a message
+///
+/// Fields may be named as in the `.proto` file or in their JSON (camelCase)
+/// form, and fields left out keep their default value.
+pub fn encode_from_json(
+    json: &serde_json::Value,
+    descriptor: &MessageDescriptor,
+) -> Result<Vec<u8>> {
+    let message = DynamicMessage::deserialize(descriptor.clone(), json).map_err(|e| {
+        Error::InvalidMessage {
+            type_name: descriptor.full_name().to_string(),
+            reason: e.to_string(),
+        }
+    })?;
+    Ok(message.encode_to_vec())
+}
@@ -0,0 +1,93 @@
+//! Retry policies for Zenobuf
+
+use std::time::Duration;
+
+/// Exponential backoff policy for retrying an operation
+///
+/// The delay before retry `n` (starting at 0) is `initial_delay * multiplier^n`,
+/// capped at `max_delay`. Service clients also honor `jitter` and `max_total`,
+/// while Zenoh's reconnection only uses the delays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound on the delay between retries
+    pub max_delay: Duration,
+    /// Factor applied to the delay after each retry
+    pub multiplier: f64,
+    /// Whether each delay is randomized by up to ±50%, so that clients retrying
+    /// together spread out instead of hitting a recovering service at once
+    pub jitter: bool,
+    /// Upper bound on the time spent on all attempts and delays together
+    pub max_total: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(4),
+            multiplier: 2.0,
+            jitter: false,
+            max_total: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy with the default delays
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the delay before the first retry
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the upper bound on the delay between retries
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets the factor applied to the delay after each retry
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Enables or disables randomizing each delay by up to ±50%
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the upper bound on the time spent on all attempts and delays together
+    pub fn max_total(mut self, max_total: Duration) -> Self {
+        self.max_total = Some(max_total);
+        self
+    }
+
+    /// Returns the delay before retry `attempt`, counting from 0
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.min(i32::MAX as u32) as i32);
+        self.initial_delay
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_delay)
+    }
+
+    /// Returns the delay before retry `attempt`, randomized if jitter is enabled
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+        if self.jitter {
+            delay.mul_f64(rand::random_range(0.5..1.5))
+        } else {
+            delay
+        }
+    }
+}
@@ -1,8 +1,64 @@
 //! Service implementation for Zenobuf
 
-use crate::error::Result;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+use crate::time::Time;
 use crate::transport;
 
+/// Metadata of a service request, received by handlers built with
+/// [`ServiceBuilder::build_with_context`](crate::node::ServiceBuilder::build_with_context)
+///
+/// Clients attach it to every request without changing the request message.
+/// Requests from other sources, e.g. the CLI, carry an empty context with a nil
+/// trace ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestContext {
+    /// Identifier of the trace the request belongs to, fresh for each call
+    /// unless the caller passed one with
+    /// [`Client::call_with_trace_id`](crate::Client::call_with_trace_id)
+    pub trace_id: Uuid,
+    /// Name of the node that made the request
+    pub caller: String,
+    /// Time at which the caller gives up on the request, if it set a timeout
+    pub deadline: Option<Time>,
+}
+
+/// Payload of the error reply sent when a service fails to answer a request
+///
+/// Services reply with `{"error": "<message>"}` when a request cannot be decoded,
+/// the handler returns an error or the response cannot be encoded, so that
+/// clients and tools such as `zenobuf-cli call` can show the reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReply {
+    /// Why the request failed
+    pub error: String,
+}
+
+impl ErrorReply {
+    /// Creates an error reply with the given message
+    pub fn new(error: impl Into<String>) -> Self {
+        Self {
+            error: error.into(),
+        }
+    }
+
+    /// Encodes the reply as JSON
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    /// Decodes an error reply, returning `None` if the payload is not one
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
+
 /// Service for Zenobuf
 ///
 /// A Service is used to handle requests and send responses.
@@ -29,3 +85,94 @@ impl Service {
         self.inner.close()
     }
 }
+
+/// What a service does with the requests it accepts
+pub(crate) enum RequestDispatch<Req, Res> {
+    /// Answers each request with the result of a handler
+    Handler(Box<dyn Fn(Req, RequestContext) -> Result<Res> + Send + Sync>),
+    /// Hands each request to a [`RequestStream`] along with its [`Responder`]
+    Stream(mpsc::Sender<(Req, Responder<Res>)>),
+}
+
+/// Requests of a service built with
+/// [`ServiceBuilder::build_manual`](crate::node::ServiceBuilder::build_manual)
+///
+/// Each item is a request and the [`Responder`] answering it, so the owner of
+/// the stream decides in which order and how concurrently requests are served.
+/// The stream ends once the service is closed.
+pub struct RequestStream<Req, Res> {
+    receiver: mpsc::Receiver<(Req, Responder<Res>)>,
+}
+
+impl<Req, Res> RequestStream<Req, Res> {
+    /// Number of accepted requests waiting to be taken from the stream before
+    /// the service stops accepting more
+    pub(crate) const CAPACITY: usize = 16;
+
+    /// Creates a stream along with the sender feeding it
+    pub(crate) fn new() -> (mpsc::Sender<(Req, Responder<Res>)>, Self) {
+        let (sender, receiver) = mpsc::channel(Self::CAPACITY);
+        (sender, Self { receiver })
+    }
+
+    /// Waits for the next request, or returns `None` once the service is closed
+    pub async fn next(&mut self) -> Option<(Req, Responder<Res>)> {
+        self.receiver.recv().await
+    }
+}
+
+impl<Req, Res> futures::Stream for RequestStream<Req, Res> {
+    type Item = (Req, Responder<Res>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+/// Reply function of a [`Responder`]
+type ReplyFn<Res> = Box<dyn FnOnce(Result<Res>) + Send>;
+
+/// Answers one request taken from a [`RequestStream`]
+///
+/// A responder dropped without sending answers its request with an error, so
+/// the client does not wait for its timeout.
+pub struct Responder<Res> {
+    context: RequestContext,
+    reply: Option<ReplyFn<Res>>,
+}
+
+impl<Res> Responder<Res> {
+    /// Creates a responder answering a request with `reply`
+    pub(crate) fn new(
+        context: RequestContext,
+        reply: impl FnOnce(Result<Res>) + Send + 'static,
+    ) -> Self {
+        Self {
+            context,
+            reply: Some(Box::new(reply)),
+        }
+    }
+
+    /// Returns the context of the request, such as its trace ID and caller
+    pub fn context(&self) -> &RequestContext {
+        &self.context
+    }
+
+    /// Replies with a response, or with an error the client receives as
+    /// [`Error::ServiceCallFailed`]
+    pub fn send(mut self, response: Result<Res>) {
+        if let Some(reply) = self.reply.take() {
+            reply(response);
+        }
+    }
+}
+
+impl<Res> Drop for Responder<Res> {
+    fn drop(&mut self) {
+        if let Some(reply) = self.reply.take() {
+            reply(Err(Error::other(
+                "the service dropped the request without replying",
+            )));
+        }
+    }
+}
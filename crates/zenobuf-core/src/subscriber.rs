@@ -1,8 +1,338 @@
 //! Subscriber implementation for Zenobuf
 
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+
 use crate::error::Result;
+use crate::message::{decode_message_as, Encoding, Message};
 use crate::transport;
 
+/// Statistics for a subscriber
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriberStats {
+    /// Number of messages decoded and delivered to the callback
+    pub messages_received: u64,
+    /// Number of messages dropped because their type hash did not match
+    pub type_mismatches: u64,
+    /// Number of times the QoS deadline passed without a message arriving
+    pub deadlines_missed: u64,
+    /// Number of times the callback panicked
+    pub callback_panics: u64,
+    /// Number of messages dropped because they failed validation
+    pub invalid_messages: u64,
+    /// Number of messages dropped as duplicates by a deduplicating subscriber
+    pub duplicates: u64,
+    /// Number of messages dropped because they exceeded the maximum message size
+    pub oversized_messages: u64,
+}
+
+/// How a subscriber buffers the samples Zenoh receives before its callback runs
+///
+/// The channels hold up to the QoS `depth` samples, on top of the one whose
+/// callback is pending, which bounds the memory a slow callback can back up.
+/// With a [`History::KeepAll`](crate::qos::History::KeepAll) QoS history, both
+/// channels are replaced by an unbounded FIFO that never drops samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubscriberHandler {
+    /// Queues every sample for the callback without bound
+    #[default]
+    Callback,
+    /// Bounded FIFO channel; when full, Zenoh blocks until the callback catches up
+    Fifo,
+    /// Ring channel; when full, the oldest sample is dropped
+    Ring,
+}
+
+/// Counters updated by a transport's receive path
+#[derive(Debug, Default)]
+pub(crate) struct SubscriberCounters {
+    messages_received: AtomicU64,
+    type_mismatches: AtomicU64,
+    /// Publishers whose type hash mismatch was already reported
+    mismatched_publishers: Mutex<HashSet<Option<[u8; 16]>>>,
+    callback_panics: AtomicU64,
+    invalid_messages: AtomicU64,
+    duplicates: AtomicU64,
+    oversized_messages: AtomicU64,
+}
+
+impl SubscriberCounters {
+    /// Records a delivered message
+    pub fn record_received(&self) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a message dropped due to a type hash mismatch, returning true
+    /// if it is the first one from its publisher
+    pub fn record_type_mismatch(&self, publisher_id: Option<[u8; 16]>) -> bool {
+        self.type_mismatches.fetch_add(1, Ordering::Relaxed);
+        self.mismatched_publishers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(publisher_id)
+    }
+
+    /// Records a panic raised by the callback
+    pub fn record_callback_panic(&self) {
+        self.callback_panics.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a message dropped because it failed validation
+    pub fn record_invalid(&self) {
+        self.invalid_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a message dropped as a duplicate
+    pub fn record_duplicate(&self) {
+        self.duplicates.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a message dropped because it exceeded the maximum message size
+    pub fn record_oversized(&self) {
+        self.oversized_messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the counters
+    pub fn snapshot(&self) -> SubscriberStats {
+        SubscriberStats {
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            type_mismatches: self.type_mismatches.load(Ordering::Relaxed),
+            deadlines_missed: 0,
+            callback_panics: self.callback_panics.load(Ordering::Relaxed),
+            invalid_messages: self.invalid_messages.load(Ordering::Relaxed),
+            duplicates: self.duplicates.load(Ordering::Relaxed),
+            oversized_messages: self.oversized_messages.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Remembers the `(publisher ID, sequence number)` of the most recent messages
+/// to recognize duplicates
+///
+/// Only the last `capacity` messages are remembered, so a duplicate arriving
+/// after more messages than that is delivered again.
+#[derive(Debug)]
+pub(crate) struct DedupWindow {
+    capacity: usize,
+    seen: HashSet<([u8; 16], u64)>,
+    order: VecDeque<([u8; 16], u64)>,
+}
+
+impl DedupWindow {
+    /// Creates a window remembering `capacity` messages
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Records a message, returning false if it was already seen
+    pub fn insert(&mut self, publisher_id: [u8; 16], sequence: u64) -> bool {
+        let key = (publisher_id, sequence);
+        if !self.seen.insert(key) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Minimum interval between two warnings about dropped messages
+const DROP_WARNING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Counts the messages a channel subscriber dropped because its channel was full
+///
+/// Drops are reported with `tracing::warn!` at most once per
+/// [`DROP_WARNING_INTERVAL`], with the number of drops since the last warning.
+#[derive(Debug, Default)]
+pub(crate) struct DropCounter {
+    dropped: AtomicU64,
+    /// Time of the last warning, and the total reported by it
+    last_warning: Mutex<Option<(Instant, u64)>>,
+}
+
+impl DropCounter {
+    /// Records a message dropped on `topic`
+    pub fn record(&self, topic: &str) {
+        let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut last_warning = self.last_warning.lock().unwrap_or_else(|e| e.into_inner());
+        let reported = match *last_warning {
+            Some((at, _)) if at.elapsed() < DROP_WARNING_INTERVAL => return,
+            Some((_, reported)) => reported,
+            None => 0,
+        };
+        tracing::warn!(
+            "Subscriber on {} dropped {} messages because its channel is full ({} in total)",
+            topic,
+            dropped - reported,
+            dropped
+        );
+        *last_warning = Some((Instant::now(), dropped));
+    }
+
+    /// Returns the number of dropped messages
+    pub fn count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Callback invoked when a subscriber's deadline passes without a message
+pub type DeadlineCallback = Box<dyn Fn() + Send + Sync>;
+
+/// Detects when a subscriber goes longer than its QoS deadline without a message
+///
+/// A timer is restarted whenever a message arrives. Each time it runs out, the
+/// miss is counted and the callback, if any, is invoked, after which the timer
+/// starts over.
+pub(crate) struct DeadlineMonitor {
+    received: Arc<Notify>,
+    missed: Arc<AtomicU64>,
+    callback: Arc<Mutex<Option<DeadlineCallback>>>,
+    task: tokio::task::AbortHandle,
+}
+
+impl DeadlineMonitor {
+    /// Starts monitoring a deadline
+    pub fn spawn(deadline: Duration) -> Self {
+        let received = Arc::new(Notify::new());
+        let missed = Arc::new(AtomicU64::new(0));
+        let callback: Arc<Mutex<Option<DeadlineCallback>>> = Arc::default();
+
+        let task_received = received.clone();
+        let task_missed = missed.clone();
+        let task_callback = callback.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = task_received.notified() => {}
+                    _ = tokio::time::sleep(deadline) => {
+                        task_missed.fetch_add(1, Ordering::Relaxed);
+                        if let Some(callback) = &*task_callback.lock().unwrap_or_else(|e| e.into_inner()) {
+                            callback();
+                        }
+                    }
+                }
+            }
+        })
+        .abort_handle();
+
+        Self {
+            received,
+            missed,
+            callback,
+            task,
+        }
+    }
+
+    /// Returns a notifier to signal each received message with
+    pub fn receiver_notifier(&self) -> Arc<Notify> {
+        self.received.clone()
+    }
+
+    /// Sets the callback invoked on each missed deadline
+    pub fn set_callback(&self, callback: DeadlineCallback) {
+        *self.callback.lock().unwrap_or_else(|e| e.into_inner()) = Some(callback);
+    }
+
+    /// Returns the number of missed deadlines
+    pub fn missed(&self) -> u64 {
+        self.missed.load(Ordering::Relaxed)
+    }
+
+    /// Stops monitoring, keeping the number of deadlines missed so far
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for DeadlineMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Borrowed view of a received message payload
+///
+/// Handed to zero-copy subscriber callbacks, it points into the received sample,
+/// which is kept alive for the duration of the callback. Payloads that arrived
+/// compressed are viewed after decompression.
+#[derive(Debug, Clone, Copy)]
+pub struct PayloadView<'a> {
+    topic: &'a str,
+    bytes: &'a [u8],
+    encoding: Encoding,
+    type_name: Option<&'a str>,
+}
+
+impl<'a> PayloadView<'a> {
+    /// Creates a view of a payload in the given encoding, sent as a message of
+    /// type `type_name` if the publisher attached it
+    pub(crate) fn new(
+        topic: &'a str,
+        bytes: &'a [u8],
+        encoding: Encoding,
+        type_name: Option<&'a str>,
+    ) -> Self {
+        Self {
+            topic,
+            bytes,
+            encoding,
+            type_name,
+        }
+    }
+
+    /// Returns the topic the payload was received on
+    pub fn topic(&self) -> &'a str {
+        self.topic
+    }
+
+    /// Returns the encoded message
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Returns the length of the encoded message
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns true if the encoded message is empty
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns the encoding of the payload
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Returns the type name of the message, as attached by its publisher
+    ///
+    /// Returns `None` if the publisher did not attach it, e.g. a peer that does
+    /// not use Zenobuf.
+    pub fn type_name(&self) -> Option<&'a str> {
+        self.type_name
+    }
+
+    /// Decodes the message and checks its invariants
+    pub fn decode<M: Message>(&self) -> Result<M> {
+        let message = decode_message_as::<M>(self.bytes, self.encoding)?;
+        message.validate()?;
+        Ok(message)
+    }
+}
+
 /// Subscriber for Zenobuf
 ///
 /// A Subscriber is used to receive messages on a topic.
@@ -24,6 +354,16 @@ impl Subscriber {
         &self.topic
     }
 
+    /// Returns the subscriber statistics
+    pub fn stats(&self) -> SubscriberStats {
+        self.inner.stats()
+    }
+
+    /// Returns the number of publishers currently matched with this subscriber
+    pub fn publisher_count(&self) -> usize {
+        self.inner.publisher_count()
+    }
+
     /// Closes the subscriber
     pub fn close(&self) -> Result<()> {
         self.inner.close()
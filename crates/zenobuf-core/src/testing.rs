@@ -0,0 +1,61 @@
+//! Assertions for checking [`Message`] implementations in tests
+//!
+//! Hand-written `prost::Message` implementations are easy to get subtly wrong,
+//! e.g. by forgetting a field when decoding. These helpers check a message in one
+//! line. They are enabled by the `testing` feature, typically in dev-dependencies:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! zenobuf-core = { version = "0.3", features = ["testing"] }
+//! ```
+
+use crate::message::{decode_message, encode_message, Message};
+
+/// Asserts that `message` decodes back to an equal message once encoded
+///
+/// # Panics
+///
+/// Panics, naming the message type, if encoding or decoding fails or if the
+/// decoded message differs from `message`.
+#[track_caller]
+pub fn assert_roundtrip<M: Message + PartialEq + std::fmt::Debug>(message: &M) {
+    let bytes = encode(message);
+    let decoded = decode_message::<M>(&bytes).unwrap_or_else(|e| {
+        panic!(
+            "{} failed to decode its own encoding {:?}: {}",
+            M::type_name(),
+            bytes,
+            e
+        )
+    });
+    assert!(
+        decoded == *message,
+        "{} did not round-trip:\n  original: {:?}\n   decoded: {:?}\n     bytes: {:?}",
+        M::type_name(),
+        message,
+        decoded,
+        bytes
+    );
+}
+
+/// Asserts that `encoded_len` reports the length `message` actually encodes to
+///
+/// # Panics
+///
+/// Panics, naming the message type, if encoding fails or the lengths differ.
+#[track_caller]
+pub fn assert_encoded_len<M: Message>(message: &M) {
+    let bytes = encode(message);
+    assert!(
+        message.encoded_len() == bytes.len(),
+        "{}::encoded_len() returned {} but the message encodes to {} bytes",
+        M::type_name(),
+        message.encoded_len(),
+        bytes.len()
+    );
+}
+
+#[track_caller]
+fn encode<M: Message>(message: &M) -> Vec<u8> {
+    encode_message(message).unwrap_or_else(|e| panic!("{} failed to encode: {}", M::type_name(), e))
+}
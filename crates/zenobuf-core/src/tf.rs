@@ -0,0 +1,505 @@
+//! Coordinate frame transforms, similar to tf in ROS
+//!
+//! A [`TransformBroadcaster`] publishes [`StampedTransform`]s from a parent frame to
+//! a child frame on the [`TF_TOPIC`] topic. A [`TransformBuffer`] collects them into
+//! a tree of frames and looks up the transform between any two connected frames at
+//! a given time, composing the transforms along the tree and interpolating between
+//! the stamped samples of each edge.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::message::Message;
+use crate::node::{Node, PublisherHandle, SubscriberHandle};
+use crate::time::Time;
+
+/// Topic on which transforms are published
+pub const TF_TOPIC: &str = "/tf";
+
+/// Vector in 3D space
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Vector3 {
+    #[prost(double, tag = "1")]
+    pub x: f64,
+    #[prost(double, tag = "2")]
+    pub y: f64,
+    #[prost(double, tag = "3")]
+    pub z: f64,
+}
+
+impl Vector3 {
+    /// Creates a vector from its components
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+
+    fn scale(self, factor: f64) -> Self {
+        Self::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+
+    fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    fn lerp(self, other: Self, ratio: f64) -> Self {
+        self.add(other.add(self.scale(-1.0)).scale(ratio))
+    }
+}
+
+/// Rotation in 3D space, as a unit quaternion
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Quaternion {
+    #[prost(double, tag = "1")]
+    pub x: f64,
+    #[prost(double, tag = "2")]
+    pub y: f64,
+    #[prost(double, tag = "3")]
+    pub z: f64,
+    #[prost(double, tag = "4")]
+    pub w: f64,
+}
+
+impl Quaternion {
+    /// Creates a quaternion from its components
+    pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Returns the rotation that leaves vectors unchanged
+    pub fn identity() -> Self {
+        Self::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    /// Creates the rotation of `angle` radians around `axis`
+    pub fn from_axis_angle(axis: Vector3, angle: f64) -> Self {
+        let norm = (axis.x * axis.x + axis.y * axis.y + axis.z * axis.z).sqrt();
+        if norm == 0.0 {
+            return Self::identity();
+        }
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        let axis = axis.scale(sin / norm);
+        Self::new(axis.x, axis.y, axis.z, cos)
+    }
+
+    /// Creates the rotation of `yaw` radians around the z axis
+    pub fn from_yaw(yaw: f64) -> Self {
+        Self::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), yaw)
+    }
+
+    /// Returns the inverse rotation
+    pub fn inverse(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Rotates a vector
+    pub fn rotate(self, vector: Vector3) -> Vector3 {
+        let axis = Vector3::new(self.x, self.y, self.z);
+        let t = axis.cross(vector).scale(2.0);
+        vector.add(t.scale(self.w)).add(axis.cross(t))
+    }
+
+    fn dot(self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    fn normalize(self) -> Self {
+        let norm = self.dot(self).sqrt();
+        if norm == 0.0 {
+            return Self::identity();
+        }
+        Self::new(self.x / norm, self.y / norm, self.z / norm, self.w / norm)
+    }
+
+    /// Spherically interpolates between two rotations, with `ratio` in `0.0..=1.0`
+    pub fn slerp(self, other: Self, ratio: f64) -> Self {
+        // Take the shortest path between the two rotations
+        let mut dot = self.dot(other);
+        let other = if dot < 0.0 {
+            dot = -dot;
+            Self::new(-other.x, -other.y, -other.z, -other.w)
+        } else {
+            other
+        };
+
+        let (from, to) = if dot > 0.9995 {
+            // Nearly identical rotations, where linear interpolation is accurate
+            (1.0 - ratio, ratio)
+        } else {
+            let angle = dot.acos();
+            let sin = angle.sin();
+            (
+                ((1.0 - ratio) * angle).sin() / sin,
+                (ratio * angle).sin() / sin,
+            )
+        };
+        Self::new(
+            from * self.x + to * other.x,
+            from * self.y + to * other.y,
+            from * self.z + to * other.z,
+            from * self.w + to * other.w,
+        )
+        .normalize()
+    }
+}
+
+impl std::ops::Mul for Quaternion {
+    type Output = Quaternion;
+
+    /// Returns the rotation applying `other` first, then `self`
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+/// Rigid transform from a child frame to its parent frame
+///
+/// A point `p` in the child frame is at `rotation.rotate(p) + translation` in the
+/// parent frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// Position of the child frame's origin in the parent frame
+    pub translation: Vector3,
+    /// Orientation of the child frame in the parent frame
+    pub rotation: Quaternion,
+}
+
+impl Transform {
+    /// Creates a transform from a translation and a rotation
+    pub fn new(translation: Vector3, rotation: Quaternion) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    /// Returns the transform between a frame and itself
+    pub fn identity() -> Self {
+        Self::new(Vector3::default(), Quaternion::identity())
+    }
+
+    /// Returns the transform applying `other` first, then `self`
+    ///
+    /// Composing the transform from `b` to `a` with the transform from `c` to `b`
+    /// gives the transform from `c` to `a`.
+    pub fn compose(&self, other: &Transform) -> Transform {
+        Transform::new(
+            self.rotation
+                .rotate(other.translation)
+                .add(self.translation),
+            self.rotation * other.rotation,
+        )
+    }
+
+    /// Returns the transform in the opposite direction
+    pub fn inverse(&self) -> Transform {
+        let rotation = self.rotation.inverse();
+        Transform::new(rotation.rotate(self.translation).scale(-1.0), rotation)
+    }
+
+    /// Maps a point from the child frame to the parent frame
+    pub fn apply(&self, point: Vector3) -> Vector3 {
+        self.rotation.rotate(point).add(self.translation)
+    }
+
+    /// Interpolates between two transforms, with `ratio` in `0.0..=1.0`
+    pub fn interpolate(&self, other: &Transform, ratio: f64) -> Transform {
+        Transform::new(
+            self.translation.lerp(other.translation, ratio),
+            self.rotation.slerp(other.rotation, ratio),
+        )
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Transform from a child frame to its parent frame at a point in time
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StampedTransform {
+    /// Name of the parent frame
+    #[prost(string, tag = "1")]
+    pub parent: String,
+    /// Name of the child frame
+    #[prost(string, tag = "2")]
+    pub child: String,
+    /// Position of the child frame's origin in the parent frame
+    #[prost(message, optional, tag = "3")]
+    pub translation: Option<Vector3>,
+    /// Orientation of the child frame in the parent frame
+    #[prost(message, optional, tag = "4")]
+    pub rotation: Option<Quaternion>,
+    /// Time of the transform, in nanoseconds since the Unix epoch
+    #[prost(uint64, tag = "5")]
+    pub stamp: u64,
+}
+
+impl StampedTransform {
+    /// Creates a stamped transform from `child` to `parent`
+    pub fn new(parent: &str, child: &str, transform: Transform, stamp: Time) -> Self {
+        Self {
+            parent: parent.to_string(),
+            child: child.to_string(),
+            translation: Some(transform.translation),
+            rotation: Some(transform.rotation),
+            stamp: stamp.to_duration().as_nanos() as u64,
+        }
+    }
+
+    /// Returns the transform, treating missing fields as the identity
+    pub fn transform(&self) -> Transform {
+        Transform::new(
+            self.translation.unwrap_or_default(),
+            self.rotation.unwrap_or_else(Quaternion::identity),
+        )
+    }
+
+    /// Returns the time of the transform
+    pub fn time(&self) -> Time {
+        Time::from_duration(Duration::from_nanos(self.stamp))
+    }
+}
+
+impl Message for StampedTransform {
+    fn type_name() -> &'static str {
+        "zenobuf.tf.StampedTransform"
+    }
+}
+
+/// Stamped transforms from a frame to its parent, oldest first
+struct FrameHistory {
+    parent: String,
+    samples: VecDeque<(Time, Transform)>,
+}
+
+impl FrameHistory {
+    /// Returns the transform at `at`, interpolating between the nearest samples
+    ///
+    /// A frame with a single sample is treated as static.
+    fn at(&self, at: Time) -> std::result::Result<Transform, String> {
+        let (first, last) = match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err("no samples".to_string()),
+        };
+        if self.samples.len() == 1 {
+            return Ok(first.1);
+        }
+        if at < first.0 || at > last.0 {
+            return Err(format!(
+                "{:?} is outside the buffered range {:?} to {:?}",
+                at.to_duration(),
+                first.0.to_duration(),
+                last.0.to_duration()
+            ));
+        }
+
+        let after = self.samples.partition_point(|(stamp, _)| *stamp < at);
+        let (t1, transform1) = self.samples[after];
+        if t1 == at || after == 0 {
+            return Ok(transform1);
+        }
+        let (t0, transform0) = self.samples[after - 1];
+        let span = t1.elapsed_since(t0).as_secs_f64();
+        let ratio = at.elapsed_since(t0).as_secs_f64() / span;
+        Ok(transform0.interpolate(&transform1, ratio))
+    }
+}
+
+/// Frame histories, keyed by child frame
+type FrameTree = Arc<Mutex<HashMap<String, FrameHistory>>>;
+
+/// Buffer of recent transforms, answering lookups between frames
+///
+/// Samples older than the cache duration, relative to the newest sample of the
+/// same frame, are discarded.
+pub struct TransformBuffer {
+    frames: FrameTree,
+    cache_duration: Duration,
+    _subscriber: Option<SubscriberHandle>,
+}
+
+impl TransformBuffer {
+    /// Default duration for which samples are kept
+    pub const DEFAULT_CACHE_DURATION: Duration = Duration::from_secs(10);
+
+    /// Creates an empty buffer, filled with [`TransformBuffer::insert`]
+    pub fn new() -> Self {
+        Self {
+            frames: Arc::default(),
+            cache_duration: Self::DEFAULT_CACHE_DURATION,
+            _subscriber: None,
+        }
+    }
+
+    /// Creates a buffer filled by the transforms published on [`TF_TOPIC`]
+    ///
+    /// Transforms are inserted as the node processes its callbacks, so the node
+    /// must be spinning. A node can only have one subscriber on the topic.
+    pub async fn subscribe(node: &Node) -> Result<Self> {
+        let frames = FrameTree::default();
+        let cache_duration = Self::DEFAULT_CACHE_DURATION;
+        let callback_frames = frames.clone();
+        let subscriber = node
+            .subscriber::<StampedTransform>(TF_TOPIC)
+            .build(move |transform| {
+                Self::insert_into(&callback_frames, cache_duration, &transform);
+            })
+            .await?;
+
+        Ok(Self {
+            frames,
+            cache_duration,
+            _subscriber: Some(subscriber),
+        })
+    }
+
+    /// Adds a transform to the buffer
+    ///
+    /// A transform that gives a frame a new parent replaces its history.
+    pub fn insert(&self, transform: &StampedTransform) {
+        Self::insert_into(&self.frames, self.cache_duration, transform);
+    }
+
+    fn insert_into(frames: &FrameTree, cache_duration: Duration, transform: &StampedTransform) {
+        let mut frames = frames.lock().unwrap_or_else(|e| e.into_inner());
+        let history = frames
+            .entry(transform.child.clone())
+            .or_insert_with(|| FrameHistory {
+                parent: transform.parent.clone(),
+                samples: VecDeque::new(),
+            });
+        if history.parent != transform.parent {
+            history.parent = transform.parent.clone();
+            history.samples.clear();
+        }
+
+        // Keep the samples ordered by time, replacing one with the same stamp
+        let stamp = transform.time();
+        let index = history.samples.partition_point(|(time, _)| *time < stamp);
+        match history.samples.get_mut(index) {
+            Some(sample) if sample.0 == stamp => sample.1 = transform.transform(),
+            _ => history
+                .samples
+                .insert(index, (stamp, transform.transform())),
+        }
+
+        if let Some(&(newest, _)) = history.samples.back() {
+            let oldest_kept = newest - cache_duration;
+            while history
+                .samples
+                .front()
+                .is_some_and(|(time, _)| *time < oldest_kept)
+            {
+                history.samples.pop_front();
+            }
+        }
+    }
+
+    /// Returns the names of all known frames, sorted
+    pub fn frames(&self) -> Vec<String> {
+        let frames = self.frames.lock().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<String> = frames
+            .iter()
+            .flat_map(|(child, history)| [child.clone(), history.parent.clone()])
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Looks up the transform from `child` to `parent` at time `at`
+    ///
+    /// `parent` and `child` can be any two frames of the same tree. Returns
+    /// [`Error::Transform`] if they are not connected or if a transform along
+    /// the way would have to be extrapolated beyond its buffered samples.
+    pub fn lookup(&self, parent: &str, child: &str, at: Time) -> Result<Transform> {
+        let frames = self.frames.lock().unwrap_or_else(|e| e.into_inner());
+        let to_ancestors = |frame: &str| -> Result<Vec<(String, Transform)>> {
+            // Transforms from `frame` to itself and to each of its ancestors
+            let mut chain = vec![(frame.to_string(), Transform::identity())];
+            let mut current = frame;
+            while let Some(history) = frames.get(current) {
+                if chain.iter().any(|(name, _)| *name == history.parent) {
+                    return Err(Error::transform(parent, child, "the frames form a cycle"));
+                }
+                let step = history.at(at).map_err(|reason| {
+                    Error::transform(
+                        parent,
+                        child,
+                        format!("transform from '{current}': {reason}"),
+                    )
+                })?;
+                let to_frame = chain[chain.len() - 1].1;
+                chain.push((history.parent.clone(), step.compose(&to_frame)));
+                current = &history.parent;
+            }
+            Ok(chain)
+        };
+
+        let child_chain = to_ancestors(child)?;
+        let parent_chain = to_ancestors(parent)?;
+        for (ancestor, parent_to_ancestor) in &parent_chain {
+            if let Some((_, child_to_ancestor)) =
+                child_chain.iter().find(|(name, _)| name == ancestor)
+            {
+                return Ok(parent_to_ancestor.inverse().compose(child_to_ancestor));
+            }
+        }
+        Err(Error::transform(
+            parent,
+            child,
+            "the frames are not connected",
+        ))
+    }
+}
+
+impl Default for TransformBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Publishes transforms on [`TF_TOPIC`]
+pub struct TransformBroadcaster {
+    publisher: PublisherHandle<StampedTransform>,
+}
+
+impl TransformBroadcaster {
+    /// Creates a broadcaster publishing from `node`
+    pub async fn new(node: &Node) -> Result<Self> {
+        let publisher = node.publisher::<StampedTransform>(TF_TOPIC).build().await?;
+        Ok(Self { publisher })
+    }
+
+    /// Publishes a transform
+    pub fn send(&self, transform: &StampedTransform) -> Result<()> {
+        self.publisher.publish(transform)
+    }
+
+    /// Publishes the transform from `child` to `parent` at the current time
+    pub fn send_now(&self, parent: &str, child: &str, transform: Transform) -> Result<()> {
+        self.send(&StampedTransform::new(
+            parent,
+            child,
+            transform,
+            Time::now(),
+        ))
+    }
+}
@@ -1,11 +1,17 @@
 //! Time utilities for Zenobuf
 
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use serde::{Deserialize, Serialize};
+
+/// Nanoseconds per second
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
 /// Time representation for Zenobuf
 ///
 /// This struct represents a point in time, similar to the Time message in ROS.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Time {
     /// Seconds since the Unix epoch
     pub sec: u64,
@@ -62,6 +68,82 @@ impl Time {
             .unwrap_or_else(|| Duration::new(0, 0));
         Self::from_duration(new_duration)
     }
+
+    /// Returns the time elapsed from `earlier` to this time, or zero if
+    /// `earlier` is later
+    pub fn elapsed_since(&self, earlier: Time) -> Duration {
+        self.to_duration().saturating_sub(earlier.to_duration())
+    }
+
+    /// Nanoseconds since the Unix epoch
+    fn as_nanos(&self) -> i128 {
+        i128::from(self.sec) * NANOS_PER_SEC + i128::from(self.nsec)
+    }
+
+    /// Creates a Time from nanoseconds since the Unix epoch, clamped to the
+    /// representable range
+    fn from_nanos(nanos: i128) -> Self {
+        let nanos = nanos.max(0);
+        let sec = u64::try_from(nanos / NANOS_PER_SEC).unwrap_or(u64::MAX);
+        Self::new(sec, (nanos % NANOS_PER_SEC) as u32)
+    }
+}
+
+impl Add<Duration> for Time {
+    type Output = Time;
+
+    fn add(self, duration: Duration) -> Time {
+        Time::add(&self, duration)
+    }
+}
+
+impl Sub<Duration> for Time {
+    type Output = Time;
+
+    /// Subtracts a Duration, saturating at the Unix epoch
+    fn sub(self, duration: Duration) -> Time {
+        Time::sub(&self, duration)
+    }
+}
+
+impl AddAssign<Duration> for Time {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl SubAssign<Duration> for Time {
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Add<ZenobufDuration> for Time {
+    type Output = Time;
+
+    /// Adds a signed duration, saturating at the Unix epoch
+    fn add(self, duration: ZenobufDuration) -> Time {
+        Time::from_nanos(self.as_nanos() + duration.as_nanos())
+    }
+}
+
+impl Sub<ZenobufDuration> for Time {
+    type Output = Time;
+
+    /// Subtracts a signed duration, saturating at the Unix epoch
+    fn sub(self, duration: ZenobufDuration) -> Time {
+        Time::from_nanos(self.as_nanos() - duration.as_nanos())
+    }
+}
+
+impl Sub<Time> for Time {
+    type Output = ZenobufDuration;
+
+    /// Returns the signed duration from `other` to this time, negative if
+    /// `other` is later
+    fn sub(self, other: Time) -> ZenobufDuration {
+        ZenobufDuration::from_nanos(self.as_nanos() - other.as_nanos())
+    }
 }
 
 impl From<Duration> for Time {
@@ -119,6 +201,69 @@ impl ZenobufDuration {
             self.nsec += sec_adj * 1_000_000_000;
         }
     }
+
+    /// Returns true if the Duration is negative
+    pub fn is_negative(&self) -> bool {
+        self.sec < 0
+    }
+
+    /// Total nanoseconds, negative for a negative Duration
+    fn as_nanos(&self) -> i128 {
+        i128::from(self.sec) * NANOS_PER_SEC + i128::from(self.nsec)
+    }
+
+    /// Creates a Duration from nanoseconds, clamped to the representable range
+    fn from_nanos(nanos: i128) -> Self {
+        let sec = nanos.div_euclid(NANOS_PER_SEC);
+        let nsec = nanos.rem_euclid(NANOS_PER_SEC) as i32;
+        match i32::try_from(sec) {
+            Ok(sec) => Self { sec, nsec },
+            Err(_) if sec < 0 => Self {
+                sec: i32::MIN,
+                nsec: 0,
+            },
+            Err(_) => Self {
+                sec: i32::MAX,
+                nsec: 999_999_999,
+            },
+        }
+    }
+}
+
+impl Add for ZenobufDuration {
+    type Output = ZenobufDuration;
+
+    fn add(self, other: ZenobufDuration) -> ZenobufDuration {
+        ZenobufDuration::from_nanos(self.as_nanos() + other.as_nanos())
+    }
+}
+
+impl Sub for ZenobufDuration {
+    type Output = ZenobufDuration;
+
+    fn sub(self, other: ZenobufDuration) -> ZenobufDuration {
+        ZenobufDuration::from_nanos(self.as_nanos() - other.as_nanos())
+    }
+}
+
+impl Neg for ZenobufDuration {
+    type Output = ZenobufDuration;
+
+    fn neg(self) -> ZenobufDuration {
+        ZenobufDuration::from_nanos(-self.as_nanos())
+    }
+}
+
+impl AddAssign for ZenobufDuration {
+    fn add_assign(&mut self, other: ZenobufDuration) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for ZenobufDuration {
+    fn sub_assign(&mut self, other: ZenobufDuration) {
+        *self = *self - other;
+    }
 }
 
 impl From<Duration> for ZenobufDuration {
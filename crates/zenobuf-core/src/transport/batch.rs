@@ -0,0 +1,57 @@
+//! Framing of several encoded messages into a single payload
+//!
+//! Each message is prefixed with its length as a varint, the same framing
+//! Protobuf uses for length-delimited fields.
+
+use prost::encoding::{decode_varint, encode_varint, encoded_len_varint};
+
+/// Concatenates encoded messages into one framed payload
+pub(crate) fn encode_batch(messages: &[Vec<u8>]) -> Vec<u8> {
+    let len = messages
+        .iter()
+        .map(|message| encoded_len_varint(message.len() as u64) + message.len())
+        .sum();
+    let mut buf = Vec::with_capacity(len);
+    for message in messages {
+        encode_varint(message.len() as u64, &mut buf);
+        buf.extend_from_slice(message);
+    }
+    buf
+}
+
+/// Splits a framed payload back into the encoded messages
+///
+/// Returns `None` if the payload is truncated or malformed.
+pub(crate) fn decode_batch(mut bytes: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut messages = Vec::new();
+    while !bytes.is_empty() {
+        let len = usize::try_from(decode_varint(&mut bytes).ok()?).ok()?;
+        if bytes.len() < len {
+            return None;
+        }
+        let (message, rest) = bytes.split_at(len);
+        messages.push(message);
+        bytes = rest;
+    }
+    Some(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let messages = vec![vec![], vec![1, 2, 3], vec![0xff; 300]];
+        let batch = encode_batch(&messages);
+        let decoded = decode_batch(&batch).unwrap();
+        assert_eq!(decoded, messages);
+    }
+
+    #[test]
+    fn test_rejects_truncated_batch() {
+        let batch = encode_batch(&[vec![1, 2, 3]]);
+        assert!(decode_batch(&batch[..batch.len() - 1]).is_none());
+        assert_eq!(decode_batch(&[]), Some(vec![]));
+    }
+}
@@ -0,0 +1,248 @@
+//! Per-message metadata carried alongside the payload
+//!
+//! The header is encoded as a sequence of `[tag: u8][len: u8][value]` entries so
+//! that peers can skip entries they do not understand.
+
+/// Tag for the message type hash entry
+const TAG_TYPE_HASH: u8 = 1;
+
+/// Tag for the entry marking the payload as a batch of framed messages
+const TAG_BATCH: u8 = 2;
+
+/// Tag for the entry naming the codec the payload is compressed with
+const TAG_COMPRESSION: u8 = 3;
+
+/// Tag for the trace ID of a service request
+const TAG_TRACE_ID: u8 = 4;
+
+/// Tag for the name of the node making a service request
+const TAG_CALLER: u8 = 5;
+
+/// Tag for the deadline of a service request, in nanoseconds since the Unix epoch
+const TAG_DEADLINE: u8 = 6;
+
+/// Tag for the ID of the publisher that sent a message
+const TAG_PUBLISHER_ID: u8 = 7;
+
+/// Tag for the sequence number of a message among those of its publisher
+const TAG_SEQUENCE: u8 = 8;
+
+/// Tag for the name of the codec the payload was encoded with, if not the default
+const TAG_CODEC: u8 = 9;
+
+/// Tag for the type name of the published message
+const TAG_TYPE_NAME: u8 = 10;
+
+/// Metadata attached to each published message or service request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MessageHeader {
+    /// Schema hash of the published message type
+    pub type_hash: Option<u64>,
+    /// Whether the payload is a batch of framed messages
+    pub batch: bool,
+    /// Tag of the codec the payload is compressed with, 0 if uncompressed
+    pub compression: u8,
+    /// Trace ID of a service request
+    pub trace_id: Option<[u8; 16]>,
+    /// Name of the node making a service request, at most 255 bytes long
+    pub caller: Option<String>,
+    /// Deadline of a service request, in nanoseconds since the Unix epoch
+    pub deadline: Option<u64>,
+    /// ID of the publisher that sent the message
+    pub publisher_id: Option<[u8; 16]>,
+    /// Sequence number of the message among those of its publisher
+    pub sequence: Option<u64>,
+    /// Name of the codec the payload was encoded with, `None` for
+    /// [`ProstCodec`](crate::codec::ProstCodec)
+    pub codec: Option<String>,
+    /// Type name of the published message, at most 255 bytes long
+    pub type_name: Option<String>,
+}
+
+impl MessageHeader {
+    /// Encodes the header into bytes
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        if let Some(hash) = self.type_hash {
+            buf.push(TAG_TYPE_HASH);
+            buf.push(8);
+            buf.extend_from_slice(&hash.to_le_bytes());
+        }
+        if self.batch {
+            buf.push(TAG_BATCH);
+            buf.push(0);
+        }
+        if self.compression != 0 {
+            buf.extend_from_slice(&[TAG_COMPRESSION, 1, self.compression]);
+        }
+        if let Some(trace_id) = self.trace_id {
+            buf.extend_from_slice(&[TAG_TRACE_ID, 16]);
+            buf.extend_from_slice(&trace_id);
+        }
+        if let Some(caller) = &self.caller {
+            push_str(&mut buf, TAG_CALLER, caller);
+        }
+        if let Some(deadline) = self.deadline {
+            buf.extend_from_slice(&[TAG_DEADLINE, 8]);
+            buf.extend_from_slice(&deadline.to_le_bytes());
+        }
+        if let Some(publisher_id) = self.publisher_id {
+            buf.extend_from_slice(&[TAG_PUBLISHER_ID, 16]);
+            buf.extend_from_slice(&publisher_id);
+        }
+        if let Some(sequence) = self.sequence {
+            buf.extend_from_slice(&[TAG_SEQUENCE, 8]);
+            buf.extend_from_slice(&sequence.to_le_bytes());
+        }
+        if let Some(codec) = &self.codec {
+            push_str(&mut buf, TAG_CODEC, codec);
+        }
+        if let Some(type_name) = &self.type_name {
+            push_str(&mut buf, TAG_TYPE_NAME, type_name);
+        }
+        buf
+    }
+
+    /// Decodes a header from bytes
+    ///
+    /// Unknown entries are skipped and a truncated entry ends decoding.
+    pub fn decode(bytes: &[u8]) -> Self {
+        let mut header = Self::default();
+        let mut rest = bytes;
+        while let [tag, len, tail @ ..] = rest {
+            let len = usize::from(*len);
+            if tail.len() < len {
+                break;
+            }
+            let (value, tail) = tail.split_at(len);
+            match *tag {
+                TAG_TYPE_HASH => {
+                    if let Ok(value) = <[u8; 8]>::try_from(value) {
+                        header.type_hash = Some(u64::from_le_bytes(value));
+                    }
+                }
+                TAG_BATCH => header.batch = true,
+                TAG_COMPRESSION => {
+                    if let [codec] = value {
+                        header.compression = *codec;
+                    }
+                }
+                TAG_TRACE_ID => header.trace_id = <[u8; 16]>::try_from(value).ok(),
+                TAG_CALLER => header.caller = String::from_utf8(value.to_vec()).ok(),
+                TAG_DEADLINE => {
+                    if let Ok(value) = <[u8; 8]>::try_from(value) {
+                        header.deadline = Some(u64::from_le_bytes(value));
+                    }
+                }
+                TAG_PUBLISHER_ID => header.publisher_id = <[u8; 16]>::try_from(value).ok(),
+                TAG_SEQUENCE => {
+                    if let Ok(value) = <[u8; 8]>::try_from(value) {
+                        header.sequence = Some(u64::from_le_bytes(value));
+                    }
+                }
+                TAG_CODEC => header.codec = String::from_utf8(value.to_vec()).ok(),
+                TAG_TYPE_NAME => header.type_name = String::from_utf8(value.to_vec()).ok(),
+                _ => {}
+            }
+            rest = tail;
+        }
+        header
+    }
+}
+
+/// Returns the message type name carried by the attachment of a sample
+///
+/// Zenobuf publishers attach the [`Message::type_name`](crate::Message::type_name)
+/// of every message they send, which lets generic tools such as `zenobuf-cli
+/// monitor` tell what a topic carries. Returns `None` for samples sent by peers
+/// that do not attach it.
+pub fn attached_type_name(attachment: &[u8]) -> Option<String> {
+    MessageHeader::decode(attachment).type_name
+}
+
+/// Appends a string entry
+///
+/// Entries hold at most 255 bytes, so longer strings are cut at a char boundary.
+fn push_str(buf: &mut Vec<u8>, tag: u8, value: &str) {
+    let mut len = value.len().min(usize::from(u8::MAX));
+    while !value.is_char_boundary(len) {
+        len -= 1;
+    }
+    buf.extend_from_slice(&[tag, len as u8]);
+    buf.extend_from_slice(&value.as_bytes()[..len]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let header = MessageHeader {
+            type_hash: Some(0x0123_4567_89ab_cdef),
+            batch: false,
+            compression: 0,
+            ..MessageHeader::default()
+        };
+        assert_eq!(MessageHeader::decode(&header.encode()), header);
+
+        let batch = MessageHeader {
+            batch: true,
+            ..header.clone()
+        };
+        assert_eq!(MessageHeader::decode(&batch.encode()), batch);
+
+        let compressed = MessageHeader {
+            compression: 2,
+            ..batch
+        };
+        assert_eq!(MessageHeader::decode(&compressed.encode()), compressed);
+
+        let request = MessageHeader {
+            trace_id: Some([7; 16]),
+            caller: Some("planner".to_string()),
+            deadline: Some(1_700_000_000_000_000_000),
+            ..compressed
+        };
+        assert_eq!(MessageHeader::decode(&request.encode()), request);
+
+        let stamped = MessageHeader {
+            publisher_id: Some([3; 16]),
+            sequence: Some(42),
+            ..MessageHeader::default()
+        };
+        assert_eq!(MessageHeader::decode(&stamped.encode()), stamped);
+
+        let coded = MessageHeader {
+            codec: Some("bincode".to_string()),
+            ..stamped
+        };
+        assert_eq!(MessageHeader::decode(&coded.encode()), coded);
+
+        let named = MessageHeader {
+            type_name: Some("my_app.Point".to_string()),
+            ..coded
+        };
+        assert_eq!(MessageHeader::decode(&named.encode()), named);
+        assert_eq!(
+            attached_type_name(&named.encode()).as_deref(),
+            Some("my_app.Point")
+        );
+    }
+
+    #[test]
+    fn test_skips_unknown_and_truncated_entries() {
+        let mut bytes = vec![0xff, 2, 0xaa, 0xbb];
+        bytes.extend(
+            MessageHeader {
+                type_hash: Some(7),
+                ..MessageHeader::default()
+            }
+            .encode(),
+        );
+        bytes.extend([TAG_TYPE_HASH, 8, 1]);
+
+        assert_eq!(MessageHeader::decode(&bytes).type_hash, Some(7));
+        assert_eq!(MessageHeader::decode(&[]), MessageHeader::default());
+    }
+}
@@ -0,0 +1,245 @@
+//! Liveliness tokens used to track matching publishers and subscribers
+//!
+//! Each publisher, subscriber and service declares a token under
+//! `zenobuf/liveliness/<name>/<role>/<id>`. Publishers and subscribers watch the
+//! tokens of the opposite role on the same topic, and clients query for service
+//! tokens to find out whether a server is available.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::StreamExt;
+use zenoh::sample::SampleKind;
+
+use super::{GraphEvent, GraphEvents};
+use crate::error::{Error, Result};
+
+/// Prefix for liveliness key expressions
+pub(crate) const LIVELINESS_PREFIX: &str = "zenobuf/liveliness/";
+
+/// Interval between liveliness queries while waiting for an endpoint
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Counter used to give each endpoint of a session a unique token
+static NEXT_ENDPOINT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Role of an endpoint on a topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    Publisher,
+    Subscriber,
+    Service,
+}
+
+impl Role {
+    /// Returns the key chunk for this role
+    fn as_str(self) -> &'static str {
+        match self {
+            Role::Publisher => "pub",
+            Role::Subscriber => "sub",
+            Role::Service => "srv",
+        }
+    }
+}
+
+/// Parses a token key into the name and role of its endpoint
+fn parse_token_key(key: &str) -> Option<(&str, Role)> {
+    let (rest, _id) = key.strip_prefix(LIVELINESS_PREFIX)?.rsplit_once('/')?;
+    let (name, role) = rest.rsplit_once('/')?;
+    let role = match role {
+        "pub" => Role::Publisher,
+        "sub" => Role::Subscriber,
+        "srv" => Role::Service,
+        _ => return None,
+    };
+    Some((name, role))
+}
+
+/// Returns the key expression matching every token of a role under a name
+fn role_key(name: &str, role: Role) -> String {
+    format!("{LIVELINESS_PREFIX}{name}/{}/*", role.as_str())
+}
+
+/// Returns an identifier unique to one endpoint across all sessions
+pub(crate) fn endpoint_id(session: &zenoh::Session) -> String {
+    let id = NEXT_ENDPOINT_ID.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{id}", session.zid())
+}
+
+/// Declares a liveliness token for an endpoint
+pub(crate) async fn declare_token(
+    session: &zenoh::Session,
+    name: &str,
+    role: Role,
+) -> Result<zenoh::liveliness::LivelinessToken> {
+    let token_key = format!(
+        "{LIVELINESS_PREFIX}{name}/{}/{}",
+        role.as_str(),
+        endpoint_id(session)
+    );
+
+    session
+        .liveliness()
+        .declare_token(token_key)
+        .await
+        .map_err(Error::from)
+}
+
+/// Returns whether at least one token of a role is alive under a name
+pub(crate) async fn is_alive(
+    session: &zenoh::Session,
+    name: &str,
+    role: Role,
+    timeout: Duration,
+) -> Result<bool> {
+    let replies = session
+        .liveliness()
+        .get(role_key(name, role))
+        .timeout(timeout)
+        .await
+        .map_err(Error::from)?;
+
+    while let Ok(reply) = replies.recv_async().await {
+        if reply.result().is_ok() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Waits until at least one token of a role is alive under a name
+///
+/// Returns `false` if none appears within `timeout`.
+pub(crate) async fn wait_until_alive(
+    session: &zenoh::Session,
+    name: &str,
+    role: Role,
+    timeout: Duration,
+) -> Result<bool> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if is_alive(session, name, role, POLL_INTERVAL).await? {
+            return Ok(true);
+        }
+
+        let now = tokio::time::Instant::now();
+        if now >= deadline {
+            return Ok(false);
+        }
+        tokio::time::sleep(POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
+/// Calls `on_added` whenever a token of a role appears under a name
+///
+/// Tokens that are already alive are reported first. The watch ends when the
+/// returned subscriber is dropped.
+pub(crate) async fn watch_added<F>(
+    session: &zenoh::Session,
+    name: &str,
+    role: Role,
+    on_added: F,
+) -> Result<zenoh::pubsub::Subscriber<()>>
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    session
+        .liveliness()
+        .declare_subscriber(role_key(name, role))
+        .history(true)
+        .callback(move |sample| {
+            if sample.kind() == SampleKind::Put {
+                on_added();
+            }
+        })
+        .await
+        .map_err(Error::from)
+}
+
+/// Watches the tokens of every endpoint, reporting them as graph events
+///
+/// Endpoints that are already alive are reported as added first.
+pub(crate) async fn watch_graph(session: &zenoh::Session) -> Result<GraphEvents> {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let subscriber = session
+        .liveliness()
+        .declare_subscriber(format!("{LIVELINESS_PREFIX}**"))
+        .history(true)
+        .callback(move |sample| {
+            let Some((name, role)) = parse_token_key(sample.key_expr().as_str()) else {
+                return;
+            };
+            let name = name.to_string();
+            let added = sample.kind() == SampleKind::Put;
+            let event = match (role, added) {
+                (Role::Publisher, true) => GraphEvent::PublisherAdded { topic: name },
+                (Role::Publisher, false) => GraphEvent::PublisherRemoved { topic: name },
+                (Role::Subscriber, true) => GraphEvent::SubscriberAdded { topic: name },
+                (Role::Subscriber, false) => GraphEvent::SubscriberRemoved { topic: name },
+                (Role::Service, true) => GraphEvent::ServiceAdded { name },
+                (Role::Service, false) => GraphEvent::ServiceRemoved { name },
+            };
+            let _ = sender.send(event);
+        })
+        .await
+        .map_err(Error::from)?;
+
+    // The stream owns the liveliness subscriber, which is undeclared when it is dropped
+    Ok(futures::stream::unfold(
+        (receiver, subscriber),
+        |(mut receiver, subscriber)| async move {
+            let event = receiver.recv().await?;
+            Some((event, (receiver, subscriber)))
+        },
+    )
+    .boxed())
+}
+
+/// Liveliness token of an endpoint, along with the matching peers it has seen
+pub(crate) struct Liveliness {
+    _token: zenoh::liveliness::LivelinessToken,
+    _peers: zenoh::pubsub::Subscriber<()>,
+    matched: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Liveliness {
+    /// Declares a token for an endpoint and starts tracking the peers with role `peer`
+    pub async fn declare(
+        session: &zenoh::Session,
+        topic: &str,
+        role: Role,
+        peer: Role,
+    ) -> Result<Self> {
+        let token = declare_token(session, topic, role).await?;
+
+        let matched = Arc::new(Mutex::new(HashSet::new()));
+        let callback_matched = matched.clone();
+        let peers = session
+            .liveliness()
+            .declare_subscriber(role_key(topic, peer))
+            .history(true)
+            .callback(move |sample| {
+                let key = sample.key_expr().to_string();
+                let mut matched = callback_matched.lock().unwrap_or_else(|e| e.into_inner());
+                match sample.kind() {
+                    SampleKind::Put => matched.insert(key),
+                    SampleKind::Delete => matched.remove(&key),
+                };
+            })
+            .await
+            .map_err(Error::from)?;
+
+        Ok(Self {
+            _token: token,
+            _peers: peers,
+            matched,
+        })
+    }
+
+    /// Returns the number of live peers with the opposite role
+    pub fn matched_count(&self) -> usize {
+        self.matched.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+}
@@ -0,0 +1,416 @@
+//! In-memory mock transport for testing without a Zenoh session
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::codec::{check_codec, codec_tag, Codec, ProstCodec, SharedCodec};
+use crate::error::{Error, Result, NO_SERVICE};
+use crate::message::{check_encoding, decode_message, encode_message, Encoding, Message};
+
+use super::{
+    event_sender, event_stream, BoxFuture, Client, Publisher, Service, Subscriber, Transport,
+    TransportEvent, TransportEvents,
+};
+
+/// A payload recorded by the mock transport, along with its encoding tag
+#[derive(Debug, Clone)]
+pub struct MockSample {
+    /// Raw payload bytes
+    pub payload: Vec<u8>,
+    /// Encoding tag, or `None` for untagged payloads
+    pub encoding: Option<Encoding>,
+    /// Type hash of the published message, or `None` for untyped payloads
+    pub type_hash: Option<u64>,
+    /// Name of the codec the payload was encoded with, `None` for
+    /// [`ProstCodec`]
+    pub codec: Option<String>,
+}
+
+/// Type-erased service handler operating on encoded payloads
+type MockHandler = Arc<dyn Fn(&[u8], Option<Encoding>) -> Result<Vec<u8>> + Send + Sync>;
+
+/// Type-erased subscriber callback operating on recorded samples
+type SampleHandler = Arc<dyn Fn(&MockSample) + Send + Sync>;
+
+/// Live subscribers per topic, each with the ID it was registered under
+type SubscriberRegistry = Arc<Mutex<HashMap<String, Vec<(u64, SampleHandler)>>>>;
+
+/// In-memory transport for tests
+///
+/// Published messages are recorded per topic. Subscribers receive the messages
+/// already recorded on their topic when they are created, then every message
+/// published on it, in order, on the publishing thread. Service calls invoke
+/// the registered handler directly on the calling thread. Connectivity changes
+/// are only reported when simulated with [`MockTransport::emit_event`].
+///
+/// A [`Node`](crate::Node) can run on the mock with
+/// [`Node::with_transport`](crate::Node::with_transport), queueing subscriber
+/// callbacks until it spins. Features that need Zenoh, such as the builders or
+/// discovery, are not available on such a node.
+#[derive(Clone)]
+pub struct MockTransport {
+    topics: Arc<Mutex<HashMap<String, Vec<MockSample>>>>,
+    subscribers: SubscriberRegistry,
+    next_subscriber_id: Arc<AtomicU64>,
+    services: Arc<Mutex<HashMap<String, MockHandler>>>,
+    events: tokio::sync::broadcast::Sender<TransportEvent>,
+    congested: Arc<AtomicBool>,
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        Self {
+            topics: Arc::default(),
+            subscribers: Arc::default(),
+            next_subscriber_id: Arc::default(),
+            services: Arc::default(),
+            events: event_sender(),
+            congested: Arc::default(),
+        }
+    }
+}
+
+impl MockTransport {
+    /// Creates a new, empty mock transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reports a connectivity change, as if the links of the transport had changed
+    pub fn emit_event(&self, event: TransportEvent) {
+        // Sending only fails when nobody is listening
+        let _ = self.events.send(event);
+    }
+
+    /// Simulates a congested network whose send buffers are full
+    ///
+    /// While congested, [`Publisher::try_publish`](crate::Publisher::try_publish)
+    /// reports messages as not sent, while `publish` still records them as if it
+    /// had waited for the congestion to clear.
+    pub fn set_congested(&self, congested: bool) {
+        self.congested.store(congested, Ordering::Relaxed);
+    }
+
+    /// Records a raw payload on a topic, as if it had been sent by another peer
+    pub fn publish_raw(&self, topic: &str, payload: Vec<u8>, encoding: Option<Encoding>) {
+        self.record(
+            topic,
+            MockSample {
+                payload,
+                encoding,
+                type_hash: None,
+                codec: None,
+            },
+        );
+    }
+
+    /// Records a sample on a topic and delivers it to the topic's subscribers
+    ///
+    /// The callbacks run without holding any lock, so they may publish in turn.
+    fn record(&self, topic: &str, sample: MockSample) {
+        // Recording under the subscriber lock keeps new subscribers from missing the sample
+        let handlers: Vec<SampleHandler> = {
+            let subscribers = self.subscribers.lock().unwrap();
+            self.topics
+                .lock()
+                .unwrap()
+                .entry(topic.to_string())
+                .or_default()
+                .push(sample.clone());
+            subscribers
+                .get(topic)
+                .map(|handlers| {
+                    handlers
+                        .iter()
+                        .map(|(_, handler)| handler.clone())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        for handler in handlers {
+            handler(&sample);
+        }
+    }
+
+    /// Returns the samples recorded on a topic
+    pub fn samples(&self, topic: &str) -> Vec<MockSample> {
+        self.topics
+            .lock()
+            .unwrap()
+            .get(topic)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for MockTransport {
+    async fn create_publisher<M: Message>(
+        &self,
+        topic: &str,
+    ) -> Result<Arc<crate::publisher::Publisher<M>>> {
+        self.create_publisher_with_codec(topic, Arc::new(ProstCodec))
+            .await
+    }
+
+    async fn create_subscriber<M: Message, F>(
+        &self,
+        topic: &str,
+        callback: F,
+    ) -> Result<Arc<crate::subscriber::Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        self.create_subscriber_with_codec(topic, Arc::new(ProstCodec), callback)
+            .await
+    }
+
+    async fn create_publisher_with_codec<M: Message>(
+        &self,
+        topic: &str,
+        codec: Arc<dyn Codec<M>>,
+    ) -> Result<Arc<crate::publisher::Publisher<M>>> {
+        let inner = MockPublisher {
+            topic: topic.to_string(),
+            transport: self.clone(),
+            codec,
+        };
+        Ok(Arc::new(crate::publisher::Publisher::new(
+            topic.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    async fn create_subscriber_with_codec<M: Message, F>(
+        &self,
+        topic: &str,
+        codec: Arc<dyn Codec<M>>,
+        callback: F,
+    ) -> Result<Arc<crate::subscriber::Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        let handler: SampleHandler = {
+            let topic = topic.to_string();
+            Arc::new(move |sample| deliver(&topic, sample, codec.as_ref(), &callback))
+        };
+        // Registering and reading the recorded samples under the subscriber lock
+        // delivers each sample exactly once, either replayed or live
+        let id = self.next_subscriber_id.fetch_add(1, Ordering::Relaxed);
+        let recorded = {
+            let mut subscribers = self.subscribers.lock().unwrap();
+            subscribers
+                .entry(topic.to_string())
+                .or_default()
+                .push((id, handler.clone()));
+            self.samples(topic)
+        };
+        for sample in &recorded {
+            handler(sample);
+        }
+
+        Ok(Arc::new(crate::subscriber::Subscriber::new(
+            topic.to_string(),
+            Box::new(MockSubscriber {
+                topic: topic.to_string(),
+                id,
+                subscribers: self.subscribers.clone(),
+            }),
+        )))
+    }
+
+    async fn create_service<Req: Message, Res: Message, F>(
+        &self,
+        service_name: &str,
+        handler: F,
+    ) -> Result<Arc<crate::service::Service>>
+    where
+        F: Fn(Req) -> Result<Res> + Send + Sync + 'static,
+    {
+        let handler: MockHandler = Arc::new(move |bytes, encoding| {
+            check_encoding(Req::ENCODING, encoding)?;
+            let request = decode_message::<Req>(bytes)?;
+            encode_message(&handler(request)?)
+        });
+        self.services
+            .lock()
+            .unwrap()
+            .insert(service_name.to_string(), handler);
+
+        Ok(Arc::new(crate::service::Service::new(
+            service_name.to_string(),
+            Box::new(MockService),
+        )))
+    }
+
+    fn create_client<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+    ) -> Result<Arc<crate::client::Client<Req, Res>>> {
+        let inner = MockClient {
+            service_name: service_name.to_string(),
+            transport: self.clone(),
+            _phantom: PhantomData,
+        };
+        Ok(Arc::new(crate::client::Client::new(
+            service_name.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    fn transport_events(&self) -> TransportEvents {
+        event_stream(self.events.subscribe())
+    }
+}
+
+/// Mock publisher that records encoded messages on the transport
+struct MockPublisher<M: Message> {
+    topic: String,
+    transport: MockTransport,
+    codec: SharedCodec<M>,
+}
+
+impl<M: Message> Publisher<M> for MockPublisher<M> {
+    fn publish(&self, message: &M) -> Result<usize> {
+        let payload = self.codec.encode(message)?;
+        let len = payload.len();
+        let sample = MockSample {
+            payload,
+            encoding: Some(M::ENCODING),
+            type_hash: Some(M::type_hash()),
+            codec: codec_tag(self.codec.name()),
+        };
+        self.transport.record(&self.topic, sample);
+        Ok(len)
+    }
+
+    fn try_publish(&self, message: &M) -> Result<Option<usize>> {
+        if self.transport.congested.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        self.publish(message).map(Some)
+    }
+}
+
+/// Decodes a recorded sample with `codec` and passes it to a subscriber callback
+///
+/// Samples of another encoding, type or codec, or that fail validation, are
+/// dropped with a warning.
+fn deliver<M: Message>(
+    topic: &str,
+    sample: &MockSample,
+    codec: &dyn Codec<M>,
+    callback: &impl Fn(M),
+) {
+    if let Err(e) = check_encoding(M::ENCODING, sample.encoding) {
+        tracing::warn!("Dropping message on {}: {}", topic, e);
+        return;
+    }
+    if let Err(e) = check_codec(codec.name(), sample.codec.as_deref()) {
+        tracing::warn!("Dropping message on {}: {}", topic, e);
+        return;
+    }
+    if sample.type_hash.is_some_and(|hash| hash != M::type_hash()) {
+        tracing::warn!(
+            "Dropping message on {}: type hash does not match {}",
+            topic,
+            M::type_name()
+        );
+        return;
+    }
+    match codec.decode(&sample.payload) {
+        Ok(message) => match message.validate() {
+            Ok(()) => callback(message),
+            Err(e) => tracing::warn!("Dropping message on {}: {}", topic, e),
+        },
+        Err(e) => tracing::warn!("Failed to decode subscriber message: {}", e),
+    }
+}
+
+/// Mock subscriber, receiving messages until it is closed or dropped
+struct MockSubscriber {
+    topic: String,
+    id: u64,
+    subscribers: SubscriberRegistry,
+}
+
+impl Subscriber for MockSubscriber {
+    fn close(&self) -> Result<()> {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(handlers) = subscribers.get_mut(&self.topic) {
+            handlers.retain(|(id, _)| *id != self.id);
+            if handlers.is_empty() {
+                subscribers.remove(&self.topic);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for MockSubscriber {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Mock service
+struct MockService;
+
+impl Service for MockService {
+    fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Mock client that invokes registered handlers directly
+struct MockClient<Req: Message, Res: Message> {
+    service_name: String,
+    transport: MockTransport,
+    _phantom: PhantomData<(Req, Res)>,
+}
+
+impl<Req: Message, Res: Message> Client<Req, Res> for MockClient<Req, Res> {
+    fn call(&self, request: &Req) -> Result<Res> {
+        let handler = self
+            .transport
+            .services
+            .lock()
+            .unwrap()
+            .get(&self.service_name)
+            .cloned()
+            .ok_or_else(|| Error::service_call_failed(&self.service_name, NO_SERVICE))?;
+
+        let bytes = encode_message(request)?;
+        let response = handler(&bytes, Some(Req::ENCODING))?;
+        decode_message::<Res>(&response)
+    }
+
+    fn call_async<'a>(&'a self, request: &'a Req) -> BoxFuture<'a, Result<Res>> {
+        Box::pin(async move { self.call(request) })
+    }
+
+    fn wait_for_service(&self, timeout: Duration) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let deadline = tokio::time::Instant::now() + timeout;
+            while !self
+                .transport
+                .services
+                .lock()
+                .unwrap()
+                .contains_key(&self.service_name)
+            {
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(Error::service_call_timeout(
+                        &self.service_name,
+                        timeout.as_millis() as u64,
+                    ));
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Ok(())
+        })
+    }
+}
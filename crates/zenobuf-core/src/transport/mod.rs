@@ -3,22 +3,110 @@
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::error::Result;
+use uuid::Uuid;
+
+use crate::codec::Codec;
+use crate::error::{Error, Result};
 use crate::message::Message;
+use crate::subscriber::{DeadlineCallback, SubscriberStats};
+mod batch;
+mod header;
+mod liveliness;
+mod mock;
+mod pool;
+mod span;
 mod zenoh;
 
-pub use self::zenoh::ZenohTransport;
+pub use self::header::attached_type_name;
+pub use self::mock::{MockSample, MockTransport};
+pub(crate) use self::zenoh::{DecodedSink, PayloadSink, SampleSink, SinkOptions};
+pub use self::zenoh::{SharedSession, ZenohTransport};
 
 /// A boxed future for async operations
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// A stream of connectivity changes reported by a transport
+pub type TransportEvents = futures::stream::BoxStream<'static, TransportEvent>;
+
+/// Change in the connectivity of a transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportEvent {
+    /// The transport gained its first link to a router or peer
+    Connected,
+    /// The transport lost its last link to a router or peer
+    Disconnected,
+}
+
+/// A Zenoh session known to a transport, with the endpoints it is reached on
+///
+/// For the transport's own session, `endpoints` are the locators it listens on.
+/// For a connected peer or router, they are the remote ends of its links.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PeerInfo {
+    /// Zenoh ID of the session
+    pub zid: String,
+    /// Locators of the session, e.g. `tcp/192.168.1.2:7447`
+    pub endpoints: Vec<String>,
+}
+
+/// A stream of changes in the graph of publishers, subscribers and services
+pub type GraphEvents = futures::stream::BoxStream<'static, GraphEvent>;
+
+/// Appearance or disappearance of a publisher, subscriber or service
+///
+/// Every endpoint is reported separately, so two publishers on a topic produce
+/// two `PublisherAdded` events. Names are given without a leading `/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphEvent {
+    /// A publisher was created on a topic
+    PublisherAdded { topic: String },
+    /// A publisher was dropped, or its node became unreachable
+    PublisherRemoved { topic: String },
+    /// A subscriber was created on a topic
+    SubscriberAdded { topic: String },
+    /// A subscriber was dropped, or its node became unreachable
+    SubscriberRemoved { topic: String },
+    /// A service was created
+    ServiceAdded { name: String },
+    /// A service was dropped, or its node became unreachable
+    ServiceRemoved { name: String },
+}
+
+/// Capacity of the broadcast channels carrying transport events
+const EVENT_CAPACITY: usize = 16;
+
+/// Creates the sender that transports broadcast their events on
+fn event_sender() -> tokio::sync::broadcast::Sender<TransportEvent> {
+    tokio::sync::broadcast::channel(EVENT_CAPACITY).0
+}
+
+/// Turns a subscription to a transport's events into a stream
+///
+/// Events missed because the receiver lagged behind are skipped.
+fn event_stream(receiver: tokio::sync::broadcast::Receiver<TransportEvent>) -> TransportEvents {
+    use futures::StreamExt;
+    use tokio::sync::broadcast::error::RecvError;
+
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
 /// Transport layer abstraction
 ///
 /// This trait defines the interface that all transport implementations must provide.
 /// It allows for pluggable transport layers while maintaining a consistent API.
-// TODO: Not yet wired into Node — Node currently uses ZenohTransport directly.
-// Refactor Node to accept a generic Transport to enable pluggable backends.
+/// A [`Node`](crate::Node) can run on any implementation, see
+/// [`Node::with_transport`](crate::Node::with_transport).
 #[async_trait::async_trait]
 pub trait Transport: Send + Sync + 'static {
     /// Create a publisher for the given topic
@@ -36,6 +124,25 @@ pub trait Transport: Send + Sync + 'static {
     where
         F: Fn(M) + Send + Sync + 'static;
 
+    /// Create a publisher for the given topic, encoding messages with `codec`
+    async fn create_publisher_with_codec<M: Message>(
+        &self,
+        topic: &str,
+        codec: Arc<dyn Codec<M>>,
+    ) -> Result<Arc<crate::publisher::Publisher<M>>>;
+
+    /// Create a subscriber for the given topic, decoding messages with `codec`
+    ///
+    /// Messages encoded with another codec are dropped.
+    async fn create_subscriber_with_codec<M: Message, F>(
+        &self,
+        topic: &str,
+        codec: Arc<dyn Codec<M>>,
+        callback: F,
+    ) -> Result<Arc<crate::subscriber::Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static;
+
     /// Create a service for the given service name with a handler
     async fn create_service<Req: Message, Res: Message, F>(
         &self,
@@ -50,18 +157,101 @@ pub trait Transport: Send + Sync + 'static {
         &self,
         service_name: &str,
     ) -> Result<Arc<crate::client::Client<Req, Res>>>;
+
+    /// Returns a stream of the connectivity changes of the transport
+    fn transport_events(&self) -> TransportEvents;
+
+    /// Returns the Zenoh transport this transport runs on, if any
+    ///
+    /// A node on a Zenoh transport can also be discovered, configured and
+    /// diagnosed remotely, and checks the QoS profiles of the publishers its
+    /// subscribers match. The default implementation returns `None`.
+    fn as_zenoh(&self) -> Option<&ZenohTransport> {
+        None
+    }
+
+    /// Mutable counterpart of [`Transport::as_zenoh`]
+    fn as_zenoh_mut(&mut self) -> Option<&mut ZenohTransport> {
+        None
+    }
 }
 
 /// Publisher abstraction
 pub trait Publisher<M: Message>: Send + Sync + 'static {
-    /// Publishes a message
-    fn publish(&self, message: &M) -> Result<()>;
+    /// Publishes a message, returning the size in bytes of the payload sent,
+    /// after any compression
+    fn publish(&self, message: &M) -> Result<usize>;
+
+    /// Publishes several messages at once, returning the size of the payload
+    /// sent, including the framing of the batch
+    ///
+    /// The default implementation publishes the messages one by one.
+    fn publish_batch(&self, messages: &[M]) -> Result<usize> {
+        messages.iter().map(|message| self.publish(message)).sum()
+    }
+
+    /// Publishes a message without waiting on a congested network
+    ///
+    /// Returns the size of the payload, or `Ok(None)` if the message was not
+    /// sent because sending it would have blocked. The default implementation
+    /// publishes normally.
+    fn try_publish(&self, message: &M) -> Result<Option<usize>> {
+        self.publish(message).map(Some)
+    }
+
+    /// Returns the number of subscribers currently matched with this publisher
+    ///
+    /// Transports that do not track matching report 0.
+    fn subscriber_count(&self) -> usize {
+        0
+    }
+
+    /// Returns the capacity in bytes of the buffer reused to encode messages
+    ///
+    /// Transports that encode into a fresh buffer every time report 0.
+    fn buffer_capacity(&self) -> usize {
+        0
+    }
+
+    /// Publishes a message and waits until a subscriber acknowledges it,
+    /// returning the size of its payload
+    ///
+    /// The default implementation reports that acknowledgments are not supported.
+    fn publish_acked<'a>(
+        &'a self,
+        _message: &'a M,
+        _timeout: Duration,
+    ) -> BoxFuture<'a, Result<usize>> {
+        Box::pin(async {
+            Err(Error::not_supported(
+                "publish_reliable",
+                "the transport does not support acknowledgments",
+            ))
+        })
+    }
 }
 
 /// Subscriber abstraction
 pub trait Subscriber: Send + Sync + 'static {
     /// Closes the subscriber
     fn close(&self) -> Result<()>;
+
+    /// Returns the subscriber statistics
+    fn stats(&self) -> SubscriberStats {
+        SubscriberStats::default()
+    }
+
+    /// Returns the number of publishers currently matched with this subscriber
+    ///
+    /// Transports that do not track matching report 0.
+    fn publisher_count(&self) -> usize {
+        0
+    }
+
+    /// Sets a callback invoked whenever the QoS deadline passes without a message
+    ///
+    /// Transports that do not track deadlines ignore the callback.
+    fn set_deadline_callback(&self, _callback: DeadlineCallback) {}
 }
 
 /// Service abstraction
@@ -77,4 +267,56 @@ pub trait Client<Req: Message, Res: Message>: Send + Sync + 'static {
 
     /// Calls the service with the given request asynchronously
     fn call_async<'a>(&'a self, request: &'a Req) -> BoxFuture<'a, Result<Res>>;
+
+    /// Calls the service asynchronously within an overall `timeout`
+    ///
+    /// Transports with their own retry loop override this so that retries stop at the
+    /// deadline. The default implementation ignores the timeout, which
+    /// [`crate::Client::call_with_timeout`] enforces around it.
+    fn call_with_timeout<'a>(
+        &'a self,
+        request: &'a Req,
+        _timeout: Duration,
+    ) -> BoxFuture<'a, Result<Res>> {
+        self.call_async(request)
+    }
+
+    /// Sends the request to every server of the service and collects the
+    /// responses that arrive within `timeout`
+    ///
+    /// Transports that can only reach one server return its response alone, or
+    /// no response if the call fails.
+    fn call_all<'a>(
+        &'a self,
+        request: &'a Req,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Vec<Res>>> {
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, self.call_with_timeout(request, timeout)).await {
+                Ok(Ok(response)) => Ok(vec![response]),
+                _ => Ok(Vec::new()),
+            }
+        })
+    }
+
+    /// Calls the service asynchronously as part of the trace `trace_id`
+    ///
+    /// Transports that do not carry request metadata ignore the trace ID.
+    fn call_traced<'a>(&'a self, request: &'a Req, _trace_id: Uuid) -> BoxFuture<'a, Result<Res>> {
+        self.call_async(request)
+    }
+
+    /// Returns the number of retries made across all calls so far
+    ///
+    /// Transports that never retry report zero.
+    fn retries(&self) -> u64 {
+        0
+    }
+
+    /// Waits until a server for the service is available
+    ///
+    /// Transports that cannot detect servers return immediately.
+    fn wait_for_service(&self, _timeout: Duration) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
 }
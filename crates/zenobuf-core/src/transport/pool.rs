@@ -0,0 +1,109 @@
+//! Buffers publishers encode messages into, reused across publishes
+//!
+//! An encoded buffer is handed to Zenoh as is, and returns to its pool once
+//! Zenoh drops the payload, so publishing neither allocates nor copies it.
+
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Number of free buffers a pool keeps, enough for a few concurrent publishes
+const MAX_FREE_BUFFERS: usize = 8;
+
+/// Free buffers of a pool, most recently returned last
+type FreeBuffers = Mutex<Vec<Vec<u8>>>;
+
+/// Pool of the buffers a publisher encodes messages into
+#[derive(Debug, Default)]
+pub(crate) struct BufferPool {
+    free: Arc<FreeBuffers>,
+}
+
+impl BufferPool {
+    /// Makes sure the next buffer taken has room for `capacity` bytes
+    pub(crate) fn reserve(&self, capacity: usize) {
+        let mut free = self.free.lock().unwrap_or_else(|e| e.into_inner());
+        match free.last_mut() {
+            Some(buffer) => buffer.reserve_exact(capacity),
+            None => free.push(Vec::with_capacity(capacity)),
+        }
+    }
+
+    /// Takes an empty buffer, allocating one if none is free
+    pub(crate) fn take(&self) -> PooledBuffer {
+        let buffer = self
+            .free
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop()
+            .unwrap_or_default();
+        PooledBuffer {
+            buffer,
+            pool: Arc::downgrade(&self.free),
+        }
+    }
+
+    /// Returns the capacity of the largest free buffer
+    pub(crate) fn capacity(&self) -> usize {
+        self.free
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(Vec::capacity)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// A payload, returned to the pool it was taken from when dropped
+pub(crate) struct PooledBuffer {
+    buffer: Vec<u8>,
+    pool: Weak<FreeBuffers>,
+}
+
+impl PooledBuffer {
+    /// Wraps a buffer that belongs to no pool, such as a compressed payload
+    pub(crate) fn unpooled(buffer: Vec<u8>) -> Self {
+        Self {
+            buffer,
+            pool: Weak::new(),
+        }
+    }
+
+    /// Returns the buffer to encode into
+    pub(crate) fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+
+    /// Hands the payload over to Zenoh without copying it
+    pub(crate) fn into_zbytes(self) -> zenoh::bytes::ZBytes {
+        bytes::Bytes::from_owner(self).into()
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl AsRef<[u8]> for PooledBuffer {
+    fn as_ref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let Some(pool) = self.pool.upgrade() else {
+            return;
+        };
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        let mut free = pool.lock().unwrap_or_else(|e| e.into_inner());
+        if free.len() < MAX_FREE_BUFFERS {
+            free.push(buffer);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Tracing spans around transport operations
+//!
+//! Every publish, received sample, service request and client call runs in a
+//! span carrying the node name, the topic or service, and a per-endpoint
+//! sequence number, so that logs emitted by the framework and by user callbacks
+//! can be attributed to the operation they belong to.
+
+use std::sync::Arc;
+
+use tracing::{Level, Span};
+
+/// Creates a span at a level only known at runtime
+macro_rules! span_at {
+    ($level:expr, $name:literal, $($fields:tt)*) => {{
+        let level = $level;
+        if level == Level::ERROR {
+            tracing::span!(Level::ERROR, $name, $($fields)*)
+        } else if level == Level::WARN {
+            tracing::span!(Level::WARN, $name, $($fields)*)
+        } else if level == Level::INFO {
+            tracing::span!(Level::INFO, $name, $($fields)*)
+        } else if level == Level::DEBUG {
+            tracing::span!(Level::DEBUG, $name, $($fields)*)
+        } else {
+            tracing::span!(Level::TRACE, $name, $($fields)*)
+        }
+    }};
+}
+
+/// Node name and level of the spans created by a transport
+#[derive(Debug, Clone)]
+pub(crate) struct SpanContext {
+    node: Arc<str>,
+    level: Level,
+}
+
+impl SpanContext {
+    /// Sets the name of the node the spans belong to
+    pub(crate) fn set_node(&mut self, node: &str) {
+        self.node = node.into();
+    }
+
+    /// Returns the name of the node the spans belong to
+    pub(crate) fn node(&self) -> &str {
+        &self.node
+    }
+
+    /// Sets the level the spans are created at
+    pub(crate) fn set_level(&mut self, level: Level) {
+        self.level = level;
+    }
+
+    /// Span of publishing the `seq`-th message on a topic
+    pub(crate) fn publish(&self, topic: &str, seq: u64) -> Span {
+        span_at!(self.level, "publish", node = &*self.node, topic, seq)
+    }
+
+    /// Span of receiving the `seq`-th sample on a topic, including its callback
+    pub(crate) fn receive(&self, topic: &str, seq: u64) -> Span {
+        span_at!(self.level, "receive", node = &*self.node, topic, seq)
+    }
+
+    /// Span of handling the `seq`-th request of a service
+    pub(crate) fn serve(&self, service: &str, seq: u64) -> Span {
+        span_at!(self.level, "serve", node = &*self.node, service, seq)
+    }
+
+    /// Span of the `seq`-th call made by a client, including its retries
+    pub(crate) fn call(&self, service: &str, seq: u64) -> Span {
+        span_at!(self.level, "call", node = &*self.node, service, seq)
+    }
+}
+
+impl Default for SpanContext {
+    fn default() -> Self {
+        Self {
+            node: "".into(),
+            level: Level::INFO,
+        }
+    }
+}
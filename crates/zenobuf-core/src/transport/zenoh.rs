@@ -1,44 +1,197 @@
 //! Zenoh transport implementation for Zenobuf
 
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
 use std::time::Duration;
 
+use tokio::sync::{broadcast, mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+use uuid::Uuid;
+use zenoh::handlers::{
+    FifoChannel, FifoChannelHandler, IntoHandler, RingChannel, RingChannelHandler,
+};
 use zenoh::qos::{CongestionControl, Priority};
-use zenoh::{self, key_expr::KeyExpr};
+use zenoh::sample::Sample;
+use zenoh::{self, key_expr::KeyExpr, Wait};
 
-use crate::error::{Error, Result};
-use crate::executor::CallbackExecutor;
-use crate::message::{decode_message, encode_message, Message};
-use crate::qos::{QosProfile, Reliability};
+use crate::codec::{check_codec, codec_tag, Codec, ProstCodec, SharedCodec};
+use crate::compression::Compression;
+use crate::error::{Error, Result, NO_SERVICE};
+use crate::executor::{self, CallbackExecutor};
+use crate::message::{
+    check_encoding, decode_message, decode_message_as, encode_message, encode_message_as,
+    encode_message_into, Encoding, Message, DEFAULT_MAX_MESSAGE_SIZE,
+};
+use crate::qos::{Durability, History, QosProfile};
+use crate::retry::RetryPolicy;
+use crate::service::{ErrorReply, RequestContext, RequestDispatch, Responder};
+use crate::subscriber::{
+    DeadlineCallback, DeadlineMonitor, DedupWindow, PayloadView, SubscriberCounters,
+    SubscriberHandler, SubscriberStats,
+};
+use crate::time::Time;
 
-use super::{BoxFuture, Client, Publisher, Service, Subscriber};
+use super::batch::{decode_batch, encode_batch};
+use super::header::MessageHeader;
+use super::liveliness::{self, Liveliness, Role};
+use super::pool::{BufferPool, PooledBuffer};
+use super::span::SpanContext;
+use super::{
+    event_sender, event_stream, BoxFuture, Client, GraphEvents, PeerInfo, Publisher, Service,
+    Subscriber, Transport, TransportEvent, TransportEvents,
+};
+
+/// Maps a Zenobuf encoding to the Zenoh encoding used to tag payloads
+fn to_zenoh_encoding(encoding: Encoding) -> zenoh::bytes::Encoding {
+    match encoding {
+        Encoding::Protobuf => zenoh::bytes::Encoding::APPLICATION_PROTOBUF,
+        Encoding::Json => zenoh::bytes::Encoding::APPLICATION_JSON,
+        Encoding::Cdr => zenoh::bytes::Encoding::APPLICATION_CDR,
+    }
+}
+
+/// Maps a Zenoh encoding tag back to a Zenobuf encoding
+///
+/// Returns `None` for untagged or unknown encodings (e.g. payloads sent by the CLI).
+fn from_zenoh_encoding(encoding: &zenoh::bytes::Encoding) -> Option<Encoding> {
+    let encoding = encoding.to_string();
+    let mime_type = encoding.split(';').next().unwrap_or_default();
+    Encoding::from_mime_type(mime_type)
+}
+
+/// Interval between checks of the session's links in the connectivity monitor
+const CONNECTIVITY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Interval between checks of the session's links while waiting for a peer
+const PEER_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Reports changes in the links of a session until it is dropped or closed
+///
+/// The first observation only sets the initial state, so events are only sent for
+/// actual transitions.
+async fn monitor_connectivity(
+    session: Weak<zenoh::Session>,
+    events: broadcast::Sender<TransportEvent>,
+) {
+    let mut connected = None;
+    let mut interval = tokio::time::interval(CONNECTIVITY_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(session) = session.upgrade() else {
+            return;
+        };
+        if session.is_closed() {
+            if connected == Some(true) {
+                let _ = events.send(TransportEvent::Disconnected);
+            }
+            return;
+        }
+
+        let info = session.info();
+        let linked =
+            info.routers_zid().await.next().is_some() || info.peers_zid().await.next().is_some();
+        drop(session);
+
+        if connected != Some(linked) {
+            if connected.is_some() {
+                let event = if linked {
+                    TransportEvent::Connected
+                } else {
+                    TransportEvent::Disconnected
+                };
+                tracing::info!("Zenoh transport {:?}", event);
+                let _ = events.send(event);
+            }
+            connected = Some(linked);
+        }
+    }
+}
 
 /// Zenoh transport implementation
 pub struct ZenohTransport {
     session: Arc<zenoh::Session>,
+    events: broadcast::Sender<TransportEvent>,
+    /// Set once the task reporting the link changes to `events` is started
+    monitor: OnceLock<()>,
+    /// Node name and level of the spans around operations
+    spans: SpanContext,
+    /// Number of requests the services of this transport are handling
+    in_flight: Arc<AtomicUsize>,
 }
 
 impl ZenohTransport {
     /// Creates a new Zenoh transport
     pub async fn new() -> Result<Self> {
-        let config = zenoh::config::Config::default();
-        let session = zenoh::open(config).await.map_err(Error::from)?;
-        Ok(Self {
-            session: Arc::new(session),
-        })
+        Self::with_config(zenoh::config::Config::default()).await
     }
 
     /// Prefixes for Zenoh key expressions
     pub const TOPIC_PREFIX: &str = "zenobuf/topic/";
     pub const SERVICE_PREFIX: &str = "zenobuf/service/";
+    pub const CACHE_PREFIX: &str = "zenobuf/cache/";
+    pub const ACK_PREFIX: &str = "zenobuf/ack/";
+
+    /// Selector parameter marking a query as a discovery query rather than a call
+    ///
+    /// Services ignore queries carrying it, leaving them to the node's metadata
+    /// advertisement on the same key.
+    pub const DISCOVERY_PARAMETER: &str = "discovery";
+
+    /// Key suffix under which a service answers with its request and response types
+    ///
+    /// `zenobuf/service/<name>/__types__` replies with
+    /// `{"request_type": ..., "response_type": ...}`.
+    pub const SERVICE_TYPES_SUFFIX: &str = "/__types__";
 
     /// Creates a new Zenoh transport with the given configuration
     pub async fn with_config(config: zenoh::config::Config) -> Result<Self> {
         let session = zenoh::open(config).await.map_err(Error::from)?;
-        Ok(Self {
-            session: Arc::new(session),
-        })
+        Ok(Self::from_session(Arc::new(session)))
+    }
+
+    /// Creates a transport over an existing session
+    ///
+    /// Several transports, and thus several nodes, can share one session. The
+    /// session stays open until the last of them is dropped. This does not need
+    /// a Tokio runtime, see [`Self::transport_events`].
+    pub fn from_session(session: Arc<zenoh::Session>) -> Self {
+        Self {
+            session,
+            events: event_sender(),
+            monitor: OnceLock::new(),
+            spans: SpanContext::default(),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Creates a new Zenoh transport that keeps reconnecting to its endpoints
+    ///
+    /// Lost links are retried indefinitely with the delays of `policy`. Zenoh
+    /// re-declares the publishers, subscribers and services of the session once a
+    /// link is back, and the change is reported through [`Self::transport_events`].
+    pub async fn with_reconnect(policy: RetryPolicy) -> Result<Self> {
+        let mut config = zenoh::config::Config::default();
+        let retry = serde_json::json!({
+            "period_init_ms": policy.initial_delay.as_millis() as u64,
+            "period_max_ms": policy.max_delay.as_millis() as u64,
+            "period_increase_factor": policy.multiplier,
+        });
+        for (key, value) in [
+            ("connect/retry", retry.to_string()),
+            ("connect/timeout_ms", "-1".to_string()),
+            ("connect/exit_on_failure", "false".to_string()),
+        ] {
+            config
+                .insert_json5(key, &value)
+                .map_err(|e| Error::configuration(format!("Invalid {key}: {e}")))?;
+        }
+        Self::with_config(config).await
     }
 
     /// Returns a reference to the Zenoh session
@@ -46,42 +199,238 @@ impl ZenohTransport {
         &self.session
     }
 
-    /// Maps Zenobuf reliability to Zenoh CongestionControl
-    fn map_reliability(qos: &QosProfile) -> CongestionControl {
-        match qos.reliability {
-            Reliability::Reliable => CongestionControl::Block,
-            Reliability::BestEffort => CongestionControl::Drop,
+    /// Returns the number of requests the services of this transport are
+    /// handling, including those waiting for their [`Responder`] to be used
+    pub(crate) fn in_flight_requests(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Returns the span settings of the transport, to set the node name and level
+    pub(crate) fn spans_mut(&mut self) -> &mut SpanContext {
+        &mut self.spans
+    }
+
+    /// Returns a stream of the changes in the session's links to routers and peers
+    ///
+    /// The links are watched by a task started on the current Tokio runtime the
+    /// first time this is called. Called outside a runtime, the stream reports
+    /// nothing until a later call starts the task.
+    pub fn transport_events(&self) -> TransportEvents {
+        let events = event_stream(self.events.subscribe());
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                self.monitor.get_or_init(|| {
+                    runtime.spawn(monitor_connectivity(
+                        Arc::downgrade(&self.session),
+                        self.events.clone(),
+                    ));
+                });
+            }
+            Err(_) if self.monitor.get().is_none() => {
+                tracing::warn!(
+                    "Transport events requested outside a Tokio runtime are not reported yet"
+                );
+            }
+            Err(_) => {}
+        }
+        events
+    }
+
+    /// Returns the sessions this transport knows of
+    ///
+    /// The first entry is the transport's own session, followed by the peers and
+    /// routers it currently has links to.
+    pub async fn peers(&self) -> Vec<PeerInfo> {
+        let info = self.session.info();
+        let mut peers = vec![PeerInfo {
+            zid: info.zid().await.to_string(),
+            endpoints: info
+                .locators()
+                .await
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }];
+        for transport in info.transports().await {
+            peers.push(PeerInfo {
+                zid: transport.zid().to_string(),
+                endpoints: Vec::new(),
+            });
+        }
+        for link in info.links().await {
+            let zid = link.zid().to_string();
+            if let Some(peer) = peers.iter_mut().skip(1).find(|peer| peer.zid == zid) {
+                peer.endpoints.push(link.dst().to_string());
+            }
+        }
+        peers
+    }
+
+    /// Waits until the session has a link to at least one peer or router
+    ///
+    /// Returns [`Error::Network`] if none is discovered within `timeout`.
+    pub async fn wait_for_peers(&self, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let info = self.session.info();
+            if info.routers_zid().await.next().is_some() || info.peers_zid().await.next().is_some()
+            {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::network("no peers discovered"));
+            }
+            tokio::time::sleep(PEER_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Returns a stream of the publishers, subscribers and services appearing
+    /// and disappearing, starting with those that already exist
+    pub async fn graph_events(&self) -> Result<GraphEvents> {
+        liveliness::watch_graph(&self.session).await
+    }
+
+    /// Waits until a publisher exists on `topic`
+    ///
+    /// Returns [`Error::Timeout`] if none appears within `timeout`.
+    pub async fn wait_for_publisher(&self, topic: &str, timeout: Duration) -> Result<()> {
+        if liveliness::wait_until_alive(&self.session, topic, Role::Publisher, timeout).await? {
+            Ok(())
+        } else {
+            Err(Error::timeout(
+                format!("waiting for a publisher on '{topic}'"),
+                timeout.as_millis() as u64,
+            ))
         }
     }
 
+    /// Calls `on_added` for each publisher on `topic`, including those that
+    /// already exist, until the returned subscriber is dropped
+    pub(crate) async fn watch_publishers<F>(
+        &self,
+        topic: &str,
+        on_added: F,
+    ) -> Result<zenoh::pubsub::Subscriber<()>>
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        liveliness::watch_added(&self.session, topic, Role::Publisher, on_added).await
+    }
+
     /// Creates a publisher for the given topic with QoS settings
     pub async fn create_publisher<M: Message>(
         &self,
         topic: &str,
         qos: &QosProfile,
+    ) -> Result<ZenohPublisher<M>> {
+        self.create_encoded_publisher(topic, qos, M::ENCODING).await
+    }
+
+    /// Creates a publisher sending messages in the given encoding
+    pub(crate) async fn create_encoded_publisher<M: Message>(
+        &self,
+        topic: &str,
+        qos: &QosProfile,
+        encoding: Encoding,
     ) -> Result<ZenohPublisher<M>> {
         let prefixed_topic = format!("{}{topic}", Self::TOPIC_PREFIX);
+        let liveliness =
+            Liveliness::declare(&self.session, topic, Role::Publisher, Role::Subscriber).await?;
+        let cache = match qos.durability {
+            Durability::TransientLocal => {
+                let cache_key = format!(
+                    "{}{topic}/{}",
+                    Self::CACHE_PREFIX,
+                    liveliness::endpoint_id(&self.session)
+                );
+                Some(PublicationCache::new(&self.session, cache_key, qos.depth, encoding).await?)
+            }
+            Durability::Volatile => None,
+        };
         ZenohPublisher::new(
             self.session.clone(),
             prefixed_topic,
-            Self::map_reliability(qos),
-            Priority::Data,
+            encoding,
+            qos,
+            liveliness,
+            cache,
+            self.spans.clone(),
         )
         .await
     }
 
     /// Creates a subscriber for the given topic
+    ///
+    /// Transient-local subscribers also fetch the samples retained by
+    /// transient-local publishers on the topic.
     pub async fn create_subscriber<M: Message, F>(
         &self,
         topic: &str,
+        qos: &QosProfile,
         callback: F,
         executor: Option<Arc<CallbackExecutor>>,
     ) -> Result<ZenohSubscriber>
     where
         F: Fn(M) + Send + Sync + 'static,
     {
-        let prefixed_topic = format!("{}{topic}", Self::TOPIC_PREFIX);
-        ZenohSubscriber::new(self.session.clone(), &prefixed_topic, callback, executor).await
+        let callback = move |messages: Vec<M>| messages.into_iter().for_each(&callback);
+        self.create_batched_subscriber(topic, qos, callback, executor)
+            .await
+    }
+
+    /// Creates a subscriber that receives the messages of each sample together
+    ///
+    /// A batch published with `publish_batch` is delivered as one vector, while
+    /// other messages arrive as single-element vectors.
+    pub async fn create_batched_subscriber<M: Message, F>(
+        &self,
+        topic: &str,
+        qos: &QosProfile,
+        callback: F,
+        executor: Option<Arc<CallbackExecutor>>,
+    ) -> Result<ZenohSubscriber>
+    where
+        F: Fn(Vec<M>) + Send + Sync + 'static,
+    {
+        let callback = move |_topic: String, messages: Vec<M>| callback(messages);
+        self.create_wildcard_subscriber(topic, qos, callback, executor)
+            .await
+    }
+
+    /// Creates a subscriber on a topic pattern such as `sensors/**`
+    ///
+    /// The pattern is used as a Zenoh key expression, so `*` matches a single
+    /// chunk and `**` any number of chunks. The callback receives the concrete
+    /// topic of each sample along with its messages.
+    pub async fn create_wildcard_subscriber<M: Message, F>(
+        &self,
+        pattern: &str,
+        qos: &QosProfile,
+        callback: F,
+        executor: Option<Arc<CallbackExecutor>>,
+    ) -> Result<ZenohSubscriber>
+    where
+        F: Fn(String, Vec<M>) + Send + Sync + 'static,
+    {
+        let sink = DecodedSink::new(M::ENCODING, callback);
+        self.create_sink_subscriber::<M, _>(pattern, qos, sink, executor, SinkOptions::default())
+            .await
+    }
+
+    /// Creates a subscriber on a topic pattern for messages of type `M`, handing
+    /// the samples to `sink`
+    ///
+    /// With `dedup`, samples whose publisher ID and sequence number are among
+    /// the last `dedup` received are dropped.
+    pub(crate) async fn create_sink_subscriber<M: Message, S: SampleSink>(
+        &self,
+        pattern: &str,
+        qos: &QosProfile,
+        sink: S,
+        executor: Option<Arc<CallbackExecutor>>,
+        options: SinkOptions,
+    ) -> Result<ZenohSubscriber> {
+        ZenohSubscriber::new::<M, S>(self, pattern, qos, sink, executor, options).await
     }
 
     /// Creates a service for the given name
@@ -93,41 +442,289 @@ impl ZenohTransport {
     where
         F: Fn(Req) -> Result<Res> + Send + Sync + 'static,
     {
+        self.create_contextual_service(service_name, move |request, _| handler(request))
+            .await
+    }
+
+    /// Creates a service whose handler also receives the context of each request
+    pub(crate) async fn create_contextual_service<Req: Message, Res: Message, F>(
+        &self,
+        service_name: &str,
+        handler: F,
+    ) -> Result<ZenohService>
+    where
+        F: Fn(Req, RequestContext) -> Result<Res> + Send + Sync + 'static,
+    {
+        self.create_dispatching_service(
+            service_name,
+            RequestDispatch::Handler(Box::new(handler)),
+            DEFAULT_MAX_MESSAGE_SIZE,
+        )
+        .await
+    }
+
+    /// Creates a service handing its requests over as set by `dispatch`
+    ///
+    /// Requests larger than `max_message_size` bytes are answered with an error
+    /// without decoding them.
+    pub(crate) async fn create_dispatching_service<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+        dispatch: RequestDispatch<Req, Res>,
+        max_message_size: usize,
+    ) -> Result<ZenohService> {
         let prefixed_service_name = format!("{}{service_name}", Self::SERVICE_PREFIX);
-        ZenohService::new(self.session.clone(), &prefixed_service_name, handler).await
+        let token = liveliness::declare_token(&self.session, service_name, Role::Service).await?;
+        ZenohService::new(
+            self.session.clone(),
+            &prefixed_service_name,
+            dispatch,
+            max_message_size,
+            token,
+            self.spans.clone(),
+            self.in_flight.clone(),
+        )
+        .await
     }
 
     /// Creates a client for the given service name
+    ///
+    /// The QoS deadline, when set, bounds each request attempt.
     pub fn create_client<Req: Message, Res: Message>(
         &self,
         service_name: &str,
+        qos: &QosProfile,
+    ) -> Result<ZenohClient<Req, Res>> {
+        self.create_retrying_client(service_name, qos, None)
+    }
+
+    /// Creates a client retrying failed attempts according to `retry`, or with
+    /// the default backoff if it is `None`
+    pub(crate) fn create_retrying_client<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+        qos: &QosProfile,
+        retry: Option<RetryPolicy>,
     ) -> Result<ZenohClient<Req, Res>> {
         let prefixed_service_name = format!("{}{service_name}", Self::SERVICE_PREFIX);
         Ok(ZenohClient::new(
             self.session.clone(),
             &prefixed_service_name,
+            service_name,
+            qos.deadline,
+            retry.unwrap_or_else(ZenohClient::<Req, Res>::default_retry_policy),
+            self.spans.clone(),
         ))
     }
 }
 
+/// Delegates to the inherent methods with the default QoS, so that entities
+/// created through the trait use the same keys as those created by a `Node`
+#[async_trait::async_trait]
+impl Transport for ZenohTransport {
+    async fn create_publisher<M: Message>(
+        &self,
+        topic: &str,
+    ) -> Result<Arc<crate::publisher::Publisher<M>>> {
+        let inner =
+            ZenohTransport::create_publisher::<M>(self, topic, &QosProfile::default()).await?;
+        Ok(Arc::new(crate::publisher::Publisher::new(
+            topic.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    async fn create_subscriber<M: Message, F>(
+        &self,
+        topic: &str,
+        callback: F,
+    ) -> Result<Arc<crate::subscriber::Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        let inner =
+            ZenohTransport::create_subscriber(self, topic, &QosProfile::default(), callback, None)
+                .await?;
+        Ok(Arc::new(crate::subscriber::Subscriber::new(
+            topic.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    async fn create_publisher_with_codec<M: Message>(
+        &self,
+        topic: &str,
+        codec: Arc<dyn Codec<M>>,
+    ) -> Result<Arc<crate::publisher::Publisher<M>>> {
+        let inner = self
+            .create_encoded_publisher::<M>(topic, &QosProfile::default(), M::ENCODING)
+            .await?
+            .with_codec(codec);
+        Ok(Arc::new(crate::publisher::Publisher::new(
+            topic.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    async fn create_subscriber_with_codec<M: Message, F>(
+        &self,
+        topic: &str,
+        codec: Arc<dyn Codec<M>>,
+        callback: F,
+    ) -> Result<Arc<crate::subscriber::Subscriber>>
+    where
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        let callback =
+            move |_topic: String, messages: Vec<M>| messages.into_iter().for_each(&callback);
+        let sink = DecodedSink::new(M::ENCODING, callback).with_codec(codec);
+        let inner = self
+            .create_sink_subscriber::<M, _>(
+                topic,
+                &QosProfile::default(),
+                sink,
+                None,
+                SinkOptions::default(),
+            )
+            .await?;
+        Ok(Arc::new(crate::subscriber::Subscriber::new(
+            topic.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    async fn create_service<Req: Message, Res: Message, F>(
+        &self,
+        service_name: &str,
+        handler: F,
+    ) -> Result<Arc<crate::service::Service>>
+    where
+        F: Fn(Req) -> Result<Res> + Send + Sync + 'static,
+    {
+        let inner = ZenohTransport::create_service(self, service_name, handler).await?;
+        Ok(Arc::new(crate::service::Service::new(
+            service_name.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    fn create_client<Req: Message, Res: Message>(
+        &self,
+        service_name: &str,
+    ) -> Result<Arc<crate::client::Client<Req, Res>>> {
+        let inner =
+            ZenohTransport::create_client::<Req, Res>(self, service_name, &QosProfile::default())?;
+        Ok(Arc::new(crate::client::Client::new(
+            service_name.to_string(),
+            Box::new(inner),
+        )))
+    }
+
+    fn transport_events(&self) -> TransportEvents {
+        ZenohTransport::transport_events(self)
+    }
+
+    fn as_zenoh(&self) -> Option<&ZenohTransport> {
+        Some(self)
+    }
+
+    fn as_zenoh_mut(&mut self) -> Option<&mut ZenohTransport> {
+        Some(self)
+    }
+}
+
+/// Process-wide Zenoh session shared by the nodes of a process
+///
+/// Hosting several nodes in one process, e.g. in tests or composed
+/// applications, does not require a session per node:
+///
+/// ```no_run
+/// # async fn example() -> zenobuf_core::Result<()> {
+/// use zenobuf_core::transport::{SharedSession, ZenohTransport};
+/// use zenobuf_core::Node;
+///
+/// let session = SharedSession::get().await?;
+/// let driver = Node::with_transport("driver", ZenohTransport::from_session(session.clone())).await?;
+/// let planner = Node::with_transport("planner", ZenohTransport::from_session(session)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SharedSession;
+
+/// The shared session, kept alive only by its users
+static SHARED_SESSION: tokio::sync::Mutex<Weak<zenoh::Session>> =
+    tokio::sync::Mutex::const_new(Weak::new());
+
+impl SharedSession {
+    /// Returns the shared session, opening it with the default configuration
+    /// if no node currently uses it
+    pub async fn get() -> Result<Arc<zenoh::Session>> {
+        let mut shared = SHARED_SESSION.lock().await;
+        if let Some(session) = shared.upgrade().filter(|session| !session.is_closed()) {
+            return Ok(session);
+        }
+        let session = Arc::new(
+            zenoh::open(zenoh::config::Config::default())
+                .await
+                .map_err(Error::from)?,
+        );
+        *shared = Arc::downgrade(&session);
+        Ok(session)
+    }
+}
+
 /// Zenoh publisher implementation
 pub struct ZenohPublisher<M: Message> {
-    publisher: zenoh::pubsub::Publisher<'static>,
+    session: Arc<zenoh::Session>,
+    /// Shared with the task sending the payloads handed over by `try_publish`
+    publisher: Arc<zenoh::pubsub::Publisher<'static>>,
+    /// Encoding of the payloads, the message's own unless CDR was selected
+    encoding: Encoding,
+    header: MessageHeader,
+    /// Codec encoding the messages in place of their own encoding, if any
+    codec: Option<SharedCodec<M>>,
+    /// Codec applied to payloads of at least `compression_threshold` bytes
+    compression: Compression,
+    compression_threshold: usize,
+    /// Buffers reused to encode messages, growing to the largest one
+    buffers: BufferPool,
+    liveliness: Liveliness,
+    cache: Option<PublicationCache>,
+    spans: SpanContext,
+    /// Sequence number of the next payload, stamped in its header and used for its span
+    next_seq: AtomicU64,
+    /// Queue of the payloads handed over by `try_publish`, started on first use
+    pending: OnceLock<mpsc::Sender<PendingPut>>,
+    /// Number of payloads that may wait in the queue, the QoS depth
+    pending_capacity: usize,
     _phantom: PhantomData<M>,
 }
 
+/// Payload handed over by `try_publish`, waiting to be sent
+struct PendingPut {
+    payload: PooledBuffer,
+    attachment: Vec<u8>,
+    span: tracing::Span,
+}
+
 impl<M: Message> ZenohPublisher<M> {
     /// Creates a new Zenoh publisher with QoS settings
     async fn new(
         session: Arc<zenoh::Session>,
         topic: String,
-        congestion_control: CongestionControl,
-        priority: Priority,
+        encoding: Encoding,
+        qos: &QosProfile,
+        liveliness: Liveliness,
+        cache: Option<PublicationCache>,
+        spans: SpanContext,
     ) -> Result<Self> {
+        let congestion_control: CongestionControl = qos.congestion_control.into();
+        let priority: Priority = qos.priority.into();
         let key_expr = KeyExpr::try_from(topic.clone())
             .map_err(|e| Error::publisher(&topic, e.to_string()))?;
         let publisher = session
             .declare_publisher(key_expr)
+            .encoding(to_zenoh_encoding(encoding))
             .congestion_control(congestion_control)
             .priority(priority)
             .await
@@ -140,168 +737,1215 @@ impl<M: Message> ZenohPublisher<M> {
             priority
         );
 
+        let header = MessageHeader {
+            type_hash: Some(M::type_hash()),
+            type_name: Some(M::type_name().to_string()),
+            publisher_id: Some(Uuid::new_v4().into_bytes()),
+            ..MessageHeader::default()
+        };
+
         Ok(Self {
-            publisher,
+            session,
+            publisher: Arc::new(publisher),
+            encoding,
+            header,
+            codec: None,
+            compression: Compression::None,
+            compression_threshold: 0,
+            buffers: BufferPool::default(),
+            liveliness,
+            cache,
+            spans,
+            next_seq: AtomicU64::new(0),
+            pending: OnceLock::new(),
+            pending_capacity: qos.depth.max(1),
             _phantom: PhantomData,
         })
     }
-}
 
-impl<M: Message> Publisher<M> for ZenohPublisher<M> {
-    fn publish(&self, message: &M) -> Result<()> {
-        let bytes = encode_message(message)?;
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current()
-                .block_on(async { self.publisher.put(bytes).await.map_err(Error::from) })
-        })
+    /// Compresses payloads of at least `threshold` bytes with `compression`
+    pub(crate) fn with_compression(mut self, compression: Compression, threshold: usize) -> Self {
+        self.compression = compression;
+        self.compression_threshold = threshold;
+        self
     }
-}
 
-/// Zenoh subscriber implementation
-pub struct ZenohSubscriber {
-    _subscriber: zenoh::pubsub::Subscriber<()>,
-}
+    /// Encodes messages with `codec`, tagging the payloads with its name
+    pub(crate) fn with_codec(mut self, codec: SharedCodec<M>) -> Self {
+        self.header.codec = codec_tag(codec.name());
+        self.codec = Some(codec);
+        self
+    }
 
-impl ZenohSubscriber {
-    /// Creates a new Zenoh subscriber
+    /// Pre-sizes the buffer messages are encoded into to `capacity` bytes
+    pub(crate) fn with_buffer_capacity(self, capacity: usize) -> Self {
+        self.buffers.reserve(capacity);
+        self
+    }
+
+    /// Encodes a message in a buffer taken from the pool
     ///
-    /// If an executor is provided, callbacks will be queued to it for later processing
-    /// by the node's spin methods. Otherwise, callbacks are executed directly in the
-    /// Zenoh callback thread.
-    async fn new<M: Message, F>(
-        session: Arc<zenoh::Session>,
-        topic: &str,
-        callback: F,
-        executor: Option<Arc<CallbackExecutor>>,
-    ) -> Result<Self>
-    where
-        F: Fn(M) + Send + Sync + 'static,
-    {
-        let key_expr =
-            KeyExpr::try_from(topic).map_err(|e| Error::subscriber(topic, e.to_string()))?;
+    /// The buffer keeps its capacity across calls and returns to the pool once
+    /// Zenoh is done with the payload, so encoding does not grow a fresh vector
+    /// for every message.
+    fn encode(&self, message: &M) -> Result<PooledBuffer> {
+        if let Some(codec) = &self.codec {
+            return codec.encode(message).map(PooledBuffer::unpooled);
+        }
+        let mut buffer = self.buffers.take();
+        encode_message_into(message, self.encoding, buffer.as_mut_vec())?;
+        Ok(buffer)
+    }
 
-        let callback = Arc::new(callback);
+    /// Returns the sequence number of the next payload
+    fn next_sequence(&self) -> u64 {
+        self.next_seq.fetch_add(1, Ordering::Relaxed)
+    }
 
-        let subscriber = session
-            .declare_subscriber(key_expr)
-            .callback(move |sample| {
-                let bytes = sample.payload().to_bytes();
-                match decode_message::<M>(bytes.as_ref()) {
-                    Ok(message) => {
-                        if let Some(ref exec) = executor {
-                            let cb = callback.clone();
-                            exec.enqueue(Box::new(move || cb(message)));
-                        } else {
-                            callback(message);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to decode subscriber message: {}", e);
-                    }
-                }
-            })
-            .await
-            .map_err(Error::from)?;
+    /// Returns the header stamping the `seq`-th payload with the publisher's ID
+    fn stamped_header(&self, seq: u64) -> MessageHeader {
+        MessageHeader {
+            sequence: Some(seq),
+            ..self.header.clone()
+        }
+    }
 
-        Ok(Self {
-            _subscriber: subscriber,
+    /// Sends the `seq`-th payload, compressing it if it reaches the threshold
+    ///
+    /// Returns the size of the payload as sent, after compression.
+    fn put(&self, payload: PooledBuffer, seq: u64, batch: bool) -> Result<usize> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.send(payload, seq, batch))
         })
     }
-}
 
-impl Subscriber for ZenohSubscriber {
-    fn close(&self) -> Result<()> {
-        // The subscriber will be closed when it's dropped
-        Ok(())
+    /// Sends the `seq`-th payload, as [`ZenohPublisher::put`] does without
+    /// blocking the thread
+    async fn send(&self, payload: PooledBuffer, seq: u64, batch: bool) -> Result<usize> {
+        let (payload, header, span) = self.prepare(payload, seq, batch)?;
+        let len = payload.len();
+        async {
+            self.publisher
+                .put(payload.into_zbytes())
+                .attachment(header.encode())
+                .await
+        }
+        .instrument(span)
+        .await
+        .map(|()| len)
+        .map_err(Error::from)
     }
-}
 
-/// Zenoh service implementation
-pub struct ZenohService {
-    _queryable: zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>,
-    _task: tokio::task::JoinHandle<()>,
+    /// Compresses the `seq`-th payload if it reaches the threshold, returning it
+    /// with its header and the span of its publication
+    fn prepare(
+        &self,
+        payload: PooledBuffer,
+        seq: u64,
+        batch: bool,
+    ) -> Result<(PooledBuffer, MessageHeader, tracing::Span)> {
+        let span = self
+            .spans
+            .publish(sample_topic(self.publisher.key_expr().as_str()), seq);
+        let mut header = MessageHeader {
+            batch,
+            ..self.stamped_header(seq)
+        };
+        let payload = {
+            let _entered = span.enter();
+            if self.compression != Compression::None && payload.len() >= self.compression_threshold
+            {
+                header.compression = self.compression.tag();
+                self.compression
+                    .compress(&payload)
+                    .map(PooledBuffer::unpooled)
+                    .map_err(|e| {
+                        Error::publisher(self.publisher.key_expr().as_str(), e.to_string())
+                    })?
+            } else {
+                payload
+            }
+        };
+        Ok((payload, header, span))
+    }
+
+    /// Starts the task sending the payloads handed over by `try_publish` in order
+    ///
+    /// The task waits on the network as `publish` does, and ends once the
+    /// publisher is dropped and the queue is empty.
+    fn spawn_pending(&self) -> mpsc::Sender<PendingPut> {
+        let (sender, mut queue) = mpsc::channel::<PendingPut>(self.pending_capacity);
+        let publisher = self.publisher.clone();
+        tokio::spawn(async move {
+            while let Some(pending) = queue.recv().await {
+                let put = publisher
+                    .put(pending.payload.into_zbytes())
+                    .attachment(pending.attachment);
+                let result = async { put.await }.instrument(pending.span).await;
+                if let Err(e) = result {
+                    tracing::warn!("Failed to publish on {}: {}", publisher.key_expr(), e);
+                }
+            }
+        });
+        sender
+    }
 }
 
-impl ZenohService {
-    /// Creates a new Zenoh service
-    async fn new<Req: Message, Res: Message, F>(
-        session: Arc<zenoh::Session>,
-        service_name: &str,
-        handler: F,
-    ) -> Result<Self>
-    where
-        F: Fn(Req) -> Result<Res> + Send + Sync + 'static,
-    {
-        let key_expr = KeyExpr::try_from(service_name)
-            .map_err(|e| Error::service(service_name, e.to_string()))?;
-        tracing::info!("Declaring service: {}", service_name);
+impl<M: Message> Publisher<M> for ZenohPublisher<M> {
+    fn publish(&self, message: &M) -> Result<usize> {
+        let bytes = self.encode(message)?;
+        let seq = self.next_sequence();
+        if let Some(cache) = &self.cache {
+            cache.push(bytes.to_vec(), &self.stamped_header(seq));
+        }
+        self.put(bytes, seq, false)
+    }
+
+    /// Hands the message over to a queue of up to the QoS depth, sent in order
+    /// by a background task, and returns `Ok(None)` if the queue is full
+    ///
+    /// The queue fills up while the network is congested and the task waits on
+    /// it, so this never blocks. Messages that were queued are sent as
+    /// `publish` sends them.
+    fn try_publish(&self, message: &M) -> Result<Option<usize>> {
+        let bytes = self.encode(message)?;
+        let sender = self.pending.get_or_init(|| self.spawn_pending());
+        let permit = match sender.try_reserve() {
+            Ok(permit) => permit,
+            Err(mpsc::error::TrySendError::Full(())) => return Ok(None),
+            Err(mpsc::error::TrySendError::Closed(())) => {
+                return Err(Error::publisher(
+                    self.publisher.key_expr().as_str(),
+                    "the send queue was closed",
+                ))
+            }
+        };
+        let seq = self.next_sequence();
+        let retained = self.cache.is_some().then(|| bytes.to_vec());
+        let (payload, header, span) = self.prepare(bytes, seq, false)?;
+        let len = payload.len();
+        if let (Some(cache), Some(bytes)) = (&self.cache, retained) {
+            cache.push(bytes, &self.stamped_header(seq));
+        }
+        permit.send(PendingPut {
+            payload,
+            attachment: header.encode(),
+            span,
+        });
+        Ok(Some(len))
+    }
+
+    fn publish_batch(&self, messages: &[M]) -> Result<usize> {
+        if messages.is_empty() {
+            return Ok(0);
+        }
+        let encoded = messages
+            .iter()
+            .map(|message| match &self.codec {
+                Some(codec) => codec.encode(message),
+                None => encode_message_as(message, self.encoding),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let seq = self.next_sequence();
+        // The messages of a batch share its sequence number, so they are
+        // retained without one
+        if let Some(cache) = &self.cache {
+            for bytes in &encoded {
+                cache.push(bytes.clone(), &self.header);
+            }
+        }
+        self.put(PooledBuffer::unpooled(encode_batch(&encoded)), seq, true)
+    }
+
+    fn subscriber_count(&self) -> usize {
+        self.liveliness.matched_count()
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        self.buffers.capacity()
+    }
+
+    /// Listens on the message's acknowledgment key before publishing it, so an
+    /// acking subscriber cannot answer before the publisher listens
+    fn publish_acked<'a>(
+        &'a self,
+        message: &'a M,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<usize>> {
+        Box::pin(async move {
+            let bytes = self.encode(message)?;
+            let seq = self.next_sequence();
+            let publisher_id = self.header.publisher_id.unwrap_or_default();
+            let acks = self
+                .session
+                .declare_subscriber(ack_key(publisher_id, seq))
+                .await
+                .map_err(Error::from)?;
+            if let Some(cache) = &self.cache {
+                cache.push(bytes.to_vec(), &self.stamped_header(seq));
+            }
+            let len = self.send(bytes, seq, false).await?;
+
+            let topic = sample_topic(self.publisher.key_expr().as_str());
+            match tokio::time::timeout(timeout, acks.recv_async()).await {
+                Ok(Ok(_)) => Ok(len),
+                Ok(Err(e)) => Err(Error::publisher(topic, e.to_string())),
+                Err(_) => Err(Error::timeout(
+                    format!("acknowledgment of message {seq} on {topic}"),
+                    timeout.as_millis() as u64,
+                )),
+            }
+        })
+    }
+}
+
+/// Payload and encoded header of a sample retained by a publication cache
+type RetainedSample = (Vec<u8>, Vec<u8>);
+
+/// Retains the most recent samples of a transient-local publisher
+///
+/// The samples are served through a queryable so that subscribers joining
+/// later can fetch them, each with the header it was published with.
+struct PublicationCache {
+    samples: Arc<Mutex<VecDeque<RetainedSample>>>,
+    depth: usize,
+    _queryable: zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>,
+    _task: tokio::task::JoinHandle<()>,
+}
+
+impl PublicationCache {
+    /// Declares the cache queryable for a publisher
+    async fn new(
+        session: &zenoh::Session,
+        cache_key: String,
+        depth: usize,
+        encoding: Encoding,
+    ) -> Result<Self> {
+        let key_expr = KeyExpr::try_from(cache_key.clone())
+            .map_err(|e| Error::publisher(&cache_key, e.to_string()))?;
         let queryable = session
-            .declare_queryable(key_expr)
+            .declare_queryable(key_expr.clone())
             .await
             .map_err(Error::from)?;
 
-        // Clone the queryable for the task
+        let samples = Arc::new(Mutex::new(VecDeque::new()));
+        let task_samples = samples.clone();
         let queryable_clone = queryable.clone();
 
         let task = tokio::spawn(async move {
             while let Ok(query) = queryable_clone.recv_async().await {
-                tracing::info!("Received query on: {}", query.key_expr());
+                let retained: Vec<RetainedSample> = task_samples
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .iter()
+                    .cloned()
+                    .collect();
+                for (payload, header) in retained {
+                    if let Err(e) = query
+                        .reply(key_expr.clone(), payload)
+                        .encoding(to_zenoh_encoding(encoding))
+                        .attachment(header)
+                        .await
+                    {
+                        tracing::warn!("Failed to send retained sample: {}", e);
+                    }
+                }
+            }
+        });
 
-                let Some(payload) = query.payload() else {
-                    tracing::error!("Query has no payload");
-                    let _ = query
-                        .reply_err("Query has no payload".as_bytes().to_vec())
-                        .await;
-                    continue;
-                };
+        tracing::debug!(
+            "Publication cache declared: {} (depth {})",
+            cache_key,
+            depth
+        );
+
+        Ok(Self {
+            samples,
+            depth: depth.max(1),
+            _queryable: queryable,
+            _task: task,
+        })
+    }
+
+    /// Retains an encoded sample, evicting the oldest beyond the depth
+    fn push(&self, payload: Vec<u8>, header: &MessageHeader) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.push_back((payload, header.encode()));
+        while samples.len() > self.depth {
+            samples.pop_front();
+        }
+    }
+}
+
+impl Drop for PublicationCache {
+    fn drop(&mut self) {
+        self._task.abort();
+    }
+}
+
+/// Payload of a received sample, decompressed if it arrived compressed
+pub(crate) enum SamplePayload {
+    /// The payload as received, sharing the sample's buffers
+    Received(zenoh::bytes::ZBytes),
+    /// The payload after decompression
+    Decompressed(Vec<u8>),
+}
+
+impl SamplePayload {
+    /// Takes the payload of a sample, or `None` if it cannot be decompressed
+    /// or decompresses to more than `max_size` bytes
+    fn from_sample(
+        sample: &zenoh::sample::Sample,
+        header: &MessageHeader,
+        max_size: usize,
+        counters: &SubscriberCounters,
+    ) -> Option<Self> {
+        match Compression::from_tag(header.compression) {
+            Some(Compression::None) => Some(Self::Received(sample.payload().clone())),
+            Some(compression) => {
+                match compression.decompress(&sample.payload().to_bytes(), max_size) {
+                    Ok(Some(bytes)) => Some(Self::Decompressed(bytes)),
+                    Ok(None) => {
+                        tracing::warn!(
+                            "Dropping message on {}: decompresses to more than {} bytes",
+                            sample.key_expr(),
+                            max_size
+                        );
+                        counters.record_oversized();
+                        None
+                    }
+                    Err(e) => {
+                        tracing::warn!("Dropping message on {}: {}", sample.key_expr(), e);
+                        None
+                    }
+                }
+            }
+            None => {
+                tracing::warn!(
+                    "Dropping message on {}: unknown compression codec {}",
+                    sample.key_expr(),
+                    header.compression
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the payload bytes, borrowed unless the received payload is fragmented
+    fn bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            Self::Received(bytes) => bytes.to_bytes(),
+            Self::Decompressed(bytes) => Cow::Borrowed(bytes),
+        }
+    }
+}
+
+/// Splits a payload into the encoded messages it carries
+fn payload_frames(bytes: &[u8], batch: bool) -> Option<Vec<&[u8]>> {
+    if batch {
+        decode_batch(bytes)
+    } else {
+        Some(vec![bytes])
+    }
+}
+
+/// Receives the samples accepted by a subscriber
+///
+/// `extract` runs as samples arrive, while `deliver` runs the callback, either
+/// right away or when the node spins.
+pub(crate) trait SampleSink: Send + Sync + 'static {
+    /// What is carried from `extract` to `deliver`
+    type Item: Send + 'static;
+
+    /// Returns the encoding payloads are expected in
+    fn encoding(&self) -> Encoding;
+
+    /// Extracts the item of an accepted sample, or `None` to drop it
+    ///
+    /// Compressed payloads are not decompressed beyond `max_size` bytes.
+    fn extract(
+        &self,
+        sample: &zenoh::sample::Sample,
+        header: &MessageHeader,
+        max_size: usize,
+        counters: &SubscriberCounters,
+    ) -> Option<Self::Item>;
+
+    /// Runs the callback with the item of a sample received on `topic`
+    fn deliver(&self, topic: String, item: Self::Item);
+}
+
+/// Sink decoding samples into messages, handed to a callback with their topic
+pub(crate) struct DecodedSink<M, F> {
+    encoding: Encoding,
+    /// Codec decoding the messages in place of their own encoding, if any
+    codec: Option<SharedCodec<M>>,
+    callback: F,
+    _message: PhantomData<fn() -> M>,
+}
 
-                let request = match decode_message::<Req>(payload.to_bytes().as_ref()) {
-                    Ok(req) => req,
-                    Err(_) => {
-                        tracing::error!("Failed to decode request");
-                        let _ = query
-                            .reply_err("Failed to decode request".as_bytes().to_vec())
-                            .await;
+impl<M, F> DecodedSink<M, F> {
+    /// Creates a sink decoding messages in `encoding`
+    pub(crate) fn new(encoding: Encoding, callback: F) -> Self {
+        Self {
+            encoding,
+            codec: None,
+            callback,
+            _message: PhantomData,
+        }
+    }
+
+    /// Decodes messages with `codec`, dropping payloads tagged with another codec
+    pub(crate) fn with_codec(mut self, codec: SharedCodec<M>) -> Self {
+        self.codec = Some(codec);
+        self
+    }
+}
+
+impl<M: Message, F> SampleSink for DecodedSink<M, F>
+where
+    F: Fn(String, Vec<M>) + Send + Sync + 'static,
+{
+    type Item = Vec<M>;
+
+    fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    fn extract(
+        &self,
+        sample: &zenoh::sample::Sample,
+        header: &MessageHeader,
+        max_size: usize,
+        counters: &SubscriberCounters,
+    ) -> Option<Vec<M>> {
+        let expected = self
+            .codec
+            .as_ref()
+            .map_or(ProstCodec::NAME, |codec| codec.name());
+        if let Err(e) = check_codec(expected, header.codec.as_deref()) {
+            tracing::warn!("Dropping message on {}: {}", sample.key_expr(), e);
+            return None;
+        }
+
+        let payload = SamplePayload::from_sample(sample, header, max_size, counters)?;
+        let bytes = payload.bytes();
+        let Some(frames) = payload_frames(&bytes, header.batch) else {
+            tracing::warn!("Dropping malformed batch on {}", sample.key_expr());
+            return None;
+        };
+
+        let mut messages = Vec::with_capacity(frames.len());
+        for frame in frames {
+            let decoded = match &self.codec {
+                Some(codec) => codec.decode(frame),
+                None => decode_message_as::<M>(frame, self.encoding),
+            };
+            match decoded {
+                Ok(message) => {
+                    if let Err(e) = message.validate() {
+                        tracing::warn!("Dropping message on {}: {}", sample.key_expr(), e);
+                        counters.record_invalid();
                         continue;
                     }
+                    counters.record_received();
+                    messages.push(message);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to decode subscriber message: {}", e);
+                }
+            }
+        }
+        (!messages.is_empty()).then_some(messages)
+    }
+
+    fn deliver(&self, topic: String, messages: Vec<M>) {
+        (self.callback)(topic, messages)
+    }
+}
+
+/// Sink handing borrowed views of the payloads to a callback, without decoding
+pub(crate) struct PayloadSink<F> {
+    encoding: Encoding,
+    callback: F,
+}
+
+impl<F> PayloadSink<F> {
+    /// Creates a sink for payloads in `encoding`
+    pub(crate) fn new(encoding: Encoding, callback: F) -> Self {
+        Self { encoding, callback }
+    }
+}
+
+impl<F> SampleSink for PayloadSink<F>
+where
+    F: for<'a> Fn(PayloadView<'a>) + Send + Sync + 'static,
+{
+    /// The payload, kept alive until the callback has run, whether it is a
+    /// batch, and the attached type name
+    type Item = (SamplePayload, bool, Option<String>);
+
+    fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    fn extract(
+        &self,
+        sample: &zenoh::sample::Sample,
+        header: &MessageHeader,
+        max_size: usize,
+        counters: &SubscriberCounters,
+    ) -> Option<Self::Item> {
+        let payload = SamplePayload::from_sample(sample, header, max_size, counters)?;
+        let frames = if header.batch {
+            let Some(frames) = decode_batch(&payload.bytes()).map(|frames| frames.len()) else {
+                tracing::warn!("Dropping malformed batch on {}", sample.key_expr());
+                return None;
+            };
+            frames
+        } else {
+            1
+        };
+        for _ in 0..frames {
+            counters.record_received();
+        }
+        Some((payload, header.batch, header.type_name.clone()))
+    }
+
+    fn deliver(&self, topic: String, (payload, batch, type_name): Self::Item) {
+        let bytes = payload.bytes();
+        for frame in payload_frames(&bytes, batch).unwrap_or_default() {
+            (self.callback)(PayloadView::new(
+                &topic,
+                frame,
+                self.encoding,
+                type_name.as_deref(),
+            ));
+        }
+    }
+}
+
+/// Zenoh subscriber implementation
+pub struct ZenohSubscriber {
+    /// Subscriber and liveliness token, undeclared when the subscriber is closed
+    declarations: Mutex<Option<(zenoh::pubsub::Subscriber<()>, Liveliness)>>,
+    /// Set once closed, so that samples still in flight are dropped
+    closed: Arc<AtomicBool>,
+    counters: Arc<SubscriberCounters>,
+    /// Deadline tracking, if the QoS profile sets a deadline
+    deadline: Option<DeadlineMonitor>,
+}
+
+/// How a subscriber created by [`ZenohTransport::create_sink_subscriber`] handles
+/// its samples
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SinkOptions {
+    /// Number of recent stamped samples remembered to drop duplicates, if any
+    pub dedup: Option<usize>,
+    /// How samples are buffered before the callback runs
+    pub handler: SubscriberHandler,
+    /// Whether each stamped sample is acknowledged once its callback has run
+    pub ack: bool,
+    /// Largest payload decoded, or [`DEFAULT_MAX_MESSAGE_SIZE`] if not set
+    pub max_message_size: Option<usize>,
+}
+
+/// Channel between Zenoh and the callbacks of a subscriber with a queueing handler
+enum SampleQueue {
+    Fifo(FifoChannelHandler<Sample>),
+    Ring(RingChannelHandler<Sample>),
+    Unbounded(mpsc::UnboundedReceiver<Sample>),
+}
+
+impl SampleQueue {
+    /// Waits for the next sample, or `None` once the subscriber is undeclared
+    async fn recv(&mut self) -> Option<Sample> {
+        match self {
+            SampleQueue::Fifo(queue) => queue.recv_async().await.ok(),
+            SampleQueue::Ring(queue) => queue.recv_async().await.ok(),
+            SampleQueue::Unbounded(queue) => queue.recv().await,
+        }
+    }
+}
+
+/// Returns the topic of a sample, whether it was published live or retained by a cache
+///
+/// Retained samples are keyed `zenobuf/cache/<topic>/<publisher id>`.
+fn sample_topic(key: &str) -> &str {
+    if let Some(topic) = key.strip_prefix(ZenohTransport::TOPIC_PREFIX) {
+        topic
+    } else if let Some(cached) = key.strip_prefix(ZenohTransport::CACHE_PREFIX) {
+        cached.rsplit_once('/').map_or(cached, |(topic, _)| topic)
+    } else {
+        key
+    }
+}
+
+/// Returns the key on which subscribers acknowledge a publisher's `seq`-th payload
+fn ack_key(publisher_id: [u8; 16], seq: u64) -> String {
+    format!(
+        "{}{}/{seq}",
+        ZenohTransport::ACK_PREFIX,
+        Uuid::from_bytes(publisher_id).simple()
+    )
+}
+
+/// Returns the message of a panic payload, if it is a string
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "non-string panic payload"
+    }
+}
+
+impl ZenohSubscriber {
+    /// Creates a new Zenoh subscriber
+    ///
+    /// The sink extracts what its callback receives from each sample. If an executor
+    /// is provided, callbacks will be queued to it for later processing by the
+    /// node's spin methods. Otherwise, callbacks are executed directly in the Zenoh
+    /// callback thread. With a QoS deadline, the time between received messages is
+    /// monitored as soon as the subscriber is declared. With `dedup`, samples
+    /// already seen among the last `dedup` stamped ones are dropped. With a
+    /// bounded `handler`, samples wait in a Zenoh channel of `qos.depth` until
+    /// the callback of the previous one has run, or in an unbounded FIFO if the
+    /// QoS history is [`History::KeepAll`]. With `ack`, the publisher of a
+    /// stamped sample is acknowledged once the callback has run without panicking.
+    async fn new<M: Message, S: SampleSink>(
+        transport: &ZenohTransport,
+        pattern: &str,
+        qos: &QosProfile,
+        sink: S,
+        executor: Option<Arc<CallbackExecutor>>,
+        options: SinkOptions,
+    ) -> Result<Self> {
+        let SinkOptions {
+            dedup,
+            handler,
+            ack,
+            max_message_size,
+        } = options;
+        let max_message_size = max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE);
+        let keep_all = qos.history == History::KeepAll;
+        if handler != SubscriberHandler::Callback && !keep_all && qos.depth == 0 {
+            return Err(Error::configuration(format!(
+                "Subscriber on '{pattern}' needs a depth of at least 1 for a {handler:?} handler"
+            )));
+        }
+        let session = transport.session.clone();
+        let spans = transport.spans.clone();
+        let topic = format!("{}{pattern}", ZenohTransport::TOPIC_PREFIX);
+        let key_expr = KeyExpr::try_from(topic.as_str())
+            .map_err(|e| Error::subscriber(&topic, e.to_string()))?;
+        let liveliness =
+            Liveliness::declare(&session, pattern, Role::Subscriber, Role::Publisher).await?;
+        let cache_selector = match qos.durability {
+            Durability::TransientLocal => {
+                Some(format!("{}{pattern}/*", ZenohTransport::CACHE_PREFIX))
+            }
+            Durability::Volatile => None,
+        };
+        let deadline = qos.deadline;
+
+        let encoding = sink.encoding();
+        let sink = Arc::new(sink);
+        let counters = Arc::new(SubscriberCounters::default());
+        let callback_counters = counters.clone();
+        let deadline = deadline.map(DeadlineMonitor::spawn);
+        let received = deadline.as_ref().map(DeadlineMonitor::receiver_notifier);
+        let next_seq = AtomicU64::new(0);
+        let dedup = dedup.map(|capacity| Mutex::new(DedupWindow::new(capacity)));
+        // Session on which stamped samples are acknowledged, if they are
+        let acker = ack.then(|| session.clone());
+        // Keeps the callbacks of this subscriber in order on a multi-threaded spin
+        let ordering_key = executor::ordering_key();
+        let closed = Arc::new(AtomicBool::new(false));
+        let sample_closed = closed.clone();
+
+        let handle_sample = Arc::new(
+            move |sample: &Sample, permit: Option<OwnedSemaphorePermit>| {
+                if sample_closed.load(Ordering::Acquire) {
+                    return;
+                }
+                let seq = next_seq.fetch_add(1, Ordering::Relaxed);
+                let span = spans.receive(sample_topic(sample.key_expr().as_str()), seq);
+                let _entered = span.enter();
+
+                if let Err(e) = check_encoding(encoding, from_zenoh_encoding(sample.encoding())) {
+                    tracing::warn!("Dropping message on {}: {}", sample.key_expr(), e);
+                    return;
+                }
+
+                let size = sample.payload().len();
+                if size > max_message_size {
+                    tracing::warn!(
+                        "Dropping message of {} bytes on {}: larger than the maximum of {} bytes",
+                        size,
+                        sample.key_expr(),
+                        max_message_size
+                    );
+                    callback_counters.record_oversized();
+                    return;
+                }
+
+                let header = sample
+                    .attachment()
+                    .map(|attachment| MessageHeader::decode(&attachment.to_bytes()))
+                    .unwrap_or_default();
+                if let Some(hash) = header.type_hash {
+                    if hash != M::type_hash() {
+                        // Only the first mismatch of a publisher is reported,
+                        // the following ones are counted
+                        if callback_counters.record_type_mismatch(header.publisher_id) {
+                            let publisher = header.publisher_id.map_or_else(
+                                || "unknown".into(),
+                                |id| Uuid::from_bytes(id).to_string(),
+                            );
+                            tracing::warn!(
+                                "Dropping messages on {} from publisher {}: type hash {:#018x} ({}) does not match {} ({:#018x})",
+                                sample.key_expr(),
+                                publisher,
+                                hash,
+                                header.type_name.as_deref().unwrap_or("unknown type"),
+                                M::type_name(),
+                                M::type_hash()
+                            );
+                        }
+                        return;
+                    }
+                }
+                if let (Some(window), Some(publisher_id), Some(sequence)) =
+                    (&dedup, header.publisher_id, header.sequence)
+                {
+                    let fresh = window
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(publisher_id, sequence);
+                    if !fresh {
+                        tracing::debug!(
+                            "Dropping duplicate message {} from {} on {}",
+                            sequence,
+                            Uuid::from_bytes(publisher_id),
+                            sample.key_expr()
+                        );
+                        callback_counters.record_duplicate();
+                        return;
+                    }
+                }
+
+                let Some(item) =
+                    sink.extract(sample, &header, max_message_size, &callback_counters)
+                else {
+                    return;
                 };
+                if let Some(received) = &received {
+                    received.notify_one();
+                }
 
-                tracing::info!("Decoded request successfully");
-                let response = match handler(request) {
-                    Ok(res) => res,
-                    Err(e) => {
-                        tracing::error!("Service handler error: {}", e);
-                        let _ = query
-                            .reply_err(format!("Service error: {e}").as_bytes().to_vec())
-                            .await;
-                        continue;
+                let topic = sample_topic(sample.key_expr().as_str()).to_string();
+                let sink = sink.clone();
+                let panic_counters = callback_counters.clone();
+                let span = span.clone();
+                let ack = acker.clone().zip(header.publisher_id.zip(header.sequence));
+                let invoke_closed = sample_closed.clone();
+                let invoke = move || {
+                    // Callbacks queued before the subscriber was closed do not run
+                    if invoke_closed.load(Ordering::Acquire) {
+                        return;
+                    }
+                    let _entered = span.enter();
+                    // A panicking callback must not take down the subscriber
+                    match panic::catch_unwind(AssertUnwindSafe(|| {
+                        sink.deliver(topic.clone(), item)
+                    })) {
+                        Ok(()) => {
+                            if let Some((session, (publisher_id, seq))) = ack {
+                                let key = ack_key(publisher_id, seq);
+                                if let Err(e) = session.put(key, Vec::<u8>::new()).wait() {
+                                    tracing::warn!(
+                                        "Failed to acknowledge a message on {}: {}",
+                                        topic,
+                                        e
+                                    );
+                                }
+                            }
+                        }
+                        Err(panic) => {
+                            panic_counters.record_callback_panic();
+                            tracing::error!(
+                                "Subscriber callback panicked on {}: {}",
+                                topic,
+                                panic_message(panic.as_ref())
+                            );
+                        }
                     }
+                    // Lets a bounded handler hand over the next sample
+                    drop(permit);
                 };
+                if let Some(ref exec) = executor {
+                    exec.enqueue_ordered(ordering_key, Box::new(invoke));
+                } else {
+                    invoke();
+                }
+            },
+        );
 
-                tracing::info!("Handler returned response");
-                let bytes = match encode_message(&response) {
-                    Ok(b) => b,
+        let live_handler = handle_sample.clone();
+        let (callback, queue) = match handler {
+            SubscriberHandler::Callback => {
+                let callback = move |sample: Sample| live_handler(&sample, None);
+                (
+                    Box::new(callback) as Box<dyn Fn(Sample) + Send + Sync>,
+                    None,
+                )
+            }
+            SubscriberHandler::Fifo | SubscriberHandler::Ring if keep_all => {
+                let (sender, queue) = mpsc::unbounded_channel();
+                let callback = move |sample| {
+                    let _ = sender.send(sample);
+                };
+                (Box::new(callback) as _, Some(SampleQueue::Unbounded(queue)))
+            }
+            SubscriberHandler::Fifo => {
+                let (callback, queue) = FifoChannel::new(qos.depth).into_handler();
+                let callback = move |sample| callback.call(sample);
+                (Box::new(callback) as _, Some(SampleQueue::Fifo(queue)))
+            }
+            SubscriberHandler::Ring => {
+                let (callback, queue) = RingChannel::new(qos.depth).into_handler();
+                let callback = move |sample| callback.call(sample);
+                (Box::new(callback) as _, Some(SampleQueue::Ring(queue)))
+            }
+        };
+        let subscriber = session
+            .declare_subscriber(key_expr)
+            .callback(callback)
+            .await
+            .map_err(Error::from)?;
+
+        // Hands the queued samples over one at a time, so that they stay in the
+        // bounded channel while a callback is pending
+        if let Some(mut queue) = queue {
+            let handle_sample = handle_sample.clone();
+            let idle = Arc::new(Semaphore::new(1));
+            tokio::spawn(async move {
+                while let Ok(permit) = idle.clone().acquire_owned().await {
+                    let Some(sample) = queue.recv().await else {
+                        break;
+                    };
+                    handle_sample(&sample, Some(permit));
+                }
+            });
+        }
+
+        // Fetch the samples retained by transient-local publishers
+        if let Some(selector) = cache_selector {
+            tokio::spawn(async move {
+                let replies = match session.get(selector.clone()).await {
+                    Ok(replies) => replies,
                     Err(e) => {
-                        tracing::error!("Failed to encode response: {}", e);
-                        let _ = query
-                            .reply_err(format!("Encode error: {e}").as_bytes().to_vec())
-                            .await;
-                        continue;
+                        tracing::warn!("Failed to query retained samples on {}: {}", selector, e);
+                        return;
                     }
                 };
+                while let Ok(reply) = replies.recv_async().await {
+                    if let Ok(sample) = reply.result() {
+                        handle_sample(sample, None);
+                    }
+                }
+            });
+        }
+
+        Ok(Self {
+            declarations: Mutex::new(Some((subscriber, liveliness))),
+            closed,
+            counters,
+            deadline,
+        })
+    }
+}
+
+impl Subscriber for ZenohSubscriber {
+    /// Stops delivering messages and undeclares the subscriber
+    ///
+    /// Callbacks queued for the node's spin methods but not yet run are
+    /// skipped. Closing again has no effect.
+    fn close(&self) -> Result<()> {
+        self.closed.store(true, Ordering::Release);
+        // Dropping the subscriber and token undeclares them
+        drop(
+            self.declarations
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take(),
+        );
+        if let Some(deadline) = &self.deadline {
+            deadline.stop();
+        }
+        Ok(())
+    }
 
-                match query.reply(query.key_expr(), bytes).await {
-                    Ok(_) => tracing::info!("Reply sent successfully"),
-                    Err(e) => tracing::error!("Failed to send reply: {}", e),
+    fn stats(&self) -> SubscriberStats {
+        let mut stats = self.counters.snapshot();
+        if let Some(deadline) = &self.deadline {
+            stats.deadlines_missed = deadline.missed();
+        }
+        stats
+    }
+
+    fn publisher_count(&self) -> usize {
+        self.declarations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map_or(0, |(_, liveliness)| liveliness.matched_count())
+    }
+
+    fn set_deadline_callback(&self, callback: DeadlineCallback) {
+        if let Some(deadline) = &self.deadline {
+            deadline.set_callback(callback);
+        }
+    }
+}
+
+/// Zenoh service implementation
+pub struct ZenohService {
+    /// Queryable and liveliness token, undeclared when the service is closed
+    declarations: Mutex<Option<ServiceDeclarations>>,
+    /// Stops the task receiving queries
+    shutdown: CancellationToken,
+    _task: tokio::task::JoinHandle<()>,
+}
+
+/// Zenoh entities declared for a service
+type ServiceDeclarations = (
+    zenoh::query::Queryable<zenoh::handlers::FifoChannelHandler<zenoh::query::Query>>,
+    zenoh::liveliness::LivelinessToken,
+);
+
+impl ZenohService {
+    /// Creates a new Zenoh service
+    async fn new<Req: Message, Res: Message>(
+        session: Arc<zenoh::Session>,
+        service_name: &str,
+        dispatch: RequestDispatch<Req, Res>,
+        max_message_size: usize,
+        token: zenoh::liveliness::LivelinessToken,
+        spans: SpanContext,
+        in_flight: Arc<AtomicUsize>,
+    ) -> Result<Self> {
+        let key_expr = KeyExpr::try_from(service_name)
+            .map_err(|e| Error::service(service_name, e.to_string()))?;
+        tracing::debug!("Declaring service: {}", service_name);
+        let queryable = session
+            .declare_queryable(key_expr)
+            .await
+            .map_err(Error::from)?;
+
+        // Clone the queryable for the task
+        let queryable_clone = queryable.clone();
+        let name = service_name
+            .strip_prefix(ZenohTransport::SERVICE_PREFIX)
+            .unwrap_or(service_name)
+            .to_string();
+        let shutdown = CancellationToken::new();
+        let stopped = shutdown.clone();
+
+        let task = tokio::spawn(async move {
+            let mut next_seq = 0;
+            loop {
+                let query = tokio::select! {
+                    query = queryable_clone.recv_async() => match query {
+                        Ok(query) => query,
+                        Err(_) => break,
+                    },
+                    _ = stopped.cancelled() => break,
+                };
+                if query
+                    .parameters()
+                    .contains_key(ZenohTransport::DISCOVERY_PARAMETER)
+                {
+                    continue;
                 }
+
+                let span = spans.serve(&name, next_seq);
+                next_seq += 1;
+                let request = InFlightRequest::start(&in_flight);
+                Self::handle_query::<Req, Res>(&name, query, &dispatch, max_message_size, request)
+                    .instrument(span)
+                    .await;
             }
         });
 
         Ok(Self {
-            _queryable: queryable,
+            declarations: Mutex::new(Some((queryable, token))),
+            shutdown,
             _task: task,
         })
     }
+
+    /// Decodes a request and dispatches it, replying with the response or an error
+    ///
+    /// Requests whose deadline has already passed are answered with
+    /// [`Error::ServiceCallTimeout`] without dispatching them, carrying how
+    /// long ago the deadline passed. Requests for a [`RequestStream`] are
+    /// answered once their [`Responder`] is used, and wait while the stream
+    /// is full. Requests larger than `max_size` bytes are answered with an
+    /// error without decoding them. The request counts as in flight until it
+    /// is answered.
+    ///
+    /// [`RequestStream`]: crate::service::RequestStream
+    async fn handle_query<Req: Message, Res: Message>(
+        name: &str,
+        query: zenoh::query::Query,
+        dispatch: &RequestDispatch<Req, Res>,
+        max_size: usize,
+        in_flight: InFlightRequest,
+    ) {
+        tracing::trace!("Received query on: {}", query.key_expr());
+
+        let Some(payload) = query.payload() else {
+            tracing::error!("Query has no payload");
+            reply_error(&query, "Query has no payload").await;
+            return;
+        };
+        if payload.len() > max_size {
+            let error = format!(
+                "Request of {} bytes is larger than the maximum of {} bytes",
+                payload.len(),
+                max_size
+            );
+            tracing::warn!("Rejecting request: {}", error);
+            reply_error(&query, error).await;
+            return;
+        }
+
+        let encoding = query.encoding().and_then(from_zenoh_encoding);
+        if let Err(e) = check_encoding(Req::ENCODING, encoding) {
+            tracing::error!("Rejecting request: {}", e);
+            reply_error(&query, e.to_string()).await;
+            return;
+        }
+
+        let header = query
+            .attachment()
+            .map(|attachment| MessageHeader::decode(&attachment.to_bytes()))
+            .unwrap_or_default();
+        let context = RequestContext {
+            trace_id: header.trace_id.map(Uuid::from_bytes).unwrap_or_default(),
+            caller: header.caller.unwrap_or_default(),
+            deadline: header
+                .deadline
+                .map(|nanos| Time::from_duration(Duration::from_nanos(nanos))),
+        };
+        if let Some(deadline) = context.deadline {
+            let overdue = Time::now().elapsed_since(deadline);
+            if !overdue.is_zero() {
+                tracing::warn!(
+                    "Skipping request from '{}' whose deadline passed {:?} ago",
+                    context.caller,
+                    overdue
+                );
+                let error = Error::service_call_timeout(name, overdue.as_millis() as u64);
+                reply_error(&query, error.to_string()).await;
+                return;
+            }
+        }
+
+        let request = match decode_message::<Req>(payload.to_bytes().as_ref()) {
+            Ok(req) => req,
+            Err(e) => {
+                tracing::error!("Failed to decode request: {}", e);
+                reply_error(&query, format!("Failed to decode request: {e}")).await;
+                return;
+            }
+        };
+
+        tracing::trace!("Decoded request successfully");
+        match dispatch {
+            RequestDispatch::Handler(handler) => {
+                let response = handler(request, context);
+                reply_response(&query, response).await;
+            }
+            RequestDispatch::Stream(requests) => {
+                let runtime = tokio::runtime::Handle::current();
+                let span = tracing::Span::current();
+                let responder = Responder::new(context, move |response| {
+                    runtime.spawn(
+                        async move {
+                            reply_response(&query, response).await;
+                            drop(in_flight);
+                        }
+                        .instrument(span),
+                    );
+                });
+                // A request the stream no longer takes is answered with an
+                // error by its dropped responder
+                if requests.send((request, responder)).await.is_err() {
+                    tracing::debug!("Request stream of {} was dropped", name);
+                }
+            }
+        }
+    }
+}
+
+/// A request counted as in flight by a transport until it is dropped
+struct InFlightRequest(Arc<AtomicUsize>);
+
+impl InFlightRequest {
+    /// Counts a request as in flight
+    fn start(in_flight: &Arc<AtomicUsize>) -> Self {
+        in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(in_flight.clone())
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Replies to a query with a response, or with the error returned in its place
+async fn reply_response<Res: Message>(query: &zenoh::query::Query, response: Result<Res>) {
+    let response = match response {
+        Ok(res) => res,
+        Err(e) => {
+            tracing::error!("Service handler error: {}", e);
+            reply_error(query, e.to_string()).await;
+            return;
+        }
+    };
+
+    tracing::trace!("Handler returned response");
+    let bytes = match encode_message(&response) {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::error!("Failed to encode response: {}", e);
+            reply_error(query, format!("Failed to encode response: {e}")).await;
+            return;
+        }
+    };
+
+    match query
+        .reply(query.key_expr(), bytes)
+        .encoding(to_zenoh_encoding(Res::ENCODING))
+        .await
+    {
+        Ok(_) => tracing::trace!("Reply sent successfully"),
+        Err(e) => tracing::error!("Failed to send reply: {}", e),
+    }
+}
+
+/// Replies to a query with an [`ErrorReply`] carrying `error`
+async fn reply_error(query: &zenoh::query::Query, error: impl Into<String>) {
+    let _ = query
+        .reply_err(ErrorReply::new(error).to_bytes())
+        .encoding(zenoh::bytes::Encoding::APPLICATION_JSON)
+        .await;
 }
 
 impl Drop for ZenohService {
@@ -311,8 +1955,18 @@ impl Drop for ZenohService {
 }
 
 impl Service for ZenohService {
+    /// Stops answering requests and undeclares the service
+    ///
+    /// A request being handled is still answered. Closing again has no effect.
     fn close(&self) -> Result<()> {
-        // The queryable will be closed when it's dropped
+        self.shutdown.cancel();
+        // Dropping the queryable and token undeclares them
+        drop(
+            self.declarations
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take(),
+        );
         Ok(())
     }
 }
@@ -321,114 +1975,325 @@ impl Service for ZenohService {
 pub struct ZenohClient<Req: Message, Res: Message> {
     session: Arc<zenoh::Session>,
     service_name: String,
+    /// Service name without the key prefix, used for liveliness lookups
+    name: String,
+    /// Timeout of a single request attempt
+    attempt_timeout: Duration,
+    /// Delays between attempts and bound on the time spent on a call
+    retry: RetryPolicy,
+    /// Number of retries made across all calls
+    retries: AtomicU64,
+    spans: SpanContext,
+    /// Sequence number of the next call, for its span
+    next_seq: AtomicU64,
     _phantom: PhantomData<(Req, Res)>,
 }
 
 impl<Req: Message, Res: Message> ZenohClient<Req, Res> {
+    /// Timeout of a single request attempt when the QoS profile sets no deadline
+    const DEFAULT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Number of attempts made by calls without a deadline
+    const MAX_ATTEMPTS: u32 = 3;
+
+    /// Backoff of clients without a retry policy: 200ms, doubled for each
+    /// further retry up to 1.6s
+    fn default_retry_policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .initial_delay(Duration::from_millis(200))
+            .max_delay(Duration::from_millis(1600))
+    }
+
     /// Creates a new Zenoh client
-    fn new(session: Arc<zenoh::Session>, service_name: &str) -> Self {
+    fn new(
+        session: Arc<zenoh::Session>,
+        service_name: &str,
+        name: &str,
+        deadline: Option<Duration>,
+        retry: RetryPolicy,
+        spans: SpanContext,
+    ) -> Self {
         Self {
             session,
             service_name: service_name.to_string(),
+            name: name.to_string(),
+            attempt_timeout: deadline.unwrap_or(Self::DEFAULT_ATTEMPT_TIMEOUT),
+            retry,
+            retries: AtomicU64::new(0),
+            spans,
+            next_seq: AtomicU64::new(0),
             _phantom: PhantomData,
         }
     }
-}
 
-impl<Req: Message, Res: Message> Client<Req, Res> for ZenohClient<Req, Res> {
-    fn call(&self, request: &Req) -> Result<Res> {
-        tokio::task::block_in_place(|| {
-            tokio::runtime::Handle::current().block_on(self.call_async(request))
-        })
+    /// Sends a request, retrying with the backoff of the retry policy
+    ///
+    /// Each attempt is bounded by the QoS deadline, and an attempt that runs out of
+    /// time ends the call with [`Error::ServiceCallTimeout`]. Without an overall
+    /// deadline, up to [`Self::MAX_ATTEMPTS`] attempts are made. With one, attempts
+    /// that received no reply are retried until it passes. Every attempt and
+    /// backoff is shortened to fit in the time that remains before the deadline
+    /// and the policy's `max_total`, whichever comes first. Errors that
+    /// retrying would not fix end the call at once, including an error reply,
+    /// so that the service's handler runs only once.
+    ///
+    /// The request carries the trace ID, the node name and the deadline as
+    /// metadata for the service's [`RequestContext`](crate::RequestContext).
+    async fn request(
+        &self,
+        request: &Req,
+        deadline: Option<(tokio::time::Instant, Duration)>,
+        trace_id: Uuid,
+    ) -> Result<Res> {
+        let header = MessageHeader {
+            trace_id: Some(trace_id.into_bytes()),
+            caller: Some(self.spans.node().to_string()),
+            deadline: deadline
+                .map(|(_, timeout)| (Time::now() + timeout).to_duration().as_nanos() as u64),
+            ..MessageHeader::default()
+        };
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let span = self.spans.call(&self.name, seq);
+        self.request_attempts(request, deadline, header.encode())
+            .instrument(span)
+            .await
     }
 
-    fn call_async<'a>(&'a self, request: &'a Req) -> BoxFuture<'a, Result<Res>> {
-        let service_name = self.service_name.clone();
-        let session = self.session.clone();
+    /// Makes the attempts of a single call
+    async fn request_attempts(
+        &self,
+        request: &Req,
+        deadline: Option<(tokio::time::Instant, Duration)>,
+        attachment: Vec<u8>,
+    ) -> Result<Res> {
+        let service_name = &self.service_name;
+        let key_expr = KeyExpr::try_from(service_name.as_str())
+            .map_err(|e| Error::client(service_name, e.to_string()))?;
 
-        Box::pin(async move {
-            let key_expr = KeyExpr::try_from(service_name.clone())
-                .map_err(|e| Error::client(&service_name, e.to_string()))?;
-
-            let bytes = encode_message(request)?;
-            tracing::info!("Sending request to: {}", key_expr);
-
-            // Implement retry mechanism with exponential backoff
-            let max_retries = 3;
-            let mut retry_count = 0;
-            let mut last_error = None;
-            let base_delay = Duration::from_millis(100);
-
-            while retry_count < max_retries {
-                // Make a request with a timeout
-                match session
-                    .get(key_expr.clone())
-                    .payload(bytes.clone())
-                    .timeout(Duration::from_secs(10)) // Use a reasonable timeout
-                    .await
-                {
-                    Ok(reply) => {
-                        tracing::info!("Got reply, waiting for data");
-
-                        // Keep the reply object alive until we've received the response
-                        match reply.recv_async().await {
-                            Ok(sample) => match sample.result() {
-                                Ok(sample) => {
-                                    tracing::info!("Sample is OK");
-                                    let payload_data = sample.payload();
-                                    tracing::info!("Got payload data");
-                                    match decode_message::<Res>(payload_data.to_bytes().as_ref()) {
-                                        Ok(response) => {
-                                            tracing::info!("Decoded response successfully");
-                                            return Ok(response);
-                                        }
-                                        Err(e) => {
-                                            tracing::error!("Failed to decode response: {}", e);
-                                            last_error = Some(e);
-                                        }
+        let bytes = encode_message(request)?;
+        tracing::trace!("Sending request to: {}", key_expr);
+
+        let timed_out =
+            |timeout: Duration| Error::service_call_timeout(&self.name, timeout.as_millis() as u64);
+
+        // The end of the call, set by the deadline or the retry budget
+        let budget = self
+            .retry
+            .max_total
+            .map(|max_total| (tokio::time::Instant::now() + max_total, max_total));
+        let limit = match (deadline, budget) {
+            (Some(deadline), Some(budget)) => Some(deadline.min(budget)),
+            (deadline, budget) => deadline.or(budget),
+        };
+        let remaining =
+            || limit.map(|(limit, _)| limit.saturating_duration_since(tokio::time::Instant::now()));
+
+        let mut attempt = 0;
+
+        loop {
+            let attempt_timeout = remaining().map_or(self.attempt_timeout, |remaining| {
+                remaining.min(self.attempt_timeout)
+            });
+            let started = tokio::time::Instant::now();
+
+            let error = match self
+                .session
+                .get(key_expr.clone())
+                .payload(bytes.clone())
+                .encoding(to_zenoh_encoding(Req::ENCODING))
+                .attachment(attachment.clone())
+                .timeout(attempt_timeout)
+                .await
+            {
+                Ok(reply) => {
+                    tracing::trace!("Got reply, waiting for data");
+
+                    // Keep the reply object alive until we've received the response
+                    match reply.recv_async().await {
+                        Ok(sample) => match sample.result() {
+                            Ok(sample) => {
+                                tracing::trace!("Sample is OK");
+                                check_encoding(
+                                    Res::ENCODING,
+                                    from_zenoh_encoding(sample.encoding()),
+                                )?;
+                                let payload_data = sample.payload();
+                                tracing::trace!("Got payload data");
+                                match decode_message::<Res>(payload_data.to_bytes().as_ref()) {
+                                    Ok(response) => {
+                                        tracing::trace!("Decoded response successfully");
+                                        return Ok(response);
+                                    }
+                                    Err(e) => {
+                                        tracing::error!("Failed to decode response: {}", e);
+                                        e
                                     }
                                 }
-                                Err(e) => {
-                                    tracing::error!("Sample error: {}", e);
-                                    last_error = Some(Error::service_call_failed(
-                                        service_name.clone(),
-                                        format!("Error in response: {e}"),
-                                    ));
-                                }
-                            },
+                            }
                             Err(e) => {
-                                tracing::error!("Receive error: {}", e);
-                                last_error = Some(Error::service_call_failed(
+                                let reason = ErrorReply::from_bytes(&e.payload().to_bytes())
+                                    .map(|reply| reply.error)
+                                    .unwrap_or_else(|| e.to_string());
+                                tracing::error!("Sample error: {}", reason);
+                                Error::service_call_failed(
                                     service_name.clone(),
-                                    format!("No response: {e}"),
-                                ));
+                                    format!("Error in response: {reason}"),
+                                )
                             }
+                        },
+                        Err(e) => {
+                            tracing::error!("Receive error: {}", e);
+                            Error::service_call_failed(
+                                service_name.clone(),
+                                format!("{NO_SERVICE}: {e}"),
+                            )
                         }
                     }
-                    Err(e) => {
-                        tracing::error!("Error getting reply: {}", e);
-                        last_error = Some(Error::from(e));
-                    }
                 }
+                Err(e) => {
+                    tracing::error!("Error getting reply: {}", e);
+                    Error::from(e)
+                }
+            };
 
-                // Increment retry count and wait before retrying
-                retry_count += 1;
-                if retry_count < max_retries {
-                    tracing::info!(
-                        "Retrying service call (attempt {}/{})",
-                        retry_count + 1,
-                        max_retries
-                    );
-                    // Use exponential backoff
-                    let backoff = base_delay * 2u32.pow(retry_count as u32);
-                    tracing::info!("Waiting for {:?} before retry", backoff);
-                    tokio::time::sleep(backoff).await;
+            // A failure once the attempt ran out of time is Zenoh reporting the timeout
+            if started.elapsed() >= attempt_timeout {
+                return Err(match limit {
+                    Some((_, timeout)) if remaining() == Some(Duration::ZERO) => timed_out(timeout),
+                    _ => timed_out(attempt_timeout),
+                });
+            }
+
+            attempt += 1;
+            let exhausted = deadline.is_none() && attempt >= Self::MAX_ATTEMPTS;
+            if exhausted || !error.is_retryable() {
+                return Err(error);
+            }
+
+            // Back off, without sleeping past the deadline or budget
+            let mut backoff = self.retry.backoff(attempt - 1);
+            if let Some(remaining) = remaining() {
+                backoff = backoff.min(remaining);
+            }
+            tracing::debug!(
+                "Retrying service call (attempt {}) after {:?}",
+                attempt + 1,
+                backoff
+            );
+            self.retries.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Sends a request to every queryable of the service, without retrying, and
+    /// collects the responses that arrive within `timeout`
+    ///
+    /// Replies are not consolidated, since all servers answer on the same key.
+    /// Error replies and responses that fail to decode are logged and skipped.
+    async fn request_all(&self, request: &Req, timeout: Duration) -> Result<Vec<Res>> {
+        let service_name = &self.service_name;
+        let key_expr = KeyExpr::try_from(service_name.as_str())
+            .map_err(|e| Error::client(service_name, e.to_string()))?;
+        let header = MessageHeader {
+            trace_id: Some(Uuid::new_v4().into_bytes()),
+            caller: Some(self.spans.node().to_string()),
+            deadline: Some((Time::now() + timeout).to_duration().as_nanos() as u64),
+            ..MessageHeader::default()
+        };
+
+        let replies = self
+            .session
+            .get(key_expr)
+            .payload(encode_message(request)?)
+            .encoding(to_zenoh_encoding(Req::ENCODING))
+            .attachment(header.encode())
+            .target(zenoh::query::QueryTarget::All)
+            .consolidation(zenoh::query::ConsolidationMode::None)
+            .timeout(timeout)
+            .await?;
+
+        let mut responses = Vec::new();
+        let deadline = tokio::time::Instant::now() + timeout;
+        // The channel closes once every server replied or the query timed out
+        while let Ok(Ok(reply)) = tokio::time::timeout_at(deadline, replies.recv_async()).await {
+            match reply.result() {
+                Ok(sample) => {
+                    let decoded =
+                        check_encoding(Res::ENCODING, from_zenoh_encoding(sample.encoding()))
+                            .and_then(|()| {
+                                decode_message::<Res>(sample.payload().to_bytes().as_ref())
+                            });
+                    match decoded {
+                        Ok(response) => responses.push(response),
+                        Err(e) => tracing::warn!("Skipping reply from {}: {}", service_name, e),
+                    }
+                }
+                Err(e) => {
+                    let reason = ErrorReply::from_bytes(&e.payload().to_bytes())
+                        .map(|reply| reply.error)
+                        .unwrap_or_else(|| e.to_string());
+                    tracing::warn!("Skipping error reply from {}: {}", service_name, reason);
                 }
             }
+        }
+        Ok(responses)
+    }
+}
 
-            Err(last_error.unwrap_or_else(|| {
-                Error::service_call_failed(&service_name, "Service call failed after retries")
-            }))
+impl<Req: Message, Res: Message> Client<Req, Res> for ZenohClient<Req, Res> {
+    fn call(&self, request: &Req) -> Result<Res> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.call_async(request))
+        })
+    }
+
+    fn call_async<'a>(&'a self, request: &'a Req) -> BoxFuture<'a, Result<Res>> {
+        Box::pin(self.request(request, None, Uuid::new_v4()))
+    }
+
+    fn call_traced<'a>(&'a self, request: &'a Req, trace_id: Uuid) -> BoxFuture<'a, Result<Res>> {
+        Box::pin(self.request(request, None, trace_id))
+    }
+
+    fn call_with_timeout<'a>(
+        &'a self,
+        request: &'a Req,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Res>> {
+        Box::pin(async move {
+            let deadline = tokio::time::Instant::now() + timeout;
+            self.request(request, Some((deadline, timeout)), Uuid::new_v4())
+                .await
+        })
+    }
+
+    fn call_all<'a>(
+        &'a self,
+        request: &'a Req,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Vec<Res>>> {
+        let span = self
+            .spans
+            .call(&self.name, self.next_seq.fetch_add(1, Ordering::Relaxed));
+        Box::pin(self.request_all(request, timeout).instrument(span))
+    }
+
+    fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    fn wait_for_service(&self, timeout: Duration) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            if liveliness::wait_until_alive(&self.session, &self.name, Role::Service, timeout)
+                .await?
+            {
+                Ok(())
+            } else {
+                Err(Error::service_call_timeout(
+                    &self.name,
+                    timeout.as_millis() as u64,
+                ))
+            }
         })
     }
 }
@@ -0,0 +1,112 @@
+//! Utilities shared across Zenobuf
+
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// Formats a duration as a human readable string, e.g. `1s`, `250ms` or `1.5s`
+///
+/// The largest unit that represents the duration exactly is used, and durations
+/// of more than a second that are not whole seconds are given in fractional
+/// seconds. The result can be parsed back with [`string_to_duration`].
+pub fn duration_to_string(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos == 0 {
+        "0s".to_string()
+    } else if nanos.is_multiple_of(1_000_000_000) {
+        format!("{}s", duration.as_secs())
+    } else if duration >= Duration::from_secs(1) {
+        format!("{}s", duration.as_secs_f64())
+    } else if nanos.is_multiple_of(1_000_000) {
+        format!("{}ms", duration.as_millis())
+    } else if nanos.is_multiple_of(1_000) {
+        format!("{}us", duration.as_micros())
+    } else {
+        format!("{nanos}ns")
+    }
+}
+
+/// Parses a duration formatted by [`duration_to_string`]
+///
+/// Accepts a number followed by one of the units `ns`, `us`, `ms`, `s`, `m` or
+/// `h`, e.g. `100ms` or `1.5s`.
+pub fn string_to_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .ok_or_else(|| Error::configuration(format!("Missing unit in duration '{value}'")))?;
+    let (number, unit) = value.split_at(split);
+
+    let unit_nanos: u64 = match unit.trim() {
+        "ns" => 1,
+        "us" => 1_000,
+        "ms" => 1_000_000,
+        "s" => 1_000_000_000,
+        "m" => 60_000_000_000,
+        "h" => 3_600_000_000_000,
+        unit => {
+            return Err(Error::configuration(format!(
+                "Unknown unit '{unit}' in duration '{value}'"
+            )))
+        }
+    };
+
+    // Whole numbers are converted exactly, fractions through floating point
+    if let Ok(whole) = number.parse::<u64>() {
+        return Ok(Duration::from_nanos(whole.saturating_mul(unit_nanos)));
+    }
+    number
+        .parse::<f64>()
+        .ok()
+        .and_then(|number| Duration::try_from_secs_f64(number * unit_nanos as f64 / 1e9).ok())
+        .ok_or_else(|| Error::configuration(format!("Invalid duration '{value}'")))
+}
+
+/// Checks that a topic or service name can be used as a Zenoh key expression
+///
+/// Names are chunks separated by `/`, such as `robot1/pose`, optionally starting
+/// with a `/`. Chunks must not be empty, and the characters `*`, `$`, `#` and `?`
+/// are reserved by Zenoh.
+pub fn validate_name(name: &str) -> Result<()> {
+    check_key(name, false)
+}
+
+/// Checks a topic pattern, which unlike a name may contain `*` and `**` chunks
+pub fn validate_pattern(pattern: &str) -> Result<()> {
+    check_key(pattern, true)
+}
+
+/// Checks a name or pattern, explaining what is wrong with it
+fn check_key(name: &str, wildcards: bool) -> Result<()> {
+    let invalid = |reason: &str| {
+        let kind = if wildcards { "topic pattern" } else { "name" };
+        Err(Error::configuration(format!(
+            "Invalid {kind} '{name}': {reason}; use chunks separated by '/', e.g. 'robot1/pose'"
+        )))
+    };
+
+    let key = name.strip_prefix('/').unwrap_or(name);
+    if key.is_empty() {
+        return invalid("it is empty");
+    }
+    if key.ends_with('/') {
+        return invalid("it ends with '/'");
+    }
+    for chunk in key.split('/') {
+        if chunk.is_empty() {
+            return invalid("it contains an empty chunk ('//')");
+        }
+        if wildcards && (chunk == "*" || chunk == "**") {
+            continue;
+        }
+        if let Some(c) = chunk.chars().find(|c| matches!(c, '*' | '$' | '#' | '?')) {
+            let reason = match c {
+                '*' if wildcards => "'*' and '**' must make up a whole chunk".to_string(),
+                '*' => "wildcards are only allowed in subscriber patterns".to_string(),
+                c => format!("'{c}' is reserved by Zenoh"),
+            };
+            return invalid(&reason);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,168 @@
+//! Tests for actions: long-running goals with feedback and cancellation
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::action::{ActionClient, ActionServer};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct CountGoal {
+    target: u32,
+}
+
+impl JsonMessage for CountGoal {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct CountFeedback {
+    current: u32,
+}
+
+impl JsonMessage for CountFeedback {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct CountResult {
+    total: u32,
+}
+
+impl JsonMessage for CountResult {}
+
+type CountServer = ActionServer<Json<CountGoal>, Json<CountFeedback>, Json<CountResult>>;
+type CountClient = ActionClient<Json<CountGoal>, Json<CountFeedback>, Json<CountResult>>;
+
+/// Starts a server counting up to the goal's target, one step every `step`
+async fn count_server(node: &Node, action: &str, step: Duration) -> CountServer {
+    ActionServer::new(node, action, move |goal: Json<CountGoal>, context| {
+        for current in 1..=goal.target {
+            if context.is_cancelled() {
+                return Ok(Json(CountResult { total: current - 1 }));
+            }
+            std::thread::sleep(step);
+            context.publish_feedback(&Json(CountFeedback { current }))?;
+        }
+        Ok(Json(CountResult { total: goal.target }))
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_goal_reports_feedback_then_result() {
+    let server_node =
+        Node::with_transport("action_server_node", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let client_node =
+        Node::with_transport("action_client_node", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let _server = count_server(&server_node, "count", Duration::from_millis(10)).await;
+    let client = CountClient::new(&client_node, "count").await.unwrap();
+    client
+        .wait_for_server(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    // Sending waits for the feedback topic to match, so no feedback is missed
+    let mut goal = client
+        .send_goal(&Json(CountGoal { target: 3 }))
+        .await
+        .unwrap();
+    let mut feedback = Vec::new();
+    while let Some(Json(update)) = goal.next_feedback().await {
+        feedback.push(update.current);
+    }
+    assert_eq!(feedback, vec![1, 2, 3]);
+    assert_eq!(goal.result().await.unwrap().total, 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cancel_aborts_goal_midway() {
+    let server_node =
+        Node::with_transport("action_cancel_server", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let client_node =
+        Node::with_transport("action_cancel_client", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let server = count_server(&server_node, "slow_count", Duration::from_millis(100)).await;
+    let client = CountClient::new(&client_node, "slow_count").await.unwrap();
+    client
+        .wait_for_server(Duration::from_secs(5))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let mut goal = client
+        .send_goal(&Json(CountGoal { target: 100 }))
+        .await
+        .unwrap();
+    let first = goal.next_feedback().await.unwrap();
+    assert_eq!(first.current, 1);
+    assert_eq!(server.running_goals(), 1);
+
+    client.cancel(goal.id()).await.unwrap();
+    let mut last = first.current;
+    while let Some(Json(update)) = goal.next_feedback().await {
+        last = update.current;
+    }
+    assert!(last < 100, "goal ran to completion");
+    assert!(matches!(
+        goal.result().await,
+        Err(Error::ServiceCallCancelled { .. })
+    ));
+
+    // The goal is gone, so it can no longer be cancelled
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(server.running_goals(), 0);
+    let id = zenobuf_core::Uuid::new_v4();
+    assert!(client.cancel(id).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_goal_finishes_when_server_goes_away() {
+    let server_node =
+        Node::with_transport("action_gone_server", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let client_node =
+        Node::with_transport("action_gone_client", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let server = count_server(&server_node, "gone_count", Duration::from_millis(100)).await;
+    let client = CountClient::new(&client_node, "gone_count").await.unwrap();
+    client
+        .wait_for_server(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let mut goal = client
+        .send_goal(&Json(CountGoal { target: 100 }))
+        .await
+        .unwrap();
+    assert_eq!(goal.next_feedback().await.unwrap().current, 1);
+
+    drop(server);
+    drop(server_node);
+
+    // The goal fails rather than waiting for an outcome that never comes
+    let result = tokio::time::timeout(Duration::from_secs(10), goal.result())
+        .await
+        .expect("the goal never finished");
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_send_goal_without_server_times_out() {
+    let node = Node::with_transport("action_no_server", ZenohTransport::new().await.unwrap())
+        .await
+        .unwrap();
+    let client = CountClient::new(&node, "missing_count").await.unwrap();
+
+    assert!(matches!(
+        client.send_goal(&Json(CountGoal { target: 1 })).await,
+        Err(Error::Timeout { .. })
+    ));
+}
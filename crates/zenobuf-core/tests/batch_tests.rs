@@ -0,0 +1,113 @@
+//! Tests for batch publishing
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Point {
+    x: f32,
+    y: f32,
+    seq: u32,
+}
+
+impl JsonMessage for Point {}
+
+/// Spins the node until `done` holds or the timeout expires
+async fn spin_until(node: &Node, timeout: Duration, done: impl Fn() -> bool) {
+    let start = Instant::now();
+    while !done() && start.elapsed() < timeout {
+        node.spin_once().unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_batched_subscriber_receives_whole_batch() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("batch_node", transport).await.unwrap();
+
+    let batches = Arc::new(Mutex::new(Vec::new()));
+    let received = batches.clone();
+    let subscriber = node
+        .subscriber::<Json<Point>>("batch/points")
+        .build_batched(move |points| received.lock().unwrap().push(points))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Point>>("batch/points")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let points: Vec<_> = (0..1000)
+        .map(|seq| {
+            Json(Point {
+                x: seq as f32,
+                y: -(seq as f32),
+                seq,
+            })
+        })
+        .collect();
+    publisher.publish_batch(&points).unwrap();
+
+    spin_until(&node, Duration::from_secs(5), || {
+        !batches.lock().unwrap().is_empty()
+    })
+    .await;
+
+    let batches = batches.lock().unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0], points);
+    assert_eq!(subscriber.stats().messages_received, 1000);
+    assert_eq!(publisher.stats().messages_sent, 1000);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_plain_subscriber_receives_batched_messages_individually() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("batch_plain_node", transport)
+        .await
+        .unwrap();
+
+    let seqs = Arc::new(Mutex::new(Vec::new()));
+    let received = seqs.clone();
+    let _subscriber = node
+        .subscriber::<Json<Point>>("batch/plain_points")
+        .build(move |point| received.lock().unwrap().push(point.seq))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Point>>("batch/plain_points")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let points: Vec<_> = (0..10)
+        .map(|seq| {
+            Json(Point {
+                seq,
+                ..Point::default()
+            })
+        })
+        .collect();
+    publisher.publish_batch(&points).unwrap();
+    publisher
+        .publish(&Json(Point {
+            seq: 10,
+            ..Point::default()
+        }))
+        .unwrap();
+
+    spin_until(&node, Duration::from_secs(5), || {
+        seqs.lock().unwrap().len() == 11
+    })
+    .await;
+
+    assert_eq!(*seqs.lock().unwrap(), (0..11).collect::<Vec<_>>());
+}
@@ -0,0 +1,65 @@
+//! Tests for bridging topics
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Count {
+    value: i64,
+}
+
+impl JsonMessage for Count {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_bridge_map_republishes_transformed_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("bridge_node", transport)
+        .await
+        .unwrap();
+
+    let bridge = node
+        .bridge_map::<Json<Count>, Json<Count>, _>("bridge_in", "bridge_out", |count| {
+            Json(Count {
+                value: count.value * 2,
+            })
+        })
+        .await
+        .unwrap();
+    assert_eq!(bridge.from(), "bridge_in");
+    assert_eq!(bridge.to(), "bridge_out");
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Count>>("bridge_out")
+        .build(move |count| sink.lock().unwrap().push(count.value))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Count>>("bridge_in")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for value in [1, 2, 3] {
+        publisher.publish(&Json(Count { value })).unwrap();
+    }
+    // The first spin runs the bridge, the second delivers its output
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![2, 4, 6]);
+
+    drop(bridge);
+    publisher.publish(&Json(Count { value: 4 })).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+    assert_eq!(received.lock().unwrap().len(), 3);
+}
@@ -0,0 +1,85 @@
+//! Tests for subscribers keeping the latest messages in a buffer
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    seq: u32,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_drain_takes_buffered_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("buffered_node", transport)
+        .await
+        .unwrap();
+    let subscriber = node
+        .subscriber::<Json<Reading>>("buffered/readings")
+        .build_buffered()
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("buffered/readings")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..4 {
+        publisher.publish(&Json(Reading { seq })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(subscriber.len(), 4);
+    assert_eq!(subscriber.peek_latest().unwrap().0.seq, 3);
+    assert_eq!(subscriber.len(), 4);
+
+    let seqs: Vec<u32> = subscriber
+        .drain()
+        .into_iter()
+        .map(|Json(reading)| reading.seq)
+        .collect();
+    assert_eq!(seqs, [0, 1, 2, 3]);
+    assert!(subscriber.drain().is_empty());
+    assert!(subscriber.peek_latest().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_buffer_keeps_latest_messages_up_to_depth() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("buffered_depth_node", transport)
+        .await
+        .unwrap();
+    let subscriber = node
+        .subscriber::<Json<Reading>>("buffered/bounded")
+        .with_depth(3)
+        .build_buffered()
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("buffered/bounded")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..5 {
+        publisher.publish(&Json(Reading { seq })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    let seqs: Vec<u32> = subscriber
+        .drain()
+        .into_iter()
+        .map(|Json(reading)| reading.seq)
+        .collect();
+    assert_eq!(seqs, [2, 3, 4]);
+}
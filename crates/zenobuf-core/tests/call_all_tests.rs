@@ -0,0 +1,76 @@
+//! Tests for collecting the responses of every server of a service
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Query {
+    topic: String,
+}
+
+impl JsonMessage for Query {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Answer {
+    responder: String,
+}
+
+impl JsonMessage for Answer {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_call_all_collects_every_response() {
+    let mut nodes = Vec::new();
+    let mut services = Vec::new();
+    for name in ["call_all_first", "call_all_second"] {
+        let transport = ZenohTransport::new().await.unwrap();
+        let node = Node::with_transport(name, transport).await.unwrap();
+        let service = node
+            .service::<Json<Query>, Json<Answer>>("call_all_discovery")
+            .build(move |_| {
+                Ok(Json(Answer {
+                    responder: name.to_string(),
+                }))
+            })
+            .await
+            .unwrap();
+        nodes.push(node);
+        services.push(service);
+    }
+
+    let client = nodes[0]
+        .client::<Json<Query>, Json<Query>>("call_all_missing")
+        .build()
+        .unwrap();
+    let responses = client
+        .call_all(
+            &Json(Query {
+                topic: "none".to_string(),
+            }),
+            Duration::from_millis(200),
+        )
+        .await
+        .unwrap();
+    assert!(responses.is_empty());
+
+    let client = nodes[0]
+        .client::<Json<Query>, Json<Answer>>("call_all_discovery")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let responses = client
+        .call_all(
+            &Json(Query {
+                topic: "cameras".to_string(),
+            }),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap();
+
+    let mut responders: Vec<String> = responses.into_iter().map(|Json(a)| a.responder).collect();
+    responders.sort();
+    assert_eq!(responders, ["call_all_first", "call_all_second"]);
+}
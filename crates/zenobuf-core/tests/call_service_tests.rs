@@ -0,0 +1,58 @@
+//! Tests for one-shot service calls through `Node::call_service`
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Sum {
+    a: i64,
+    b: i64,
+}
+
+impl JsonMessage for Sum {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_sequential_call_service() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("call_service_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Sum>, Json<Sum>>("call_service_sum")
+        .build(|Json(sum)| {
+            Ok(Json(Sum {
+                a: sum.a + sum.b,
+                b: 0,
+            }))
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let first: Json<Sum> = node
+        .call_service("call_service_sum", &Json(Sum { a: 1, b: 2 }))
+        .await
+        .unwrap();
+    assert_eq!(first.0.a, 3);
+
+    let second: Json<Sum> = node
+        .call_service("call_service_sum", &Json(Sum { a: 3, b: 4 }))
+        .await
+        .unwrap();
+    assert_eq!(second.0.a, 7);
+
+    // A registered client for the same service can coexist with one-shot calls
+    let _client = node
+        .client::<Json<Sum>, Json<Sum>>("call_service_sum")
+        .build()
+        .unwrap();
+    let third: Json<Sum> = node
+        .call_service("call_service_sum", &Json(Sum { a: 5, b: 6 }))
+        .await
+        .unwrap();
+    assert_eq!(third.0.a, 11);
+}
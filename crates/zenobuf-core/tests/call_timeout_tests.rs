@@ -0,0 +1,287 @@
+//! Tests for service calls bounded by a timeout or cancelled
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{CancellationToken, Error, Json, JsonMessage, Node, QosProfile, RetryPolicy};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ping {
+    seq: u32,
+}
+
+impl JsonMessage for Ping {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_call_with_timeout_returns_response() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timeout_ok_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("timeout_ok")
+        .build(|Json(ping)| Ok(Json(Ping { seq: ping.seq + 1 })))
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("timeout_ok")
+        .build()
+        .unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let response = client
+        .call_with_timeout(&Json(Ping { seq: 1 }), Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(response.0.seq, 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_call_with_timeout_to_missing_service_times_out() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timeout_missing_node", transport)
+        .await
+        .unwrap();
+
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("timeout_missing")
+        .build()
+        .unwrap();
+
+    let timeout = Duration::from_millis(500);
+    let start = Instant::now();
+    let err = client
+        .call_with_timeout(&Json(Ping::default()), timeout)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            Error::ServiceCallTimeout {
+                timeout_ms: 500,
+                ..
+            }
+        ),
+        "unexpected error: {err}"
+    );
+    // Retries stay within the overall deadline
+    assert!(start.elapsed() < timeout + Duration::from_millis(500));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_call_with_timeout_to_slow_service_times_out() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timeout_slow_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("timeout_slow")
+        .build(|request| {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(request)
+        })
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("timeout_slow")
+        .build()
+        .unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let err = client
+        .call_with_timeout(&Json(Ping::default()), Duration::from_millis(300))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, Error::ServiceCallTimeout { .. }),
+        "unexpected error: {err}"
+    );
+}
+
+// The blocking handler occupies a worker, leaving another to cancel the call
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cancelled_call_returns_promptly() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("cancel_slow_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("cancel_slow")
+        .build(|request| {
+            std::thread::sleep(Duration::from_secs(3));
+            Ok(request)
+        })
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("cancel_slow")
+        .build()
+        .unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        canceller.cancel();
+    });
+
+    let start = Instant::now();
+    let err = client
+        .call_cancellable(&Json(Ping::default()), token)
+        .await
+        .unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(
+        matches!(err, Error::ServiceCallCancelled { .. }),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_call_with_timeout_reports_handler_errors_as_failures() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timeout_failing_node", transport)
+        .await
+        .unwrap();
+
+    let handled = Arc::new(AtomicU32::new(0));
+    let handler_count = handled.clone();
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("timeout_failing")
+        .build(move |_request| {
+            handler_count.fetch_add(1, Ordering::SeqCst);
+            Err(Error::other("rejected"))
+        })
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("timeout_failing")
+        .build()
+        .unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let err = client
+        .call_with_timeout(&Json(Ping::default()), Duration::from_secs(10))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, Error::ServiceCallFailed { .. }),
+        "unexpected error: {err}"
+    );
+    assert!(!err.is_retryable());
+    // An error reply is not retried, so the handler ran once
+    assert_eq!(handled.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_client_qos_deadline_bounds_each_call() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timeout_deadline_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("timeout_deadline")
+        .build(|request| {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(request)
+        })
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("timeout_deadline")
+        .with_qos(QosProfile::default().deadline(Duration::from_millis(200)))
+        .build()
+        .unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let start = Instant::now();
+    let err = client.call_async(&Json(Ping::default())).await.unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            Error::ServiceCallTimeout {
+                timeout_ms: 200,
+                ..
+            }
+        ),
+        "unexpected error: {err}"
+    );
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_retry_policy_max_total_bounds_retries() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timeout_budget_node", transport)
+        .await
+        .unwrap();
+
+    // Without the budget, the two retries would wait between 1.5s and 4.5s
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("timeout_budget")
+        .with_retry_policy(
+            RetryPolicy::new()
+                .initial_delay(Duration::from_secs(1))
+                .max_delay(Duration::from_secs(2))
+                .jitter(true)
+                .max_total(Duration::from_millis(400)),
+        )
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    let err = client.call_async(&Json(Ping::default())).await.unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            Error::ServiceCallTimeout {
+                timeout_ms: 400,
+                ..
+            }
+        ),
+        "unexpected error: {err}"
+    );
+    assert!(start.elapsed() < Duration::from_millis(900));
+}
+
+#[test]
+fn test_retry_policy_jitter_stays_within_half_the_delay() {
+    let policy = RetryPolicy::new()
+        .initial_delay(Duration::from_millis(100))
+        .jitter(true);
+    let delays: Vec<Duration> = (0..50).map(|_| policy.backoff(1)).collect();
+
+    assert!(delays
+        .iter()
+        .all(|delay| (Duration::from_millis(100)..Duration::from_millis(300)).contains(delay)));
+    assert!(delays.iter().any(|delay| *delay != delays[0]));
+    assert_eq!(policy.jitter(false).backoff(1), Duration::from_millis(200));
+}
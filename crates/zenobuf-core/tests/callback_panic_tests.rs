@@ -0,0 +1,52 @@
+//! Tests for subscribers whose callback panics
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    seq: u32,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_subscriber_survives_callback_panic() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("callback_panic_node", transport)
+        .await
+        .unwrap();
+
+    let delivered = Arc::new(Mutex::new(Vec::new()));
+    let delivered_clone = delivered.clone();
+    let subscriber = node
+        .subscriber::<Json<Reading>>("callback_panic")
+        .build(move |Json(reading)| {
+            if reading.seq == 1 {
+                panic!("cannot handle reading {}", reading.seq);
+            }
+            delivered_clone.lock().unwrap().push(reading.seq);
+        })
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("callback_panic")
+        .build()
+        .await
+        .unwrap();
+
+    for seq in 1..=2 {
+        publisher.publish(&Json(Reading { seq })).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        node.spin_once().unwrap();
+    }
+
+    assert_eq!(*delivered.lock().unwrap(), vec![2]);
+    let stats = subscriber.stats();
+    assert_eq!(stats.callback_panics, 1);
+    assert_eq!(stats.messages_received, 2);
+}
@@ -0,0 +1,70 @@
+//! Tests for subscribers delivering messages into a bounded channel
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Sample {
+    seq: u32,
+}
+
+impl JsonMessage for Sample {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_channel_subscriber_receives_without_spinning() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("channel_node", transport)
+        .await
+        .unwrap();
+    let (subscriber, mut receiver) = node
+        .subscriber::<Json<Sample>>("channel/samples")
+        .build_channel(8)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Sample>>("channel/samples")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&Json(Sample { seq: 1 })).unwrap();
+    let Json(sample) = tokio::time::timeout(Duration::from_secs(2), receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.seq, 1);
+    assert_eq!(subscriber.dropped_count(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_full_channel_counts_dropped_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("channel_drop_node", transport)
+        .await
+        .unwrap();
+    let (subscriber, mut receiver) = node
+        .subscriber::<Json<Sample>>("channel/flood")
+        .build_channel(2)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Sample>>("channel/flood")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..20 {
+        publisher.publish(&Json(Sample { seq })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert_eq!(subscriber.dropped_count(), 18);
+    assert_eq!(receiver.recv().await.unwrap().0.seq, 0);
+    assert_eq!(receiver.recv().await.unwrap().0.seq, 1);
+    assert!(receiver.try_recv().is_err());
+}
@@ -0,0 +1,72 @@
+//! Tests for client call timing and statistics
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{ClientStats, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ping {
+    seq: u32,
+}
+
+impl JsonMessage for Ping {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_call_timed_records_stats() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("client_stats_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("client_stats")
+        .build(|Json(ping)| Ok(Json(Ping { seq: ping.seq + 1 })))
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("client_stats")
+        .build()
+        .unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(client.stats(), ClientStats::default());
+
+    let (response, latency) = client.call_timed(&Json(Ping { seq: 1 })).await.unwrap();
+    assert_eq!(response.0.seq, 2);
+    assert!(latency > Duration::ZERO);
+
+    client.call_async(&Json(Ping { seq: 2 })).await.unwrap();
+
+    let stats = client.stats();
+    assert_eq!(stats.calls, 2);
+    assert_eq!(stats.failures, 0);
+    assert!(stats.p50 > Duration::ZERO);
+    assert!(stats.p99 >= stats.p50);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_failed_calls_are_counted() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("client_stats_missing_node", transport)
+        .await
+        .unwrap();
+
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("client_stats_missing")
+        .build()
+        .unwrap();
+
+    assert!(client
+        .call_with_timeout(&Json(Ping::default()), Duration::from_millis(300))
+        .await
+        .is_err());
+
+    let stats = client.stats();
+    assert_eq!(stats.calls, 1);
+    assert_eq!(stats.failures, 1);
+    assert_eq!(stats.p50, Duration::ZERO);
+}
@@ -0,0 +1,170 @@
+//! Tests for pluggable message codecs
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::{MockTransport, Transport};
+use zenobuf_core::{Codec, Error, Message, Node, ProstCodec, Result};
+
+#[derive(Clone, PartialEq, prost::Message, Serialize, Deserialize)]
+struct Reading {
+    #[prost(string, tag = "1")]
+    sensor: String,
+    #[prost(double, tag = "2")]
+    value: f64,
+}
+
+impl Message for Reading {
+    fn type_name() -> &'static str {
+        "test.Reading"
+    }
+}
+
+/// Codec encoding messages as JSON with serde
+struct JsonCodec;
+
+impl<M: Serialize + DeserializeOwned> Codec<M> for JsonCodec {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn encode(&self, message: &M) -> Result<Vec<u8>> {
+        serde_json::to_vec(message).map_err(|e| Error::other(e.to_string()))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<M> {
+        serde_json::from_slice(bytes).map_err(|e| Error::other(e.to_string()))
+    }
+}
+
+fn reading() -> Reading {
+    Reading {
+        sensor: "thermometer".to_string(),
+        value: 21.5,
+    }
+}
+
+/// Subscribes to `topic` with `codec`, collecting the readings received
+async fn collect(
+    transport: &MockTransport,
+    topic: &str,
+    codec: Arc<dyn Codec<Reading>>,
+) -> (Arc<zenobuf_core::Subscriber>, Arc<Mutex<Vec<Reading>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let subscriber = transport
+        .create_subscriber_with_codec::<Reading, _>(topic, codec, move |reading| {
+            sink.lock().unwrap().push(reading)
+        })
+        .await
+        .unwrap();
+    (subscriber, received)
+}
+
+#[tokio::test]
+async fn test_json_codec_round_trip_over_mock_transport() {
+    let transport = MockTransport::new();
+    let (_subscriber, received) = collect(&transport, "readings", Arc::new(JsonCodec)).await;
+
+    let publisher = transport
+        .create_publisher_with_codec::<Reading>("readings", Arc::new(JsonCodec))
+        .await
+        .unwrap();
+    publisher.publish(&reading()).unwrap();
+
+    let samples = transport.samples("readings");
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].codec.as_deref(), Some("json"));
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&samples[0].payload).unwrap()["sensor"],
+        "thermometer"
+    );
+    assert_eq!(*received.lock().unwrap(), vec![reading()]);
+}
+
+#[tokio::test]
+async fn test_codec_mismatch_drops_message() {
+    let transport = MockTransport::new();
+    let (_prost, prost_received) = collect(&transport, "readings", Arc::new(ProstCodec)).await;
+    let (_json, json_received) = collect(&transport, "readings", Arc::new(JsonCodec)).await;
+
+    let publisher = transport
+        .create_publisher_with_codec::<Reading>("readings", Arc::new(JsonCodec))
+        .await
+        .unwrap();
+    publisher.publish(&reading()).unwrap();
+
+    assert!(prost_received.lock().unwrap().is_empty());
+    assert_eq!(json_received.lock().unwrap().len(), 1);
+
+    // Payloads of the default codec carry no codec name
+    let publisher = transport
+        .create_publisher::<Reading>("readings")
+        .await
+        .unwrap();
+    publisher.publish(&reading()).unwrap();
+
+    assert_eq!(transport.samples("readings")[1].codec, None);
+    assert_eq!(prost_received.lock().unwrap().len(), 1);
+    assert_eq!(json_received.lock().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_subscriber_only_receives_its_codec() {
+    let node = Node::new("codec_node").await.unwrap();
+    let other_node = Node::new("codec_prost_node").await.unwrap();
+    let json_received = Arc::new(Mutex::new(Vec::new()));
+    let prost_received = Arc::new(Mutex::new(Vec::new()));
+
+    let record = json_received.clone();
+    let _json = node
+        .subscriber::<Reading>("readings")
+        .with_codec(JsonCodec)
+        .build(move |reading| record.lock().unwrap().push(reading))
+        .await
+        .unwrap();
+    let record = prost_received.clone();
+    let _prost = other_node
+        .subscriber::<Reading>("readings")
+        .build(move |reading| record.lock().unwrap().push(reading))
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Reading>("readings")
+        .with_codec(JsonCodec)
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    publisher.publish(&reading()).unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    node.spin_once().unwrap();
+    other_node.spin_once().unwrap();
+
+    assert_eq!(*json_received.lock().unwrap(), vec![reading()]);
+    assert!(prost_received.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_prost_codec_matches_message_encoding() {
+    let bytes = Codec::<Reading>::encode(&ProstCodec, &reading()).unwrap();
+    assert_eq!(
+        bytes,
+        zenobuf_core::message::encode_message(&reading()).unwrap()
+    );
+    let decoded: Reading = ProstCodec.decode(&bytes).unwrap();
+    assert_eq!(decoded, reading());
+    assert_eq!(Codec::<Reading>::name(&ProstCodec), ProstCodec::NAME);
+}
+
+#[test]
+fn test_codec_mismatch_error_message() {
+    let error = Error::codec_mismatch("json", "prost");
+    assert_eq!(
+        error.to_string(),
+        "Codec mismatch: expected 'json', received 'prost'"
+    );
+}
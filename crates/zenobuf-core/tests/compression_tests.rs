@@ -0,0 +1,117 @@
+//! Tests for compressed publishing
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Compression, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Grid {
+    width: u32,
+    cells: Vec<u8>,
+}
+
+impl JsonMessage for Grid {}
+
+fn grid(width: u32) -> Grid {
+    Grid {
+        width,
+        cells: vec![0; (width * width) as usize],
+    }
+}
+
+/// Publishes a large and a small grid on `topic`, returning the grids received
+/// by a subscriber, the sizes of the payloads sent over the network and the
+/// bytes counted by the publisher
+async fn publish_grids(
+    node_name: &str,
+    topic: &str,
+    compression: Compression,
+) -> (Vec<Grid>, Vec<usize>, u64) {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport(node_name, transport).await.unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Grid>>(topic)
+        .build(move |Json(grid)| received_clone.lock().unwrap().push(grid))
+        .await
+        .unwrap();
+
+    // Observe the payloads as sent, without decompressing them
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let payload_sizes = Arc::new(Mutex::new(Vec::new()));
+    let sizes_clone = payload_sizes.clone();
+    let _raw = session
+        .declare_subscriber(format!("{}{topic}", ZenohTransport::TOPIC_PREFIX))
+        .callback(move |sample| sizes_clone.lock().unwrap().push(sample.payload().len()))
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<Grid>>(topic)
+        .with_compression(compression)
+        .with_compression_threshold(256)
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&Json(grid(100))).unwrap();
+    publisher.publish(&Json(grid(4))).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    let received = received.lock().unwrap().clone();
+    let payload_sizes = payload_sizes.lock().unwrap().clone();
+    (received, payload_sizes, publisher.stats().bytes_sent)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_lz4_payloads_are_decompressed() {
+    let (received, payload_sizes, bytes_sent) =
+        publish_grids("compression_lz4_node", "compression_lz4", Compression::Lz4).await;
+
+    assert_eq!(received, vec![grid(100), grid(4)]);
+    let large = serde_json::to_vec(&grid(100)).unwrap().len();
+    let small = serde_json::to_vec(&grid(4)).unwrap().len();
+    assert_eq!(payload_sizes.len(), 2);
+    assert!(payload_sizes[0] < large / 10);
+    // Below the threshold, the payload is sent as encoded
+    assert_eq!(payload_sizes[1], small);
+    // The publisher counts the compressed payloads
+    assert_eq!(bytes_sent, payload_sizes.iter().sum::<usize>() as u64);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_zstd_payloads_are_decompressed() {
+    let (received, payload_sizes, bytes_sent) = publish_grids(
+        "compression_zstd_node",
+        "compression_zstd",
+        Compression::Zstd,
+    )
+    .await;
+
+    assert_eq!(received, vec![grid(100), grid(4)]);
+    let large = serde_json::to_vec(&grid(100)).unwrap().len();
+    assert!(payload_sizes[0] < large / 10);
+    assert_eq!(bytes_sent, payload_sizes.iter().sum::<usize>() as u64);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_uncompressed_by_default() {
+    let (received, payload_sizes, _) = publish_grids(
+        "compression_none_node",
+        "compression_none",
+        Compression::None,
+    )
+    .await;
+
+    assert_eq!(received, vec![grid(100), grid(4)]);
+    let large = serde_json::to_vec(&grid(100)).unwrap().len();
+    assert_eq!(payload_sizes[0], large);
+}
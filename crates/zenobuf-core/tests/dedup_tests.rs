@@ -0,0 +1,63 @@
+//! Tests for dropping duplicate messages on subscribers
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Command {
+    id: u32,
+}
+
+impl JsonMessage for Command {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_dedup_drops_replayed_message() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("dedup_node", transport).await.unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let subscriber = node
+        .subscriber::<Json<Command>>("dedup_commands")
+        .dedup()
+        .build(move |command| sink.lock().unwrap().push(command.0))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Command>>("dedup_commands")
+        .build()
+        .await
+        .unwrap();
+
+    // Capture the stamped sample so it can be replayed as is
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let key = format!("{}dedup_commands", ZenohTransport::TOPIC_PREFIX);
+    let captured = session.declare_subscriber(&key).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&Json(Command { id: 1 })).unwrap();
+    let sample = tokio::time::timeout(Duration::from_secs(5), captured.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    session
+        .put(&key, sample.payload().clone())
+        .encoding(sample.encoding().clone())
+        .attachment(sample.attachment().unwrap().clone())
+        .await
+        .unwrap();
+    publisher.publish(&Json(Command { id: 2 })).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Command { id: 1 }, Command { id: 2 }]
+    );
+    assert_eq!(subscriber.stats().duplicates, 1);
+}
@@ -0,0 +1,68 @@
+//! Tests for the node diagnostics
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node, NodeDiagnostics};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    value: f64,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_diagnostics_aggregate_stats() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("diagnostics_node", transport)
+        .await
+        .unwrap();
+    assert_eq!(node.diagnostics(), NodeDiagnostics::default());
+
+    let _subscriber = node
+        .subscriber::<Json<Reading>>("diagnostics_readings")
+        .build(|reading| {
+            if reading.value < 0.0 {
+                panic!("negative reading");
+            }
+        })
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("diagnostics_readings")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for value in [1.0, 2.0, -1.0] {
+        publisher.publish(&Json(Reading { value })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    let diagnostics = node.diagnostics();
+    assert_eq!(diagnostics.publishers, 1);
+    assert_eq!(diagnostics.subscribers, 1);
+    assert_eq!(diagnostics.messages_published, 3);
+    assert_eq!(diagnostics.bytes_published, publisher.stats().bytes_sent);
+    assert!(diagnostics.bytes_published > 0);
+    assert_eq!(diagnostics.messages_received, 3);
+    assert_eq!(diagnostics.callback_panics, 1);
+    assert_eq!(diagnostics.messages_dropped, 0);
+
+    // The same totals are served to remote tools
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let key = format!(
+        "{}diagnostics_node/{}",
+        Node::NODE_PREFIX,
+        Node::DIAGNOSTICS_KEY
+    );
+    let replies = session.get(key).await.unwrap();
+    let reply = replies.recv_async().await.unwrap();
+    let remote: NodeDiagnostics =
+        serde_json::from_slice(&reply.result().unwrap().payload().to_bytes()).unwrap();
+    assert_eq!(remote, diagnostics);
+}
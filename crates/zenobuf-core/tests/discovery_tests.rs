@@ -0,0 +1,110 @@
+//! Tests for the discovery metadata advertised by nodes
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Message, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Heading {
+    degrees: f64,
+}
+
+impl JsonMessage for Heading {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ack {
+    accepted: bool,
+}
+
+impl JsonMessage for Ack {}
+
+/// Queries a fresh session for the discovery metadata matching `selector`
+async fn discover(selector: &str) -> BTreeMap<String, serde_json::Value> {
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let replies = session.get(selector).await.unwrap();
+
+    let mut found = BTreeMap::new();
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.result() {
+            let info = serde_json::from_slice(&sample.payload().to_bytes()).unwrap();
+            found.insert(sample.key_expr().to_string(), info);
+        }
+    }
+    found
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_and_topic_are_discoverable() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("discovery_node", transport)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Heading>>("discovery/heading")
+        .build()
+        .await
+        .unwrap();
+
+    let nodes = discover("zenobuf/node/**").await;
+    assert!(nodes.contains_key("zenobuf/node/discovery_node"));
+
+    let topics = discover("zenobuf/topic/discovery/**").await;
+    let info = &topics["zenobuf/topic/discovery/heading"];
+    assert_eq!(info["type"], Json::<Heading>::type_name());
+    assert_eq!(info["role"], "publisher");
+
+    drop(publisher);
+    let topics = discover("zenobuf/topic/discovery/**").await;
+    assert!(topics.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_service_is_discoverable_without_being_called() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("discovery_service_node", transport)
+        .await
+        .unwrap();
+    let _service = node
+        .service::<Json<Heading>, Json<Heading>>("discovery/turn")
+        .build(Ok)
+        .await
+        .unwrap();
+
+    let services = discover("zenobuf/service/discovery/**?discovery").await;
+    assert_eq!(services.len(), 1);
+    let info = &services["zenobuf/service/discovery/turn"];
+    assert_eq!(info["request_type"], Json::<Heading>::type_name());
+    assert_eq!(info["response_type"], Json::<Heading>::type_name());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_service_answers_type_query() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("service_types_node", transport)
+        .await
+        .unwrap();
+    let service = node
+        .service::<Json<Heading>, Json<Ack>>("types/steer")
+        .build(|_| Ok(Json(Ack { accepted: true })))
+        .await
+        .unwrap();
+
+    let types = discover("zenobuf/service/types/steer/__types__").await;
+    assert_eq!(
+        types["zenobuf/service/types/steer/__types__"],
+        serde_json::json!({
+            "request_type": Json::<Heading>::type_name(),
+            "response_type": Json::<Ack>::type_name(),
+        })
+    );
+
+    // The type query stays out of service listings
+    let services = discover("zenobuf/service/types/**?discovery").await;
+    assert_eq!(services.len(), 1);
+
+    drop(service);
+    let types = discover("zenobuf/service/types/steer/__types__").await;
+    assert!(types.is_empty());
+}
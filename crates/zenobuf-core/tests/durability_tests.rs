@@ -0,0 +1,87 @@
+//! Tests for transient-local durability
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::qos::{Durability, QosProfile};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct MapInfo {
+    version: u32,
+}
+
+impl JsonMessage for MapInfo {}
+
+fn transient_local(depth: usize) -> QosProfile {
+    QosProfile::default()
+        .durability(Durability::TransientLocal)
+        .depth(depth)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_late_subscriber_receives_retained_message() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("durability_node", transport)
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<MapInfo>>("latched_map")
+        .with_qos(transient_local(1))
+        .build()
+        .await
+        .unwrap();
+    publisher.publish(&Json(MapInfo { version: 1 })).unwrap();
+    publisher.publish(&Json(MapInfo { version: 2 })).unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<MapInfo>>("latched_map")
+        .with_qos(transient_local(1))
+        .build(move |map| {
+            received_clone.lock().unwrap().push(map.version);
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node.spin_once().unwrap();
+
+    // Only the most recent sample is retained with a depth of 1
+    assert_eq!(*received.lock().unwrap(), vec![2]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_volatile_subscriber_does_not_receive_retained_message() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("durability_volatile_node", transport)
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<MapInfo>>("latched_volatile")
+        .with_qos(transient_local(1))
+        .build()
+        .await
+        .unwrap();
+    publisher.publish(&Json(MapInfo { version: 1 })).unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<MapInfo>>("latched_volatile")
+        .build(move |map| {
+            received_clone.lock().unwrap().push(map.version);
+        })
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node.spin_once().unwrap();
+
+    assert!(received.lock().unwrap().is_empty());
+}
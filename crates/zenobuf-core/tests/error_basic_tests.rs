@@ -84,6 +84,48 @@ fn test_error_display() {
     assert_eq!(error.to_string(), "Error: other error");
 }
 
+#[test]
+fn test_error_classifiers() {
+    let error = Error::service_call_timeout("add", 1000);
+    assert!(error.is_timeout());
+    assert!(error.is_retryable());
+    assert!(!error.is_transport());
+    assert!(!error.is_not_found());
+
+    let error = Error::timeout("waiting for a publisher on 'scan'", 500);
+    assert!(error.is_timeout());
+    assert!(error.is_retryable());
+
+    let error = Error::network("connection reset");
+    assert!(error.is_transport());
+    assert!(error.is_retryable());
+    assert!(!error.is_timeout());
+
+    let error = Error::service_call_failed("add", "Error in response: division by zero");
+    assert!(!error.is_retryable());
+    assert!(!error.is_not_found());
+
+    let error = Error::service_call_failed("add", "No service answered: timed out");
+    assert!(error.is_retryable());
+    assert!(error.is_not_found());
+
+    let error = Error::invalid_message("Waypoint", "z is negative");
+    assert!(!error.is_retryable());
+    assert!(!error.is_timeout());
+    assert!(!error.is_transport());
+    assert!(!error.is_not_found());
+
+    let error = Error::configuration("invalid name");
+    assert!(!error.is_retryable());
+
+    let error = Error::service_call_cancelled("add");
+    assert!(!error.is_retryable());
+    assert!(!error.is_timeout());
+
+    let error = Error::parameter("max_speed", "failed to set parameter");
+    assert!(!error.is_not_found());
+}
+
 #[test]
 fn test_error_debug() {
     // Test Debug implementation
@@ -0,0 +1,69 @@
+//! Tests for the debug check of message encodings when declaring endpoints
+
+use prost::Message as ProstMessage;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Message, Node};
+
+/// Message encoding its value as 4 bytes but decoding it from 8
+#[derive(Clone, PartialEq, Debug, Default)]
+struct MisframedMessage {
+    value: i32,
+}
+
+impl ProstMessage for MisframedMessage {
+    fn encode(&self, buf: &mut impl prost::bytes::BufMut) -> Result<(), prost::EncodeError> {
+        buf.put_slice(&self.value.to_le_bytes());
+        Ok(())
+    }
+
+    fn decode(buf: impl prost::bytes::Buf) -> Result<Self, prost::DecodeError> {
+        let mut buf = buf;
+        if buf.remaining() < 8 {
+            #[allow(deprecated)]
+            return Err(prost::DecodeError::new("Buffer too short"));
+        }
+        let value = buf.get_i64_le() as i32;
+        Ok(MisframedMessage { value })
+    }
+
+    fn encoded_len(&self) -> usize {
+        4
+    }
+
+    fn clear(&mut self) {
+        self.value = 0;
+    }
+
+    fn merge_field(
+        &mut self,
+        _tag: u32,
+        _wire_type: prost::encoding::WireType,
+        _buf: &mut impl prost::bytes::Buf,
+        _ctx: prost::encoding::DecodeContext,
+    ) -> Result<(), prost::DecodeError> {
+        Ok(())
+    }
+
+    fn encode_raw(&self, _buf: &mut impl prost::bytes::BufMut) {}
+}
+
+impl Message for MisframedMessage {
+    fn type_name() -> &'static str {
+        "MisframedMessage"
+    }
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[should_panic(expected = "MisframedMessage does not round-trip its own Protobuf encoding")]
+async fn test_misframed_message_fails_loudly_in_debug_builds() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("misframed_node", transport)
+        .await
+        .unwrap();
+
+    let _publisher = node
+        .publisher::<MisframedMessage>("misframed")
+        .build()
+        .await;
+}
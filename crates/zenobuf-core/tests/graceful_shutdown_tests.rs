@@ -0,0 +1,136 @@
+//! Tests for shutting down a node once its in-flight requests are answered
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Job {
+    id: u32,
+}
+
+impl JsonMessage for Job {}
+
+/// Time at which the handler of `slow_job` last returned its response
+type Answered = Arc<Mutex<Option<Instant>>>;
+
+/// Creates a node serving `slow_job`, which takes `delay` to answer
+async fn slow_server(name: &str, delay: Duration) -> (Node, zenobuf_core::ServiceHandle, Answered) {
+    let node = Node::new(name).await.unwrap();
+    let answered = Answered::default();
+    let record = answered.clone();
+    let service = node
+        .service::<Json<Job>, Json<Job>>("slow_job")
+        .build(move |job| {
+            std::thread::sleep(delay);
+            *record.lock().unwrap() = Some(Instant::now());
+            Ok(job)
+        })
+        .await
+        .unwrap();
+    (node, service, answered)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_in_flight_call_is_answered_before_shutdown() {
+    let (server, _service, answered) =
+        slow_server("graceful_server", Duration::from_millis(800)).await;
+    let client_node = Node::new("graceful_client").await.unwrap();
+    let client = client_node
+        .client::<Json<Job>, Json<Job>>("slow_job")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let call = tokio::spawn(async move {
+        client
+            .call_with_timeout(&Json(Job { id: 7 }), Duration::from_secs(5))
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // The handler still has about 600ms to go, which shutdown waits for
+    assert!(answered.lock().unwrap().is_none());
+    server
+        .shutdown_graceful(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let shut_down = Instant::now();
+    assert!(server.is_shutdown());
+
+    // The request stays in flight until its reply is sent, which is after the
+    // handler returned, so the handler finished before shutdown did
+    let answered = answered.lock().unwrap().expect("handler still running");
+    assert!(answered <= shut_down);
+    // and the reply went out before the session was torn down
+    let response = call.await.unwrap().unwrap();
+    assert_eq!(response.id, 7);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_in_flight_call_of_unowned_service_is_answered() {
+    let server = Node::new("graceful_unowned_server").await.unwrap();
+    let answered = Answered::default();
+    let record = answered.clone();
+    // Only the node's registration keeps the service alive
+    let _ = server
+        .create_service::<Json<Job>, Json<Job>, _>("unowned_slow_job", move |job| {
+            std::thread::sleep(Duration::from_millis(800));
+            *record.lock().unwrap() = Some(Instant::now());
+            Ok(job)
+        })
+        .await
+        .unwrap();
+    let client_node = Node::new("graceful_unowned_client").await.unwrap();
+    let client = client_node
+        .client::<Json<Job>, Json<Job>>("unowned_slow_job")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let call = tokio::spawn(async move {
+        client
+            .call_with_timeout(&Json(Job { id: 3 }), Duration::from_secs(5))
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    server
+        .shutdown_graceful(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let shut_down = Instant::now();
+
+    let answered = answered.lock().unwrap().expect("handler still running");
+    assert!(answered <= shut_down);
+    let response = call.await.unwrap().unwrap();
+    assert_eq!(response.id, 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_graceful_shutdown_times_out() {
+    let (server, _service, _) = slow_server("graceful_slow_server", Duration::from_secs(2)).await;
+    let client_node = Node::new("graceful_slow_client").await.unwrap();
+    let client = client_node
+        .client::<Json<Job>, Json<Job>>("slow_job")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let call = tokio::spawn(async move {
+        client
+            .call_with_timeout(&Json(Job { id: 1 }), Duration::from_secs(5))
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let error = server
+        .shutdown_graceful(Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert!(error.is_timeout(), "{error}");
+    assert!(server.is_shutdown());
+    let _ = call.await;
+}
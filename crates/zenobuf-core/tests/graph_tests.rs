@@ -0,0 +1,139 @@
+//! Tests for waiting on publishers and watching graph changes
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::{GraphEvents, ZenohTransport};
+use zenobuf_core::{Error, GraphEvent, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ping {
+    count: u32,
+}
+
+impl JsonMessage for Ping {}
+
+/// Returns the next event concerning `name`, skipping those of other tests
+async fn next_event_for(events: &mut GraphEvents, name: &str) -> GraphEvent {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.next().await.unwrap();
+            let event_name = match &event {
+                GraphEvent::PublisherAdded { topic }
+                | GraphEvent::PublisherRemoved { topic }
+                | GraphEvent::SubscriberAdded { topic }
+                | GraphEvent::SubscriberRemoved { topic } => topic,
+                GraphEvent::ServiceAdded { name } | GraphEvent::ServiceRemoved { name } => name,
+            };
+            if event_name == name {
+                return event;
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_wait_for_publisher() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Arc::new(
+        Node::with_transport("wait_publisher_node", transport)
+            .await
+            .unwrap(),
+    );
+
+    let err = node
+        .wait_for_publisher("graph_ping", Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Timeout {
+            timeout_ms: 300,
+            ..
+        }
+    ));
+
+    let publishing_node = node.clone();
+    let creator = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        publishing_node
+            .publisher::<Json<Ping>>("graph_ping")
+            .build()
+            .await
+            .unwrap()
+    });
+
+    let start = Instant::now();
+    node.wait_for_publisher("graph_ping", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(2));
+    drop(creator.await.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_graph_events() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("graph_events_node", transport)
+        .await
+        .unwrap();
+
+    let existing = node
+        .subscriber::<Json<Ping>>("graph_existing")
+        .build(|_| {})
+        .await
+        .unwrap();
+
+    let mut events = node.graph_events().await.unwrap();
+    assert_eq!(
+        next_event_for(&mut events, "graph_existing").await,
+        GraphEvent::SubscriberAdded {
+            topic: "graph_existing".to_string()
+        }
+    );
+
+    let publisher = node
+        .publisher::<Json<Ping>>("/graph_events")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(
+        next_event_for(&mut events, "graph_events").await,
+        GraphEvent::PublisherAdded {
+            topic: "graph_events".to_string()
+        }
+    );
+
+    drop(publisher);
+    assert_eq!(
+        next_event_for(&mut events, "graph_events").await,
+        GraphEvent::PublisherRemoved {
+            topic: "graph_events".to_string()
+        }
+    );
+
+    let service = node
+        .service::<Json<Ping>, Json<Ping>>("graph_service")
+        .build(Ok)
+        .await
+        .unwrap();
+    assert_eq!(
+        next_event_for(&mut events, "graph_service").await,
+        GraphEvent::ServiceAdded {
+            name: "graph_service".to_string()
+        }
+    );
+
+    drop(service);
+    assert_eq!(
+        next_event_for(&mut events, "graph_service").await,
+        GraphEvent::ServiceRemoved {
+            name: "graph_service".to_string()
+        }
+    );
+    drop(existing);
+}
@@ -0,0 +1,56 @@
+//! Tests for the periodic node heartbeat
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use zenobuf_core::{Heartbeat, Node};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_heartbeat_increments_until_node_is_dropped() {
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let heartbeats = Arc::new(Mutex::new(Vec::new()));
+    let record = heartbeats.clone();
+    let _subscriber = session
+        .declare_subscriber(format!(
+            "{}heartbeat_node/{}",
+            Node::NODE_PREFIX,
+            Node::HEARTBEAT_KEY
+        ))
+        .callback(move |sample| {
+            let heartbeat: Heartbeat =
+                serde_json::from_slice(&sample.payload().to_bytes()).unwrap();
+            record.lock().unwrap().push(heartbeat);
+        })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let node = Node::builder("heartbeat_node")
+        .heartbeat_period(Duration::from_millis(100))
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    let received = heartbeats.lock().unwrap().clone();
+    assert!(received.len() >= 3, "{received:?}");
+    for pair in received.windows(2) {
+        assert!(pair[1].sequence > pair[0].sequence, "{received:?}");
+        assert!(pair[1].stamp >= pair[0].stamp, "{received:?}");
+    }
+
+    drop(node);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let count = heartbeats.lock().unwrap().len();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(heartbeats.lock().unwrap().len(), count);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_zero_heartbeat_period_is_rejected() {
+    let result = Node::builder("zero_heartbeat_node")
+        .heartbeat_period(Duration::ZERO)
+        .build()
+        .await;
+    assert!(result.is_err());
+}
@@ -4,7 +4,7 @@ use prost::Message as ProstMessage;
 use zenobuf_core::message::Message;
 use zenobuf_core::node::Node;
 use zenobuf_core::qos::QosProfile;
-use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::transport::{MockTransport, ZenohTransport};
 
 // Define a test message
 #[derive(Clone, PartialEq, Debug, Default)]
@@ -241,10 +241,10 @@ async fn test_parameter_basic() {
     assert!(result.is_err());
 }
 
-#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tokio::test]
 async fn test_service_client_basic() {
-    // Create a transport
-    let transport = ZenohTransport::new().await.unwrap();
+    // Create an in-memory transport, so the test needs no network
+    let transport = MockTransport::new();
 
     // Create a node
     let node = Node::with_transport("service_client_node", transport)
@@ -261,7 +261,7 @@ async fn test_service_client_basic() {
 
     // Create a client
     let client = node
-        .create_client::<AddRequest, AddResponse>("add_service")
+        .create_client::<AddRequest, AddResponse>("add_service", QosProfile::default())
         .unwrap();
 
     // Create a request
@@ -280,10 +280,10 @@ async fn test_service_client_basic() {
     assert_eq!(response.sum, 42);
 }
 
-#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tokio::test]
 async fn test_pub_sub_basic() {
-    // Create a transport
-    let transport = ZenohTransport::new().await.unwrap();
+    // Create an in-memory transport, so the test needs no network
+    let transport = MockTransport::new();
 
     // Create a node
     let node = Node::with_transport("pub_sub_node", transport)
@@ -332,10 +332,10 @@ async fn test_pub_sub_basic() {
     assert_eq!(received_msg.as_ref().unwrap().text, "Hello, world!");
 }
 
-#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[tokio::test]
 async fn test_complex_workflow() {
-    // Create a transport
-    let transport = ZenohTransport::new().await.unwrap();
+    // Create an in-memory transport, so the test needs no network
+    let transport = MockTransport::new();
 
     // Create a node
     let node = Node::with_transport("complex_node", transport)
@@ -361,7 +361,7 @@ async fn test_complex_workflow() {
 
     // Create a client
     let client = node
-        .create_client::<AddRequest, AddResponse>("add_service")
+        .create_client::<AddRequest, AddResponse>("add_service", QosProfile::default())
         .unwrap();
 
     // Create a variable to store the received message
@@ -0,0 +1,85 @@
+//! Tests for the node introspection API
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Message, Node, ServiceInfo, TopicInfo};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Odometry {
+    x: f64,
+}
+
+impl JsonMessage for Odometry {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Battery {
+    percent: f32,
+}
+
+impl JsonMessage for Battery {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_introspection_lists_declared_entities() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("introspection_node", transport)
+        .await
+        .unwrap();
+
+    let _odom = node
+        .publisher::<Json<Odometry>>("odom")
+        .build()
+        .await
+        .unwrap();
+    let _battery = node
+        .publisher::<Json<Battery>>("battery")
+        .build()
+        .await
+        .unwrap();
+    let _service = node
+        .service::<Json<Battery>, Json<Odometry>>("reset")
+        .build(|_request| Ok(Json(Odometry::default())))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        node.publishers(),
+        vec![
+            TopicInfo {
+                name: "battery".to_string(),
+                type_name: <Json<Battery> as Message>::type_name(),
+            },
+            TopicInfo {
+                name: "odom".to_string(),
+                type_name: <Json<Odometry> as Message>::type_name(),
+            },
+        ]
+    );
+    assert!(node.subscribers().is_empty());
+    assert_eq!(
+        node.services(),
+        vec![ServiceInfo {
+            name: "reset".to_string(),
+            request_type: <Json<Battery> as Message>::type_name(),
+            response_type: <Json<Odometry> as Message>::type_name(),
+        }]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_introspection_forgets_dropped_entities() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("introspection_drop_node", transport)
+        .await
+        .unwrap();
+
+    let subscriber = node
+        .subscriber::<Json<Odometry>>("odom")
+        .build(|_odom| {})
+        .await
+        .unwrap();
+    assert_eq!(node.subscribers().len(), 1);
+    assert!(node.subscribers()[0].type_name.ends_with("Odometry"));
+
+    drop(subscriber);
+    assert!(node.subscribers().is_empty());
+}
@@ -0,0 +1,158 @@
+//! Tests for JSON-encoded messages
+
+use std::sync::{Arc, Mutex};
+
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Serialize};
+use zenobuf_core::message::{
+    decode_message_with, encode_message_with, Encoding, Json, JsonMessage, JsonSerializer,
+    ProtobufSerializer,
+};
+use zenobuf_core::transport::MockTransport;
+use zenobuf_core::{Error, Message, Transport};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Status {
+    name: String,
+    healthy: bool,
+    load: f64,
+}
+
+impl JsonMessage for Status {}
+
+#[test]
+fn test_json_serializer_round_trip() {
+    let status = Status {
+        name: "motor".to_string(),
+        healthy: true,
+        load: 0.5,
+    };
+
+    let bytes = encode_message_with::<JsonSerializer, _>(&status).unwrap();
+    assert!(serde_json::from_slice::<serde_json::Value>(&bytes).is_ok());
+
+    let decoded: Status =
+        decode_message_with::<JsonSerializer, _>(&bytes, Some(Encoding::Json)).unwrap();
+    assert_eq!(decoded, status);
+}
+
+#[test]
+fn test_json_message_encoding() {
+    assert_eq!(<Json<Status> as Message>::ENCODING, Encoding::Json);
+    assert!(<Json<Status> as Message>::type_name().ends_with("Status"));
+}
+
+#[test]
+fn test_decode_with_mismatched_encoding() {
+    let bytes = encode_message_with::<JsonSerializer, _>(&Status::default()).unwrap();
+
+    let result =
+        decode_message_with::<ProtobufSerializer, Json<Status>>(&bytes, Some(Encoding::Json));
+    assert!(matches!(
+        result,
+        Err(Error::EncodingMismatch {
+            expected: Encoding::Protobuf,
+            actual: Encoding::Json,
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_json_round_trip_over_mock_transport() {
+    let transport = MockTransport::new();
+
+    let publisher = transport
+        .create_publisher::<Json<Status>>("status")
+        .await
+        .unwrap();
+    publisher
+        .publish(&Json(Status {
+            name: "lidar".to_string(),
+            healthy: false,
+            load: 0.25,
+        }))
+        .unwrap();
+
+    let samples = transport.samples("status");
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].encoding, Some(Encoding::Json));
+
+    let received = Arc::new(Mutex::new(None));
+    let received_clone = received.clone();
+    let _subscriber = transport
+        .create_subscriber::<Json<Status>, _>("status", move |msg: Json<Status>| {
+            *received_clone.lock().unwrap() = Some(msg.into_inner());
+        })
+        .await
+        .unwrap();
+
+    let received = received.lock().unwrap();
+    let status = received.as_ref().expect("message should be delivered");
+    assert_eq!(status.name, "lidar");
+    assert!(!status.healthy);
+    assert_eq!(status.load, 0.25);
+}
+
+#[tokio::test]
+async fn test_mismatched_encoding_is_not_delivered() {
+    let transport = MockTransport::new();
+
+    // A peer sends protobuf-tagged bytes on a JSON topic
+    transport.publish_raw("status", vec![0x08, 0x01], Some(Encoding::Protobuf));
+
+    let received = Arc::new(Mutex::new(0));
+    let received_clone = received.clone();
+    let _subscriber = transport
+        .create_subscriber::<Json<Status>, _>("status", move |_msg: Json<Status>| {
+            *received_clone.lock().unwrap() += 1;
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(*received.lock().unwrap(), 0);
+}
+
+/// A message that serde_json refuses to encode, since its map keys are not strings
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Grid {
+    cells: std::collections::BTreeMap<(u8, u8), u8>,
+}
+
+impl JsonMessage for Grid {}
+
+#[tokio::test]
+async fn test_unencodable_message_fails_to_publish() {
+    let transport = MockTransport::new();
+    let publisher = transport
+        .create_publisher::<Json<Grid>>("grid")
+        .await
+        .unwrap();
+
+    let mut grid = Grid::default();
+    grid.cells.insert((0, 0), 1);
+
+    let err = publisher.publish(&Json(grid)).unwrap_err();
+    assert!(matches!(err, Error::JsonSerialization { .. }));
+    assert!(transport.samples("grid").is_empty());
+}
+
+#[test]
+fn test_json_encoded_len_matches_encoding() {
+    let status = Json(Status {
+        name: "motor".to_string(),
+        healthy: true,
+        load: 0.5,
+    });
+    zenobuf_core::testing::assert_encoded_len(&status);
+    assert_eq!(status.encode_to_vec(), status.encode_to_bytes().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "encode JSON messages with Message::encode_to_bytes")]
+fn test_unencodable_message_panics_through_prost() {
+    let mut grid = Grid::default();
+    grid.cells.insert((0, 0), 1);
+
+    // prost cannot report the error, so it must not silently encode nothing
+    let _ = Json(grid).encode_to_vec();
+}
@@ -0,0 +1,65 @@
+//! Tests for publisher/subscriber matching through liveliness tokens
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ping {
+    seq: u64,
+}
+
+impl JsonMessage for Ping {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publisher_counts_subscribers() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("liveliness_node", transport)
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<Ping>>("liveliness_topic")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(publisher.subscriber_count(), 0);
+
+    let subscriber = node
+        .subscriber::<Json<Ping>>("liveliness_topic")
+        .build(|_ping| {})
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(publisher.subscriber_count(), 1);
+    assert_eq!(subscriber.publisher_count(), 1);
+
+    drop(subscriber);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(publisher.subscriber_count(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_other_topics_are_not_counted() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("liveliness_other_node", transport)
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<Ping>>("liveliness_a")
+        .build()
+        .await
+        .unwrap();
+    let _subscriber = node
+        .subscriber::<Json<Ping>>("liveliness_b")
+        .build(|_ping| {})
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(publisher.subscriber_count(), 0);
+}
@@ -0,0 +1,106 @@
+//! Tests for services whose requests are taken from a stream
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct AddRequest {
+    a: i64,
+    b: i64,
+}
+
+impl JsonMessage for AddRequest {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct AddResponse {
+    sum: i64,
+    /// Sum of every request served so far
+    total: i64,
+}
+
+impl JsonMessage for AddResponse {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_manual_service_replies_through_responder() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("manual_service_node", transport)
+        .await
+        .unwrap();
+
+    let (_service, mut requests) = node
+        .service::<Json<AddRequest>, Json<AddResponse>>("manual_add")
+        .build_manual()
+        .await
+        .unwrap();
+    // The loop owns its state, so it needs no lock
+    tokio::spawn(async move {
+        let mut total = 0;
+        while let Some((request, responder)) = requests.next().await {
+            let sum = request.a + request.b;
+            total += sum;
+            responder.send(Ok(Json(AddResponse { sum, total })));
+        }
+    });
+
+    let client = node
+        .client::<Json<AddRequest>, Json<AddResponse>>("manual_add")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let first = client
+        .call_async(&Json(AddRequest { a: 2, b: 3 }))
+        .await
+        .unwrap();
+    assert_eq!(first.0, AddResponse { sum: 5, total: 5 });
+    let second = client
+        .call_async(&Json(AddRequest { a: 10, b: -4 }))
+        .await
+        .unwrap();
+    assert_eq!(second.0, AddResponse { sum: 6, total: 11 });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_manual_service_errors_and_dropped_responders() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("manual_error_node", transport)
+        .await
+        .unwrap();
+
+    let (_service, requests) = node
+        .service::<Json<AddRequest>, Json<AddResponse>>("manual_checked_add")
+        .build_manual()
+        .await
+        .unwrap();
+    tokio::spawn(requests.for_each(|(request, responder)| async move {
+        if request.a < 0 {
+            responder.send(Err(Error::other("negative operand")));
+        }
+        // Other requests are dropped without a reply
+    }));
+
+    let client = node
+        .client::<Json<AddRequest>, Json<AddResponse>>("manual_checked_add")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let rejected = client
+        .call_with_timeout(&Json(AddRequest { a: -1, b: 0 }), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&rejected, Error::ServiceCallFailed { reason, .. } if reason.contains("negative operand")),
+        "{rejected}"
+    );
+    let dropped = client
+        .call_with_timeout(&Json(AddRequest { a: 1, b: 0 }), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&dropped, Error::ServiceCallFailed { reason, .. } if reason.contains("without replying")),
+        "{dropped}"
+    );
+}
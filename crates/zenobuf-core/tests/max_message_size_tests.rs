@@ -0,0 +1,100 @@
+//! Tests for dropping messages larger than the maximum message size
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use zenobuf_core::{Compression, Node, RawBytes};
+
+/// Subscribes to `topic` with a 1 KiB limit, collecting the sizes received
+async fn subscribe_limited(
+    node: &Node,
+    topic: &str,
+) -> (zenobuf_core::SubscriberHandle, Arc<Mutex<Vec<usize>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let record = received.clone();
+    let subscriber = node
+        .subscriber::<RawBytes>(topic)
+        .with_max_message_size(1024)
+        .build(move |bytes| record.lock().unwrap().push(bytes.len()))
+        .await
+        .unwrap();
+    (subscriber, received)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_oversized_messages_are_dropped() {
+    let node = Node::new("max_size_node").await.unwrap();
+    let (subscriber, received) = subscribe_limited(&node, "max_size_blobs").await;
+    let publisher = node
+        .publisher::<RawBytes>("max_size_blobs")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&RawBytes::from(vec![7; 4096])).unwrap();
+    publisher.publish(&RawBytes::from(vec![7; 512])).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![512]);
+    assert_eq!(subscriber.stats().oversized_messages, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_decompressed_size_is_limited() {
+    let node = Node::new("max_size_bomb_node").await.unwrap();
+    for (topic, compression) in [
+        ("max_size_lz4", Compression::Lz4),
+        ("max_size_zstd", Compression::Zstd),
+    ] {
+        let (subscriber, received) = subscribe_limited(&node, topic).await;
+        let publisher = node
+            .publisher::<RawBytes>(topic)
+            .with_compression(compression)
+            .with_compression_threshold(0)
+            .build()
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // Zeros compress to far less than the limit, but expand beyond it
+        publisher
+            .publish(&RawBytes::from(vec![0; 64 * 1024]))
+            .unwrap();
+        publisher.publish(&RawBytes::from(vec![0; 512])).unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        node.spin_once().unwrap();
+
+        assert_eq!(*received.lock().unwrap(), vec![512], "{compression:?}");
+        assert_eq!(subscriber.stats().oversized_messages, 1, "{compression:?}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_oversized_requests_are_rejected() {
+    let node = Node::new("max_size_service_node").await.unwrap();
+    let _service = node
+        .service::<RawBytes, RawBytes>("max_size_echo")
+        .with_max_message_size(1024)
+        .build(Ok)
+        .await
+        .unwrap();
+    let client = node
+        .client::<RawBytes, RawBytes>("max_size_echo")
+        .build()
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let response = client
+        .call_async(&RawBytes::from(vec![1; 512]))
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 512);
+
+    let error = client
+        .call_async(&RawBytes::from(vec![1; 4096]))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("maximum"), "{error}");
+}
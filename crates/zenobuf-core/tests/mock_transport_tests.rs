@@ -0,0 +1,92 @@
+//! Tests for live pub/sub delivery over the mock transport
+
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::{MockTransport, Transport};
+use zenobuf_core::{Json, JsonMessage};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Tick {
+    seq: u32,
+}
+
+impl JsonMessage for Tick {}
+
+/// Subscribes to `topic`, collecting the sequence numbers received
+async fn collect(
+    transport: &MockTransport,
+    topic: &str,
+) -> (Arc<zenobuf_core::Subscriber>, Arc<Mutex<Vec<u32>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let subscriber = transport
+        .create_subscriber::<Json<Tick>, _>(topic, move |Json(tick)| {
+            sink.lock().unwrap().push(tick.seq)
+        })
+        .await
+        .unwrap();
+    (subscriber, received)
+}
+
+#[tokio::test]
+async fn test_subscriber_receives_later_publishes() {
+    let transport = MockTransport::new();
+    let (_subscriber, received) = collect(&transport, "ticks").await;
+
+    let publisher = transport
+        .create_publisher::<Json<Tick>>("ticks")
+        .await
+        .unwrap();
+    for seq in 0..3 {
+        publisher.publish(&Json(Tick { seq })).unwrap();
+    }
+
+    assert_eq!(*received.lock().unwrap(), vec![0, 1, 2]);
+}
+
+#[tokio::test]
+async fn test_every_subscriber_receives_each_message_once() {
+    let transport = MockTransport::new();
+    let publisher = transport
+        .create_publisher::<Json<Tick>>("shared")
+        .await
+        .unwrap();
+    publisher.publish(&Json(Tick { seq: 0 })).unwrap();
+
+    let (_first, first) = collect(&transport, "shared").await;
+    let (_second, second) = collect(&transport, "shared").await;
+    let (_other, other) = collect(&transport, "other").await;
+    publisher.publish(&Json(Tick { seq: 1 })).unwrap();
+
+    assert_eq!(*first.lock().unwrap(), vec![0, 1]);
+    assert_eq!(*second.lock().unwrap(), vec![0, 1]);
+    assert!(other.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_closed_subscriber_stops_receiving() {
+    let transport = MockTransport::new();
+    let (subscriber, received) = collect(&transport, "closing").await;
+    let publisher = transport
+        .create_publisher::<Json<Tick>>("closing")
+        .await
+        .unwrap();
+
+    publisher.publish(&Json(Tick { seq: 0 })).unwrap();
+    subscriber.close().unwrap();
+    publisher.publish(&Json(Tick { seq: 1 })).unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![0]);
+}
+
+#[tokio::test]
+async fn test_call_without_service_is_not_found() {
+    let transport = MockTransport::new();
+    let client = transport
+        .create_client::<Json<Tick>, Json<Tick>>("missing")
+        .unwrap();
+
+    let err = client.call(&Json(Tick { seq: 0 })).unwrap_err();
+    assert!(err.is_not_found());
+}
@@ -0,0 +1,61 @@
+//! Tests for creating several clients of the same service
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node, QosProfile};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Count {
+    value: u32,
+}
+
+impl JsonMessage for Count {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_two_clients_for_one_service() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("multiple_clients_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Count>, Json<Count>>("multiple_clients_increment")
+        .build(|Json(count)| {
+            Ok(Json(Count {
+                value: count.value + 1,
+            }))
+        })
+        .await
+        .unwrap();
+
+    let first = node
+        .client::<Json<Count>, Json<Count>>("multiple_clients_increment")
+        .build()
+        .unwrap();
+    let second = node
+        .client::<Json<Count>, Json<Count>>("multiple_clients_increment")
+        .build()
+        .unwrap();
+    first
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let response = first.call_async(&Json(Count { value: 1 })).await.unwrap();
+    assert_eq!(response.0.value, 2);
+    let response = second.call_async(&Json(Count { value: 10 })).await.unwrap();
+    assert_eq!(response.0.value, 11);
+
+    // Dropping one client leaves the other usable, and a new one can be created
+    drop(first);
+    let response = second.call_async(&Json(Count { value: 20 })).await.unwrap();
+    assert_eq!(response.0.value, 21);
+    let _third = node
+        .create_client::<Json<Count>, Json<Count>>(
+            "multiple_clients_increment",
+            QosProfile::default(),
+        )
+        .unwrap();
+}
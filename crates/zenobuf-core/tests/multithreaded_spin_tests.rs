@@ -0,0 +1,86 @@
+//! Tests for spinning a node on a pool of worker threads
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Step {
+    seq: u32,
+}
+
+impl JsonMessage for Step {}
+
+/// Records the order of a subscriber's callbacks and how many ran at once
+#[derive(Default)]
+struct Recorder {
+    received: Mutex<Vec<u32>>,
+    /// Callbacks of this subscriber currently running
+    active: AtomicUsize,
+    /// Largest number of this subscriber's callbacks that ran at once
+    max_active: AtomicUsize,
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_spin_multithreaded_orders_per_subscriber() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Arc::new(
+        Node::with_transport("multithreaded_node", transport)
+            .await
+            .unwrap(),
+    );
+
+    // Callbacks running at once across both subscribers
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+    let mut recorders = Vec::new();
+    let mut subscribers = Vec::new();
+    let mut publishers = Vec::new();
+    for topic in ["mt_left", "mt_right"] {
+        let recorder = Arc::new(Recorder::default());
+        let (record, running, max_running) =
+            (recorder.clone(), running.clone(), max_running.clone());
+        subscribers.push(
+            node.subscriber::<Json<Step>>(topic)
+                .build(move |Json(step)| {
+                    let active = record.active.fetch_add(1, Ordering::SeqCst) + 1;
+                    record.max_active.fetch_max(active, Ordering::SeqCst);
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now, Ordering::SeqCst);
+
+                    std::thread::sleep(Duration::from_millis(100));
+                    record.received.lock().unwrap().push(step.seq);
+
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    record.active.fetch_sub(1, Ordering::SeqCst);
+                })
+                .await
+                .unwrap(),
+        );
+        publishers.push(node.publisher::<Json<Step>>(topic).build().await.unwrap());
+        recorders.push(recorder);
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..5 {
+        for publisher in &publishers {
+            publisher.publish(&Json(Step { seq })).unwrap();
+        }
+    }
+
+    let spinner = node.clone();
+    let spin = tokio::spawn(async move { spinner.spin_multithreaded(4).await });
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    node.shutdown();
+    spin.await.unwrap().unwrap();
+
+    for recorder in &recorders {
+        assert_eq!(*recorder.received.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(recorder.max_active.load(Ordering::SeqCst), 1);
+    }
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+}
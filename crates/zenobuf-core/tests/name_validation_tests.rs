@@ -0,0 +1,94 @@
+//! Tests for the validation of topic and service names
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::util::{validate_name, validate_pattern};
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ping {
+    count: u32,
+}
+
+impl JsonMessage for Ping {}
+
+/// Returns the reason of a configuration error
+fn reason(result: zenobuf_core::Result<()>) -> String {
+    match result {
+        Err(Error::Configuration { reason }) => reason,
+        other => panic!("expected a configuration error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_valid_names() {
+    for name in ["pose", "/robot1/pose", "robot_1/cmd-vel", "a/b/c"] {
+        validate_name(name).unwrap();
+    }
+    for pattern in ["sensors/**", "/robots/*/pose", "**"] {
+        validate_pattern(pattern).unwrap();
+    }
+}
+
+#[test]
+fn test_invalid_names_are_explained() {
+    assert!(reason(validate_name("")).contains("it is empty"));
+    assert!(reason(validate_name("/")).contains("it is empty"));
+    assert!(reason(validate_name("robot/")).contains("ends with '/'"));
+    assert!(reason(validate_name("robot//pose")).contains("empty chunk"));
+    assert!(reason(validate_name("robot?pose")).contains("'?' is reserved"));
+    assert!(reason(validate_name("robot#1")).contains("'#' is reserved"));
+    assert!(reason(validate_name("cost$")).contains("'$' is reserved"));
+
+    let wildcard = reason(validate_name("sensors/*"));
+    assert!(wildcard.contains("Invalid name 'sensors/*'"));
+    assert!(wildcard.contains("only allowed in subscriber patterns"));
+    assert!(wildcard.contains("e.g. 'robot1/pose'"));
+
+    assert!(reason(validate_pattern("sensors/temp*")).contains("whole chunk"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_rejects_invalid_names() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("name_validation_node", transport)
+        .await
+        .unwrap();
+
+    let err = node
+        .publisher::<Json<Ping>>("bad?topic")
+        .build()
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Configuration { .. }), "{err}");
+
+    let err = node
+        .subscriber::<Json<Ping>>("sensors/**")
+        .build(|_| {})
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Configuration { .. }), "{err}");
+
+    let err = node
+        .service::<Json<Ping>, Json<Ping>>("")
+        .build(Ok)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Configuration { .. }), "{err}");
+
+    let err = node
+        .client::<Json<Ping>, Json<Ping>>("add//two")
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Configuration { .. }), "{err}");
+
+    // Patterns remain valid for wildcard subscribers
+    node.subscriber_wildcard::<Json<Ping>>("sensors/**")
+        .build(|_, _| {})
+        .await
+        .unwrap();
+}
@@ -0,0 +1,84 @@
+//! Tests for node namespaces and name remapping
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Velocity {
+    linear: f64,
+}
+
+impl JsonMessage for Velocity {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_relative_name_is_prefixed() {
+    let node = Node::with_namespace("ns_relative_node", "robot1")
+        .await
+        .unwrap();
+    assert_eq!(node.namespace(), Some("/robot1"));
+
+    let publisher = node
+        .publisher::<Json<Velocity>>("pose")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(publisher.topic(), "/robot1/pose");
+    assert_eq!(publisher.resolved_key(), "zenobuf/topic/robot1/pose");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_absolute_name_is_not_prefixed() {
+    let node = Node::with_namespace("ns_absolute_node", "/robot1/")
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<Velocity>>("/map")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(publisher.topic(), "/map");
+    assert_eq!(node.resolve_name("/map"), "/map");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_remap_rewrites_final_key() {
+    let robot = Node::with_namespace("ns_remap_robot", "robot1")
+        .await
+        .unwrap();
+    robot.remap("cmd", "/shared/cmd_vel");
+    assert_eq!(robot.resolve_name("cmd"), "/shared/cmd_vel");
+    assert_eq!(robot.resolve_name("other"), "/robot1/other");
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let received_clone = received.clone();
+    let subscriber = robot
+        .subscriber::<Json<Velocity>>("cmd")
+        .build(move |_velocity| {
+            received_clone.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+    assert_eq!(subscriber.subscriber().topic(), "/shared/cmd_vel");
+    assert_eq!(subscriber.topic(), "/shared/cmd_vel");
+    assert_eq!(subscriber.resolved_key(), "zenobuf/topic/shared/cmd_vel");
+
+    // A node without a namespace publishing on the remapped key reaches the subscriber
+    let teleop = Node::new("ns_remap_teleop").await.unwrap();
+    let publisher = teleop
+        .publisher::<Json<Velocity>>("shared/cmd_vel")
+        .build()
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    publisher.publish(&Json(Velocity { linear: 1.0 })).unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    robot.spin_once().unwrap();
+
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+}
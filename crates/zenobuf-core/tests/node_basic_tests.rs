@@ -1,3 +1,4 @@
+use serde_json::json;
 use zenobuf_core::node::Node;
 use zenobuf_core::transport::ZenohTransport;
 
@@ -31,6 +32,29 @@ async fn test_node_parameter() {
     assert!(result.is_err());
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_parameters_snapshot() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("test_node", transport).await.unwrap();
+
+    node.set_parameter("count", 42).unwrap();
+    node.set_parameter("gain", 0.5).unwrap();
+    node.set_parameter("enabled", true).unwrap();
+    node.set_parameter("frame", "base_link".to_string())
+        .unwrap();
+    node.set_parameter("limits", vec![1, 2, 3]).unwrap();
+
+    let snapshot = node.parameters_snapshot();
+    assert_eq!(snapshot.len(), 5);
+    assert_eq!(snapshot["count"], json!(42));
+    assert!(snapshot["count"].is_i64());
+    assert_eq!(snapshot["gain"], json!(0.5));
+    assert!(snapshot["gain"].is_f64());
+    assert_eq!(snapshot["enabled"], json!(true));
+    assert_eq!(snapshot["frame"], json!("base_link"));
+    assert_eq!(snapshot["limits"], json!([1, 2, 3]));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_node_spin_once() {
     let transport = ZenohTransport::new().await.unwrap();
@@ -39,3 +63,15 @@ async fn test_node_spin_once() {
     // Spin once should not fail
     node.spin_once().unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_ok_until_shutdown() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("test_node", transport).await.unwrap();
+
+    assert!(node.ok());
+
+    node.shutdown();
+    assert!(!node.ok());
+    assert!(node.is_shutdown());
+}
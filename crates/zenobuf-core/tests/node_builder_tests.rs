@@ -0,0 +1,171 @@
+//! Tests for building nodes with explicit Zenoh settings or a shared session
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::{SharedSession, ZenohTransport};
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+use zenoh::config::WhatAmI;
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Status {
+    ok: bool,
+}
+
+impl JsonMessage for Status {}
+
+/// Returns a configuration without multicast scouting, so peers only meet
+/// through explicit endpoints
+fn isolated_config() -> zenoh::config::Config {
+    let mut config = zenoh::config::Config::default();
+    config
+        .insert_json5("scouting/multicast/enabled", "false")
+        .unwrap();
+    config
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_builder_with_unreachable_endpoint() {
+    let node = Node::builder("node_builder_unreachable")
+        .mode(WhatAmI::Peer)
+        .connect("tcp/127.0.0.1:9")
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(node.name(), "node_builder_unreachable");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_builder_with_malformed_endpoint_errors() {
+    let result = Node::builder("node_builder_malformed")
+        .connect("not an endpoint")
+        .build()
+        .await;
+    assert!(matches!(
+        result,
+        Err(Error::Configuration { .. } | Error::Transport { .. })
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_nodes_meet_through_explicit_endpoints() {
+    let endpoint = "tcp/127.0.0.1:47447";
+    let listener = Node::builder("node_builder_listener")
+        .config(isolated_config())
+        .listen(endpoint)
+        .build()
+        .await
+        .unwrap();
+    let connector = Node::builder("node_builder_connector")
+        .config(isolated_config())
+        .connect(endpoint)
+        .build()
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = listener
+        .subscriber::<Json<Status>>("node_builder_status")
+        .build(move |Json(status)| received_clone.lock().unwrap().push(status))
+        .await
+        .unwrap();
+    let publisher = connector
+        .publisher::<Json<Status>>("node_builder_status")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&Json(Status { ok: true })).unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    listener.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![Status { ok: true }]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_nodes_on_shared_session_communicate() {
+    let session = SharedSession::get().await.unwrap();
+    let publisher_node = Node::builder("shared_session_publisher")
+        .session(session.clone())
+        .build()
+        .await
+        .unwrap();
+    let subscriber_node = Node::with_transport(
+        "shared_session_subscriber",
+        ZenohTransport::from_session(session.clone()),
+    )
+    .await
+    .unwrap();
+
+    // Both nodes use the one session
+    let again = SharedSession::get().await.unwrap();
+    assert!(Arc::ptr_eq(&session, &again));
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = subscriber_node
+        .subscriber::<Json<Status>>("shared_session_status")
+        .build(move |Json(status)| received_clone.lock().unwrap().push(status))
+        .await
+        .unwrap();
+    let publisher = publisher_node
+        .publisher::<Json<Status>>("shared_session_status")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    publisher.publish(&Json(Status { ok: false })).unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    subscriber_node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![Status { ok: false }]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_require_connectivity_fails_without_peers() {
+    let started = std::time::Instant::now();
+    let result = Node::builder("node_builder_isolated")
+        .config(isolated_config())
+        .require_connectivity(Duration::from_millis(500))
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::Network { .. })));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_require_connectivity_fails_with_bogus_router() {
+    let started = std::time::Instant::now();
+    let result = Node::builder("node_builder_bogus_router")
+        .config(isolated_config())
+        .mode(WhatAmI::Client)
+        .connect("tcp/127.0.0.1:9")
+        .require_connectivity(Duration::from_millis(500))
+        .build()
+        .await;
+    assert!(result.is_err());
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_require_connectivity_succeeds_with_peer() {
+    let endpoint = "tcp/127.0.0.1:47448";
+    let _listener = Node::builder("node_builder_reachable_listener")
+        .config(isolated_config())
+        .listen(endpoint)
+        .build()
+        .await
+        .unwrap();
+    let node = Node::builder("node_builder_reachable")
+        .config(isolated_config())
+        .connect(endpoint)
+        .require_connectivity(Duration::from_secs(5))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(node.name(), "node_builder_reachable");
+}
@@ -0,0 +1,98 @@
+//! Tests for reading and setting the parameters of another node
+
+use std::time::Duration;
+
+use zenobuf_core::{Error, Node};
+
+/// Declares a speed limit and a frame name on a node
+fn declare_parameters(node: &Node) {
+    node.declare_parameter("max_speed", 2.5)
+        .with_range(0.0, 5.0)
+        .build()
+        .unwrap();
+    node.declare_parameter("frame", "base_link".to_string())
+        .build()
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_parameter_client_reads_and_updates_remote_parameters() {
+    let robot = Node::new("param_client_robot").await.unwrap();
+    declare_parameters(&robot);
+    let operator = Node::new("param_client_operator").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let client = operator.parameter_client("param_client_robot");
+    assert_eq!(client.target(), "param_client_robot");
+    assert_eq!(client.get::<f64>("max_speed").await.unwrap(), 2.5);
+    assert_eq!(client.get::<String>("frame").await.unwrap(), "base_link");
+
+    client.set("max_speed", 4.0).await.unwrap();
+    assert_eq!(robot.get_parameter::<f64>("max_speed").unwrap(), 4.0);
+    assert_eq!(client.get::<f64>("max_speed").await.unwrap(), 4.0);
+
+    // Undeclared parameters can be created remotely
+    client.set("mode", "manual").await.unwrap();
+    assert_eq!(robot.get_parameter::<String>("mode").unwrap(), "manual");
+
+    let values = client.list().await.unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values["max_speed"], serde_json::json!(4.0));
+    assert_eq!(values["frame"], serde_json::json!("base_link"));
+    assert_eq!(values["mode"], serde_json::json!("manual"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_parameter_client_reports_remote_errors() {
+    let robot = Node::new("param_error_robot").await.unwrap();
+    declare_parameters(&robot);
+    let operator = Node::new("param_error_operator").await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let client = operator.parameter_client("param_error_robot");
+    let out_of_range = client.set("max_speed", 9.0).await.unwrap_err();
+    assert!(
+        matches!(&out_of_range, Error::Parameter { reason, .. } if reason.contains("outside")),
+        "{out_of_range}"
+    );
+    assert_eq!(robot.get_parameter::<f64>("max_speed").unwrap(), 2.5);
+
+    let missing = client.get::<f64>("min_speed").await.unwrap_err();
+    assert!(missing.is_not_found(), "{missing}");
+
+    let absent = operator
+        .parameter_client("param_error_nobody")
+        .with_timeout(Duration::from_millis(500))
+        .get::<f64>("max_speed")
+        .await
+        .unwrap_err();
+    assert!(matches!(absent, Error::Parameter { .. }), "{absent}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_puts_update_parameters() {
+    let robot = Node::new("param_put_robot").await.unwrap();
+    declare_parameters(&robot);
+    // Sets and deletes as `zenobuf-cli param` sends them
+    let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    session
+        .put("zenobuf/param/param_put_robot/max_speed", "3.5")
+        .await
+        .unwrap();
+    // Out of range, so ignored
+    session
+        .put("zenobuf/param/param_put_robot/max_speed", "7.0")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(robot.get_parameter::<f64>("max_speed").unwrap(), 3.5);
+
+    session
+        .delete("zenobuf/param/param_put_robot/max_speed")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(robot.get_parameter::<f64>("max_speed").unwrap(), 2.5);
+}
@@ -0,0 +1,200 @@
+//! Tests for declaring, describing, deleting and persisting parameters
+
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Node, ParameterDescriptor};
+
+/// Declares a speed limit and a retry count on a node
+fn declare_parameters(node: &Node) {
+    node.declare_parameter("max_speed", 2.5)
+        .with_range(0.0, 5.0)
+        .with_description("Maximum linear speed in m/s")
+        .build()
+        .unwrap();
+    node.declare_parameter("retries", 3)
+        .with_range(1.0, 10.0)
+        .build()
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_declared_parameters_round_trip_through_descriptors() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_descriptor_node", transport)
+        .await
+        .unwrap();
+    declare_parameters(&node);
+
+    let descriptors = node.parameter_descriptors();
+    assert_eq!(
+        descriptors,
+        vec![
+            ParameterDescriptor {
+                name: "max_speed".to_string(),
+                type_name: "number".to_string(),
+                default: serde_json::json!(2.5),
+                min: Some(0.0),
+                max: Some(5.0),
+                description: Some("Maximum linear speed in m/s".to_string()),
+                strict: false,
+            },
+            ParameterDescriptor {
+                name: "retries".to_string(),
+                type_name: "integer".to_string(),
+                default: serde_json::json!(3),
+                min: Some(1.0),
+                max: Some(10.0),
+                description: None,
+                strict: false,
+            },
+        ]
+    );
+
+    // Declaring sets the default value
+    assert_eq!(node.get_parameter::<f64>("max_speed").unwrap(), 2.5);
+    assert_eq!(node.get_parameter::<i32>("retries").unwrap(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_declared_range_is_enforced() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_range_node", transport)
+        .await
+        .unwrap();
+    declare_parameters(&node);
+
+    node.set_parameter("max_speed", 4.0).unwrap();
+    let err = node.set_parameter("max_speed", 7.5).unwrap_err();
+    assert!(matches!(err, Error::Parameter { .. }));
+    assert_eq!(node.get_parameter::<f64>("max_speed").unwrap(), 4.0);
+
+    let err = node
+        .declare_parameter("gain", 20.0)
+        .with_range(0.0, 10.0)
+        .build()
+        .unwrap_err();
+    assert!(matches!(err, Error::Parameter { .. }));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_strict_parameter_rejects_other_types() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_strict_node", transport)
+        .await
+        .unwrap();
+    node.declare_parameter("gain", 1.5)
+        .strict()
+        .build()
+        .unwrap();
+
+    let err = node.set_parameter("gain", "high".to_string()).unwrap_err();
+    assert!(matches!(err, Error::Parameter { .. }));
+    let err = node.set_parameter("gain", true).unwrap_err();
+    assert!(matches!(err, Error::Parameter { .. }));
+    assert_eq!(node.get_parameter::<f64>("gain").unwrap(), 1.5);
+
+    // Integers convert losslessly to the declared floating-point type
+    node.set_parameter("gain", 2).unwrap();
+    assert_eq!(node.get_parameter::<f64>("gain").unwrap(), 2.0);
+    node.set_parameter("gain", 0.25).unwrap();
+    assert_eq!(node.get_parameter::<f64>("gain").unwrap(), 0.25);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_loose_parameter_accepts_other_types() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_loose_node", transport)
+        .await
+        .unwrap();
+    node.declare_parameter("gain", 1.5).build().unwrap();
+    node.set_parameter("gain", "high".to_string()).unwrap();
+    assert_eq!(node.get_parameter::<String>("gain").unwrap(), "high");
+
+    node.set_parameter("mode", 1).unwrap();
+    node.set_parameter("mode", "auto".to_string()).unwrap();
+    assert_eq!(node.get_parameter::<String>("mode").unwrap(), "auto");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_descriptors_are_queryable() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_describe_node", transport)
+        .await
+        .unwrap();
+    declare_parameters(&node);
+
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let replies = session
+        .get("zenobuf/param/param_describe_node/__describe__")
+        .await
+        .unwrap();
+    let reply = replies.recv_async().await.unwrap();
+    let sample = reply.result().unwrap();
+    let descriptors: Vec<ParameterDescriptor> =
+        serde_json::from_slice(&sample.payload().to_bytes()).unwrap();
+
+    assert_eq!(descriptors, node.parameter_descriptors());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_deleted_parameter_is_not_found() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_delete_node", transport)
+        .await
+        .unwrap();
+
+    node.set_parameter("mode", "auto".to_string()).unwrap();
+    node.delete_parameter("mode").unwrap();
+
+    let err = node.get_parameter::<String>("mode").unwrap_err();
+    assert!(matches!(err, Error::Parameter { .. }));
+    assert!(err.to_string().contains("not found"));
+    assert!(err.is_not_found());
+
+    // There is nothing left to delete
+    assert!(node.delete_parameter("mode").is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_deleted_declared_parameter_resets_to_default() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_delete_default_node", transport)
+        .await
+        .unwrap();
+    declare_parameters(&node);
+
+    node.set_parameter("max_speed", 4.0).unwrap();
+    node.delete_parameter("max_speed").unwrap();
+
+    assert_eq!(node.get_parameter::<f64>("max_speed").unwrap(), 2.5);
+    assert_eq!(node.parameter_descriptors().len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_parameters_round_trip_through_file() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("param_file_node", transport)
+        .await
+        .unwrap();
+
+    node.set_parameter("name", "rover".to_string()).unwrap();
+    node.set_parameter("wheels", 6).unwrap();
+    node.set_parameter("gains", vec![0.5, 1.5]).unwrap();
+
+    let path = std::env::temp_dir().join(format!("zenobuf_params_{}.json", std::process::id()));
+    node.save_parameters(&path).unwrap();
+
+    for name in ["name", "wheels", "gains"] {
+        node.delete_parameter(name).unwrap();
+    }
+    assert!(node.get_parameter::<i32>("wheels").is_err());
+
+    node.load_parameters(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(node.get_parameter::<String>("name").unwrap(), "rover");
+    assert_eq!(node.get_parameter::<i32>("wheels").unwrap(), 6);
+    assert_eq!(
+        node.get_parameter::<Vec<f64>>("gains").unwrap(),
+        vec![0.5, 1.5]
+    );
+}
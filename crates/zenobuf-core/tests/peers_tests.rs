@@ -0,0 +1,15 @@
+//! Tests for listing the peers of a transport
+
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::Node;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_reports_own_peer() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("peers_node", transport).await.unwrap();
+
+    let peers = node.peers().await;
+    assert!(!peers.is_empty());
+    assert!(!peers[0].zid.is_empty());
+    assert!(peers.iter().skip(1).all(|peer| peer.zid != peers[0].zid));
+}
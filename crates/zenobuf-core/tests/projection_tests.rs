@@ -0,0 +1,83 @@
+//! Tests for publishing a projection of each message
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use prost::Message as ProstMessage;
+use zenobuf_core::{Message, Node};
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Point {
+    #[prost(double, tag = "1")]
+    x: f64,
+    #[prost(double, tag = "2")]
+    y: f64,
+    #[prost(double, tag = "3")]
+    z: f64,
+}
+
+impl Message for Point {
+    fn type_name() -> &'static str {
+        "test.Point"
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Pose {
+    #[prost(message, optional, tag = "1")]
+    position: Option<Point>,
+    #[prost(double, repeated, tag = "2")]
+    covariance: Vec<f64>,
+    #[prost(string, tag = "3")]
+    frame_id: String,
+}
+
+impl Message for Pose {
+    fn type_name() -> &'static str {
+        "test.Pose"
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_projected_publisher_sends_position() {
+    let node = Node::new("projection_node").await.unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let record = received.clone();
+    let _subscriber = node
+        .subscriber::<Point>("projected_position")
+        .build(move |point| record.lock().unwrap().push(point))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Pose>("projected_position")
+        .project(|pose: &Pose| pose.position.clone().unwrap_or_default())
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let pose = Pose {
+        position: Some(Point {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        }),
+        covariance: vec![0.5; 36],
+        frame_id: "map".to_string(),
+    };
+    publisher.publish(&pose).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Point {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0
+        }]
+    );
+    assert_eq!(publisher.topic(), "projected_position");
+    assert_eq!(publisher.stats().messages_sent, 1);
+    assert!(publisher.stats().bytes_sent < pose.encoded_len() as u64);
+}
@@ -0,0 +1,166 @@
+//! Tests for the buffer publishers reuse to encode messages
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Chunk {
+    writer: u32,
+    seq: u32,
+    data: String,
+}
+
+impl JsonMessage for Chunk {}
+
+/// Size of the large messages published without allocating
+const LARGE_PAYLOAD: usize = 64 * 1024;
+
+/// Counts the allocations of half a large message or more made by threads
+/// while they are counting
+///
+/// Zenoh makes small allocations of its own on every put, so only allocations
+/// the size of a payload tell a copy of it.
+struct CountingAllocator;
+
+static LARGE_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() >= LARGE_PAYLOAD / 2 && COUNTING.with(Cell::get) {
+            LARGE_ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns a configuration opening no links, so nothing but the publisher
+/// handles its payloads
+fn isolated_config() -> zenoh::config::Config {
+    let mut config = zenoh::config::Config::default();
+    for (key, value) in [
+        ("scouting/multicast/enabled", "false"),
+        ("scouting/gossip/enabled", "false"),
+        ("listen/endpoints", "[]"),
+    ] {
+        config.insert_json5(key, value).unwrap();
+    }
+    config
+}
+
+fn chunk(writer: u32, seq: u32) -> Json<Chunk> {
+    Json(Chunk {
+        writer,
+        seq,
+        data: "x".repeat((seq % 50) as usize * 10),
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_buffer_is_reused_across_publishes() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("publish_buffer_node", transport)
+        .await
+        .unwrap();
+
+    let (_subscriber, mut chunks) = node
+        .subscriber::<Json<Chunk>>("buffered_chunks")
+        .build_channel(1000)
+        .await
+        .unwrap();
+    let publisher = Arc::new(
+        node.publisher::<Json<Chunk>>("buffered_chunks")
+            .with_buffer_capacity(256)
+            .build()
+            .await
+            .unwrap(),
+    );
+    assert!(publisher.buffer_capacity() >= 256);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // Four writers share the buffer of one publisher
+    let writers = (0..4)
+        .map(|writer| {
+            let publisher = publisher.clone();
+            tokio::spawn(async move {
+                for seq in 0..100 {
+                    publisher.publish(&chunk(writer, seq)).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for writer in writers {
+        writer.await.unwrap();
+    }
+
+    // The buffer grew to the largest message and no further
+    let capacity = publisher.buffer_capacity();
+    for seq in 0..100 {
+        publisher.publish(&chunk(4, seq)).unwrap();
+    }
+    assert_eq!(publisher.buffer_capacity(), capacity);
+    assert!(capacity < 2048, "buffer grew to {capacity} bytes");
+
+    let mut received = HashSet::new();
+    while received.len() < 500 {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), chunks.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.data.len(), (chunk.seq % 50) as usize * 10);
+        assert!(received.insert((chunk.writer, chunk.seq)));
+    }
+    assert_eq!(publisher.stats().messages_sent, 500);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_publishing_does_not_allocate_the_payload() {
+    let transport = ZenohTransport::with_config(isolated_config())
+        .await
+        .unwrap();
+    let node = Node::with_transport("publish_allocation_node", transport)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Chunk>>("large_chunks")
+        .with_buffer_capacity(LARGE_PAYLOAD + 1024)
+        .build()
+        .await
+        .unwrap();
+    let message = Json(Chunk {
+        writer: 0,
+        seq: 0,
+        data: "x".repeat(LARGE_PAYLOAD),
+    });
+    for _ in 0..10 {
+        publisher.publish(&message).unwrap();
+    }
+
+    // Publishing encodes into a pooled buffer and hands it to Zenoh as is
+    COUNTING.with(|counting| counting.set(true));
+    for _ in 0..100 {
+        publisher.publish(&message).unwrap();
+    }
+    COUNTING.with(|counting| counting.set(false));
+
+    assert_eq!(LARGE_ALLOCATIONS.load(Ordering::SeqCst), 0);
+    assert_eq!(publisher.stats().messages_sent, 110);
+}
@@ -0,0 +1,54 @@
+//! Tests for skipping the publication of unchanged messages
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Mode {
+    name: String,
+}
+
+impl JsonMessage for Mode {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publish_if_changed_skips_identical_message() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("publish_if_changed_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Mode>>("robot_mode")
+        .build(move |mode| sink.lock().unwrap().push(mode.name.clone()))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Mode>>("robot_mode")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let auto = Json(Mode {
+        name: "auto".to_string(),
+    });
+    let manual = Json(Mode {
+        name: "manual".to_string(),
+    });
+    assert!(publisher.publish_if_changed(&auto).unwrap());
+    assert!(!publisher.publish_if_changed(&auto).unwrap());
+    assert!(publisher.publish_if_changed(&manual).unwrap());
+    assert!(publisher.publish_if_changed(&auto).unwrap());
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec!["auto", "manual", "auto"]);
+    assert_eq!(publisher.stats().messages_sent, 3);
+}
@@ -0,0 +1,41 @@
+//! Tests for publisher statistics
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::time::Time;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node, PublisherStats};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    value: u32,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publisher_stats_count_messages_and_bytes() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("publisher_stats_node", transport)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("publisher_stats/readings")
+        .build()
+        .await
+        .unwrap();
+
+    assert_eq!(publisher.stats(), PublisherStats::default());
+
+    let before = Time::now();
+    let mut expected_bytes = 0;
+    for value in [7, 42, 1000] {
+        let message = Json(Reading { value });
+        expected_bytes += serde_json::to_vec(&message.0).unwrap().len() as u64;
+        publisher.publish(&message).unwrap();
+    }
+
+    let stats = publisher.stats();
+    assert_eq!(stats.messages_sent, 3);
+    assert_eq!(stats.bytes_sent, expected_bytes);
+    assert!(stats.last_publish.unwrap() >= before);
+}
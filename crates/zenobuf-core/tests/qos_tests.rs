@@ -1,6 +1,20 @@
 use std::time::Duration;
 
-use zenobuf_core::qos::{Durability, History, QosProfile, Reliability};
+use serde::{Deserialize, Serialize};
+use tracing_test::traced_test;
+use zenobuf_core::qos::{
+    Compatibility, CongestionControl, Durability, History, Priority, QosPreset, QosProfile,
+    Reliability,
+};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    value: f64,
+}
+
+impl JsonMessage for Reading {}
 
 #[test]
 fn test_qos_profile_default() {
@@ -99,3 +113,207 @@ fn test_qos_profile_debug() {
     assert!(debug_str.contains("history"));
     assert!(debug_str.contains("depth"));
 }
+
+#[test]
+fn test_qos_profile_priority_and_congestion_control() {
+    let qos = QosProfile::default();
+    assert_eq!(qos.priority, Priority::Data);
+    assert_eq!(qos.congestion_control, CongestionControl::Block);
+
+    let qos = QosProfile::default()
+        .priority(Priority::RealTime)
+        .congestion_control(CongestionControl::Drop);
+    assert_eq!(qos.priority, Priority::RealTime);
+    assert_eq!(qos.congestion_control, CongestionControl::Drop);
+
+    let low_latency = QosProfile::from(QosPreset::LowLatency);
+    assert_eq!(low_latency.priority, Priority::RealTime);
+    assert_eq!(low_latency.congestion_control, CongestionControl::Drop);
+
+    let high_throughput = QosProfile::from(QosPreset::HighThroughput);
+    assert_eq!(high_throughput.priority, Priority::DataLow);
+    assert_eq!(high_throughput.congestion_control, CongestionControl::Drop);
+
+    assert_eq!(
+        QosProfile::sensor_data().congestion_control,
+        CongestionControl::Drop
+    );
+}
+
+#[test]
+fn test_qos_zenoh_mapping() {
+    let qos = QosProfile::from(QosPreset::LowLatency);
+    assert_eq!(
+        zenoh::qos::Priority::from(qos.priority),
+        zenoh::qos::Priority::RealTime
+    );
+    assert_eq!(
+        zenoh::qos::CongestionControl::from(qos.congestion_control),
+        zenoh::qos::CongestionControl::Drop
+    );
+
+    let qos = QosProfile::default().priority(Priority::Background);
+    assert_eq!(
+        zenoh::qos::Priority::from(qos.priority),
+        zenoh::qos::Priority::Background
+    );
+    assert_eq!(
+        zenoh::qos::CongestionControl::from(qos.congestion_control),
+        zenoh::qos::CongestionControl::Block
+    );
+}
+
+#[test]
+fn test_qos_profile_json_round_trip() {
+    let qos = QosProfile::default()
+        .reliability(Reliability::BestEffort)
+        .durability(Durability::TransientLocal)
+        .depth(3)
+        .deadline(Duration::from_millis(250))
+        .lifespan(Duration::from_millis(1500))
+        .priority(Priority::RealTime)
+        .congestion_control(CongestionControl::Drop);
+
+    let json = serde_json::to_value(&qos).unwrap();
+    assert_eq!(json["reliability"], "best_effort");
+    assert_eq!(json["durability"], "transient_local");
+    assert_eq!(json["deadline"], "250ms");
+    assert_eq!(json["lifespan"], "1.5s");
+
+    let parsed: QosProfile = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, qos);
+}
+
+#[test]
+fn test_qos_profile_from_partial_config() {
+    let qos: QosProfile =
+        serde_json::from_str(r#"{"reliability": "best_effort", "deadline": "2s"}"#).unwrap();
+    assert_eq!(qos.reliability, Reliability::BestEffort);
+    assert_eq!(qos.deadline, Some(Duration::from_secs(2)));
+    assert_eq!(qos.depth, QosProfile::default().depth);
+
+    let preset: QosPreset = serde_json::from_str(r#""sensor_data""#).unwrap();
+    assert_eq!(QosProfile::from(preset), QosProfile::sensor_data());
+
+    assert!(serde_json::from_str::<QosProfile>(r#"{"deadline": "2 fortnights"}"#).is_err());
+}
+
+#[test]
+fn test_qos_profile_display() {
+    assert_eq!(
+        QosProfile::default().to_string(),
+        "reliable/volatile/keep_last(10)"
+    );
+    assert_eq!(
+        QosProfile::services().to_string(),
+        "reliable/volatile/keep_last(10)/deadline(1s)"
+    );
+    assert_eq!(
+        QosProfile::sensor_data()
+            .history(History::KeepAll)
+            .to_string(),
+        "best_effort/volatile/keep_all/drop"
+    );
+}
+
+#[test]
+fn test_reliability_compatibility() {
+    let reliable = QosProfile::default().reliability(Reliability::Reliable);
+    let best_effort = QosProfile::default().reliability(Reliability::BestEffort);
+
+    assert!(reliable.compatible_with(&reliable).is_compatible());
+    assert!(reliable.compatible_with(&best_effort).is_compatible());
+    assert!(best_effort.compatible_with(&best_effort).is_compatible());
+    assert_eq!(
+        best_effort.compatible_with(&reliable),
+        Compatibility::Incompatible {
+            reason: "the subscriber is reliable but the publisher is best effort".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_durability_compatibility() {
+    let transient = QosProfile::default().durability(Durability::TransientLocal);
+    let volatile = QosProfile::default().durability(Durability::Volatile);
+
+    assert!(transient.compatible_with(&transient).is_compatible());
+    assert!(transient.compatible_with(&volatile).is_compatible());
+    assert!(volatile.compatible_with(&volatile).is_compatible());
+    assert!(!volatile.compatible_with(&transient).is_compatible());
+
+    // Both mismatches are reported at once
+    let Compatibility::Incompatible { reason } =
+        QosProfile::sensor_data().compatible_with(&QosProfile::parameters())
+    else {
+        panic!("sensor data publishers cannot serve parameter subscribers");
+    };
+    assert!(reason.contains("reliable"), "{reason}");
+    assert!(reason.contains("transient local"), "{reason}");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn test_subscriber_warns_about_incompatible_publisher() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("qos_mismatch_node", transport)
+        .await
+        .unwrap();
+
+    let _publisher = node
+        .publisher::<Json<Reading>>("qos_mismatch_readings")
+        .best_effort()
+        .build()
+        .await
+        .unwrap();
+    let _subscriber = node
+        .subscriber::<Json<Reading>>("qos_mismatch_readings")
+        .reliable()
+        .build(|_| {})
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(logs_contain(
+        "has a QoS profile incompatible with the publisher of node \"qos_mismatch_node\""
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[traced_test]
+async fn test_subscriber_warns_about_publisher_joining_later() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("qos_late_subscriber_node", transport)
+        .await
+        .unwrap();
+    let _subscriber = node
+        .subscriber::<Json<Reading>>("qos_late_readings")
+        .reliable()
+        .build(|_| {})
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let transport = ZenohTransport::new().await.unwrap();
+    let late = Node::with_transport("qos_late_publisher_node", transport)
+        .await
+        .unwrap();
+    let _publisher = late
+        .publisher::<Json<Reading>>("qos_late_readings")
+        .best_effort()
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    logs_assert(|lines: &[&str]| {
+        let warnings = lines
+            .iter()
+            .filter(|line| line.contains("publisher of node \"qos_late_publisher_node\""))
+            .count();
+        match warnings {
+            1 => Ok(()),
+            n => Err(format!("expected one warning, got {n}")),
+        }
+    });
+}
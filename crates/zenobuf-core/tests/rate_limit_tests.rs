@@ -0,0 +1,97 @@
+//! Tests for publisher rate limiting
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct DebugLine {
+    seq: u32,
+}
+
+impl JsonMessage for DebugLine {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_max_rate_drops_excess_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("rate_limit_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<DebugLine>>("rate_limited_debug")
+        .build(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<DebugLine>>("rate_limited_debug")
+        .max_rate(10.0)
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // 100 messages over about a second, of which about 10 fit in the rate
+    let mut sent = 0;
+    for seq in 0..100 {
+        if publisher.try_publish(&Json(DebugLine { seq })).unwrap() {
+            sent += 1;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    let stats = publisher.stats();
+    assert!((9..=13).contains(&sent), "sent {sent} messages");
+    assert_eq!(stats.messages_sent, sent);
+    assert_eq!(stats.rate_limited, 100 - sent);
+    assert_eq!(received.load(Ordering::Relaxed) as u64, sent);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_max_rate_blocking_spaces_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("rate_blocking_node", transport)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<DebugLine>>("rate_blocking_debug")
+        .max_rate_blocking(20.0)
+        .build()
+        .await
+        .unwrap();
+
+    let started = Instant::now();
+    for seq in 0..5 {
+        publisher.publish(&Json(DebugLine { seq })).unwrap();
+    }
+
+    // The first message goes out right away and the others 50ms apart
+    assert!(started.elapsed() >= Duration::from_millis(200));
+    assert_eq!(publisher.stats().messages_sent, 5);
+    assert_eq!(publisher.stats().rate_limited, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_max_rate_must_be_positive() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("rate_invalid_node", transport)
+        .await
+        .unwrap();
+
+    let result = node
+        .publisher::<Json<DebugLine>>("rate_invalid_debug")
+        .max_rate(0.0)
+        .build()
+        .await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,45 @@
+//! Tests for opaque byte payloads
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use zenobuf_core::message::Message;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Node, RawBytes};
+
+#[test]
+fn test_raw_bytes_wire_format() {
+    let raw = RawBytes::new(vec![0xde, 0xad, 0xbe, 0xef]);
+
+    // Field 1, length-delimited, followed by the payload
+    let bytes = raw.encode_to_bytes().unwrap();
+    assert_eq!(bytes, [0x0a, 4, 0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(RawBytes::decode_from_slice(&bytes).unwrap(), raw);
+    assert_eq!(&*raw, &[0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_raw_bytes_round_trip() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("raw_bytes_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = node
+        .subscriber::<RawBytes>("blob")
+        .build(move |raw| received_clone.lock().unwrap().push(raw.into_inner()))
+        .await
+        .unwrap();
+    let publisher = node.publisher::<RawBytes>("blob").build().await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let payload: Vec<u8> = (0..=255).collect();
+    publisher.publish(&RawBytes::from(payload.clone())).unwrap();
+    publisher.publish(&RawBytes::default()).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![payload, Vec::new()]);
+}
@@ -0,0 +1,127 @@
+//! Tests for encoding and decoding messages from their descriptors
+
+use prost::Message as _;
+use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+use prost_reflect::prost_types::{
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+};
+use serde_json::json;
+use zenobuf_core::reflect::{decode_to_json, encode_from_json, find_message, load_descriptors};
+use zenobuf_core::Error;
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Reading {
+    #[prost(string, tag = "1")]
+    sensor_id: String,
+    #[prost(double, tag = "2")]
+    value: f64,
+    #[prost(int32, repeated, tag = "3")]
+    samples: Vec<i32>,
+    #[prost(bool, tag = "4")]
+    calibrated: bool,
+}
+
+/// Returns the encoded descriptor set of `test.Reading`
+fn descriptor_set() -> Vec<u8> {
+    let field = |name: &str, number, label: Label, r#type: Type| FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        label: Some(label as i32),
+        r#type: Some(r#type as i32),
+        ..Default::default()
+    };
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("test.proto".to_string()),
+            package: Some("test".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Reading".to_string()),
+                field: vec![
+                    field("sensor_id", 1, Label::Optional, Type::String),
+                    field("value", 2, Label::Optional, Type::Double),
+                    field("samples", 3, Label::Repeated, Type::Int32),
+                    field("calibrated", 4, Label::Optional, Type::Bool),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    }
+    .encode_to_vec()
+}
+
+#[test]
+fn test_decode_to_json() {
+    let pool = load_descriptors(&descriptor_set()).unwrap();
+    let descriptor = find_message(&pool, "test.Reading").unwrap();
+
+    let bytes = Reading {
+        sensor_id: "imu".to_string(),
+        value: 1.5,
+        samples: vec![3, 4],
+        calibrated: false,
+    }
+    .encode_to_vec();
+
+    assert_eq!(
+        decode_to_json(&bytes, &descriptor).unwrap(),
+        json!({
+            "sensor_id": "imu",
+            "value": 1.5,
+            "samples": [3, 4],
+            "calibrated": false,
+        })
+    );
+}
+
+#[test]
+fn test_encode_from_json() {
+    let pool = load_descriptors(&descriptor_set()).unwrap();
+    let descriptor = find_message(&pool, "test.Reading").unwrap();
+
+    let bytes = encode_from_json(
+        &json!({ "sensor_id": "lidar", "samples": [7], "calibrated": true }),
+        &descriptor,
+    )
+    .unwrap();
+
+    assert_eq!(
+        Reading::decode(bytes.as_slice()).unwrap(),
+        Reading {
+            sensor_id: "lidar".to_string(),
+            value: 0.0,
+            samples: vec![7],
+            calibrated: true,
+        }
+    );
+
+    assert!(matches!(
+        encode_from_json(&json!({ "value": "not a number" }), &descriptor),
+        Err(Error::InvalidMessage { .. })
+    ));
+    assert!(matches!(
+        encode_from_json(&json!({ "unknown": 1 }), &descriptor),
+        Err(Error::InvalidMessage { .. })
+    ));
+}
+
+#[test]
+fn test_decode_errors() {
+    assert!(matches!(
+        load_descriptors(b"not a descriptor set"),
+        Err(Error::Configuration { .. })
+    ));
+
+    let pool = load_descriptors(&descriptor_set()).unwrap();
+    assert!(matches!(
+        find_message(&pool, "test.Missing"),
+        Err(Error::Configuration { .. })
+    ));
+
+    let descriptor = find_message(&pool, ".test.Reading").unwrap();
+    assert!(matches!(
+        decode_to_json(&[0x0a, 0xff], &descriptor),
+        Err(Error::Decoding(_))
+    ));
+}
@@ -0,0 +1,79 @@
+//! Tests for publishing with acknowledgments from subscribers
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Command {
+    action: String,
+}
+
+impl JsonMessage for Command {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_publish_reliable_resolves_on_ack() {
+    let subscriber_node =
+        Node::with_transport("acking_subscriber", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+    let publisher_node =
+        Node::with_transport("reliable_publisher", ZenohTransport::new().await.unwrap())
+            .await
+            .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber = subscriber_node
+        .subscriber::<Json<Command>>("critical_commands")
+        .build_acking(move |command| sink.lock().unwrap().push(command.action.clone()))
+        .await
+        .unwrap();
+    let publisher = publisher_node
+        .publisher::<Json<Command>>("critical_commands")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The subscriber acknowledges once its callback has run, when its node spins
+    let spinner = tokio::spawn(async move {
+        for _ in 0..20 {
+            subscriber_node.spin_once().unwrap();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    });
+    let command = Json(Command {
+        action: "stop".to_string(),
+    });
+    publisher
+        .publish_reliable(&command, Duration::from_secs(2))
+        .await
+        .unwrap();
+    assert_eq!(*received.lock().unwrap(), vec!["stop".to_string()]);
+    assert_eq!(publisher.stats().messages_sent, 1);
+    spinner.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publish_reliable_times_out_without_subscriber() {
+    let node = Node::with_transport("lonely_publisher", ZenohTransport::new().await.unwrap())
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Command>>("unheard_commands")
+        .build()
+        .await
+        .unwrap();
+
+    let command = Json(Command {
+        action: "stop".to_string(),
+    });
+    let result = publisher
+        .publish_reliable(&command, Duration::from_millis(300))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+}
@@ -0,0 +1,105 @@
+//! Tests for the request context passed to service handlers
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::time::Time;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node, RequestContext, Uuid};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Ping {
+    count: u32,
+}
+
+impl JsonMessage for Ping {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_handler_receives_request_context() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("context_node", transport)
+        .await
+        .unwrap();
+
+    let contexts = Arc::new(Mutex::new(Vec::<RequestContext>::new()));
+    let received = contexts.clone();
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("context_ping")
+        .build_with_context(move |request, context| {
+            received.lock().unwrap().push(context);
+            Ok(request)
+        })
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Ping>, Json<Ping>>("context_ping")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let trace_id = Uuid::new_v4();
+    client
+        .call_with_trace_id(&Json(Ping::default()), trace_id)
+        .await
+        .unwrap();
+    client
+        .call_with_timeout(&Json(Ping::default()), Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let contexts = contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 2);
+    assert_eq!(contexts[0].trace_id, trace_id);
+    assert_eq!(contexts[0].caller, "context_node");
+    assert_eq!(contexts[0].deadline, None);
+
+    // Calls without a trace ID get a fresh one, and timeouts become deadlines
+    assert_ne!(contexts[1].trace_id, trace_id);
+    assert!(!contexts[1].trace_id.is_nil());
+    let deadline = contexts[1].deadline.unwrap();
+    assert!(deadline > Time::now());
+    assert!(deadline <= Time::now().add(Duration::from_secs(5)));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_expired_request_skips_handler() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("expired_node", transport)
+        .await
+        .unwrap();
+
+    let calls = Arc::new(Mutex::new(0));
+    let counter = calls.clone();
+    let _service = node
+        .service::<Json<Ping>, Json<Ping>>("expired_ping")
+        .build(move |request| {
+            *counter.lock().unwrap() += 1;
+            Ok(request)
+        })
+        .await
+        .unwrap();
+
+    // A request header holding only a deadline entry: tag 6, 8 bytes of
+    // nanoseconds since the Unix epoch
+    let deadline = Time::now() - Duration::from_secs(1);
+    let nanos = deadline.to_duration().as_nanos() as u64;
+    let mut header = vec![6, 8];
+    header.extend_from_slice(&nanos.to_le_bytes());
+
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let key = format!("{}expired_ping", ZenohTransport::SERVICE_PREFIX);
+    let replies = session
+        .get(key)
+        .payload(serde_json::to_vec(&Ping { count: 1 }).unwrap())
+        .encoding(zenoh::bytes::Encoding::APPLICATION_JSON)
+        .attachment(header)
+        .await
+        .unwrap();
+    let reply = replies.recv_async().await.unwrap();
+    let error = reply.result().unwrap_err().payload().to_bytes().to_vec();
+
+    assert!(String::from_utf8(error).unwrap().contains("timed out"));
+    assert_eq!(*calls.lock().unwrap(), 0);
+}
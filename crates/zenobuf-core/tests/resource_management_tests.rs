@@ -4,7 +4,7 @@ use prost::Message as ProstMessage;
 use std::sync::{Arc, Mutex};
 use zenobuf_core::message::Message;
 use zenobuf_core::transport::ZenohTransport;
-use zenobuf_core::{DropGuard, Node};
+use zenobuf_core::{DropGuard, Node, ResourceCounts};
 
 // Test message type
 #[derive(Clone, PartialEq, Debug, Default)]
@@ -306,6 +306,249 @@ async fn test_client_handle_automatic_cleanup() {
     // The test passes if no panics occur during cleanup
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_subscriber_close_frees_topic() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("close_subscriber_node", transport)
+        .await
+        .unwrap();
+
+    let first = Arc::new(Mutex::new(Vec::new()));
+    let first_clone = first.clone();
+    let subscriber_handle = node
+        .subscriber::<TestMessage>("close_topic")
+        .build(move |msg: TestMessage| first_clone.lock().unwrap().push(msg.value))
+        .await
+        .unwrap();
+
+    // A second subscriber on the same topic is rejected while the first is open
+    assert!(node
+        .subscriber::<TestMessage>("close_topic")
+        .build(|_: TestMessage| {})
+        .await
+        .is_err());
+
+    subscriber_handle.close().unwrap();
+    assert!(node.subscribers().is_empty());
+
+    let second = Arc::new(Mutex::new(Vec::new()));
+    let second_clone = second.clone();
+    let _subscriber_handle = node
+        .subscriber::<TestMessage>("close_topic")
+        .build(move |msg: TestMessage| second_clone.lock().unwrap().push(msg.value))
+        .await
+        .unwrap();
+
+    let publisher_handle = node
+        .publisher::<TestMessage>("close_topic")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    publisher_handle
+        .publish(&TestMessage {
+            value: 7,
+            text: String::new(),
+        })
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert!(first.lock().unwrap().is_empty());
+    assert_eq!(*second.lock().unwrap(), vec![7]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_closed_subscriber_stops_receiving() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("close_receiving_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let subscriber_handle = node
+        .subscriber::<TestMessage>("close_receiving_topic")
+        .build(move |msg: TestMessage| received_clone.lock().unwrap().push(msg.value))
+        .await
+        .unwrap();
+    // A reference kept elsewhere must not keep a closed subscriber receiving
+    let subscriber = subscriber_handle.subscriber().clone();
+    let publisher_handle = node
+        .publisher::<TestMessage>("close_receiving_topic")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    assert_eq!(subscriber.publisher_count(), 1);
+
+    // Queued before closing, but not delivered by a spin until after
+    publisher_handle
+        .publish(&TestMessage {
+            value: 1,
+            text: String::new(),
+        })
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    subscriber_handle.close().unwrap();
+    assert_eq!(subscriber.publisher_count(), 0);
+
+    publisher_handle
+        .publish(&TestMessage {
+            value: 2,
+            text: String::new(),
+        })
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert!(received.lock().unwrap().is_empty());
+    drop(subscriber);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publisher_and_service_close() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("close_publisher_node", transport)
+        .await
+        .unwrap();
+
+    let publisher_handle = node
+        .publisher::<TestMessage>("close_publisher_topic")
+        .build()
+        .await
+        .unwrap();
+    publisher_handle.close();
+    node.publisher::<TestMessage>("close_publisher_topic")
+        .build()
+        .await
+        .unwrap();
+
+    let service_handle = node
+        .service::<AddRequest, AddResponse>("close_service")
+        .build(|req: AddRequest| Ok(AddResponse { sum: req.a + req.b }))
+        .await
+        .unwrap();
+    service_handle.close().unwrap();
+    assert!(node.services().is_empty());
+    node.service::<AddRequest, AddResponse>("close_service")
+        .build(|req: AddRequest| Ok(AddResponse { sum: req.a * req.b }))
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_closed_service_stops_answering() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("close_answering_node", transport)
+        .await
+        .unwrap();
+
+    let service_handle = node
+        .service::<AddRequest, AddResponse>("close_answering_service")
+        .build(|req: AddRequest| Ok(AddResponse { sum: req.a + req.b }))
+        .await
+        .unwrap();
+    // A reference kept elsewhere must not keep a closed service answering
+    let service = service_handle.service().clone();
+    let client = node
+        .client::<AddRequest, AddResponse>("close_answering_service")
+        .build()
+        .unwrap();
+    let request = AddRequest { a: 2, b: 3 };
+    let response = client
+        .call_with_timeout(&request, std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(response.sum, 5);
+
+    service_handle.close().unwrap();
+    let err = client
+        .call_with_timeout(&request, std::time::Duration::from_millis(500))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, zenobuf_core::Error::ServiceCallTimeout { .. }),
+        "{err}"
+    );
+
+    let _service_handle = node
+        .service::<AddRequest, AddResponse>("close_answering_service")
+        .build(|req: AddRequest| Ok(AddResponse { sum: req.a * req.b }))
+        .await
+        .unwrap();
+    let response = client
+        .call_with_timeout(&request, std::time::Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(response.sum, 6);
+    drop(service);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_dropped_handles_leave_no_resources() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("resource_counts_node", transport)
+        .await
+        .unwrap();
+    assert_eq!(node.resource_counts(), ResourceCounts::default());
+
+    let publisher_handle = node
+        .publisher::<TestMessage>("counted_topic")
+        .build()
+        .await
+        .unwrap();
+    let subscriber_handle = node
+        .subscriber::<TestMessage>("counted_topic")
+        .build(|_: TestMessage| {})
+        .await
+        .unwrap();
+    let service_handle = node
+        .service::<AddRequest, AddResponse>("counted_service")
+        .build(|req: AddRequest| Ok(AddResponse { sum: req.a + req.b }))
+        .await
+        .unwrap();
+    let client_handle = node
+        .client::<AddRequest, AddResponse>("counted_service")
+        .build()
+        .unwrap();
+    assert_eq!(
+        node.resource_counts(),
+        ResourceCounts {
+            publishers: 1,
+            subscribers: 1,
+            services: 1,
+            clients: 1,
+        }
+    );
+
+    drop(publisher_handle);
+    drop(subscriber_handle);
+    drop(service_handle);
+    drop(client_handle);
+    assert_eq!(node.resource_counts().total(), 0);
+    node.assert_no_orphans();
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[should_panic(expected = "orphaned resources")]
+async fn test_assert_no_orphans_catches_live_handle() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("orphan_node", transport)
+        .await
+        .unwrap();
+    let _publisher_handle = node
+        .publisher::<TestMessage>("orphan_topic")
+        .build()
+        .await
+        .unwrap();
+
+    node.assert_no_orphans();
+}
+
 #[test]
 fn test_drop_guard() {
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -0,0 +1,80 @@
+//! Tests for spinning a node for a bounded time
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Tick {
+    seq: u32,
+}
+
+impl JsonMessage for Tick {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_spin_once_timeout_processes_queued_callbacks() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("spin_budget_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let record = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Tick>>("budget_ticks")
+        .build(move |Json(tick)| record.lock().unwrap().push(tick.seq))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Tick>>("budget_ticks")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..5 {
+        publisher.publish(&Json(Tick { seq })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let start = Instant::now();
+    let processed = node.spin_once_timeout(Duration::from_secs(1)).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(processed, 5);
+    assert_eq!(*received.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(node.spin_once_timeout(Duration::from_secs(1)).unwrap(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_spin_once_timeout_stops_at_budget() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("spin_budget_slow_node", transport)
+        .await
+        .unwrap();
+
+    let _subscriber = node
+        .subscriber::<Json<Tick>>("budget_slow_ticks")
+        .build(|_| std::thread::sleep(Duration::from_millis(50)))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Tick>>("budget_slow_ticks")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..10 {
+        publisher.publish(&Json(Tick { seq })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // Each callback takes 50ms, so a 120ms budget leaves most of them queued
+    let first = node.spin_once_timeout(Duration::from_millis(120)).unwrap();
+    assert!((1..=3).contains(&first), "processed {first}");
+    let rest = node.spin_once_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(first + rest, 10);
+}
@@ -0,0 +1,70 @@
+//! Tests for subscriber deadline monitoring
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node, QosProfile};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Heartbeat {
+    seq: u32,
+}
+
+impl JsonMessage for Heartbeat {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_deadline_missed_without_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("deadline_missed_node", transport)
+        .await
+        .unwrap();
+
+    let missed = Arc::new(AtomicUsize::new(0));
+    let callback_missed = missed.clone();
+    let subscriber = node
+        .subscriber::<Json<Heartbeat>>("deadline_missed")
+        .with_qos(QosProfile::default().deadline(Duration::from_millis(100)))
+        .on_deadline_missed(move || {
+            callback_missed.fetch_add(1, Ordering::SeqCst);
+        })
+        .build(|_| {})
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(150)).await;
+
+    assert_eq!(missed.load(Ordering::SeqCst), 1);
+    assert_eq!(subscriber.stats().deadlines_missed, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_deadline_met_by_regular_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("deadline_met_node", transport)
+        .await
+        .unwrap();
+
+    let subscriber = node
+        .subscriber::<Json<Heartbeat>>("deadline_met")
+        .with_qos(QosProfile::default().deadline(Duration::from_millis(300)))
+        .build(|_| {})
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Heartbeat>>("deadline_met")
+        .build()
+        .await
+        .unwrap();
+
+    for seq in 0..8 {
+        publisher.publish(&Json(Heartbeat { seq })).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let stats = subscriber.stats();
+    assert!(stats.messages_received > 0);
+    assert_eq!(stats.deadlines_missed, 0);
+}
@@ -0,0 +1,54 @@
+//! Tests for content-based subscriber filters
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Point {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl JsonMessage for Point {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_filter_drops_rejected_messages() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("filter_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let callback_received = received.clone();
+    let subscriber = node
+        .subscriber::<Json<Point>>("filter/points")
+        .filter(|point| point.z > 0.0)
+        .build(move |Json(point)| callback_received.lock().unwrap().push(point.z))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Point>>("filter/points")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    for z in [1.0, -2.0, 3.0, -4.0] {
+        publisher
+            .publish(&Json(Point {
+                z,
+                ..Default::default()
+            }))
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![1.0, 3.0]);
+    assert_eq!(subscriber.stats().messages_received, 4);
+}
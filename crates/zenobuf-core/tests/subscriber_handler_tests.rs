@@ -0,0 +1,111 @@
+//! Tests for the buffering of samples by bounded subscriber handlers
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::qos::History;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node, QosProfile, SubscriberHandler};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    seq: u32,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_ring_handler_keeps_latest_for_slow_consumer() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("ring_handler_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Reading>>("ring_readings")
+        .with_depth(1)
+        .with_handler(SubscriberHandler::Ring)
+        .build(move |reading| sink.lock().unwrap().push(reading.seq))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("ring_readings")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The node does not spin while the readings arrive, so the first one waits
+    // for its callback and the ring keeps only the newest of the others
+    publisher.publish(&Json(Reading { seq: 0 })).unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for seq in 1..10 {
+        publisher.publish(&Json(Reading { seq })).unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![0, 9]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_keep_all_history_drops_nothing_for_slow_consumer() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("keep_all_handler_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    // The depth of 1 is ignored in favor of an unbounded queue
+    let _subscriber = node
+        .subscriber::<Json<Reading>>("keep_all_readings")
+        .with_qos(QosProfile::default().history(History::KeepAll).depth(1))
+        .with_handler(SubscriberHandler::Ring)
+        .build(move |reading| {
+            std::thread::sleep(Duration::from_millis(5));
+            sink.lock().unwrap().push(reading.seq);
+        })
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Reading>>("keep_all_readings")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for seq in 0..50 {
+        publisher.publish(&Json(Reading { seq })).unwrap();
+    }
+    for _ in 0..200 {
+        if received.lock().unwrap().len() == 50 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        node.spin_once().unwrap();
+    }
+
+    assert_eq!(*received.lock().unwrap(), (0..50).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_bounded_handler_requires_depth() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("fifo_handler_node", transport)
+        .await
+        .unwrap();
+
+    let result = node
+        .subscriber::<Json<Reading>>("fifo_readings")
+        .with_depth(0)
+        .with_handler(SubscriberHandler::Fifo)
+        .build(|_| {})
+        .await;
+    assert!(matches!(result, Err(Error::Configuration { .. })));
+}
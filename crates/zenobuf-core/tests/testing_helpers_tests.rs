@@ -0,0 +1,102 @@
+//! Tests for the message assertions of the `testing` feature
+
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Serialize};
+use zenobuf_core::testing::{assert_encoded_len, assert_roundtrip};
+use zenobuf_core::{Json, JsonMessage, Message};
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Pose {
+    #[prost(double, tag = "1")]
+    x: f64,
+    #[prost(double, tag = "2")]
+    y: f64,
+    #[prost(string, tag = "3")]
+    frame: String,
+}
+
+impl Message for Pose {
+    fn type_name() -> &'static str {
+        "test.Pose"
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Label {
+    text: String,
+}
+
+impl JsonMessage for Label {}
+
+/// Hand-written message whose decoding forgets its second field
+#[derive(Clone, PartialEq, Debug, Default)]
+struct BrokenPoint {
+    x: u32,
+    y: u32,
+}
+
+impl ProstMessage for BrokenPoint {
+    fn encode_raw(&self, buf: &mut impl prost::bytes::BufMut) {
+        prost::encoding::uint32::encode(1, &self.x, buf);
+        prost::encoding::uint32::encode(2, &self.y, buf);
+    }
+
+    fn merge_field(
+        &mut self,
+        tag: u32,
+        wire_type: prost::encoding::WireType,
+        buf: &mut impl prost::bytes::Buf,
+        ctx: prost::encoding::DecodeContext,
+    ) -> Result<(), prost::DecodeError> {
+        match tag {
+            1 => prost::encoding::uint32::merge(wire_type, &mut self.x, buf, ctx),
+            _ => prost::encoding::skip_field(wire_type, tag, buf, ctx),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        // Only counts the first field
+        prost::encoding::uint32::encoded_len(1, &self.x)
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Message for BrokenPoint {
+    fn type_name() -> &'static str {
+        "test.BrokenPoint"
+    }
+}
+
+#[test]
+fn test_correct_messages_pass() {
+    let pose = Pose {
+        x: 1.5,
+        y: -2.0,
+        frame: "map".to_string(),
+    };
+    assert_roundtrip(&pose);
+    assert_encoded_len(&pose);
+
+    let label = Json(Label {
+        text: "dock".to_string(),
+    });
+    assert_roundtrip(&label);
+    assert_encoded_len(&label);
+}
+
+#[test]
+#[should_panic(expected = "test.BrokenPoint did not round-trip")]
+fn test_roundtrip_catches_forgotten_field() {
+    assert_roundtrip(&BrokenPoint { x: 3, y: 4 });
+}
+
+#[test]
+#[should_panic(
+    expected = "test.BrokenPoint::encoded_len() returned 2 but the message encodes to 4 bytes"
+)]
+fn test_encoded_len_catches_wrong_length() {
+    assert_encoded_len(&BrokenPoint { x: 3, y: 4 });
+}
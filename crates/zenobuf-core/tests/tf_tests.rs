@@ -0,0 +1,106 @@
+//! Tests for transform broadcasting, lookup and interpolation
+
+use std::f64::consts::FRAC_PI_2;
+use std::time::Duration;
+
+use zenobuf_core::tf::{
+    Quaternion, StampedTransform, Transform, TransformBroadcaster, TransformBuffer, Vector3,
+};
+use zenobuf_core::time::Time;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Node};
+
+const TOLERANCE: f64 = 1e-9;
+
+fn assert_vector_eq(actual: Vector3, expected: Vector3) {
+    assert!(
+        (actual.x - expected.x).abs() < TOLERANCE
+            && (actual.y - expected.y).abs() < TOLERANCE
+            && (actual.z - expected.z).abs() < TOLERANCE,
+        "{actual:?} != {expected:?}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_two_hop_lookup() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("tf_two_hop_node", transport)
+        .await
+        .unwrap();
+
+    let buffer = TransformBuffer::subscribe(&node).await.unwrap();
+    let broadcaster = TransformBroadcaster::new(&node).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The robot is 2m along x in the map, turned a quarter turn to the left,
+    // and its laser is mounted 1m in front of it
+    let stamp = Time::new(100, 0);
+    broadcaster
+        .send(&StampedTransform::new(
+            "map",
+            "base",
+            Transform::new(Vector3::new(2.0, 0.0, 0.0), Quaternion::from_yaw(FRAC_PI_2)),
+            stamp,
+        ))
+        .unwrap();
+    broadcaster
+        .send(&StampedTransform::new(
+            "base",
+            "laser",
+            Transform::new(Vector3::new(1.0, 0.0, 0.0), Quaternion::identity()),
+            stamp,
+        ))
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    let map_to_laser = buffer.lookup("map", "laser", stamp).unwrap();
+    assert_vector_eq(map_to_laser.translation, Vector3::new(2.0, 1.0, 0.0));
+    assert_vector_eq(
+        map_to_laser.apply(Vector3::new(1.0, 0.0, 0.0)),
+        Vector3::new(2.0, 2.0, 0.0),
+    );
+
+    // Looking up in the opposite direction gives the inverse
+    let laser_to_map = buffer.lookup("laser", "map", stamp).unwrap();
+    assert_vector_eq(
+        laser_to_map.apply(Vector3::new(2.0, 1.0, 0.0)),
+        Vector3::default(),
+    );
+
+    let err = buffer.lookup("map", "camera", stamp).unwrap_err();
+    assert!(matches!(err, Error::Transform { .. }));
+}
+
+#[test]
+fn test_lookup_interpolates_between_samples() {
+    let buffer = TransformBuffer::new();
+    buffer.insert(&StampedTransform::new(
+        "odom",
+        "base",
+        Transform::new(Vector3::new(0.0, 0.0, 0.0), Quaternion::identity()),
+        Time::new(10, 0),
+    ));
+    buffer.insert(&StampedTransform::new(
+        "odom",
+        "base",
+        Transform::new(Vector3::new(4.0, 2.0, 0.0), Quaternion::from_yaw(FRAC_PI_2)),
+        Time::new(12, 0),
+    ));
+
+    let halfway = buffer.lookup("odom", "base", Time::new(11, 0)).unwrap();
+    assert_vector_eq(halfway.translation, Vector3::new(2.0, 1.0, 0.0));
+    let expected = Quaternion::from_yaw(FRAC_PI_2 / 2.0);
+    assert!((halfway.rotation.z - expected.z).abs() < TOLERANCE);
+    assert!((halfway.rotation.w - expected.w).abs() < TOLERANCE);
+
+    let quarter = buffer
+        .lookup("odom", "base", Time::new(10, 500_000_000))
+        .unwrap();
+    assert_vector_eq(quarter.translation, Vector3::new(1.0, 0.5, 0.0));
+
+    // Samples are not extrapolated
+    let err = buffer.lookup("odom", "base", Time::new(13, 0)).unwrap_err();
+    assert!(matches!(err, Error::Transform { .. }));
+}
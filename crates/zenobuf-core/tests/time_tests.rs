@@ -0,0 +1,61 @@
+//! Tests for time arithmetic
+
+use std::time::Duration;
+
+use zenobuf_core::time::{Time, ZenobufDuration};
+
+#[test]
+fn test_time_difference() {
+    let t1 = Time::new(10, 750_000_000);
+    let t2 = Time::new(12, 250_000_000);
+
+    assert_eq!(t2 - t1, ZenobufDuration::new(1, 500_000_000));
+    assert_eq!(t2.elapsed_since(t1), Duration::from_millis(1500));
+
+    let negative = t1 - t2;
+    assert!(negative.is_negative());
+    assert_eq!(negative, -ZenobufDuration::new(1, 500_000_000));
+    assert_eq!(t2 + negative, t1);
+}
+
+#[test]
+fn test_elapsed_since_saturates() {
+    let t1 = Time::new(20, 0);
+    let t2 = Time::new(10, 0);
+
+    assert_eq!(t2.elapsed_since(t1), Duration::ZERO);
+    assert_eq!(t2 - Duration::from_secs(30), Time::new(0, 0));
+    assert_eq!(t2 + ZenobufDuration::new(-30, 0), Time::new(0, 0));
+}
+
+#[test]
+fn test_time_duration_operators() {
+    let mut time = Time::new(5, 900_000_000);
+    assert_eq!(time + Duration::from_millis(200), Time::new(6, 100_000_000));
+    assert_eq!(time - Duration::from_millis(950), Time::new(4, 950_000_000));
+
+    time += Duration::from_secs(1);
+    assert_eq!(time, Time::new(6, 900_000_000));
+    time -= Duration::from_millis(900);
+    assert_eq!(time, Time::new(6, 0));
+    assert_eq!(
+        time + ZenobufDuration::new(0, -500_000_000),
+        Time::new(5, 500_000_000)
+    );
+}
+
+#[test]
+fn test_duration_operators() {
+    let a = ZenobufDuration::new(1, 700_000_000);
+    let b = ZenobufDuration::new(0, 400_000_000);
+
+    assert_eq!(a + b, ZenobufDuration::new(2, 100_000_000));
+    assert_eq!(b - a, ZenobufDuration::new(-2, 700_000_000));
+    assert_eq!(-(b - a), ZenobufDuration::new(1, 300_000_000));
+
+    let mut c = a;
+    c -= a;
+    assert_eq!(c, ZenobufDuration::new(0, 0));
+    c += b;
+    assert_eq!(c, b);
+}
@@ -0,0 +1,71 @@
+//! Tests for periodic node timers
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::Node;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_timer_fires_periodically() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timer_node", transport).await.unwrap();
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let count_clone = count.clone();
+
+    let timer = node
+        .create_timer(Duration::from_millis(50), move || {
+            count_clone.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+    assert_eq!(timer.period(), Duration::from_millis(50));
+
+    tokio::time::sleep(Duration::from_millis(220)).await;
+    let fired = count.load(Ordering::SeqCst);
+    assert!(
+        (3..=5).contains(&fired),
+        "expected ~4 ticks in 220ms, got {fired}"
+    );
+
+    // Dropping the handle stops the timer
+    drop(timer);
+    let after_drop = count.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(count.load(Ordering::SeqCst), after_drop);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_timer_cancel() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timer_cancel_node", transport)
+        .await
+        .unwrap();
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let count_clone = count.clone();
+
+    let timer = node
+        .create_timer(Duration::from_millis(20), move || {
+            count_clone.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+
+    assert!(!timer.is_cancelled());
+    timer.cancel();
+    assert!(timer.is_cancelled());
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(count.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_timer_zero_period_rejected() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("timer_zero_node", transport)
+        .await
+        .unwrap();
+
+    assert!(node.create_timer(Duration::ZERO, || {}).is_err());
+}
@@ -0,0 +1,79 @@
+//! Tests for restricting the topics a node may publish or subscribe to
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    value: f64,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_allowed_and_denied_topics() {
+    let node = Node::builder("topic_policy_node")
+        .allow_topics(["sensors/**"])
+        .deny_topics(["admin/**"])
+        .build()
+        .await
+        .unwrap();
+
+    node.publisher::<Json<Reading>>("sensors/imu")
+        .build()
+        .await
+        .unwrap();
+    node.subscriber::<Json<Reading>>("sensors/lidar/range")
+        .build(|_| {})
+        .await
+        .unwrap();
+    node.subscriber_wildcard::<Json<Reading>>("sensors/*/temperature")
+        .build(|_, _| {})
+        .await
+        .unwrap();
+
+    let denied = node
+        .publisher::<Json<Reading>>("admin/reboot")
+        .build()
+        .await;
+    assert!(matches!(denied, Err(Error::Configuration { .. })));
+    let denied = node
+        .subscriber::<Json<Reading>>("admin/audit")
+        .build(|_| {})
+        .await;
+    assert!(matches!(denied, Err(Error::Configuration { .. })));
+    let not_allowed = node.publisher::<Json<Reading>>("cmd_vel").build().await;
+    assert!(matches!(not_allowed, Err(Error::Configuration { .. })));
+    // The pattern also matches topics outside `sensors/**`
+    let too_broad = node
+        .subscriber_wildcard::<Json<Reading>>("**")
+        .build(|_, _| {})
+        .await;
+    assert!(matches!(too_broad, Err(Error::Configuration { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_denied_topics_take_precedence() {
+    let node = Node::builder("topic_deny_node")
+        .allow_topics(["**"])
+        .deny_topics(["admin/**"])
+        .build()
+        .await
+        .unwrap();
+
+    node.publisher::<Json<Reading>>("status")
+        .build()
+        .await
+        .unwrap();
+    let denied = node.publisher::<Json<Reading>>("admin/keys").build().await;
+    assert!(matches!(denied, Err(Error::Configuration { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_invalid_topic_pattern_fails_to_build() {
+    let result = Node::builder("topic_pattern_node")
+        .allow_topics(["sensors//imu"])
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::Configuration { .. })));
+}
@@ -0,0 +1,45 @@
+//! Tests for the tracing spans around node operations
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing_test::traced_test;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Echo {
+    text: String,
+}
+
+impl JsonMessage for Echo {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[traced_test]
+async fn test_service_call_runs_in_span() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("tracing_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Echo>, Json<Echo>>("tracing_echo")
+        .build(Ok)
+        .await
+        .unwrap();
+    let client = node
+        .client::<Json<Echo>, Json<Echo>>("tracing_echo")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let request = Json(Echo {
+        text: "hello".to_string(),
+    });
+    let response = client.call_async(&request).await.unwrap();
+    assert_eq!(response, request);
+
+    assert!(logs_contain(
+        "call{node=\"tracing_node\" service=\"tracing_echo\" seq=0}"
+    ));
+}
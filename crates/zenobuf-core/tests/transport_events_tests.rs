@@ -0,0 +1,70 @@
+//! Tests for transport connectivity events and reconnection
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+use zenobuf_core::transport::{MockTransport, Transport, ZenohTransport};
+use zenobuf_core::{Node, RetryPolicy, TransportEvent};
+
+#[tokio::test]
+async fn test_mock_transport_reports_disconnect_then_reconnect() {
+    let transport = MockTransport::new();
+    let events = transport.transport_events();
+
+    transport.emit_event(TransportEvent::Disconnected);
+    transport.emit_event(TransportEvent::Connected);
+
+    let received = tokio::time::timeout(Duration::from_secs(1), events.take(2).collect::<Vec<_>>())
+        .await
+        .unwrap();
+    assert_eq!(
+        received,
+        vec![TransportEvent::Disconnected, TransportEvent::Connected]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_node_with_reconnecting_transport() {
+    let policy = RetryPolicy::new()
+        .initial_delay(Duration::from_millis(100))
+        .max_delay(Duration::from_secs(1));
+    let transport = ZenohTransport::with_reconnect(policy).await.unwrap();
+    let node = Node::with_transport("reconnect_node", transport)
+        .await
+        .unwrap();
+
+    // No links change while the node is idle
+    let mut events = node.transport_events();
+    let event = tokio::time::timeout(Duration::from_millis(200), events.next()).await;
+    assert!(event.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_transport_from_session_outside_runtime() {
+    let session = Arc::new(zenoh::open(zenoh::config::Config::default()).await.unwrap());
+
+    // Wrapping the session does not need a runtime
+    let transport = std::thread::spawn(move || ZenohTransport::from_session(session))
+        .join()
+        .unwrap();
+
+    // and its links are watched once events are requested from one
+    let mut events = transport.transport_events();
+    let event = tokio::time::timeout(Duration::from_millis(200), events.next()).await;
+    assert!(event.is_err());
+}
+
+#[test]
+fn test_retry_policy_delays() {
+    let policy = RetryPolicy::new()
+        .initial_delay(Duration::from_millis(100))
+        .max_delay(Duration::from_millis(500))
+        .multiplier(2.0);
+
+    assert_eq!(policy.delay(0), Duration::from_millis(100));
+    assert_eq!(policy.delay(1), Duration::from_millis(200));
+    assert_eq!(policy.delay(2), Duration::from_millis(400));
+    assert_eq!(policy.delay(3), Duration::from_millis(500));
+    assert_eq!(policy.delay(u32::MAX), Duration::from_millis(500));
+}
@@ -0,0 +1,72 @@
+//! Tests for using ZenohTransport through the Transport trait
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::{Transport, ZenohTransport};
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    value: i32,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_trait_publisher_reaches_node_subscriber() {
+    let node = Node::with_transport(
+        "trait_subscriber_node",
+        ZenohTransport::new().await.unwrap(),
+    )
+    .await
+    .unwrap();
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Reading>>("trait/reading")
+        .build(move |Json(reading)| sink.lock().unwrap().push(reading.value))
+        .await
+        .unwrap();
+
+    let transport = ZenohTransport::new().await.unwrap();
+    let publisher = Transport::create_publisher::<Json<Reading>>(&transport, "trait/reading")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&Json(Reading { value: 7 })).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![7]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_trait_client_calls_node_service() {
+    let node = Node::with_transport("trait_service_node", ZenohTransport::new().await.unwrap())
+        .await
+        .unwrap();
+    let _service = node
+        .service::<Json<Reading>, Json<Reading>>("trait/double")
+        .build(|Json(reading)| {
+            Ok(Json(Reading {
+                value: reading.value * 2,
+            }))
+        })
+        .await
+        .unwrap();
+
+    let transport = ZenohTransport::new().await.unwrap();
+    let client =
+        Transport::create_client::<Json<Reading>, Json<Reading>>(&transport, "trait/double")
+            .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let Json(response) = client
+        .call_async(&Json(Reading { value: 21 }))
+        .await
+        .unwrap();
+    assert_eq!(response.value, 42);
+}
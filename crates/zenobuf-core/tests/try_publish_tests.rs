@@ -0,0 +1,73 @@
+//! Tests for publishing without blocking on a congested network
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::{MockTransport, ZenohTransport};
+use zenobuf_core::{Json, JsonMessage, Node, Transport};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Command {
+    speed: f64,
+}
+
+impl JsonMessage for Command {}
+
+#[tokio::test]
+async fn test_try_publish_reports_backpressure() {
+    let transport = MockTransport::new();
+    let publisher = transport
+        .create_publisher::<Json<Command>>("cmd")
+        .await
+        .unwrap();
+
+    assert!(publisher
+        .try_publish(&Json(Command { speed: 1.0 }))
+        .unwrap());
+
+    transport.set_congested(true);
+    assert!(!publisher
+        .try_publish(&Json(Command { speed: 2.0 }))
+        .unwrap());
+    assert_eq!(transport.samples("cmd").len(), 1);
+    assert_eq!(publisher.stats().messages_sent, 1);
+
+    transport.set_congested(false);
+    assert!(publisher
+        .try_publish(&Json(Command { speed: 3.0 }))
+        .unwrap());
+    assert_eq!(transport.samples("cmd").len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_try_publish_over_zenoh() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("try_publish_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber = node
+        .subscriber::<Json<Command>>("try_publish_cmd")
+        .build(move |Json(command)| sink.lock().unwrap().push(command.speed))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Command>>("try_publish_cmd")
+        .reliable()
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    for speed in [0.5, 1.0, 1.5] {
+        assert!(publisher.try_publish(&Json(Command { speed })).unwrap());
+    }
+    assert_eq!(publisher.stats().messages_sent, 3);
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+    assert_eq!(*received.lock().unwrap(), [0.5, 1.0, 1.5]);
+}
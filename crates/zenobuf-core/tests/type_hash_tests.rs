@@ -0,0 +1,164 @@
+//! Tests for the message type-hash handshake
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing_test::traced_test;
+use zenobuf_core::message::type_hash_of;
+use zenobuf_core::transport::{SharedSession, ZenohTransport};
+use zenobuf_core::{Json, JsonMessage, Message, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Point2 {
+    x: f64,
+    y: f64,
+}
+
+impl JsonMessage for Point2 {}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Heading {
+    #[serde(default)]
+    x: f64,
+    #[serde(default)]
+    theta: f64,
+}
+
+impl JsonMessage for Heading {}
+
+#[test]
+fn test_type_hash_ignores_module_path() {
+    assert_eq!(type_hash_of("a::b::Point"), type_hash_of("c::Point"));
+    assert_eq!(type_hash_of("Point"), type_hash_of("c::Point"));
+    assert_ne!(type_hash_of("a::Point"), type_hash_of("a::Pose"));
+    assert_ne!(
+        <Json<Point2> as Message>::type_hash(),
+        <Json<Heading> as Message>::type_hash()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_mismatched_type_is_not_delivered() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("type_hash_node", transport)
+        .await
+        .unwrap();
+
+    let delivered = Arc::new(AtomicUsize::new(0));
+    let delivered_clone = delivered.clone();
+
+    // Heading would happily decode a Point2 JSON payload into garbage
+    let subscriber = node
+        .subscriber::<Json<Heading>>("shape")
+        .build(move |_heading| {
+            delivered_clone.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<Point2>>("shape")
+        .build()
+        .await
+        .unwrap();
+    publisher.publish(&Json(Point2 { x: 1.0, y: 2.0 })).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(delivered.load(Ordering::SeqCst), 0);
+    let stats = subscriber.stats();
+    assert_eq!(stats.type_mismatches, 1);
+    assert_eq!(stats.messages_received, 0);
+}
+
+#[traced_test]
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_mismatches_are_warned_once_per_publisher() {
+    // Nodes sharing a session deliver samples within the test's span, where
+    // its logs are captured
+    let session = SharedSession::get().await.unwrap();
+    let node = Node::builder("type_hash_warning_node")
+        .session(session.clone())
+        .build()
+        .await
+        .unwrap();
+    // A node owns one publisher per topic, so the second comes from another
+    let other = Node::builder("type_hash_warning_other_node")
+        .session(session)
+        .build()
+        .await
+        .unwrap();
+
+    let subscriber = node
+        .subscriber::<Json<Heading>>("warned_shape")
+        .build(|_heading| {})
+        .await
+        .unwrap();
+    let publishers = [
+        node.publisher::<Json<Point2>>("warned_shape")
+            .build()
+            .await
+            .unwrap(),
+        other
+            .publisher::<Json<Point2>>("warned_shape")
+            .build()
+            .await
+            .unwrap(),
+    ];
+    for publisher in &publishers {
+        for _ in 0..5 {
+            publisher.publish(&Json(Point2 { x: 1.0, y: 2.0 })).unwrap();
+        }
+    }
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(subscriber.stats().type_mismatches, 10);
+    logs_assert(|lines: &[&str]| {
+        let warnings = lines
+            .iter()
+            .filter(|line| line.contains("does not match"))
+            .count();
+        match warnings {
+            2 => Ok(()),
+            n => Err(format!("expected a warning per publisher, got {n}")),
+        }
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_matching_type_is_delivered() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("type_hash_match_node", transport)
+        .await
+        .unwrap();
+
+    let delivered = Arc::new(AtomicUsize::new(0));
+    let delivered_clone = delivered.clone();
+
+    let subscriber = node
+        .subscriber::<Json<Point2>>("point")
+        .build(move |_point| {
+            delivered_clone.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+
+    let publisher = node
+        .publisher::<Json<Point2>>("point")
+        .build()
+        .await
+        .unwrap();
+    publisher.publish(&Json(Point2 { x: 1.0, y: 2.0 })).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+    assert_eq!(subscriber.stats().messages_received, 1);
+    assert_eq!(subscriber.stats().type_mismatches, 0);
+}
@@ -0,0 +1,78 @@
+//! Tests for the message type name attached to published samples
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::message::encode_message;
+use zenobuf_core::transport::{attached_type_name, ZenohTransport};
+use zenobuf_core::{Json, JsonMessage, Message, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Odometry {
+    speed: f64,
+}
+
+impl JsonMessage for Odometry {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_published_sample_carries_type_name() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("type_name_node", transport)
+        .await
+        .unwrap();
+
+    let type_names = Arc::new(Mutex::new(Vec::new()));
+    let sink = type_names.clone();
+    let _subscriber = node
+        .subscriber::<Json<Odometry>>("type_name_odometry")
+        .build_zerocopy(move |view| {
+            sink.lock()
+                .unwrap()
+                .push(view.type_name().map(str::to_string));
+        })
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Odometry>>("type_name_odometry")
+        .build()
+        .await
+        .unwrap();
+
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let key = format!("{}type_name_odometry", ZenohTransport::TOPIC_PREFIX);
+    let captured = session.declare_subscriber(&key).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    publisher.publish(&Json(Odometry { speed: 1.5 })).unwrap();
+    let sample = tokio::time::timeout(Duration::from_secs(5), captured.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    let attachment = sample.attachment().unwrap().to_bytes();
+    assert_eq!(
+        attached_type_name(&attachment).as_deref(),
+        Some(<Json<Odometry> as Message>::type_name())
+    );
+
+    // Samples from peers that attach nothing are still delivered
+    session
+        .put(
+            &key,
+            encode_message(&Json(Odometry { speed: 2.0 })).unwrap(),
+        )
+        .encoding(zenoh::bytes::Encoding::APPLICATION_JSON)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(
+        *type_names.lock().unwrap(),
+        vec![
+            Some(<Json<Odometry> as Message>::type_name().to_string()),
+            None
+        ]
+    );
+}
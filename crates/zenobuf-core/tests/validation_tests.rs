@@ -0,0 +1,85 @@
+//! Tests for message validation on publish and receive
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node, Result};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Waypoint {
+    x: f64,
+    z: f64,
+}
+
+impl JsonMessage for Waypoint {
+    fn validate(&self) -> Result<()> {
+        if self.z < 0.0 {
+            return Err(Error::invalid_message("Waypoint", "z is below ground"));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_invalid_message_is_not_published() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("validation_publish_node", transport)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Waypoint>>("validation_publish")
+        .build()
+        .await
+        .unwrap();
+
+    let err = publisher
+        .publish(&Json(Waypoint { x: 1.0, z: -2.0 }))
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidMessage { .. }));
+    assert!(publisher
+        .publish_batch(&[
+            Json(Waypoint { x: 1.0, z: 2.0 }),
+            Json(Waypoint { x: 2.0, z: -1.0 })
+        ])
+        .is_err());
+    assert_eq!(publisher.stats().messages_sent, 0);
+
+    publisher
+        .publish(&Json(Waypoint { x: 1.0, z: 2.0 }))
+        .unwrap();
+    assert_eq!(publisher.stats().messages_sent, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_invalid_received_message_is_dropped() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("validation_receive_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let subscriber = node
+        .subscriber::<Json<Waypoint>>("validation_receive")
+        .build(move |Json(waypoint)| received_clone.lock().unwrap().push(waypoint))
+        .await
+        .unwrap();
+
+    // A peer that does not validate, such as the CLI, sends raw JSON
+    let session = zenoh::open(zenoh::config::Config::default()).await.unwrap();
+    let key = format!("{}validation_receive", ZenohTransport::TOPIC_PREFIX);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    for payload in [r#"{"x": 1.0, "z": -5.0}"#, r#"{"x": 2.0, "z": 5.0}"#] {
+        session.put(&key, payload).await.unwrap();
+    }
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![Waypoint { x: 2.0, z: 5.0 }]);
+    let stats = subscriber.stats();
+    assert_eq!(stats.invalid_messages, 1);
+    assert_eq!(stats.messages_received, 1);
+}
@@ -0,0 +1,58 @@
+//! Tests for waiting on the next message of a topic
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Status {
+    code: u32,
+}
+
+impl JsonMessage for Status {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_for_message_returns_next_message() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("wait_for_message_node", transport)
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Status>>("status_once")
+        .build()
+        .await
+        .unwrap();
+
+    let publish = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        publisher.publish(&Json(Status { code: 7 })).unwrap();
+        publisher
+    });
+    let status = node
+        .wait_for_message::<Json<Status>>("status_once", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(status.code, 7);
+
+    // The subscriber is gone, so the topic can be subscribed to again
+    let _publisher = publish.await.unwrap();
+    node.subscriber::<Json<Status>>("status_once")
+        .build(|_| {})
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_wait_for_message_times_out() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("wait_for_silence_node", transport)
+        .await
+        .unwrap();
+
+    let result = node
+        .wait_for_message::<Json<Status>>("silent_status", Duration::from_millis(200))
+        .await;
+    assert!(matches!(result, Err(Error::Timeout { .. })));
+}
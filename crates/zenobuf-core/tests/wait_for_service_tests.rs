@@ -0,0 +1,108 @@
+//! Tests for waiting on service availability
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Error, Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Echo {
+    text: String,
+}
+
+impl JsonMessage for Echo {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_wait_for_registered_service() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("wait_service_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Echo>, Json<Echo>>("wait_echo")
+        .build(Ok)
+        .await
+        .unwrap();
+
+    let client = node
+        .client::<Json<Echo>, Json<Echo>>("wait_echo")
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let response = client
+        .call_async(&Json(Echo {
+            text: "hello".to_string(),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(response.text, "hello");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_wait_for_missing_service_times_out() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("wait_missing_node", transport)
+        .await
+        .unwrap();
+
+    let client = node
+        .client::<Json<Echo>, Json<Echo>>("wait_missing")
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    let result = client.wait_for_service(Duration::from_millis(500)).await;
+    assert!(matches!(result, Err(Error::ServiceCallTimeout { .. })));
+    assert!(start.elapsed() >= Duration::from_millis(500));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_build_and_wait_for_registered_service() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("build_wait_node", transport)
+        .await
+        .unwrap();
+
+    let _service = node
+        .service::<Json<Echo>, Json<Echo>>("build_wait_echo")
+        .build(Ok)
+        .await
+        .unwrap();
+
+    let client = node
+        .client::<Json<Echo>, Json<Echo>>("build_wait_echo")
+        .build_and_wait(Duration::from_secs(5))
+        .await
+        .unwrap();
+    let response = client
+        .call_async(&Json(Echo {
+            text: "ready".to_string(),
+        }))
+        .await
+        .unwrap();
+    assert_eq!(response.text, "ready");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_build_and_wait_for_missing_service_times_out() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("build_wait_missing_node", transport)
+        .await
+        .unwrap();
+
+    let result = node
+        .client::<Json<Echo>, Json<Echo>>("build_wait_missing")
+        .build_and_wait(Duration::from_millis(500))
+        .await;
+    assert!(matches!(result, Err(Error::ServiceCallTimeout { .. })));
+}
@@ -0,0 +1,70 @@
+//! Tests for subscribers on topic patterns
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Reading {
+    value: f64,
+}
+
+impl JsonMessage for Reading {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_wildcard_subscriber_receives_matching_topics() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("wildcard_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let callback_received = received.clone();
+    let _subscriber = node
+        .subscriber_wildcard::<Json<Reading>>("sensors/**")
+        .build(move |topic, Json(reading)| {
+            callback_received
+                .lock()
+                .unwrap()
+                .push((topic, reading.value));
+        })
+        .await
+        .unwrap();
+
+    let imu = node
+        .publisher::<Json<Reading>>("sensors/imu")
+        .build()
+        .await
+        .unwrap();
+    let gps = node
+        .publisher::<Json<Reading>>("sensors/gps")
+        .build()
+        .await
+        .unwrap();
+    let other = node
+        .publisher::<Json<Reading>>("actuators/arm")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    imu.publish(&Json(Reading { value: 1.0 })).unwrap();
+    gps.publish(&Json(Reading { value: 2.0 })).unwrap();
+    other.publish(&Json(Reading { value: 3.0 })).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    node.spin_once().unwrap();
+
+    let mut received = received.lock().unwrap().clone();
+    received.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        received,
+        vec![
+            ("sensors/gps".to_string(), 2.0),
+            ("sensors/imu".to_string(), 1.0),
+        ]
+    );
+}
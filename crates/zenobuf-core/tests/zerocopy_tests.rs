@@ -0,0 +1,72 @@
+//! Tests for zero-copy subscribers
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zenobuf_core::message::encode_message;
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Json, JsonMessage, Node};
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+struct Scan {
+    ranges: Vec<f32>,
+}
+
+impl JsonMessage for Scan {}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_zerocopy_subscriber_decodes_view() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("zerocopy_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let subscriber = node
+        .subscriber::<Json<Scan>>("zerocopy_scans")
+        .build_zerocopy(move |view| {
+            assert_eq!(view.topic(), "zerocopy_scans");
+            let scan: Json<Scan> = view.decode().unwrap();
+            sink.lock()
+                .unwrap()
+                .push((view.as_bytes().to_vec(), scan.0));
+        })
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Json<Scan>>("zerocopy_scans")
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let scan = Json(Scan {
+        ranges: (0..1000).map(|i| i as f32 * 0.01).collect(),
+    });
+    publisher.publish(&scan).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].0, encode_message(&scan).unwrap());
+    assert_eq!(received[0].1, scan.0);
+    assert_eq!(subscriber.stats().messages_received, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_zerocopy_subscriber_rejects_filters() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("zerocopy_filter_node", transport)
+        .await
+        .unwrap();
+
+    let result = node
+        .subscriber::<Json<Scan>>("zerocopy_filtered")
+        .filter(|scan| !scan.ranges.is_empty())
+        .build_zerocopy(|_| {})
+        .await;
+    assert!(result.is_err());
+}
@@ -1,11 +1,51 @@
 //! Build script for the Zenobuf examples
 
+use std::fmt::Write;
 use std::io::Result;
 
+/// Emits a `#[zenobuf_service]` trait for each proto service
+struct ZenobufServiceGenerator;
+
+impl prost_build::ServiceGenerator for ZenobufServiceGenerator {
+    fn generate(&mut self, service: prost_build::Service, buf: &mut String) {
+        for line in &service.comments.leading {
+            let _ = writeln!(buf, "///{line}");
+        }
+        let _ = writeln!(
+            buf,
+            "#[zenobuf_macros::zenobuf_service(name = \"{}.{}\")]",
+            service.package, service.proto_name
+        );
+        let _ = writeln!(buf, "pub trait {} {{", service.name);
+        for method in &service.methods {
+            if method.client_streaming || method.server_streaming {
+                println!(
+                    "cargo:warning=Skipping streaming rpc {}.{}",
+                    service.proto_name, method.proto_name
+                );
+                continue;
+            }
+            for line in &method.comments.leading {
+                let _ = writeln!(buf, "    ///{line}");
+            }
+            let _ = writeln!(
+                buf,
+                "    fn {}(&self, request: {}) -> ::zenobuf_core::Result<{}>;",
+                method.name, method.input_type, method.output_type
+            );
+        }
+        let _ = writeln!(buf, "}}");
+    }
+}
+
 fn main() -> Result<()> {
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set"));
+
     // Compile Protocol Buffer definitions with derive macro
     prost_build::Config::new()
+        .file_descriptor_set_path(out_dir.join("descriptors.bin"))
         .type_attribute(".", "#[derive(zenobuf_macros::ZenobufMessage)]")
+        .service_generator(Box::new(ZenobufServiceGenerator))
         .compile_protos(
             &["protos/geometry.proto", "protos/example_service.proto"],
             &["protos"],
@@ -0,0 +1,56 @@
+//! Example of a generated service for the Zenobuf framework
+//!
+//! The `Calculator` service is declared in `protos/example_service.proto` and the
+//! build script turns it into typed server and client glue.
+
+use std::time::Duration;
+
+use zenobuf_core::{Node, Result};
+use zenobuf_examples::proto::service::{
+    AddTwoIntsRequest, AddTwoIntsResponse, CalculatorClient, CalculatorServer,
+    CalculatorServerHandle, MultiplyTwoIntsRequest, MultiplyTwoIntsResponse,
+};
+
+/// Server implementation of the Calculator service
+struct Calculator;
+
+impl CalculatorServer for Calculator {
+    fn add(&self, request: AddTwoIntsRequest) -> Result<AddTwoIntsResponse> {
+        Ok(AddTwoIntsResponse {
+            sum: request.a + request.b,
+        })
+    }
+
+    fn multiply(&self, request: MultiplyTwoIntsRequest) -> Result<MultiplyTwoIntsResponse> {
+        Ok(MultiplyTwoIntsResponse {
+            product: i64::from(request.a) * i64::from(request.b),
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing
+    tracing_subscriber::fmt::init();
+
+    // Create a node
+    let node = Node::new("calculator").await?;
+
+    // Serve every rpc of the Calculator service
+    let _server = CalculatorServerHandle::serve(&node, Calculator).await?;
+    println!("Serving {:?}", CalculatorServerHandle::service_names());
+
+    // Call the service through the typed client
+    let client = CalculatorClient::new(&node)?;
+    client.wait_for_service(Duration::from_secs(5)).await?;
+
+    let response = client.add_async(&AddTwoIntsRequest { a: 5, b: 3 }).await?;
+    println!("5 + 3 = {sum}", sum = response.sum);
+
+    let response = client
+        .multiply_async(&MultiplyTwoIntsRequest { a: 5, b: 3 })
+        .await?;
+    println!("5 * 3 = {product}", product = response.product);
+
+    Ok(())
+}
@@ -57,14 +57,14 @@ async fn main() -> Result<()> {
         })
         .await?;
 
-    // Sleep to ensure the service is registered
-    tokio::time::sleep(Duration::from_secs(1)).await;
-
     // Create a client using the builder pattern
     let client = node
         .client::<AddTwoIntsRequest, AddTwoIntsResponse>("add_two_ints")
         .build()?;
 
+    // Wait for the service to be registered
+    client.wait_for_service(Duration::from_secs(5)).await?;
+
     // Set parameters
     node.set_parameter("string_param", "hello".to_string())?;
     node.set_parameter("int_param", 42)?;
@@ -10,8 +10,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
-    // Create a node
+    // Create a node that shuts down on Ctrl+C
     let node = Node::new("talker").await?;
+    node.shutdown_on_ctrl_c();
 
     // Create a publisher using the builder pattern
     let publisher = node
@@ -35,9 +36,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }),
     };
 
-    // Publish the message periodically
+    // Publish the message periodically until shutdown
     let mut counter = 0;
-    loop {
+    while node.ok() {
         // Update the message
         if let Some(position) = &mut pose.position {
             position.x = counter as f32;
@@ -53,4 +54,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Increment the counter
         counter += 1;
     }
+
+    Ok(())
 }
@@ -0,0 +1,204 @@
+//! Math on the generated geometry messages
+//!
+//! Extension traits add vector operations to [`Point`], rotations to
+//! [`Quaternion`] and point transforms to [`Pose`], so the messages can be used
+//! directly without converting them to another math library. Poses also convert
+//! to a [`Transform`], to be broadcast with a `TransformBroadcaster` as the pose of
+//! a frame in its parent frame.
+//!
+//! ```
+//! use zenobuf_examples::geometry::{PointExt, PoseExt, QuaternionExt};
+//! use zenobuf_examples::proto::geometry::{Point, Pose, Quaternion};
+//!
+//! // A robot at (1, 2, 0), turned a quarter turn to the left
+//! let pose = Pose {
+//!     position: Some(Point { x: 1.0, y: 2.0, z: 0.0 }),
+//!     orientation: Some(Quaternion::from_yaw(std::f32::consts::FRAC_PI_2)),
+//! };
+//! // One meter ahead of the robot
+//! let ahead = pose.transform_point(&Point { x: 1.0, y: 0.0, z: 0.0 });
+//! assert!(ahead.sub(&Point { x: 1.0, y: 3.0, z: 0.0 }).norm() < 1e-6);
+//! ```
+
+use zenobuf_core::tf::{self, Transform};
+
+use crate::proto::geometry::{Point, Pose, Quaternion};
+
+/// Vector operations on a [`Point`]
+pub trait PointExt {
+    /// Returns the sum of two vectors
+    fn add(&self, other: &Point) -> Point;
+
+    /// Returns the difference of two vectors
+    fn sub(&self, other: &Point) -> Point;
+
+    /// Returns the vector multiplied by `factor`
+    fn scale(&self, factor: f32) -> Point;
+
+    /// Returns the dot product of two vectors
+    fn dot(&self, other: &Point) -> f32;
+
+    /// Returns the cross product of two vectors
+    fn cross(&self, other: &Point) -> Point;
+
+    /// Returns the length of the vector
+    fn norm(&self) -> f32;
+}
+
+impl PointExt for Point {
+    fn add(&self, other: &Point) -> Point {
+        Point {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+
+    fn sub(&self, other: &Point) -> Point {
+        self.add(&other.scale(-1.0))
+    }
+
+    fn scale(&self, factor: f32) -> Point {
+        Point {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
+    fn dot(&self, other: &Point) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn cross(&self, other: &Point) -> Point {
+        Point {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    fn norm(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+}
+
+/// Rotation operations on a [`Quaternion`]
+pub trait QuaternionExt: Sized {
+    /// Returns the rotation that leaves vectors unchanged
+    ///
+    /// The default message, with all components zero, is not a rotation.
+    fn identity() -> Self;
+
+    /// Creates the rotation of `yaw` radians around the z axis
+    fn from_yaw(yaw: f32) -> Self;
+
+    /// Returns the quaternion scaled to unit length, or the identity if all
+    /// components are zero
+    fn normalize(&self) -> Self;
+
+    /// Returns the rotation as `(roll, pitch, yaw)` in radians
+    ///
+    /// The angles rotate around the fixed x, y and z axes, in that order, as in
+    /// ROS. Pitch is in `-π/2..=π/2`, roll and yaw in `-π..=π`.
+    fn to_euler(&self) -> (f32, f32, f32);
+
+    /// Rotates a vector, normalizing the quaternion first
+    fn rotate(&self, point: &Point) -> Point;
+}
+
+impl QuaternionExt for Quaternion {
+    fn identity() -> Self {
+        Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        }
+    }
+
+    fn from_yaw(yaw: f32) -> Self {
+        let (sin, cos) = (yaw / 2.0).sin_cos();
+        Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: sin,
+            w: cos,
+        }
+    }
+
+    fn normalize(&self) -> Self {
+        let norm = (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt();
+        if norm == 0.0 {
+            return Self::identity();
+        }
+        Quaternion {
+            x: self.x / norm,
+            y: self.y / norm,
+            z: self.z / norm,
+            w: self.w / norm,
+        }
+    }
+
+    fn to_euler(&self) -> (f32, f32, f32) {
+        let Quaternion { x, y, z, w } = self.normalize();
+        let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+        // Clamp rounding errors near the poles, where asin is undefined
+        let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+        let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+        (roll, pitch, yaw)
+    }
+
+    fn rotate(&self, point: &Point) -> Point {
+        let rotation = self.normalize();
+        let axis = Point {
+            x: rotation.x,
+            y: rotation.y,
+            z: rotation.z,
+        };
+        let t = axis.cross(point).scale(2.0);
+        point.add(&t.scale(rotation.w)).add(&axis.cross(&t))
+    }
+}
+
+/// Frame operations on a [`Pose`]
+///
+/// A pose is the position and orientation of a child frame in its parent frame.
+/// A missing position is the origin and a missing orientation the identity.
+pub trait PoseExt {
+    /// Maps a point from the frame at this pose to the parent frame
+    fn transform_point(&self, point: &Point) -> Point;
+
+    /// Returns the transform from the frame at this pose to the parent frame
+    fn to_transform(&self) -> Transform;
+}
+
+impl PoseExt for Pose {
+    fn transform_point(&self, point: &Point) -> Point {
+        let rotated = match &self.orientation {
+            Some(orientation) => orientation.rotate(point),
+            None => *point,
+        };
+        match &self.position {
+            Some(position) => rotated.add(position),
+            None => rotated,
+        }
+    }
+
+    fn to_transform(&self) -> Transform {
+        let position = self.position.unwrap_or_default();
+        let orientation = self
+            .orientation
+            .as_ref()
+            .map_or_else(Quaternion::identity, QuaternionExt::normalize);
+        Transform::new(
+            tf::Vector3::new(position.x.into(), position.y.into(), position.z.into()),
+            tf::Quaternion::new(
+                orientation.x.into(),
+                orientation.y.into(),
+                orientation.z.into(),
+                orientation.w.into(),
+            ),
+        )
+    }
+}
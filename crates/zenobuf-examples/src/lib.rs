@@ -15,6 +15,7 @@
 //!
 //! - **[service.rs](src/bin/service.rs)** - Service example that provides addition functionality
 //! - **[client.rs](src/bin/client.rs)** - Client example that calls the addition service
+//! - **[calculator.rs](src/bin/calculator.rs)** - Typed service and client generated from a proto `service`
 //!
 //! ### Advanced Examples
 //!
@@ -43,6 +44,13 @@
 //! cargo run --bin client 5 3
 //! ```
 //!
+//! ### Generated Service Example
+//!
+//! ```bash
+//! # Serve and call the Calculator service in one process
+//! cargo run --bin calculator
+//! ```
+//!
 //! ### Complete Application
 //!
 //! ```bash
@@ -67,10 +75,16 @@
 //! - **Quaternion** - Orientation representation
 //! - **Pose** - Position and orientation combined
 //!
+//! The [`geometry`] module adds vector and rotation math to these messages through
+//! extension traits, e.g. `pose.transform_point(&point)`.
+//!
 //! ### Service Messages (`protos/example_service.proto`)
 //!
 //! - **AddTwoIntsRequest** - Request with two integers to add
 //! - **AddTwoIntsResponse** - Response with the sum
+//! - **MultiplyTwoIntsRequest** / **MultiplyTwoIntsResponse** - Multiplication request and response
+//! - **Calculator** - Service whose rpcs generate `CalculatorServer`, `CalculatorClient` and
+//!   `CalculatorServerHandle`
 //!
 //! ## Key Concepts Demonstrated
 //!
@@ -185,6 +199,8 @@
 //! - Check the [API Reference](../../docs/api-guide.md)
 //! - Use the [Starter Template](../../starter-template/) for your own projects
 
+pub mod geometry;
+
 // Include the generated Protocol Buffer code
 pub mod proto {
     pub mod geometry {
@@ -194,4 +210,9 @@ pub mod proto {
     pub mod service {
         include!(concat!(env!("OUT_DIR"), "/zenobuf.examples.service.rs"));
     }
+
+    /// Encoded `FileDescriptorSet` of the example messages, for tools such as
+    /// `zenobuf-cli monitor --descriptor` that decode them without their types
+    pub const FILE_DESCRIPTOR_SET: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/descriptors.bin"));
 }
@@ -0,0 +1,111 @@
+//! Tests for the math on the generated geometry messages
+
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use zenobuf_examples::geometry::{PointExt, PoseExt, QuaternionExt};
+use zenobuf_examples::proto::geometry::{Point, Pose, Quaternion};
+
+fn point(x: f32, y: f32, z: f32) -> Point {
+    Point { x, y, z }
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-5,
+        "{actual} is not {expected}"
+    );
+}
+
+fn assert_point(actual: &Point, expected: &Point) {
+    assert!(
+        actual.sub(expected).norm() < 1e-5,
+        "{actual:?} is not {expected:?}"
+    );
+}
+
+#[test]
+fn test_point_vector_ops() {
+    let a = point(1.0, 2.0, 3.0);
+    let b = point(4.0, -5.0, 6.0);
+    assert_eq!(a.add(&b), point(5.0, -3.0, 9.0));
+    assert_eq!(a.sub(&b), point(-3.0, 7.0, -3.0));
+    assert_eq!(a.scale(2.0), point(2.0, 4.0, 6.0));
+    assert_eq!(a.dot(&b), 12.0);
+    assert_eq!(
+        point(1.0, 0.0, 0.0).cross(&point(0.0, 1.0, 0.0)),
+        point(0.0, 0.0, 1.0)
+    );
+    assert_eq!(a.cross(&b), point(27.0, 6.0, -13.0));
+    assert_eq!(point(3.0, 4.0, 0.0).norm(), 5.0);
+}
+
+#[test]
+fn test_quaternion_normalization() {
+    let q = Quaternion {
+        x: 0.0,
+        y: 0.0,
+        z: 2.0,
+        w: 2.0,
+    }
+    .normalize();
+    assert_close(q.z, FRAC_PI_4.sin());
+    assert_close(q.w, FRAC_PI_4.cos());
+    assert_close(q.x * q.x + q.y * q.y + q.z * q.z + q.w * q.w, 1.0);
+
+    // The all-zero default message normalizes to the identity
+    assert_eq!(Quaternion::default().normalize(), Quaternion::identity());
+}
+
+#[test]
+fn test_quaternion_to_euler() {
+    let (roll, pitch, yaw) = Quaternion::identity().to_euler();
+    assert_eq!((roll, pitch, yaw), (0.0, 0.0, 0.0));
+
+    let (roll, pitch, yaw) = Quaternion::from_yaw(FRAC_PI_2).to_euler();
+    assert_close(roll, 0.0);
+    assert_close(pitch, 0.0);
+    assert_close(yaw, FRAC_PI_2);
+
+    // A quarter turn around x, given unnormalized
+    let (roll, pitch, yaw) = Quaternion {
+        x: 3.0,
+        y: 0.0,
+        z: 0.0,
+        w: 3.0,
+    }
+    .to_euler();
+    assert_close(roll, FRAC_PI_2);
+    assert_close(pitch, 0.0);
+    assert_close(yaw, 0.0);
+}
+
+#[test]
+fn test_pose_transforms_point() {
+    // At (1, 2, 3), turned a quarter turn to the left
+    let pose = Pose {
+        position: Some(point(1.0, 2.0, 3.0)),
+        orientation: Some(Quaternion::from_yaw(FRAC_PI_2)),
+    };
+    assert_point(
+        &pose.transform_point(&point(1.0, 0.0, 0.0)),
+        &point(1.0, 3.0, 3.0),
+    );
+    assert_point(
+        &pose.transform_point(&point(2.0, 1.0, -1.0)),
+        &point(0.0, 4.0, 2.0),
+    );
+
+    // The transform maps points the same way
+    let transform = pose.to_transform();
+    let mapped = transform.apply(zenobuf_core::tf::Vector3::new(2.0, 1.0, -1.0));
+    assert_point(
+        &point(mapped.x as f32, mapped.y as f32, mapped.z as f32),
+        &point(0.0, 4.0, 2.0),
+    );
+
+    // Without position and orientation, the pose is the parent frame itself
+    assert_eq!(
+        Pose::default().transform_point(&point(1.0, 2.0, 3.0)),
+        point(1.0, 2.0, 3.0)
+    );
+}
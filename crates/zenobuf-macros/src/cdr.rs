@@ -0,0 +1,49 @@
+//! CDR layout generated by `#[zenobuf(cdr)]`
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DataStruct, Fields, Index};
+
+/// Generates `Message::write_cdr` and `Message::read_cdr` for a struct
+///
+/// Fields are written and read in declaration order, which is the order of the
+/// corresponding ROS message.
+pub(crate) fn expand(data: &DataStruct) -> TokenStream {
+    let members: Vec<_> = match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| {
+                let ident = &f.ident;
+                quote! { #ident }
+            })
+            .collect(),
+        Fields::Unnamed(fields) => (0..fields.unnamed.len())
+            .map(|i| {
+                let index = Index::from(i);
+                quote! { #index }
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+
+    quote! {
+        fn write_cdr(
+            &self,
+            writer: &mut ::zenobuf_core::cdr::CdrWriter,
+        ) -> ::zenobuf_core::Result<()> {
+            #(::zenobuf_core::cdr::CdrField::write_cdr(&self.#members, writer)?;)*
+            let _ = writer;
+            Ok(())
+        }
+
+        fn read_cdr(
+            reader: &mut ::zenobuf_core::cdr::CdrReader<'_>,
+        ) -> ::zenobuf_core::Result<Self> {
+            let _ = &reader;
+            Ok(Self {
+                #(#members: ::zenobuf_core::cdr::CdrField::read_cdr(reader)?,)*
+            })
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Field descriptors generated by the `ZenobufMessage` derive
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DataEnum, DataStruct, Field, GenericArgument, LitStr, PathArguments, Token, Type};
+
+/// Protocol Buffer scalar and message types accepted in `#[prost(...)]` attributes
+const PROST_TYPES: &[&str] = &[
+    "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+    "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes", "message", "group",
+];
+
+/// Descriptor of a single field, mirroring `zenobuf_core::message::FieldInfo`
+struct FieldDescriptor {
+    name: String,
+    proto_type: String,
+    repeated: bool,
+    optional: bool,
+    /// Path of the enum holding the variants of a `oneof` field
+    oneof: Option<syn::Path>,
+    /// Wire tags of the field, as written in `#[prost(tag = ...)]` or `tags`
+    tags: Option<String>,
+    /// Name of the message or enum type of the field, if it has one
+    type_ref: Option<String>,
+}
+
+impl FieldDescriptor {
+    fn parse(field: &Field) -> syn::Result<Option<Self>> {
+        let Some(ident) = &field.ident else {
+            return Ok(None);
+        };
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+
+        match field.attrs.iter().find(|a| a.path().is_ident("prost")) {
+            Some(attr) => Self::from_prost_attribute(name, attr, &field.ty).map(Some),
+            None => Ok(Some(Self::from_type(name, &field.ty))),
+        }
+    }
+
+    /// Reads the descriptor from a `#[prost(...)]` field attribute
+    fn from_prost_attribute(name: String, attr: &syn::Attribute, ty: &Type) -> syn::Result<Self> {
+        let mut descriptor = Self {
+            name,
+            proto_type: String::from("message"),
+            repeated: false,
+            optional: false,
+            oneof: None,
+            tags: None,
+            type_ref: None,
+        };
+
+        attr.parse_nested_meta(|meta| {
+            let key = meta
+                .path
+                .get_ident()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            let value = if meta.input.peek(Token![=]) {
+                Some(meta.value()?.parse::<LitStr>()?)
+            } else {
+                None
+            };
+
+            match key.as_str() {
+                "repeated" => descriptor.repeated = true,
+                "optional" => descriptor.optional = true,
+                "enumeration" => {
+                    descriptor.proto_type = String::from("enum");
+                    descriptor.type_ref = value.map(|v| v.value());
+                }
+                "oneof" => {
+                    descriptor.proto_type = String::from("oneof");
+                    if let Some(value) = value {
+                        descriptor.oneof = Some(value.parse()?);
+                    }
+                }
+                "tag" | "tags" => descriptor.tags = value.map(|v| v.value()),
+                "map" | "btree_map" | "hash_map" => {
+                    let types = value.map(|v| v.value()).unwrap_or_default();
+                    descriptor.proto_type = format!("map<{types}>");
+                }
+                key if PROST_TYPES.contains(&key) => descriptor.proto_type = key.to_string(),
+                // Tags, packing, defaults and the like do not affect the descriptor
+                _ => {}
+            }
+            Ok(())
+        })?;
+
+        if descriptor.proto_type == "message" {
+            descriptor.type_ref = Some(type_ident(ty));
+        }
+        Ok(descriptor)
+    }
+
+    /// Infers the descriptor from the Rust type of a field without a prost attribute
+    fn from_type(name: String, ty: &Type) -> Self {
+        let mut descriptor = Self {
+            name,
+            proto_type: String::new(),
+            repeated: false,
+            optional: false,
+            oneof: None,
+            tags: None,
+            type_ref: None,
+        };
+
+        let mut ty = ty;
+        if let Some(inner) = generic_argument(ty, "Option", 0) {
+            descriptor.optional = true;
+            ty = inner;
+        }
+        if let Some(inner) = generic_argument(ty, "Vec", 0) {
+            if is_named(inner, "u8") {
+                descriptor.proto_type = String::from("bytes");
+                return descriptor;
+            }
+            descriptor.repeated = true;
+            ty = inner;
+        }
+        descriptor.proto_type = proto_type_of(ty);
+        if descriptor.proto_type == "message" {
+            descriptor.type_ref = Some(type_ident(ty));
+        }
+        descriptor
+    }
+
+    /// Describes the wire layout of the field, e.g. `3:pose:message(Pose)?`
+    fn layout(&self, position: usize) -> String {
+        let tags = self
+            .tags
+            .as_deref()
+            .map(|tags| tags.replace(' ', ""))
+            .unwrap_or_else(|| (position + 1).to_string());
+        let mut layout = format!("{tags}:{}:{}", self.name, self.proto_type);
+        if let Some(type_ref) = &self.type_ref {
+            layout.push_str(&format!("({type_ref})"));
+        }
+        if self.repeated {
+            layout.push('*');
+        }
+        if self.optional {
+            layout.push('?');
+        }
+        layout
+    }
+
+    fn to_tokens(&self) -> TokenStream {
+        let Self {
+            name,
+            proto_type,
+            repeated,
+            optional,
+            ..
+        } = self;
+        let variants = match &self.oneof {
+            Some(path) => quote! { <#path as ::zenobuf_core::message::Variants>::VARIANTS },
+            None => quote! { &[] },
+        };
+        quote! {
+            ::zenobuf_core::message::FieldInfo {
+                name: #name,
+                proto_type: #proto_type,
+                repeated: #repeated,
+                optional: #optional,
+                variants: #variants,
+            }
+        }
+    }
+}
+
+/// Generates `Message::fields` for a struct
+pub(crate) fn expand_fields(data: &DataStruct) -> syn::Result<TokenStream> {
+    let mut fields = Vec::new();
+    for field in &data.fields {
+        if let Some(descriptor) = FieldDescriptor::parse(field)? {
+            fields.push(descriptor.to_tokens());
+        }
+    }
+
+    Ok(quote! {
+        fn fields() -> &'static [::zenobuf_core::message::FieldInfo] {
+            const FIELDS: &[::zenobuf_core::message::FieldInfo] = &[#(#fields),*];
+            FIELDS
+        }
+    })
+}
+
+/// Describes the wire layout of a struct, which `Message::type_hash` is derived from
+///
+/// The layout lists the name, tag and type of every field after the unqualified
+/// type name, e.g. `Point{1:x:double,2:y:double}`.
+pub(crate) fn schema(name: &syn::Ident, data: &DataStruct) -> syn::Result<String> {
+    let mut fields = Vec::new();
+    for (position, field) in data.fields.iter().enumerate() {
+        if let Some(descriptor) = FieldDescriptor::parse(field)? {
+            fields.push(descriptor.layout(position));
+        }
+    }
+    Ok(format!("{name}{{{}}}", fields.join(",")))
+}
+
+/// Generates an implementation of `Variants` for an enum
+pub(crate) fn expand_variants(input: &syn::DeriveInput, data: &DataEnum) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let variants = data.variants.iter().map(|v| v.ident.to_string());
+
+    quote! {
+        impl #impl_generics ::zenobuf_core::message::Variants for #name #ty_generics #where_clause {
+            const VARIANTS: &'static [&'static str] = &[#(#variants),*];
+        }
+    }
+}
+
+/// Returns the `index`-th generic argument of `ty` if its last path segment is `wrapper`
+fn generic_argument<'a>(ty: &'a Type, wrapper: &str, index: usize) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    args.args
+        .iter()
+        .filter_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        })
+        .nth(index)
+}
+
+/// Returns the last identifier of the type wrapped by any `Option`, `Box` or `Vec`
+fn type_ident(ty: &Type) -> String {
+    for wrapper in ["Option", "Box", "Vec"] {
+        if let Some(inner) = generic_argument(ty, wrapper, 0) {
+            return type_ident(inner);
+        }
+    }
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Returns true if `ty` is a path whose last segment is `name`
+fn is_named(ty: &Type, name: &str) -> bool {
+    matches!(ty, Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == name))
+}
+
+/// Maps a Rust scalar type to its Protocol Buffer type
+fn scalar_type(ty: &Type) -> Option<&'static str> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let ident = path.path.segments.last()?.ident.to_string();
+    Some(match ident.as_str() {
+        "f64" => "double",
+        "f32" => "float",
+        "i32" | "i16" | "i8" => "int32",
+        "i64" => "int64",
+        "u32" | "u16" | "u8" => "uint32",
+        "u64" => "uint64",
+        "bool" => "bool",
+        "String" => "string",
+        _ => return None,
+    })
+}
+
+/// Maps a Rust field type to its Protocol Buffer type
+fn proto_type_of(ty: &Type) -> String {
+    for map in ["HashMap", "BTreeMap"] {
+        if let (Some(key), Some(value)) =
+            (generic_argument(ty, map, 0), generic_argument(ty, map, 1))
+        {
+            return format!("map<{}, {}>", proto_type_of(key), proto_type_of(value));
+        }
+    }
+    scalar_type(ty).unwrap_or("message").to_string()
+}
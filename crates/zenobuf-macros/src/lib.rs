@@ -75,6 +75,7 @@
 //! The `ZenobufMessage` derive macro implements the [`zenobuf_core::Message`] trait,
 //! which provides:
 //! - Type name information for debugging and introspection
+//! - Field descriptors for rendering messages field by field
 //! - Integration with Zenobuf's type-safe messaging system
 //! - Automatic serialization/deserialization support
 //!
@@ -86,10 +87,20 @@
 //! - `Default`
 //! - `prost::Message` (for Protocol Buffer types)
 //! - `Send + Sync + 'static` (automatically satisfied by most types)
+//!
+//! ## Typed Topics
+//!
+//! The [`topics!`] macro declares the topics of an application along with their
+//! message types, so that call sites cannot pair a topic with the wrong type.
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, DeriveInput, ItemTrait, LitInt, LitStr};
+
+mod cdr;
+mod fields;
+mod service;
+mod topics;
 
 /// Derives the [`zenobuf_core::Message`] trait for Protocol Buffer messages
 ///
@@ -155,6 +166,135 @@ use syn::{parse_macro_input, DeriveInput};
 /// }
 /// ```
 ///
+/// # Custom Type Names
+///
+/// By default `type_name()` reports the Rust path of the struct. Protocol Buffer
+/// messages usually want to report their fully-qualified proto name instead, which
+/// can be set with the `zenobuf` attribute:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, Default, ZenobufMessage)]
+/// #[zenobuf(type_name = "my_app.Point")]
+/// pub struct Point {
+///     pub x: f32,
+/// }
+/// ```
+///
+/// With `prost-build`, add it per message alongside the derive:
+///
+/// ```rust,ignore
+/// prost_build::Config::new()
+///     .type_attribute(".", "#[derive(zenobuf_macros::ZenobufMessage)]")
+///     .type_attribute(".my_app.Point", "#[zenobuf(type_name = \"my_app.Point\")]")
+///     .compile_protos(&["protos/messages.proto"], &["protos"])?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// ## Writing `type_name` by Hand
+///
+/// `#[zenobuf(no_type_name)]` leaves the type name to the type itself, e.g. while
+/// migrating messages that already have one. The generated `type_name()` then
+/// forwards to an inherent `fn type_name() -> &'static str`, which the type must
+/// provide:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+/// #[zenobuf(no_type_name)]
+/// pub struct Point {
+///     #[prost(float, tag = "1")]
+///     pub x: f32,
+/// }
+///
+/// impl Point {
+///     pub fn type_name() -> &'static str {
+///         "legacy.Point"
+///     }
+/// }
+/// ```
+///
+/// # Type Hash
+///
+/// The generated `type_hash()` hashes the unqualified struct name together with
+/// the name, tag and type of every field, so peers whose copies of a message
+/// have drifted apart drop each other's messages instead of misreading them.
+/// Set the hash explicitly to keep a changed layout compatible, or to version a
+/// schema by hand:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+/// #[zenobuf(type_hash = 0x5a17_0002)]
+/// pub struct Point {
+///     #[prost(double, tag = "1")]
+///     pub x: f64,
+/// }
+/// ```
+///
+/// # Validation
+///
+/// Messages can enforce invariants by naming a function that checks them. The
+/// function takes the message and returns `zenobuf_core::Result<()>`, and is
+/// called by the generated `validate()`:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, Default, ZenobufMessage)]
+/// #[zenobuf(validate = "check_altitude")]
+/// pub struct Waypoint {
+///     pub z: f64,
+/// }
+///
+/// fn check_altitude(waypoint: &Waypoint) -> zenobuf_core::Result<()> {
+///     if waypoint.z < 0.0 {
+///         return Err(zenobuf_core::Error::invalid_message("Waypoint", "z is negative"));
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # ROS 2 Interop
+///
+/// `#[zenobuf(cdr)]` generates the CDR layout of the message, so that it can be
+/// exchanged with ROS 2 nodes by selecting `Encoding::Cdr` on a publisher or
+/// subscriber. Fields are laid out in declaration order according to their Rust
+/// types, so they must match the ROS message, e.g. `double` fields for
+/// `geometry_msgs/Point`:
+///
+/// ```rust,ignore
+/// #[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+/// #[zenobuf(type_name = "geometry_msgs/msg/Point", cdr)]
+/// pub struct Point {
+///     #[prost(double, tag = "1")]
+///     pub x: f64,
+///     #[prost(double, tag = "2")]
+///     pub y: f64,
+///     #[prost(double, tag = "3")]
+///     pub z: f64,
+/// }
+///
+/// let publisher = node
+///     .publisher::<Point>("points")
+///     .with_encoding(Encoding::Cdr)
+///     .build()
+///     .await?;
+/// ```
+///
+/// # Field Descriptors
+///
+/// The generated `fields()` describes each field with its name, Protocol Buffer
+/// type and whether it is repeated or optional, read from the `#[prost(...)]`
+/// attributes that prost generates, or inferred from the Rust type otherwise.
+///
+/// For `oneof` fields the descriptor also lists the variant names. They are
+/// taken from the oneof enum, which therefore needs the derive as well; with
+/// `type_attribute(".", ...)` in `build.rs` this is already the case. Deriving
+/// `ZenobufMessage` on an enum implements [`zenobuf_core::message::Variants`]:
+///
+/// ```rust,ignore
+/// let fields = Reading::fields();
+/// assert_eq!(fields[0].name, "sensor");
+/// assert_eq!(fields[1].proto_type, "oneof");
+/// assert_eq!(fields[1].variants, ["Temperature", "Humidity"]);
+/// ```
+///
 /// # Topic Naming
 ///
 /// When using derived message types with Zenobuf publishers or subscribers, topic names
@@ -172,29 +312,269 @@ use syn::{parse_macro_input, DeriveInput};
 ///         // Returns fully qualified name, e.g. "my_app::proto::Point"
 ///         concat!(module_path!(), "::", stringify!(Point))
 ///     }
+///
+///     fn fields() -> &'static [zenobuf_core::message::FieldInfo] {
+///         // One descriptor per field, e.g. `x: float`
+///     }
 /// }
 /// ```
-#[proc_macro_derive(ZenobufMessage)]
+#[proc_macro_derive(ZenobufMessage, attributes(zenobuf))]
 pub fn derive_zenobuf_message(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    if !matches!(input.data, syn::Data::Struct(_)) {
-        return TokenStream::from(
-            syn::Error::new_spanned(&input, "ZenobufMessage can only be derived for structs")
+    let data = match &input.data {
+        syn::Data::Struct(data) => data,
+        syn::Data::Enum(data) => return TokenStream::from(fields::expand_variants(&input, data)),
+        syn::Data::Union(_) => {
+            return TokenStream::from(
+                syn::Error::new_spanned(
+                    &input,
+                    "ZenobufMessage can only be derived for structs and enums",
+                )
                 .to_compile_error(),
-        );
-    }
+            )
+        }
+    };
+
+    let options = match MessageOptions::parse(&input) {
+        Ok(options) => options,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
 
     let name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
+    let type_name = match options.type_name {
+        Some(type_name) => quote! { #type_name },
+        // Inherent associated functions take precedence over trait ones
+        None if options.no_type_name => quote! { <#name #ty_generics>::type_name() },
+        None => quote! { concat!(module_path!(), "::", stringify!(#name)) },
+    };
+
+    let validate = options.validate.map(|validate| {
+        quote! {
+            fn validate(&self) -> ::zenobuf_core::Result<()> {
+                #validate(self)
+            }
+        }
+    });
+
+    let cdr = options.cdr.then(|| cdr::expand(data));
+
+    let type_hash = match options.type_hash {
+        Some(type_hash) => quote! { #type_hash },
+        None => match fields::schema(name, data) {
+            Ok(schema) => quote! { ::zenobuf_core::message::type_hash_of_schema(#schema) },
+            Err(err) => return TokenStream::from(err.to_compile_error()),
+        },
+    };
+
+    let fields = match fields::expand_fields(data) {
+        Ok(fields) => fields,
+        Err(err) => return TokenStream::from(err.to_compile_error()),
+    };
+
     let expanded = quote! {
         impl #impl_generics ::zenobuf_core::Message for #name #ty_generics #where_clause {
             fn type_name() -> &'static str {
-                concat!(module_path!(), "::", stringify!(#name))
+                #type_name
+            }
+
+            fn type_hash() -> u64 {
+                #type_hash
             }
+
+            #validate
+
+            #cdr
+
+            #fields
         }
     };
 
     TokenStream::from(expanded)
 }
+
+/// Options parsed from `#[zenobuf(...)]` attributes
+#[derive(Default)]
+struct MessageOptions {
+    /// Overrides the value returned by `type_name()`
+    type_name: Option<LitStr>,
+    /// Overrides the value returned by `type_hash()`
+    type_hash: Option<LitInt>,
+    /// Function called by `validate()`
+    validate: Option<syn::Path>,
+    /// Whether to generate the CDR layout
+    cdr: bool,
+    /// Whether `type_name()` forwards to an inherent function written by the user
+    no_type_name: bool,
+}
+
+impl MessageOptions {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("zenobuf")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("type_name") {
+                    options.type_name = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("type_hash") {
+                    options.type_hash = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("validate") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    options.validate = Some(path.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("cdr") {
+                    options.cdr = true;
+                    Ok(())
+                } else if meta.path.is_ident("no_type_name") {
+                    options.no_type_name = true;
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unsupported zenobuf attribute, expected `type_name`, `type_hash`, `validate`, `cdr` or `no_type_name`",
+                    ))
+                }
+            })?;
+        }
+
+        if options.no_type_name {
+            if let Some(type_name) = &options.type_name {
+                return Err(syn::Error::new_spanned(
+                    type_name,
+                    "`type_name` cannot be combined with `no_type_name`",
+                ));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Generates typed server and client glue for a service definition
+///
+/// The attribute is applied to a trait describing the rpcs of a service. Each
+/// method takes `&self` and a request message and returns `Result<Response>`.
+/// For a trait named `Calculator` the macro generates:
+///
+/// - `CalculatorServer`, the trait to implement on the server side
+/// - `CalculatorClient`, a struct with one typed method (and an `_async` variant) per rpc
+/// - `CalculatorServerHandle`, which registers a `CalculatorServer` implementation on a node
+///
+/// Each rpc is served under `<name>/<method>`, where `name` defaults to the trait
+/// name and can be set with `#[zenobuf_service(name = "...")]`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zenobuf_core::{Node, Result};
+/// use zenobuf_macros::zenobuf_service;
+///
+/// #[zenobuf_service(name = "calculator")]
+/// pub trait Calculator {
+///     fn add(&self, request: AddRequest) -> Result<AddResponse>;
+///     fn multiply(&self, request: MultiplyRequest) -> Result<MultiplyResponse>;
+/// }
+///
+/// struct Impl;
+///
+/// impl CalculatorServer for Impl {
+///     fn add(&self, request: AddRequest) -> Result<AddResponse> {
+///         Ok(AddResponse { sum: request.a + request.b })
+///     }
+///
+///     fn multiply(&self, request: MultiplyRequest) -> Result<MultiplyResponse> {
+///         Ok(MultiplyResponse { product: request.a * request.b })
+///     }
+/// }
+///
+/// let _server = CalculatorServerHandle::serve(&node, Impl).await?;
+/// let client = CalculatorClient::new(&node)?;
+/// let response = client.add(&AddRequest { a: 1, b: 2 })?;
+/// ```
+///
+/// ## With Protocol Buffers
+///
+/// A `prost_build::ServiceGenerator` can emit the trait for each proto `service`,
+/// so that `service Calculator { rpc Add(AddRequest) returns (AddResponse); }`
+/// produces the glue above. See the `zenobuf-examples` build script for one.
+#[proc_macro_attribute]
+pub fn zenobuf_service(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = service::ServiceOptions::default();
+    if let Err(err) = syn::parse::Parser::parse(options.parser(), attr) {
+        return TokenStream::from(err.to_compile_error());
+    }
+    let item = parse_macro_input!(item as ItemTrait);
+
+    match service::expand(options, item) {
+        Ok(expanded) => TokenStream::from(expanded),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+/// Declares topics along with their message types
+///
+/// Each `name: Type` entry generates, inside a `topics` module:
+/// - `NAME`, a constant holding the topic name
+/// - `name(&node)`, returning a [`PublisherBuilder`] for the topic
+/// - `name_subscriber(&node)`, returning a [`SubscriberBuilder`] for the topic
+///
+/// The topic name defaults to the entry name and can be set with `= "topic"`.
+/// Attributes such as doc comments are copied onto the generated functions.
+///
+/// [`PublisherBuilder`]: zenobuf_core::node::PublisherBuilder
+/// [`SubscriberBuilder`]: zenobuf_core::node::SubscriberBuilder
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use zenobuf_macros::topics;
+///
+/// topics! {
+///     /// Current pose of the robot
+///     pose: Pose,
+///     velocity: Twist = "robot/cmd_vel",
+/// }
+///
+/// let publisher = topics::pose(&node).build().await?; // PublisherHandle<Pose>
+/// let subscriber = topics::velocity_subscriber(&node)
+///     .build(|twist: Twist| println!("{twist:?}"))
+///     .await?;
+/// ```
+///
+/// Using a topic with the wrong message type fails to compile:
+///
+/// ```rust,compile_fail
+/// use zenobuf_core::{Node, PublisherHandle, Result};
+/// use zenobuf_macros::{topics, ZenobufMessage};
+///
+/// #[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+/// struct Pose {
+///     #[prost(double, tag = "1")]
+///     x: f64,
+/// }
+///
+/// #[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+/// struct Twist {
+///     #[prost(double, tag = "1")]
+///     linear: f64,
+/// }
+///
+/// topics! {
+///     pose: Pose,
+/// }
+///
+/// async fn publish(node: &Node) -> Result<()> {
+///     let publisher: PublisherHandle<Twist> = topics::pose(node).build().await?;
+///     Ok(())
+/// }
+/// ```
+#[proc_macro]
+pub fn topics(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as topics::Topics);
+    topics::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
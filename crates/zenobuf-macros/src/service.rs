@@ -0,0 +1,263 @@
+//! Expansion of the `#[zenobuf_service]` attribute
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    Attribute, FnArg, GenericArgument, ItemTrait, LitStr, PathArguments, ReturnType, TraitItem,
+    TraitItemFn, Type,
+};
+
+/// Options parsed from `#[zenobuf_service(...)]`
+#[derive(Default)]
+pub(crate) struct ServiceOptions {
+    /// Prefix used for the service names of each method
+    name: Option<LitStr>,
+}
+
+impl ServiceOptions {
+    pub(crate) fn parser(&mut self) -> impl syn::parse::Parser<Output = ()> + '_ {
+        syn::meta::parser(|meta| {
+            if meta.path.is_ident("name") {
+                self.name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported zenobuf_service attribute, expected `name`"))
+            }
+        })
+    }
+}
+
+/// A single rpc of the service
+struct Rpc<'a> {
+    attrs: &'a [Attribute],
+    method: &'a TraitItemFn,
+    request: &'a Type,
+    response: &'a Type,
+}
+
+impl<'a> Rpc<'a> {
+    fn parse(method: &'a TraitItemFn) -> syn::Result<Self> {
+        let sig = &method.sig;
+        if sig.asyncness.is_some() || !sig.generics.params.is_empty() {
+            return Err(syn::Error::new_spanned(
+                sig,
+                "service methods must be synchronous and non-generic",
+            ));
+        }
+
+        let mut inputs = sig.inputs.iter();
+        match inputs.next() {
+            Some(FnArg::Receiver(receiver))
+                if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    sig,
+                    "service methods must take `&self`",
+                ))
+            }
+        }
+        let request = match (inputs.next(), inputs.next()) {
+            (Some(FnArg::Typed(arg)), None) => &*arg.ty,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &sig.inputs,
+                    "service methods must take exactly one request argument",
+                ))
+            }
+        };
+
+        let response = match &sig.output {
+            ReturnType::Type(_, ty) => result_ok_type(ty),
+            ReturnType::Default => None,
+        }
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &sig.output,
+                "service methods must return `Result<Response>`",
+            )
+        })?;
+
+        Ok(Self {
+            attrs: &method.attrs,
+            method,
+            request,
+            response,
+        })
+    }
+}
+
+/// Extracts `T` from a `Result<T>` style return type
+fn result_ok_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Expands a service trait into a server trait, a client and a server handle
+pub(crate) fn expand(options: ServiceOptions, item: ItemTrait) -> syn::Result<TokenStream> {
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "service traits cannot be generic",
+        ));
+    }
+
+    let mut rpcs = Vec::new();
+    for trait_item in &item.items {
+        match trait_item {
+            TraitItem::Fn(method) => rpcs.push(Rpc::parse(method)?),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "service traits may only contain methods",
+                ))
+            }
+        }
+    }
+
+    let vis = &item.vis;
+    let attrs = &item.attrs;
+    let ident = &item.ident;
+    let server = format_ident!("{}Server", ident);
+    let client = format_ident!("{}Client", ident);
+    let handle = format_ident!("{}ServerHandle", ident);
+    let prefix = options
+        .name
+        .map(|name| name.value())
+        .unwrap_or_else(|| ident.to_string());
+
+    let names: Vec<String> = rpcs
+        .iter()
+        .map(|rpc| format!("{}/{}", prefix, rpc.method.sig.ident))
+        .collect();
+
+    let server_methods = rpcs.iter().map(|rpc| {
+        let method = rpc.method;
+        quote! { #method }
+    });
+
+    let client_fields = rpcs.iter().map(|rpc| {
+        let field = &rpc.method.sig.ident;
+        let (req, res) = (rpc.request, rpc.response);
+        quote! { #field: ::zenobuf_core::ClientHandle<#req, #res> }
+    });
+
+    let client_inits = rpcs.iter().zip(&names).map(|(rpc, name)| {
+        let field = &rpc.method.sig.ident;
+        let (req, res) = (rpc.request, rpc.response);
+        quote! { #field: node.client::<#req, #res>(#name).build()? }
+    });
+
+    let client_methods = rpcs.iter().map(|rpc| {
+        let method = &rpc.method.sig.ident;
+        let method_async = format_ident!("{}_async", method);
+        let method_attrs = rpc.attrs;
+        let (req, res) = (rpc.request, rpc.response);
+        quote! {
+            #(#method_attrs)*
+            pub fn #method(&self, request: &#req) -> ::zenobuf_core::Result<#res> {
+                self.#method.call(request)
+            }
+
+            #(#method_attrs)*
+            pub async fn #method_async(&self, request: &#req) -> ::zenobuf_core::Result<#res> {
+                self.#method.call_async(request).await
+            }
+        }
+    });
+
+    let client_waits = rpcs.iter().map(|rpc| {
+        let field = &rpc.method.sig.ident;
+        quote! { self.#field.wait_for_service(timeout).await?; }
+    });
+
+    let registrations = rpcs.iter().zip(&names).map(|(rpc, name)| {
+        let method = &rpc.method.sig.ident;
+        let (req, res) = (rpc.request, rpc.response);
+        quote! {
+            {
+                let server = server.clone();
+                services.push(
+                    node.service::<#req, #res>(#name)
+                        .build(move |request| server.#method(request))
+                        .await?,
+                );
+            }
+        }
+    });
+
+    let count = rpcs.len();
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis trait #server: Send + Sync + 'static {
+            #(#server_methods)*
+        }
+
+        /// Typed client for the service, with one method per rpc
+        #vis struct #client {
+            #(#client_fields,)*
+        }
+
+        impl #client {
+            /// Creates clients for every rpc of the service
+            pub fn new(node: &::zenobuf_core::Node) -> ::zenobuf_core::Result<Self> {
+                Ok(Self {
+                    #(#client_inits,)*
+                })
+            }
+
+            /// Waits until a server is available for every rpc, allowing `timeout` for each
+            pub async fn wait_for_service(
+                &self,
+                timeout: ::std::time::Duration,
+            ) -> ::zenobuf_core::Result<()> {
+                #(#client_waits)*
+                Ok(())
+            }
+
+            #(#client_methods)*
+        }
+
+        /// Handle to the services registered for a server implementation
+        ///
+        /// The services are unregistered when the handle is dropped.
+        #vis struct #handle {
+            services: Vec<::zenobuf_core::ServiceHandle>,
+        }
+
+        impl #handle {
+            /// Registers one service per rpc, dispatching to `server`
+            pub async fn serve<S: #server>(
+                node: &::zenobuf_core::Node,
+                server: S,
+            ) -> ::zenobuf_core::Result<Self> {
+                let server = ::std::sync::Arc::new(server);
+                let mut services = Vec::with_capacity(#count);
+                #(#registrations)*
+                Ok(Self { services })
+            }
+
+            /// Returns the service names served by this handle
+            pub fn service_names() -> &'static [&'static str] {
+                &[#(#names),*]
+            }
+
+            /// Returns the registered service handles
+            pub fn services(&self) -> &[::zenobuf_core::ServiceHandle] {
+                &self.services
+            }
+        }
+    })
+}
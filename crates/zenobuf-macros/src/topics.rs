@@ -0,0 +1,113 @@
+//! Expansion of the `topics!` macro
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Ident, LitStr, Token, Type};
+
+/// A single `name: Type = "topic"` entry
+struct Topic {
+    attrs: Vec<Attribute>,
+    name: Ident,
+    ty: Type,
+    topic: Option<LitStr>,
+}
+
+impl Parse for Topic {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        let topic = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self {
+            attrs,
+            name,
+            ty,
+            topic,
+        })
+    }
+}
+
+/// The entries of a `topics!` invocation
+pub(crate) struct Topics {
+    topics: Punctuated<Topic, Token![,]>,
+}
+
+impl Parse for Topics {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            topics: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// Expands the entries into a `topics` module of typed accessors
+pub(crate) fn expand(input: Topics) -> syn::Result<TokenStream> {
+    let mut seen = std::collections::HashSet::new();
+    for topic in &input.topics {
+        if !seen.insert(topic.name.to_string()) {
+            return Err(syn::Error::new_spanned(
+                &topic.name,
+                format!("topic `{}` is declared more than once", topic.name),
+            ));
+        }
+    }
+
+    let items = input.topics.iter().map(|topic| {
+        let Topic {
+            attrs,
+            name,
+            ty,
+            topic,
+        } = topic;
+        let key = topic
+            .as_ref()
+            .map(LitStr::value)
+            .unwrap_or_else(|| name.to_string());
+        let constant = format_ident!("{}", name.to_string().to_uppercase());
+        let subscriber = format_ident!("{}_subscriber", name);
+        let constant_doc = format!("Name of the `{key}` topic");
+        let publisher_doc = format!("Returns a publisher builder for the `{key}` topic");
+        let subscriber_doc = format!("Returns a subscriber builder for the `{key}` topic");
+
+        quote! {
+            #[doc = #constant_doc]
+            pub const #constant: &str = #key;
+
+            #(#attrs)*
+            #[doc = ""]
+            #[doc = #publisher_doc]
+            pub fn #name(
+                node: &::zenobuf_core::Node,
+            ) -> ::zenobuf_core::node::PublisherBuilder<'_, #ty> {
+                node.publisher::<#ty>(#constant)
+            }
+
+            #(#attrs)*
+            #[doc = ""]
+            #[doc = #subscriber_doc]
+            pub fn #subscriber(
+                node: &::zenobuf_core::Node,
+            ) -> ::zenobuf_core::node::SubscriberBuilder<'_, #ty> {
+                node.subscriber::<#ty>(#constant)
+            }
+        }
+    });
+
+    Ok(quote! {
+        /// Typed accessors for the topics declared with `topics!`
+        pub mod topics {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #(#items)*
+        }
+    })
+}
@@ -0,0 +1,169 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{cdr, Encoding, Error, Node};
+use zenobuf_macros::ZenobufMessage;
+
+// Layout of geometry_msgs/msg/Point
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(type_name = "geometry_msgs/msg/Point", cdr)]
+struct Point {
+    #[prost(double, tag = "1")]
+    x: f64,
+    #[prost(double, tag = "2")]
+    y: f64,
+    #[prost(double, tag = "3")]
+    z: f64,
+}
+
+// Layout of geometry_msgs/msg/Quaternion
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(type_name = "geometry_msgs/msg/Quaternion", cdr)]
+struct Quaternion {
+    #[prost(double, tag = "1")]
+    x: f64,
+    #[prost(double, tag = "2")]
+    y: f64,
+    #[prost(double, tag = "3")]
+    z: f64,
+    #[prost(double, tag = "4")]
+    w: f64,
+}
+
+// Layout of geometry_msgs/msg/Pose
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(type_name = "geometry_msgs/msg/Pose", cdr)]
+struct Pose {
+    #[prost(message, optional, tag = "1")]
+    position: Option<Point>,
+    #[prost(message, optional, tag = "2")]
+    orientation: Option<Quaternion>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(cdr)]
+struct Status {
+    #[prost(bool, tag = "1")]
+    ok: bool,
+    #[prost(double, tag = "2")]
+    voltage: f64,
+    #[prost(string, tag = "3")]
+    label: String,
+    #[prost(uint32, repeated, tag = "4")]
+    codes: Vec<u32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct Plain {
+    #[prost(int32, tag = "1")]
+    value: i32,
+}
+
+/// `geometry_msgs/msg/Point { x: 1.0, y: 2.0, z: 3.0 }` as serialized by rclcpp
+const POINT_REFERENCE: [u8; 28] = [
+    0x00, 0x01, 0x00, 0x00, // CDR little-endian encapsulation
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, // x
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, // y
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x40, // z
+];
+
+#[test]
+fn test_point_matches_ros_reference() {
+    let point = Point {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    let bytes = cdr::encode(&point).unwrap();
+    assert_eq!(bytes, POINT_REFERENCE);
+    assert_eq!(cdr::decode::<Point>(&POINT_REFERENCE).unwrap(), point);
+}
+
+#[test]
+fn test_pose_nests_fields_in_order() {
+    let pose = Pose {
+        position: Some(Point {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        }),
+        orientation: Some(Quaternion {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        }),
+    };
+    let bytes = cdr::encode(&pose).unwrap();
+    assert_eq!(bytes.len(), 4 + 7 * 8);
+    assert_eq!(bytes[..28], POINT_REFERENCE);
+    assert_eq!(bytes[52..], 1.0f64.to_le_bytes());
+    assert_eq!(cdr::decode::<Pose>(&bytes).unwrap(), pose);
+}
+
+#[test]
+fn test_alignment_strings_and_sequences() {
+    let status = Status {
+        ok: true,
+        voltage: 12.5,
+        label: "hi".to_string(),
+        codes: vec![7, 9],
+    };
+    let bytes = cdr::encode(&status).unwrap();
+
+    let mut expected = vec![0x00, 0x01, 0x00, 0x00, 0x01];
+    expected.extend([0; 7]); // the double is aligned to 8 bytes
+    expected.extend(12.5f64.to_le_bytes());
+    expected.extend([3, 0, 0, 0, b'h', b'i', 0]);
+    expected.push(0); // the sequence length is aligned to 4 bytes
+    expected.extend([2, 0, 0, 0, 7, 0, 0, 0, 9, 0, 0, 0]);
+    assert_eq!(bytes, expected);
+    assert_eq!(cdr::decode::<Status>(&bytes).unwrap(), status);
+
+    let err = cdr::decode::<Status>(&bytes[..20]).unwrap_err();
+    assert!(matches!(err, Error::Cdr { .. }));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_publish_and_subscribe_in_cdr() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("cdr_node", transport).await.unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let _subscriber = node
+        .subscriber::<Point>("cdr_points")
+        .with_encoding(Encoding::Cdr)
+        .build(move |point| received_clone.lock().unwrap().push(point))
+        .await
+        .unwrap();
+    let publisher = node
+        .publisher::<Point>("cdr_points")
+        .with_encoding(Encoding::Cdr)
+        .build()
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let point = Point {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    publisher.publish(&point).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    node.spin_once().unwrap();
+
+    assert_eq!(*received.lock().unwrap(), vec![point]);
+
+    // Messages without a CDR layout are rejected when the publisher is built
+    let err = node
+        .publisher::<Plain>("cdr_plain")
+        .with_encoding(Encoding::Cdr)
+        .build()
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::NotSupported { .. }));
+}
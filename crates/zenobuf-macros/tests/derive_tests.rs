@@ -76,3 +76,180 @@ fn test_derive_macro() {
     // Check that the decoded message matches the original
     assert_eq!(decoded.value, message.value);
 }
+
+// Define a message with an explicit type name
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(type_name = "my_app.Point")]
+struct NamedPoint {
+    #[prost(float, tag = "1")]
+    x: f32,
+}
+
+#[test]
+fn test_derive_macro_custom_type_name() {
+    assert_eq!(NamedPoint::type_name(), "my_app.Point");
+
+    let message = NamedPoint { x: 1.5 };
+    let decoded = NamedPoint::decode(message.encode_to_vec().as_slice()).unwrap();
+    assert_eq!(decoded, message);
+}
+
+// Define a message that provides its own type name
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(no_type_name)]
+struct LegacyPoint {
+    #[prost(float, tag = "1")]
+    x: f32,
+}
+
+impl LegacyPoint {
+    fn type_name() -> &'static str {
+        "legacy.Point"
+    }
+}
+
+fn message_type_name<M: Message>() -> &'static str {
+    M::type_name()
+}
+
+#[test]
+fn test_derive_macro_without_type_name() {
+    assert_eq!(message_type_name::<LegacyPoint>(), "legacy.Point");
+    assert_eq!(LegacyPoint::fields()[0].name, "x");
+
+    let message = LegacyPoint { x: 2.5 };
+    let decoded = LegacyPoint::decode(message.encode_to_vec().as_slice()).unwrap();
+    assert_eq!(decoded, message);
+}
+
+// Define a message whose invariants are checked by a function
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+#[zenobuf(validate = "check_altitude")]
+struct Waypoint {
+    #[prost(double, tag = "1")]
+    z: f64,
+}
+
+fn check_altitude(waypoint: &Waypoint) -> zenobuf_core::Result<()> {
+    if waypoint.z < 0.0 {
+        return Err(zenobuf_core::Error::invalid_message(
+            "Waypoint",
+            "z is negative",
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_derive_macro_validate() {
+    assert!(Waypoint { z: 1.0 }.validate().is_ok());
+    assert!(Waypoint { z: -1.0 }.validate().is_err());
+
+    // Without the attribute, every message is valid
+    assert!(NamedPoint { x: -1.0 }.validate().is_ok());
+}
+
+// Define a message covering the different kinds of fields
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct Reading {
+    #[prost(string, tag = "1")]
+    sensor: String,
+    #[prost(double, repeated, tag = "2")]
+    samples: Vec<f64>,
+    #[prost(uint32, optional, tag = "3")]
+    sequence: Option<u32>,
+    #[prost(oneof = "ReadingValue", tags = "4, 5")]
+    value: Option<ReadingValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof, ZenobufMessage)]
+enum ReadingValue {
+    #[prost(float, tag = "4")]
+    Temperature(f32),
+    #[prost(string, tag = "5")]
+    Label(String),
+}
+
+#[test]
+fn test_derive_macro_fields() {
+    let fields = Reading::fields();
+    let summary: Vec<_> = fields
+        .iter()
+        .map(|f| (f.name, f.proto_type, f.repeated, f.optional))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("sensor", "string", false, false),
+            ("samples", "double", true, false),
+            ("sequence", "uint32", false, true),
+            ("value", "oneof", false, false),
+        ]
+    );
+    assert!(fields[0].variants.is_empty());
+    assert_eq!(fields[3].variants, ["Temperature", "Label"]);
+
+    // Without prost attributes the descriptors are inferred from the Rust types
+    assert_eq!(TestMessage::fields()[0].name, "value");
+    assert_eq!(TestMessage::fields()[0].proto_type, "int32");
+}
+
+// Define copies of a message as two peers might have compiled them
+mod ours {
+    #[derive(Clone, PartialEq, prost::Message, zenobuf_macros::ZenobufMessage)]
+    pub struct Pose {
+        #[prost(double, tag = "1")]
+        pub x: f64,
+        #[prost(double, tag = "2")]
+        pub y: f64,
+    }
+}
+
+mod theirs {
+    #[derive(Clone, PartialEq, prost::Message, zenobuf_macros::ZenobufMessage)]
+    pub struct Pose {
+        #[prost(double, tag = "1")]
+        pub x: f64,
+        #[prost(float, tag = "2")]
+        pub y: f32,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message, zenobuf_macros::ZenobufMessage)]
+    #[zenobuf(type_hash = 0x5a17_0002)]
+    pub struct PinnedPose {
+        #[prost(double, tag = "1")]
+        pub x: f64,
+    }
+}
+
+mod same {
+    #[derive(Clone, PartialEq, prost::Message, zenobuf_macros::ZenobufMessage)]
+    pub struct Pose {
+        #[prost(double, tag = "1")]
+        pub x: f64,
+        #[prost(double, tag = "2")]
+        pub y: f64,
+    }
+}
+
+#[test]
+fn test_derive_macro_type_hash() {
+    use zenobuf_core::message::type_hash_of_schema;
+
+    assert_eq!(
+        ours::Pose::type_hash(),
+        type_hash_of_schema("Pose{1:x:double,2:y:double}")
+    );
+    assert_eq!(
+        Reading::type_hash(),
+        type_hash_of_schema(
+            "Reading{1:sensor:string,2:samples:double*,3:sequence:uint32?,4,5:value:oneof}"
+        )
+    );
+
+    // The module path does not matter, the field layout does
+    assert_eq!(ours::Pose::type_hash(), same::Pose::type_hash());
+    assert_ne!(ours::Pose::type_hash(), theirs::Pose::type_hash());
+
+    assert_eq!(theirs::PinnedPose::type_hash(), 0x5a17_0002);
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Node, Result};
+use zenobuf_macros::{zenobuf_service, ZenobufMessage};
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct AddRequest {
+    #[prost(int32, tag = "1")]
+    a: i32,
+    #[prost(int32, tag = "2")]
+    b: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct AddResponse {
+    #[prost(int32, tag = "1")]
+    sum: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct NegateRequest {
+    #[prost(int32, tag = "1")]
+    value: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct NegateResponse {
+    #[prost(int32, tag = "1")]
+    value: i32,
+}
+
+// Equivalent to what a prost-build service generator emits for
+// `service Calculator { rpc Add(..) returns (..); rpc Negate(..) returns (..); }`
+#[zenobuf_service(name = "test.Calculator")]
+trait Calculator {
+    fn add(&self, request: AddRequest) -> Result<AddResponse>;
+    fn negate(&self, request: NegateRequest) -> Result<NegateResponse>;
+}
+
+struct CalculatorImpl;
+
+impl CalculatorServer for CalculatorImpl {
+    fn add(&self, request: AddRequest) -> Result<AddResponse> {
+        Ok(AddResponse {
+            sum: request.a + request.b,
+        })
+    }
+
+    fn negate(&self, request: NegateRequest) -> Result<NegateResponse> {
+        Ok(NegateResponse {
+            value: -request.value,
+        })
+    }
+}
+
+#[test]
+fn test_service_names() {
+    assert_eq!(
+        CalculatorServerHandle::service_names(),
+        &["test.Calculator/add", "test.Calculator/negate"]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_generated_service() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("calculator_node", transport)
+        .await
+        .unwrap();
+
+    let server = CalculatorServerHandle::serve(&node, CalculatorImpl)
+        .await
+        .unwrap();
+    assert_eq!(server.services().len(), 2);
+
+    let client = CalculatorClient::new(&node).unwrap();
+    client
+        .wait_for_service(Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let response = client.add_async(&AddRequest { a: 2, b: 3 }).await.unwrap();
+    assert_eq!(response.sum, 5);
+
+    let response = client
+        .negate_async(&NegateRequest { value: 7 })
+        .await
+        .unwrap();
+    assert_eq!(response.value, -7);
+}
@@ -0,0 +1,61 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use zenobuf_core::transport::ZenohTransport;
+use zenobuf_core::{Node, PublisherHandle, SubscriberHandle};
+use zenobuf_macros::{topics, ZenobufMessage};
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct Pose {
+    #[prost(double, tag = "1")]
+    x: f64,
+    #[prost(double, tag = "2")]
+    y: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message, ZenobufMessage)]
+struct Twist {
+    #[prost(double, tag = "1")]
+    linear: f64,
+}
+
+topics! {
+    /// Current pose of the robot
+    pose: Pose = "topics_test/pose",
+    cmd_vel: Twist,
+}
+
+#[test]
+fn test_topics_macro_names() {
+    assert_eq!(topics::POSE, "topics_test/pose");
+    assert_eq!(topics::CMD_VEL, "cmd_vel");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_topics_macro_builders() {
+    let transport = ZenohTransport::new().await.unwrap();
+    let node = Node::with_transport("topics_macro_node", transport)
+        .await
+        .unwrap();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink = received.clone();
+    let _subscriber: SubscriberHandle = topics::pose_subscriber(&node)
+        .build(move |pose: Pose| sink.lock().unwrap().push(pose))
+        .await
+        .unwrap();
+    let publisher: PublisherHandle<Pose> = topics::pose(&node).build().await.unwrap();
+    assert_eq!(publisher.topic(), "topics_test/pose");
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    publisher.publish(&Pose { x: 1.0, y: 2.0 }).unwrap();
+
+    for _ in 0..100 {
+        node.spin_once().unwrap();
+        if !received.lock().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(*received.lock().unwrap(), vec![Pose { x: 1.0, y: 2.0 }]);
+}